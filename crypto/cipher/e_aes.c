@@ -76,6 +76,13 @@ int GFp_aes_gcm_seal(const void *ctx_buf, uint8_t *in_out, size_t in_out_len,
                      uint8_t tag_out[EVP_AEAD_AES_GCM_TAG_LEN],
                      const uint8_t nonce[EVP_AEAD_AES_GCM_NONCE_LEN],
                      const uint8_t *ad, size_t ad_len);
+int GFp_aes_block_init(void *ctx_buf, size_t ctx_buf_len, const uint8_t *key,
+                       size_t key_len);
+void GFp_aes_block_encrypt(const void *ctx_buf, const uint8_t in[16],
+                           uint8_t out[16]);
+void GFp_aes_block_ctr32_encrypt_blocks(const void *ctx_buf, const uint8_t *in,
+                                        uint8_t *out, size_t blocks,
+                                        const uint8_t ivec[16]);
 int GFp_has_aes_hardware(void);
 
 
@@ -131,9 +138,17 @@ void GFp_aes_hw_ctr32_encrypt_blocks(const uint8_t *in, uint8_t *out,
                                      const uint8_t ivec[16]);
 #endif
 
+#if defined(VPAES)
 static void aes_ctr32_encrypt_blocks(const uint8_t *in, uint8_t *out,
                                      size_t len, const AES_KEY *key,
                                      const uint8_t ivec[16]);
+#endif
+
+#if defined(BSAES)
+static void bsaes_ctr32_encrypt_blocks(const uint8_t *in, uint8_t *out,
+                                       size_t blocks, const AES_KEY *key,
+                                       const uint8_t ivec[16]);
+#endif
 
 #if defined(VPAES)
 /* On platforms where VPAES gets defined (just above), then these functions are
@@ -151,6 +166,16 @@ void GFp_aesni_encrypt(const uint8_t *in, uint8_t *out, const AES_KEY *key);
 static char aesni_capable(void);
 #endif
 
+/* Provided by the Rust code in src/aead/aes.rs. These are bitsliced and
+ * constant-time, unlike the table-based |GFp_AES_encrypt|, and the key schedule
+ * has the same layout as |GFp_AES_set_encrypt_key|'s, as bsaes requires. */
+int GFp_aes_nohw_set_encrypt_key(const uint8_t *key, unsigned bits,
+                                 AES_KEY *aeskey);
+void GFp_aes_nohw_encrypt(const uint8_t *in, uint8_t *out, const AES_KEY *key);
+void GFp_aes_nohw_ctr32_encrypt_blocks(const uint8_t *in, uint8_t *out,
+                                       size_t blocks, const AES_KEY *key,
+                                       const uint8_t ivec[16]);
+
 typedef int (*aes_set_key_f)(const uint8_t *userKey, unsigned bits,
                              AES_KEY *key);
 
@@ -169,7 +194,7 @@ static aes_set_key_f aes_set_key(void) {
 
 #if defined(BSAES)
   if (bsaes_capable()) {
-    return GFp_AES_set_encrypt_key;
+    return GFp_aes_nohw_set_encrypt_key;
   }
 #endif
 
@@ -179,7 +204,7 @@ static aes_set_key_f aes_set_key(void) {
   }
 #endif
 
-  return GFp_AES_set_encrypt_key;
+  return GFp_aes_nohw_set_encrypt_key;
 }
 
 static aes_block_f aes_block(void) {
@@ -197,19 +222,22 @@ static aes_block_f aes_block(void) {
   }
 #endif
 
-#if defined(VPAES)
+  /* bsaes is only used for eight or more blocks at a time (see
+   * |bsaes_ctr32_encrypt_blocks|), so single blocks use the constant-time Rust
+   * code, which shares bsaes's key schedule. */
 #if defined(BSAES)
   if (bsaes_capable()) {
-    return GFp_AES_encrypt;
+    return GFp_aes_nohw_encrypt;
   }
 #endif
 
+#if defined(VPAES)
   if (vpaes_capable()) {
     return GFp_vpaes_encrypt;
   }
 #endif
 
-  return GFp_AES_encrypt;
+  return GFp_aes_nohw_encrypt;
 }
 
 static aes_ctr_f aes_ctr(void) {
@@ -229,11 +257,17 @@ static aes_ctr_f aes_ctr(void) {
 
 #if defined(BSAES)
   if (bsaes_capable()) {
-    return GFp_bsaes_ctr32_encrypt_blocks;
+    return bsaes_ctr32_encrypt_blocks;
+  }
+#endif
+
+#if defined(VPAES)
+  if (vpaes_capable()) {
+    return aes_ctr32_encrypt_blocks;
   }
 #endif
 
-  return aes_ctr32_encrypt_blocks;
+  return GFp_aes_nohw_ctr32_encrypt_blocks;
 }
 
 #if defined(AESNI)
@@ -242,6 +276,7 @@ static char aesni_capable(void) {
 }
 #endif
 
+#if defined(VPAES)
 static void aes_ctr32_encrypt_blocks(const uint8_t *in, uint8_t *out,
                                      size_t blocks, const AES_KEY *key,
                                      const uint8_t ivec[16]) {
@@ -265,6 +300,22 @@ static void aes_ctr32_encrypt_blocks(const uint8_t *in, uint8_t *out,
     in += 16;
   }
 }
+#endif
+
+#if defined(BSAES)
+/* |GFp_bsaes_ctr32_encrypt_blocks| encrypts inputs shorter than eight blocks
+ * one block at a time with the table-based |GFp_AES_encrypt|, so those are
+ * handled by |GFp_aes_nohw_ctr32_encrypt_blocks| instead. */
+static void bsaes_ctr32_encrypt_blocks(const uint8_t *in, uint8_t *out,
+                                       size_t blocks, const AES_KEY *key,
+                                       const uint8_t ivec[16]) {
+  if (blocks < 8) {
+    GFp_aes_nohw_ctr32_encrypt_blocks(in, out, blocks, key, ivec);
+    return;
+  }
+  GFp_bsaes_ctr32_encrypt_blocks(in, out, blocks, key, ivec);
+}
+#endif
 
 int GFp_aes_gcm_init(void *ctx_buf, size_t ctx_buf_len, const uint8_t *key,
                      size_t key_len) {
@@ -274,9 +325,9 @@ int GFp_aes_gcm_init(void *ctx_buf, size_t ctx_buf_len, const uint8_t *key,
     return 0;
   }
 
-  /* XXX: Ignores return value. TODO: These functions should return |void|
-   * anyway. */
-  (void)(aes_set_key())(key, (unsigned)key_len * 8, &ks);
+  if ((aes_set_key())(key, (unsigned)key_len * 8, &ks) != 0) {
+    return 0;
+  }
 
   GFp_gcm128_init_serialized((uint8_t *)ctx_buf + sizeof(ks), &ks, aes_block());
   memcpy(ctx_buf, &ks, sizeof(ks));
@@ -345,6 +396,36 @@ int GFp_aes_gcm_open(const void *ctx_buf, uint8_t *out, size_t in_out_len,
   return 1;
 }
 
+/* |GFp_aes_block_init|, |GFp_aes_block_encrypt|, and
+ * |GFp_aes_block_ctr32_encrypt_blocks| expose the same AES implementation
+ * that the AES-GCM code uses to the Rust code that implements other AES-based
+ * modes. */
+
+int GFp_aes_block_init(void *ctx_buf, size_t ctx_buf_len, const uint8_t *key,
+                       size_t key_len) {
+  alignas(16) AES_KEY ks;
+  assert(ctx_buf_len >= sizeof(ks));
+  if (ctx_buf_len < sizeof(ks)) {
+    return 0;
+  }
+
+  if ((aes_set_key())(key, (unsigned)key_len * 8, &ks) != 0) {
+    return 0;
+  }
+  memcpy(ctx_buf, &ks, sizeof(ks));
+  return 1;
+}
+
+void GFp_aes_block_encrypt(const void *ctx_buf, const uint8_t in[16],
+                           uint8_t out[16]) {
+  (aes_block())(in, out, ctx_buf);
+}
+
+void GFp_aes_block_ctr32_encrypt_blocks(const void *ctx_buf, const uint8_t *in,
+                                        uint8_t *out, size_t blocks,
+                                        const uint8_t ivec[16]) {
+  (aes_ctr())(in, out, blocks, ctx_buf, ivec);
+}
 
 int GFp_has_aes_hardware(void) {
 #if defined(AESNI)
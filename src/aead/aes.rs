@@ -0,0 +1,888 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! The AES block functions, as used outside of AES-GCM.
+//!
+//! Encryption uses the implementation (AES-NI, vpaes, etc.) that the C code
+//! selects for the current CPU. Without AES instructions or vpaes, that is the
+//! bitsliced, constant-time implementation below, which encrypts up to four
+//! blocks at a time; the table-based `GFp_AES_encrypt` is never used. The C
+//! code doesn't implement the inverse cipher, so decryption is implemented
+//! here, also bitsliced. With the `pure_rust` feature, the bitsliced
+//! implementation is always used.
+
+use {bssl, c, error, polyfill, zeroize};
+
+use core;
+
+pub const BLOCK_LEN: usize = 16;
+
+pub type Block = [u8; BLOCK_LEN];
+
+pub const AES_128_KEY_LEN: usize = 128 / 8;
+pub const AES_256_KEY_LEN: usize = 32; // 256 / 8
+
+// Keep this in sync with `AES_KEY` in aes.h.
+pub const KEY_BUF_LEN: usize = (4 * 4 * (MAX_ROUNDS + 1)) + 8;
+
+pub const KEY_BUF_ELEMS: usize = (KEY_BUF_LEN + 7) / 8;
+
+// Keep this in sync with `AES_MAXNR` in aes.h.
+pub const MAX_ROUNDS: usize = 14;
+
+/// Expands `key` into the AES key schedule at the start of `ctx_buf`.
+pub fn init(ctx_buf: &mut [u8], key: &[u8]) -> Result<(), error::Unspecified> {
+    bssl::map_result(unsafe {
+        GFp_aes_block_init(ctx_buf.as_mut_ptr(), ctx_buf.len(), key.as_ptr(),
+                           key.len())
+    })
+}
+
+/// Encrypts `block` in place using the key schedule that `init` wrote into
+/// `ctx`.
+#[inline]
+pub fn encrypt_block(ctx: &[u64], block: &mut Block) {
+    debug_assert!(ctx.len() >= KEY_BUF_ELEMS);
+    let ctx = polyfill::slice::u64_as_u8(ctx);
+    let input = *block;
+    unsafe {
+        GFp_aes_block_encrypt(ctx.as_ptr(), &input, block);
+    }
+}
+
+/// XORs the CTR mode keystream that starts at the counter block `counter`
+/// into `in_out`, using the key schedule that `init` wrote into `ctx`.
+///
+/// Only the last four bytes of the counter block are incremented, as a
+/// big-endian number; the caller must ensure that they don't wrap around.
+/// `in_out.len()` must be a multiple of `BLOCK_LEN`.
+#[inline]
+pub fn ctr32_encrypt_blocks(ctx: &[u64], in_out: &mut [u8], counter: &Block) {
+    debug_assert!(ctx.len() >= KEY_BUF_ELEMS);
+    debug_assert_eq!(in_out.len() % BLOCK_LEN, 0);
+    let ctx = polyfill::slice::u64_as_u8(ctx);
+    let blocks = in_out.len() / BLOCK_LEN;
+    let in_out = in_out.as_mut_ptr();
+    unsafe {
+        GFp_aes_block_ctr32_encrypt_blocks(ctx.as_ptr(), in_out, in_out,
+                                           blocks, counter);
+    }
+}
+
+#[cfg(not(feature = "pure_rust"))]
+extern {
+    fn GFp_aes_block_init(ctx_buf: *mut u8, ctx_buf_len: c::size_t,
+                          key: *const u8, key_len: c::size_t) -> c::int;
+    fn GFp_aes_block_encrypt(ctx_buf: *const u8, in_: &Block, out: &mut Block);
+    fn GFp_aes_block_ctr32_encrypt_blocks(ctx_buf: *const u8, in_: *const u8,
+                                          out: *mut u8, blocks: c::size_t,
+                                          ivec: &Block);
+}
+
+// Keep this in sync with `AES_KEY` in aes.h.
+#[repr(C)]
+pub struct AES_KEY {
+    rd_key: [u32; 4 * (MAX_ROUNDS + 1)],
+    rounds: c::uint,
+}
+
+// The constant-time implementation that the C code uses when the CPU has
+// neither AES instructions nor SSSE3 (for vpaes), and for single blocks and
+// short inputs alongside bsaes, instead of the table-based `GFp_AES_encrypt`.
+// See `aes_set_key()` in crypto/cipher/e_aes.c. The key schedule has the same
+// layout as `GFp_AES_set_encrypt_key`'s, which bsaes depends on.
+
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern fn GFp_aes_nohw_set_encrypt_key(key: *const u8,
+                                                  bits: c::uint,
+                                                  aes_key: *mut AES_KEY)
+                                                  -> c::int {
+    if key.is_null() || aes_key.is_null() {
+        return -1;
+    }
+    if bits != 128 && bits != 256 {
+        return -2;
+    }
+    let key = core::slice::from_raw_parts(key, (bits / 8) as usize);
+    let aes_key = &mut *aes_key;
+
+    let mut round_keys = [[0u8; BLOCK_LEN]; MAX_ROUNDS + 1];
+    let rounds = match expand_key(key, &mut round_keys) {
+        Ok(rounds) => rounds,
+        Err(error::Unspecified) => { return -2; },
+    };
+    for (words, round_key) in
+            aes_key.rd_key.chunks_mut(4).zip(round_keys.iter()) {
+        for (word, bytes) in words.iter_mut().zip(round_key.chunks(4)) {
+            *word = word_from_bytes(bytes);
+        }
+    }
+    aes_key.rounds = rounds as c::uint;
+    for round_key in round_keys.iter_mut() {
+        zeroize::zeroize(round_key);
+    }
+    0
+}
+
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern fn GFp_aes_nohw_encrypt(in_: *const u8, out: *mut u8,
+                                          aes_key: *const AES_KEY) {
+    let schedule = Schedule::from_aes_key(&*aes_key);
+    let mut blocks = [[0u8; BLOCK_LEN]; 1];
+    blocks[0].copy_from_slice(core::slice::from_raw_parts(in_, BLOCK_LEN));
+    schedule.encrypt(&mut blocks);
+    core::slice::from_raw_parts_mut(out, BLOCK_LEN).copy_from_slice(&blocks[0]);
+}
+
+#[allow(non_snake_case)]
+#[no_mangle]
+pub unsafe extern fn GFp_aes_nohw_ctr32_encrypt_blocks(in_: *const u8,
+                                                       out: *mut u8,
+                                                       blocks: c::size_t,
+                                                       aes_key: *const AES_KEY,
+                                                       ivec: *const u8) {
+    let schedule = Schedule::from_aes_key(&*aes_key);
+    let mut counter_block = [0u8; BLOCK_LEN];
+    counter_block.copy_from_slice(core::slice::from_raw_parts(ivec, BLOCK_LEN));
+    let mut counter = polyfill::slice::u32_from_be_u8(
+        slice_as_array_ref!(&counter_block[12..], 4).unwrap());
+
+    let mut done = 0;
+    while done < blocks {
+        let n = core::cmp::min(blocks - done, BATCH_BLOCKS);
+        let mut keystream = [counter_block; BATCH_BLOCKS];
+        for keystream in keystream[..n].iter_mut() {
+            keystream[12..].copy_from_slice(
+                &polyfill::slice::be_u8_from_u32(counter));
+            counter = counter.wrapping_add(1);
+        }
+        schedule.encrypt(&mut keystream[..n]);
+
+        // `in_` and `out` may be equal, so they aren't turned into slices.
+        let offset = (done * BLOCK_LEN) as isize;
+        for (i, k) in keystream[..n].iter().flat_map(|k| k.iter()).enumerate() {
+            let i = offset + (i as isize);
+            *out.offset(i) = *in_.offset(i) ^ *k;
+        }
+        done += n;
+    }
+}
+
+// The x86 and x86-64 assembly language code stores each round key as bytes;
+// the C code and the ARM assembly language code store it as four big-endian
+// words. `word_to_le` returns the little-endian value of a word's bytes, as
+// the bitsliced code loads them.
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn word_from_bytes(bytes: &[u8]) -> u32 { le_word(bytes) }
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn word_to_le(word: u32) -> u32 { word }
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn word_from_bytes(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |acc, b| (acc << 8) | u32::from(*b))
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn word_to_le(word: u32) -> u32 { word.swap_bytes() }
+
+fn le_word(bytes: &[u8]) -> u32 {
+    bytes.iter().rev().fold(0, |acc, b| (acc << 8) | u32::from(*b))
+}
+
+// Implementations of the C functions for builds with the `pure_rust`
+// feature.
+
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe fn GFp_aes_block_init(ctx_buf: *mut u8, ctx_buf_len: c::size_t,
+                             key: *const u8, key_len: c::size_t) -> c::int {
+    if ctx_buf_len < KEY_BUF_LEN {
+        return 0;
+    }
+    let bits = (key_len * 8) as c::uint;
+    let res = GFp_aes_nohw_set_encrypt_key(key, bits, ctx_buf as *mut AES_KEY);
+    (res == 0) as c::int
+}
+
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe fn GFp_aes_block_encrypt(ctx_buf: *const u8, in_: &Block,
+                                out: &mut Block) {
+    GFp_aes_nohw_encrypt(in_.as_ptr(), out.as_mut_ptr(),
+                         ctx_buf as *const AES_KEY);
+}
+
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe fn GFp_aes_block_ctr32_encrypt_blocks(ctx_buf: *const u8,
+                                             in_: *const u8, out: *mut u8,
+                                             blocks: c::size_t,
+                                             ivec: &Block) {
+    GFp_aes_nohw_ctr32_encrypt_blocks(in_, out, blocks,
+                                      ctx_buf as *const AES_KEY,
+                                      ivec.as_ptr());
+}
+
+/// An expanded AES key for the inverse cipher.
+///
+/// Decryption is bitsliced, like encryption without AES instructions, so it
+/// runs in constant time. The round keys are bitsliced again for every block,
+/// which makes decryption slower than encryption.
+pub struct DecryptKey {
+    round_keys: [Block; MAX_ROUNDS + 1],
+    rounds: usize,
+}
+
+impl Drop for DecryptKey {
+    fn drop(&mut self) {
+        for round_key in self.round_keys.iter_mut() {
+            zeroize::zeroize(round_key);
+        }
+    }
+}
+
+impl DecryptKey {
+    /// Expands a 128-bit or 256-bit key.
+    pub fn new(key: &[u8]) -> Result<DecryptKey, error::Unspecified> {
+        let mut round_keys = [[0u8; BLOCK_LEN]; MAX_ROUNDS + 1];
+        let rounds = expand_key(key, &mut round_keys)?;
+        Ok(DecryptKey { round_keys, rounds })
+    }
+
+    /// Decrypts `block` in place.
+    pub fn decrypt_block(&self, block: &mut Block) {
+        let schedule = Schedule::new(self.rounds, |round, words| {
+            for (word, bytes) in
+                    words.iter_mut().zip(self.round_keys[round].chunks(4)) {
+                *word = le_word(bytes);
+            }
+        });
+        let mut blocks = [*block];
+        schedule.decrypt(&mut blocks);
+        *block = blocks[0];
+    }
+}
+
+/// Expands a 128-bit or 256-bit key into `round_keys`, returning the number
+/// of rounds.
+fn expand_key(key: &[u8], round_keys: &mut [Block; MAX_ROUNDS + 1])
+              -> Result<usize, error::Unspecified> {
+    if key.len() != AES_128_KEY_LEN && key.len() != AES_256_KEY_LEN {
+        return Err(error::Unspecified);
+    }
+    let nk = key.len() / 4;
+    let rounds = nk + 6;
+
+    // FIPS 197 Section 5.2.
+    let mut w = [[0u8; 4]; 4 * (MAX_ROUNDS + 1)];
+    for (w, key) in w.iter_mut().zip(key.chunks(4)) {
+        w.copy_from_slice(key);
+    }
+    let mut rcon = 1u8;
+    for i in nk..(4 * (rounds + 1)) {
+        let mut temp = w[i - 1];
+        if i % nk == 0 {
+            temp = sub_word([temp[1], temp[2], temp[3], temp[0]]);
+            temp[0] ^= rcon;
+            rcon = xtime(rcon);
+        } else if nk > 6 && i % nk == 4 {
+            temp = sub_word(temp);
+        }
+        for j in 0..4 {
+            w[i][j] = w[i - nk][j] ^ temp[j];
+        }
+    }
+
+    for (round_key, w) in round_keys.iter_mut().zip(w.chunks(4)) {
+        for (round_key, w) in round_key.chunks_mut(4).zip(w.iter()) {
+            round_key.copy_from_slice(w);
+        }
+    }
+
+    Ok(rounds)
+}
+
+fn sub_word(word: [u8; 4]) -> [u8; 4] {
+    let mut blocks = [[0u8; BLOCK_LEN]; 1];
+    blocks[0][..4].copy_from_slice(&word);
+    let mut q = load(&blocks);
+    sub_bytes(&mut q);
+    store(&mut q, &mut blocks);
+    [blocks[0][0], blocks[0][1], blocks[0][2], blocks[0][3]]
+}
+
+// The round constants aren't secret.
+#[inline(always)]
+fn xtime(a: u8) -> u8 { (a << 1) ^ ((a >> 7) * 0x1b) }
+
+// The bitsliced implementation is a port of BearSSL's `aes_ct64`. Up to four
+// blocks are loaded into eight `u64`s, with bit `i` of every byte of the
+// blocks in `q[i]`, so that the S-box can be computed for all 64 bytes at
+// once by a circuit of XOR, AND and NOT operations (Boyar and Peralta, "A
+// depth-16 circuit for the AES S-box"). Nothing branches on, or indexes memory
+// with, secret data.
+
+const BATCH_BLOCKS: usize = 4;
+
+type State = [u64; 8];
+
+/// Bitsliced round keys.
+struct Schedule {
+    round_keys: [State; MAX_ROUNDS + 1],
+    rounds: usize,
+}
+
+impl Drop for Schedule {
+    fn drop(&mut self) {
+        for round_key in self.round_keys[..(self.rounds + 1)].iter_mut() {
+            zeroize::zeroize(round_key);
+        }
+    }
+}
+
+impl Schedule {
+    /// Bitslices the round keys for `rounds` rounds. `round_key(i, words)`
+    /// sets `words` to the little-endian words of round key `i`; round key 0
+    /// is the one for the initial `AddRoundKey`.
+    fn new<F>(rounds: usize, round_key: F) -> Schedule
+              where F: Fn(usize, &mut [u32; 4]) {
+        debug_assert!(rounds <= MAX_ROUNDS);
+        let mut schedule = Schedule {
+            round_keys: [[0; 8]; MAX_ROUNDS + 1],
+            rounds,
+        };
+        for (i, q) in
+                schedule.round_keys[..(rounds + 1)].iter_mut().enumerate() {
+            let mut words = [0u32; 4];
+            round_key(i, &mut words);
+            // The same round key is used for all four blocks.
+            let (lo, hi) = interleave_in(&words);
+            *q = [lo, lo, lo, lo, hi, hi, hi, hi];
+            ortho(q);
+            zeroize::zeroize(&mut words);
+        }
+        schedule
+    }
+
+    fn from_aes_key(aes_key: &AES_KEY) -> Schedule {
+        Schedule::new(aes_key.rounds as usize, |round, words| {
+            for (word, rd_key) in
+                    words.iter_mut().zip(aes_key.rd_key[(4 * round)..].iter()) {
+                *word = word_to_le(*rd_key);
+            }
+        })
+    }
+
+    /// Encrypts up to four blocks in place (FIPS 197 Section 5.1).
+    fn encrypt(&self, blocks: &mut [Block]) {
+        let mut q = load(blocks);
+        add_round_key(&mut q, &self.round_keys[0]);
+        for round_key in self.round_keys[1..self.rounds].iter() {
+            sub_bytes(&mut q);
+            shift_rows(&mut q);
+            mix_columns(&mut q);
+            add_round_key(&mut q, round_key);
+        }
+        sub_bytes(&mut q);
+        shift_rows(&mut q);
+        add_round_key(&mut q, &self.round_keys[self.rounds]);
+        store(&mut q, blocks);
+    }
+
+    /// Decrypts up to four blocks in place (FIPS 197 Section 5.3).
+    fn decrypt(&self, blocks: &mut [Block]) {
+        let mut q = load(blocks);
+        add_round_key(&mut q, &self.round_keys[self.rounds]);
+        for round_key in self.round_keys[1..self.rounds].iter().rev() {
+            inv_shift_rows(&mut q);
+            inv_sub_bytes(&mut q);
+            add_round_key(&mut q, round_key);
+            inv_mix_columns(&mut q);
+        }
+        inv_shift_rows(&mut q);
+        inv_sub_bytes(&mut q);
+        add_round_key(&mut q, &self.round_keys[0]);
+        store(&mut q, blocks);
+    }
+}
+
+fn load(blocks: &[Block]) -> State {
+    debug_assert!(blocks.len() <= BATCH_BLOCKS);
+    let mut q = [0u64; 8];
+    for (i, block) in blocks.iter().enumerate() {
+        let mut words = [0u32; 4];
+        for (word, bytes) in words.iter_mut().zip(block.chunks(4)) {
+            *word = le_word(bytes);
+        }
+        let (lo, hi) = interleave_in(&words);
+        q[i] = lo;
+        q[i + 4] = hi;
+    }
+    ortho(&mut q);
+    q
+}
+
+fn store(q: &mut State, blocks: &mut [Block]) {
+    debug_assert!(blocks.len() <= BATCH_BLOCKS);
+    ortho(q);
+    for (i, block) in blocks.iter_mut().enumerate() {
+        interleave_out(q[i], q[i + 4], block);
+    }
+}
+
+// Spreads the bytes of the four words of a block over two `u64`s, with the
+// bytes of the even-numbered words in the first.
+fn interleave_in(words: &[u32; 4]) -> (u64, u64) {
+    let mut x = [0u64; 4];
+    for (x, word) in x.iter_mut().zip(words.iter()) {
+        let mut w = u64::from(*word);
+        w |= w << 16;
+        w &= 0x0000ffff_0000ffff;
+        w |= w << 8;
+        w &= 0x00ff00ff_00ff00ff;
+        *x = w;
+    }
+    (x[0] | (x[2] << 8), x[1] | (x[3] << 8))
+}
+
+fn interleave_out(q0: u64, q1: u64, block: &mut Block) {
+    let mut x = [q0 & 0x00ff00ff_00ff00ff, q1 & 0x00ff00ff_00ff00ff,
+                 (q0 >> 8) & 0x00ff00ff_00ff00ff,
+                 (q1 >> 8) & 0x00ff00ff_00ff00ff];
+    for (x, word) in x.iter_mut().zip(block.chunks_mut(4)) {
+        *x |= *x >> 8;
+        *x &= 0x0000ffff_0000ffff;
+        let w = (*x as u32) | ((*x >> 16) as u32);
+        for (i, b) in word.iter_mut().enumerate() {
+            *b = (w >> (8 * i)) as u8;
+        }
+    }
+}
+
+// Transposes each 8x8 matrix of bits formed by taking bit `j` of each of
+// `q[0..8]`, for every `j`. This is its own inverse.
+fn ortho(q: &mut State) {
+    fn swap(q: &mut State, x: usize, y: usize, low: u64, shift: u32) {
+        let (a, b) = (q[x], q[y]);
+        q[x] = (a & low) | ((b & low) << shift);
+        q[y] = ((a & !low) >> shift) | (b & !low);
+    }
+
+    for &(x, y) in [(0, 1), (2, 3), (4, 5), (6, 7)].iter() {
+        swap(q, x, y, 0x55555555_55555555, 1);
+    }
+    for &(x, y) in [(0, 2), (1, 3), (4, 6), (5, 7)].iter() {
+        swap(q, x, y, 0x33333333_33333333, 2);
+    }
+    for &(x, y) in [(0, 4), (1, 5), (2, 6), (3, 7)].iter() {
+        swap(q, x, y, 0x0f0f0f0f_0f0f0f0f, 4);
+    }
+}
+
+fn add_round_key(q: &mut State, round_key: &State) {
+    for (q, k) in q.iter_mut().zip(round_key.iter()) {
+        *q ^= *k;
+    }
+}
+
+// The Boyar-Peralta circuit, with the bits numbered from the most significant
+// (`x0`) to the least significant (`x7`).
+fn sub_bytes(q: &mut State) {
+    let x0 = q[7];
+    let x1 = q[6];
+    let x2 = q[5];
+    let x3 = q[4];
+    let x4 = q[3];
+    let x5 = q[2];
+    let x6 = q[1];
+    let x7 = q[0];
+
+    // Top linear transformation.
+    let y14 = x3 ^ x5;
+    let y13 = x0 ^ x6;
+    let y9 = x0 ^ x3;
+    let y8 = x0 ^ x5;
+    let t0 = x1 ^ x2;
+    let y1 = t0 ^ x7;
+    let y4 = y1 ^ x3;
+    let y12 = y13 ^ y14;
+    let y2 = y1 ^ x0;
+    let y5 = y1 ^ x6;
+    let y3 = y5 ^ y8;
+    let t1 = x4 ^ y12;
+    let y15 = t1 ^ x5;
+    let y20 = t1 ^ x1;
+    let y6 = y15 ^ x7;
+    let y10 = y15 ^ t0;
+    let y11 = y20 ^ y9;
+    let y7 = x7 ^ y11;
+    let y17 = y10 ^ y11;
+    let y19 = y10 ^ y8;
+    let y16 = t0 ^ y11;
+    let y21 = y13 ^ y16;
+    let y18 = x0 ^ y16;
+
+    // Non-linear section.
+    let t2 = y12 & y15;
+    let t3 = y3 & y6;
+    let t4 = t3 ^ t2;
+    let t5 = y4 & x7;
+    let t6 = t5 ^ t2;
+    let t7 = y13 & y16;
+    let t8 = y5 & y1;
+    let t9 = t8 ^ t7;
+    let t10 = y2 & y7;
+    let t11 = t10 ^ t7;
+    let t12 = y9 & y11;
+    let t13 = y14 & y17;
+    let t14 = t13 ^ t12;
+    let t15 = y8 & y10;
+    let t16 = t15 ^ t12;
+    let t17 = t4 ^ t14;
+    let t18 = t6 ^ t16;
+    let t19 = t9 ^ t14;
+    let t20 = t11 ^ t16;
+    let t21 = t17 ^ y20;
+    let t22 = t18 ^ y19;
+    let t23 = t19 ^ y21;
+    let t24 = t20 ^ y18;
+
+    let t25 = t21 ^ t22;
+    let t26 = t21 & t23;
+    let t27 = t24 ^ t26;
+    let t28 = t25 & t27;
+    let t29 = t28 ^ t22;
+    let t30 = t23 ^ t24;
+    let t31 = t22 ^ t26;
+    let t32 = t31 & t30;
+    let t33 = t32 ^ t24;
+    let t34 = t23 ^ t33;
+    let t35 = t27 ^ t33;
+    let t36 = t24 & t35;
+    let t37 = t36 ^ t34;
+    let t38 = t27 ^ t36;
+    let t39 = t29 & t38;
+    let t40 = t25 ^ t39;
+
+    let t41 = t40 ^ t37;
+    let t42 = t29 ^ t33;
+    let t43 = t29 ^ t40;
+    let t44 = t33 ^ t37;
+    let t45 = t42 ^ t41;
+    let z0 = t44 & y15;
+    let z1 = t37 & y6;
+    let z2 = t33 & x7;
+    let z3 = t43 & y16;
+    let z4 = t40 & y1;
+    let z5 = t29 & y7;
+    let z6 = t42 & y11;
+    let z7 = t45 & y17;
+    let z8 = t41 & y10;
+    let z9 = t44 & y12;
+    let z10 = t37 & y3;
+    let z11 = t33 & y4;
+    let z12 = t43 & y13;
+    let z13 = t40 & y5;
+    let z14 = t29 & y2;
+    let z15 = t42 & y9;
+    let z16 = t45 & y14;
+    let z17 = t41 & y8;
+
+    // Bottom linear transformation.
+    let t46 = z15 ^ z16;
+    let t47 = z10 ^ z11;
+    let t48 = z5 ^ z13;
+    let t49 = z9 ^ z10;
+    let t50 = z2 ^ z12;
+    let t51 = z2 ^ z5;
+    let t52 = z7 ^ z8;
+    let t53 = z0 ^ z3;
+    let t54 = z6 ^ z7;
+    let t55 = z16 ^ z17;
+    let t56 = z12 ^ t48;
+    let t57 = t50 ^ t53;
+    let t58 = z4 ^ t46;
+    let t59 = z3 ^ t54;
+    let t60 = t46 ^ t57;
+    let t61 = z14 ^ t57;
+    let t62 = t52 ^ t58;
+    let t63 = t49 ^ t58;
+    let t64 = z4 ^ t59;
+    let t65 = t61 ^ t62;
+    let t66 = z1 ^ t63;
+    let s0 = t59 ^ t63;
+    let s6 = t56 ^ !t62;
+    let s7 = t48 ^ !t60;
+    let t67 = t64 ^ t65;
+    let s3 = t53 ^ t66;
+    let s4 = t51 ^ t66;
+    let s5 = t47 ^ t65;
+    let s1 = t64 ^ !s3;
+    let s2 = t55 ^ !t67;
+
+    q[7] = s0;
+    q[6] = s1;
+    q[5] = s2;
+    q[4] = s3;
+    q[3] = s4;
+    q[2] = s5;
+    q[1] = s6;
+    q[0] = s7;
+}
+
+// The inverse S-box is the S-box with the inverse of its affine
+// transformation applied before and after it.
+fn inv_sub_bytes(q: &mut State) {
+    inv_sub_bytes_affine(q);
+    sub_bytes(q);
+    inv_sub_bytes_affine(q);
+}
+
+fn inv_sub_bytes_affine(q: &mut State) {
+    let q0 = !q[0];
+    let q1 = !q[1];
+    let q2 = q[2];
+    let q3 = q[3];
+    let q4 = q[4];
+    let q5 = !q[5];
+    let q6 = !q[6];
+    let q7 = q[7];
+    q[7] = q1 ^ q4 ^ q6;
+    q[6] = q0 ^ q3 ^ q5;
+    q[5] = q7 ^ q2 ^ q4;
+    q[4] = q6 ^ q1 ^ q3;
+    q[3] = q5 ^ q0 ^ q2;
+    q[2] = q4 ^ q7 ^ q1;
+    q[1] = q3 ^ q6 ^ q0;
+    q[0] = q2 ^ q5 ^ q7;
+}
+
+// Each row of the state is 16 bits of each `u64`, with the columns in groups
+// of four bits.
+fn shift_rows(q: &mut State) {
+    for x in q.iter_mut() {
+        let y = *x;
+        *x = (y & 0x00000000_0000ffff) |
+             ((y & 0x00000000_fff00000) >> 4) |
+             ((y & 0x00000000_000f0000) << 12) |
+             ((y & 0x0000ff00_00000000) >> 8) |
+             ((y & 0x000000ff_00000000) << 8) |
+             ((y & 0xf0000000_00000000) >> 12) |
+             ((y & 0x0fff0000_00000000) << 4);
+    }
+}
+
+fn inv_shift_rows(q: &mut State) {
+    for x in q.iter_mut() {
+        let y = *x;
+        *x = (y & 0x00000000_0000ffff) |
+             ((y & 0x00000000_0fff0000) << 4) |
+             ((y & 0x00000000_f0000000) >> 12) |
+             ((y & 0x000000ff_00000000) << 8) |
+             ((y & 0x0000ff00_00000000) >> 8) |
+             ((y & 0x000f0000_00000000) << 12) |
+             ((y & 0xfff00000_00000000) >> 4);
+    }
+}
+
+#[inline(always)]
+fn rotr32(x: u64) -> u64 { x.rotate_right(32) }
+
+fn mix_columns(q: &mut State) {
+    let (q0, q1, q2, q3) = (q[0], q[1], q[2], q[3]);
+    let (q4, q5, q6, q7) = (q[4], q[5], q[6], q[7]);
+    let r0 = q0.rotate_right(16);
+    let r1 = q1.rotate_right(16);
+    let r2 = q2.rotate_right(16);
+    let r3 = q3.rotate_right(16);
+    let r4 = q4.rotate_right(16);
+    let r5 = q5.rotate_right(16);
+    let r6 = q6.rotate_right(16);
+    let r7 = q7.rotate_right(16);
+
+    q[0] = q7 ^ r7 ^ r0 ^ rotr32(q0 ^ r0);
+    q[1] = q0 ^ r0 ^ q7 ^ r7 ^ r1 ^ rotr32(q1 ^ r1);
+    q[2] = q1 ^ r1 ^ r2 ^ rotr32(q2 ^ r2);
+    q[3] = q2 ^ r2 ^ q7 ^ r7 ^ r3 ^ rotr32(q3 ^ r3);
+    q[4] = q3 ^ r3 ^ q7 ^ r7 ^ r4 ^ rotr32(q4 ^ r4);
+    q[5] = q4 ^ r4 ^ r5 ^ rotr32(q5 ^ r5);
+    q[6] = q5 ^ r5 ^ r6 ^ rotr32(q6 ^ r6);
+    q[7] = q6 ^ r6 ^ r7 ^ rotr32(q7 ^ r7);
+}
+
+fn inv_mix_columns(q: &mut State) {
+    let (q0, q1, q2, q3) = (q[0], q[1], q[2], q[3]);
+    let (q4, q5, q6, q7) = (q[4], q[5], q[6], q[7]);
+    let r0 = q0.rotate_right(16);
+    let r1 = q1.rotate_right(16);
+    let r2 = q2.rotate_right(16);
+    let r3 = q3.rotate_right(16);
+    let r4 = q4.rotate_right(16);
+    let r5 = q5.rotate_right(16);
+    let r6 = q6.rotate_right(16);
+    let r7 = q7.rotate_right(16);
+
+    q[0] = q5 ^ q6 ^ q7 ^ r0 ^ r5 ^ r7 ^ rotr32(q0 ^ q5 ^ q6 ^ r0 ^ r5);
+    q[1] = q0 ^ q5 ^ r0 ^ r1 ^ r5 ^ r6 ^ r7 ^
+           rotr32(q1 ^ q5 ^ q7 ^ r1 ^ r5 ^ r6);
+    q[2] = q0 ^ q1 ^ q6 ^ r1 ^ r2 ^ r6 ^ r7 ^
+           rotr32(q0 ^ q2 ^ q6 ^ r2 ^ r6 ^ r7);
+    q[3] = q0 ^ q1 ^ q2 ^ q5 ^ q6 ^ r0 ^ r2 ^ r3 ^ r5 ^
+           rotr32(q0 ^ q1 ^ q3 ^ q5 ^ q6 ^ q7 ^ r0 ^ r3 ^ r5 ^ r7);
+    q[4] = q1 ^ q2 ^ q3 ^ q5 ^ r1 ^ r3 ^ r4 ^ r5 ^ r6 ^ r7 ^
+           rotr32(q1 ^ q2 ^ q4 ^ q5 ^ q7 ^ r1 ^ r4 ^ r5 ^ r6);
+    q[5] = q2 ^ q3 ^ q4 ^ q6 ^ r2 ^ r4 ^ r5 ^ r6 ^ r7 ^
+           rotr32(q2 ^ q3 ^ q5 ^ q6 ^ r2 ^ r5 ^ r6 ^ r7);
+    q[6] = q3 ^ q4 ^ q5 ^ q7 ^ r3 ^ r5 ^ r6 ^ r7 ^
+           rotr32(q3 ^ q4 ^ q6 ^ q7 ^ r3 ^ r6 ^ r7);
+    q[7] = q4 ^ q5 ^ q6 ^ r4 ^ r6 ^ r7 ^ rotr32(q4 ^ q5 ^ q7 ^ r4 ^ r7);
+}
+
+#[cfg(test)]
+mod tests {
+    use test;
+    use super::*;
+
+    #[test]
+    fn test_decrypt_block() {
+        test::from_file("src/aead/aes_tests.txt", |section, test_case| {
+            assert_eq!(section, "");
+            let key = test_case.consume_bytes("Key");
+            let input = test_case.consume_bytes("Input");
+            let output = test_case.consume_bytes("Output");
+
+            let decrypt_key = DecryptKey::new(&key).unwrap();
+            let mut block = [0u8; BLOCK_LEN];
+            block.copy_from_slice(&output);
+            decrypt_key.decrypt_block(&mut block);
+            assert_eq!(&block[..], &input[..]);
+
+            let mut ctx = [0u64; KEY_BUF_ELEMS];
+            init(polyfill::slice::u64_as_u8_mut(&mut ctx), &key).unwrap();
+            encrypt_block(&ctx, &mut block);
+            assert_eq!(&block[..], &output[..]);
+
+            // Decryption inverts encryption for other blocks.
+            for i in 0..256 {
+                let original = [i as u8; BLOCK_LEN];
+                let mut block = original;
+                encrypt_block(&ctx, &mut block);
+                decrypt_key.decrypt_block(&mut block);
+                assert_eq!(block, original);
+            }
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_ctr32_encrypt_blocks() {
+        let key = [0x2bu8; AES_256_KEY_LEN];
+        let mut ctx = [0u64; KEY_BUF_ELEMS];
+        init(polyfill::slice::u64_as_u8_mut(&mut ctx), &key).unwrap();
+        let mut aes_key = AES_KEY { rd_key: [0; 4 * (MAX_ROUNDS + 1)],
+                                    rounds: 0 };
+        assert_eq!(unsafe {
+            GFp_aes_nohw_set_encrypt_key(key.as_ptr(), 256, &mut aes_key)
+        }, 0);
+
+        let mut counter = [0u8; BLOCK_LEN];
+        counter[..12].copy_from_slice(&[7u8; 12]);
+        counter[12..].copy_from_slice(&[0xff, 0xff, 0xff, 0xf0]);
+
+        // Lengths that are and aren't multiples of four blocks.
+        for blocks in 0..10 {
+            let mut expected = [0u8; 10 * BLOCK_LEN];
+            for (i, b) in expected.iter_mut().enumerate() {
+                *b = i as u8;
+            }
+            let expected = &mut expected[..(blocks * BLOCK_LEN)];
+            let mut actual = [0u8; 10 * BLOCK_LEN];
+            actual[..expected.len()].copy_from_slice(expected);
+            let mut nohw_actual = actual;
+
+            for (i, chunk) in expected.chunks_mut(BLOCK_LEN).enumerate() {
+                let mut keystream = counter;
+                keystream[BLOCK_LEN - 1] += i as u8;
+                encrypt_block(&ctx, &mut keystream);
+                for (b, k) in chunk.iter_mut().zip(keystream.iter()) {
+                    *b ^= *k;
+                }
+            }
+
+            ctr32_encrypt_blocks(&ctx, &mut actual[..expected.len()],
+                                 &counter);
+            assert_eq!(&actual[..expected.len()], &expected[..]);
+
+            let nohw_actual = &mut nohw_actual[..expected.len()];
+            let ptr = nohw_actual.as_mut_ptr();
+            unsafe {
+                GFp_aes_nohw_ctr32_encrypt_blocks(ptr, ptr, blocks, &aes_key,
+                                                  counter.as_ptr());
+            }
+            assert_eq!(&nohw_actual[..], &expected[..]);
+        }
+    }
+
+    // bsaes uses the key schedule of `GFp_aes_nohw_set_encrypt_key`.
+    #[cfg(not(feature = "pure_rust"))]
+    #[test]
+    fn test_nohw_key_schedule() {
+        extern {
+            fn GFp_AES_set_encrypt_key(key: *const u8, bits: c::uint,
+                                       aes_key: *mut AES_KEY) -> c::int;
+        }
+
+        for &key_len in [AES_128_KEY_LEN, AES_256_KEY_LEN].iter() {
+            let mut key = [0u8; AES_256_KEY_LEN];
+            for (i, b) in key.iter_mut().enumerate() {
+                *b = (i * 29) as u8;
+            }
+            let key = &key[..key_len];
+            let bits = (key_len * 8) as c::uint;
+            let mut expected = AES_KEY { rd_key: [0; 4 * (MAX_ROUNDS + 1)],
+                                         rounds: 0 };
+            let mut actual = AES_KEY { rd_key: [0; 4 * (MAX_ROUNDS + 1)],
+                                       rounds: 0 };
+            unsafe {
+                assert_eq!(GFp_AES_set_encrypt_key(key.as_ptr(), bits,
+                                                   &mut expected), 0);
+                assert_eq!(GFp_aes_nohw_set_encrypt_key(key.as_ptr(), bits,
+                                                        &mut actual), 0);
+            }
+            assert_eq!(&actual.rd_key[..], &expected.rd_key[..]);
+            assert_eq!(actual.rounds, expected.rounds);
+        }
+
+        let mut aes_key = AES_KEY { rd_key: [0; 4 * (MAX_ROUNDS + 1)],
+                                    rounds: 0 };
+        let key = [0u8; 24];
+        assert_eq!(unsafe {
+            GFp_aes_nohw_set_encrypt_key(key.as_ptr(), 192, &mut aes_key)
+        }, -2);
+    }
+
+    #[test]
+    fn test_decrypt_key_lengths() {
+        assert!(DecryptKey::new(&[0u8; 16]).is_ok());
+        assert!(DecryptKey::new(&[0u8; 32]).is_ok());
+        assert!(DecryptKey::new(&[0u8; 24]).is_err());
+        assert!(DecryptKey::new(&[0u8; 15]).is_err());
+        assert!(DecryptKey::new(&[]).is_err());
+    }
+}
@@ -0,0 +1,311 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! AES-CCM, as specified in [RFC 3610] and [NIST SP 800-38C].
+//!
+//! Most of the variants here use 96-bit nonces, so the length field `L` is
+//! three bytes long, as in [RFC 6655] (TLS) and TLS 1.3. The `_L2` variants
+//! use 104-bit nonces, so `L` is two bytes long, as in Bluetooth Low Energy
+//! and IEEE 802.15.4. Bluetooth Low Energy uses `AES_128_CCM_4_L2`.
+//!
+//! [RFC 3610]: https://tools.ietf.org/html/rfc3610
+//! [NIST SP 800-38C]: http://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38c.pdf
+//! [RFC 6655]: https://tools.ietf.org/html/rfc6655
+
+use {aead, core, error, polyfill};
+use super::aes::{self, AES_128_KEY_LEN, AES_256_KEY_LEN};
+
+/// AES-128 in CCM mode with 128-bit tags and 96 bit nonces.
+///
+/// C analog: `EVP_aead_aes_128_ccm`
+pub static AES_128_CCM: aead::Algorithm = aead::Algorithm {
+    key_len: AES_128_KEY_LEN,
+    nonce_len: NONCE_LEN,
+    tag_len: 16,
+    init: aes::init,
+    seal: aes_ccm_seal,
+    open: aes_ccm_open,
+    id: aead::AlgorithmID::AES_128_CCM,
+    max_input_len: AES_CCM_MAX_INPUT_LEN,
+};
+
+/// AES-256 in CCM mode with 128-bit tags and 96 bit nonces.
+///
+/// C analog: `EVP_aead_aes_256_ccm`
+pub static AES_256_CCM: aead::Algorithm = aead::Algorithm {
+    key_len: AES_256_KEY_LEN,
+    nonce_len: NONCE_LEN,
+    tag_len: 16,
+    init: aes::init,
+    seal: aes_ccm_seal,
+    open: aes_ccm_open,
+    id: aead::AlgorithmID::AES_256_CCM,
+    max_input_len: AES_CCM_MAX_INPUT_LEN,
+};
+
+/// AES-128 in CCM mode with 64-bit tags and 96 bit nonces (“CCM_8”).
+///
+/// Short tags make forgeries much more likely to succeed; use this only when
+/// a protocol requires it.
+pub static AES_128_CCM_8: aead::Algorithm = aead::Algorithm {
+    key_len: AES_128_KEY_LEN,
+    nonce_len: NONCE_LEN,
+    tag_len: 8,
+    init: aes::init,
+    seal: aes_ccm_seal,
+    open: aes_ccm_open,
+    id: aead::AlgorithmID::AES_128_CCM_8,
+    max_input_len: AES_CCM_MAX_INPUT_LEN,
+};
+
+/// AES-256 in CCM mode with 64-bit tags and 96 bit nonces (“CCM_8”).
+///
+/// Short tags make forgeries much more likely to succeed; use this only when
+/// a protocol requires it.
+pub static AES_256_CCM_8: aead::Algorithm = aead::Algorithm {
+    key_len: AES_256_KEY_LEN,
+    nonce_len: NONCE_LEN,
+    tag_len: 8,
+    init: aes::init,
+    seal: aes_ccm_seal,
+    open: aes_ccm_open,
+    id: aead::AlgorithmID::AES_256_CCM_8,
+    max_input_len: AES_CCM_MAX_INPUT_LEN,
+};
+
+/// AES-128 in CCM mode with 128-bit tags and 104 bit nonces.
+pub static AES_128_CCM_L2: aead::Algorithm = aead::Algorithm {
+    key_len: AES_128_KEY_LEN,
+    nonce_len: NONCE_LEN_L2,
+    tag_len: 16,
+    init: aes::init,
+    seal: aes_ccm_seal,
+    open: aes_ccm_open,
+    id: aead::AlgorithmID::AES_128_CCM_L2,
+    max_input_len: AES_CCM_L2_MAX_INPUT_LEN,
+};
+
+/// AES-128 in CCM mode with 64-bit tags and 104 bit nonces, as in IEEE
+/// 802.15.4 with a 64-bit MIC and the RFC 3610 packet vectors.
+///
+/// Short tags make forgeries much more likely to succeed; use this only when
+/// a protocol requires it.
+pub static AES_128_CCM_8_L2: aead::Algorithm = aead::Algorithm {
+    key_len: AES_128_KEY_LEN,
+    nonce_len: NONCE_LEN_L2,
+    tag_len: 8,
+    init: aes::init,
+    seal: aes_ccm_seal,
+    open: aes_ccm_open,
+    id: aead::AlgorithmID::AES_128_CCM_8_L2,
+    max_input_len: AES_CCM_L2_MAX_INPUT_LEN,
+};
+
+/// AES-128 in CCM mode with 32-bit tags and 104 bit nonces, as in Bluetooth
+/// Low Energy link-layer encryption and IEEE 802.15.4 with a 32-bit MIC.
+///
+/// A 32-bit tag can be forged with a probability of 2**-32 per attempt; use
+/// this only when a protocol requires it.
+pub static AES_128_CCM_4_L2: aead::Algorithm = aead::Algorithm {
+    key_len: AES_128_KEY_LEN,
+    nonce_len: NONCE_LEN_L2,
+    tag_len: 4,
+    init: aes::init,
+    seal: aes_ccm_seal,
+    open: aes_ccm_open,
+    id: aead::AlgorithmID::AES_128_CCM_4_L2,
+    max_input_len: AES_CCM_L2_MAX_INPUT_LEN,
+};
+
+// The tag length is `tag_out.len()`, and the length of the length field `L`
+// is determined by the nonce length.
+fn aes_ccm_seal(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS], nonce: &[u8], ad: &[u8],
+                in_out: &mut [u8], tag_out: &mut [u8])
+                -> Result<(), error::Unspecified> {
+    check_lengths(nonce, in_out.len(), tag_out.len())?;
+    cbc_mac(ctx, nonce, ad, in_out, tag_out);
+    let s_0 = ctr_xor(ctx, nonce, in_out, 0);
+    xor_tag(tag_out, &s_0);
+    Ok(())
+}
+
+fn aes_ccm_open(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS], nonce: &[u8], ad: &[u8],
+                in_prefix_len: usize, in_out: &mut [u8], tag_out: &mut [u8])
+                -> Result<(), error::Unspecified> {
+    let plaintext_len = in_out.len() - in_prefix_len;
+    check_lengths(nonce, plaintext_len, tag_out.len())?;
+    // The tag is calculated over the plaintext, so decrypt first.
+    let s_0 = ctr_xor(ctx, nonce, in_out, in_prefix_len);
+    cbc_mac(ctx, nonce, ad, &in_out[..plaintext_len], tag_out);
+    xor_tag(tag_out, &s_0);
+    Ok(())
+}
+
+fn check_lengths(nonce: &[u8], plaintext_len: usize, tag_len: usize)
+                 -> Result<(), error::Unspecified> {
+    if nonce.len() < 7 || nonce.len() > 13 {
+        return Err(error::Unspecified);
+    }
+    if tag_len < 4 || tag_len > 16 || tag_len % 2 != 0 {
+        return Err(error::Unspecified);
+    }
+    let l = 15 - nonce.len();
+    if l < 8 && (polyfill::u64_from_usize(plaintext_len) >> (8 * l)) != 0 {
+        return Err(error::Unspecified);
+    }
+    Ok(())
+}
+
+fn xor_tag(tag: &mut [u8], s_0: &aes::Block) {
+    for (tag, s) in tag.iter_mut().zip(s_0.iter()) {
+        *tag ^= *s;
+    }
+}
+
+// Calculates the unencrypted tag `T` into `tag_out`.
+fn cbc_mac(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS], nonce: &[u8], ad: &[u8],
+           plaintext: &[u8], tag_out: &mut [u8]) {
+    let l = 15 - nonce.len();
+    let tag_len = tag_out.len();
+
+    let mut b_0 = [0u8; aes::BLOCK_LEN];
+    let adata = if ad.is_empty() { 0 } else { 0x40 };
+    b_0[0] = adata | ((((tag_len - 2) / 2) as u8) << 3) | ((l - 1) as u8);
+    b_0[1..][..nonce.len()].copy_from_slice(nonce);
+    let plaintext_len =
+        polyfill::slice::be_u8_from_u64(polyfill::u64_from_usize(
+            plaintext.len()));
+    b_0[(1 + nonce.len())..].copy_from_slice(&plaintext_len[(8 - l)..]);
+
+    let mut mac = CbcMac::new(ctx, &b_0);
+    if !ad.is_empty() {
+        let ad_len = polyfill::u64_from_usize(ad.len());
+        if ad_len < 0xff00 {
+            mac.update(&polyfill::slice::be_u8_from_u64(ad_len)[6..]);
+        } else if ad_len <= 0xffff_ffff {
+            mac.update(&[0xff, 0xfe]);
+            mac.update(&polyfill::slice::be_u8_from_u64(ad_len)[4..]);
+        } else {
+            mac.update(&[0xff, 0xff]);
+            mac.update(&polyfill::slice::be_u8_from_u64(ad_len));
+        }
+        mac.update(ad);
+        mac.pad();
+    }
+    mac.update(plaintext);
+    mac.pad();
+
+    tag_out.copy_from_slice(&mac.x[..tag_len]);
+}
+
+struct CbcMac<'a> {
+    ctx: &'a [u64; aead::KEY_CTX_BUF_ELEMS],
+    x: aes::Block,
+    x_used: usize,
+}
+
+impl<'a> CbcMac<'a> {
+    fn new(ctx: &'a [u64; aead::KEY_CTX_BUF_ELEMS], b_0: &aes::Block) -> Self {
+        let mut x = *b_0;
+        aes::encrypt_block(ctx, &mut x);
+        CbcMac { ctx, x, x_used: 0 }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let n = core::cmp::min(aes::BLOCK_LEN - self.x_used, data.len());
+            let x = &mut self.x[self.x_used..][..n];
+            for (x, d) in x.iter_mut().zip(&data[..n]) {
+                *x ^= *d;
+            }
+            self.x_used += n;
+            data = &data[n..];
+            if self.x_used == aes::BLOCK_LEN {
+                aes::encrypt_block(self.ctx, &mut self.x);
+                self.x_used = 0;
+            }
+        }
+    }
+
+    // Pads the input processed so far with zeros to a block boundary.
+    fn pad(&mut self) {
+        if self.x_used != 0 {
+            aes::encrypt_block(self.ctx, &mut self.x);
+            self.x_used = 0;
+        }
+    }
+}
+
+// Encrypts or decrypts `in_out[in_prefix_len..]` into
+// `in_out[..(in_out.len() - in_prefix_len)]` using the counter blocks
+// `A_1, A_2, ...`. Returns the encrypted `A_0`, which encrypts the tag.
+fn ctr_xor(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS], nonce: &[u8],
+           in_out: &mut [u8], in_prefix_len: usize) -> aes::Block {
+    let l = 15 - nonce.len();
+    let mut counter = [0u8; aes::BLOCK_LEN];
+    counter[0] = (l - 1) as u8;
+    counter[1..][..nonce.len()].copy_from_slice(nonce);
+
+    let mut s_0 = counter;
+    aes::encrypt_block(ctx, &mut s_0);
+
+    let len = in_out.len() - in_prefix_len;
+    let mut i = 0;
+    let mut block_counter = 1u64;
+    while i < len {
+        let n = core::cmp::min(aes::BLOCK_LEN, len - i);
+        let block_counter_bytes =
+            polyfill::slice::be_u8_from_u64(block_counter);
+        counter[(aes::BLOCK_LEN - l)..]
+            .copy_from_slice(&block_counter_bytes[(8 - l)..]);
+        let mut s = counter;
+        aes::encrypt_block(ctx, &mut s);
+        // Output never overlaps input that hasn't been read yet, because the
+        // output is never ahead of the input.
+        for j in 0..n {
+            in_out[i + j] = in_out[in_prefix_len + i + j] ^ s[j];
+        }
+        i += n;
+        block_counter += 1;
+    }
+
+    s_0
+}
+
+const NONCE_LEN: usize = 96 / 8;
+
+// With 96-bit nonces, the length field is three bytes long, so no more than
+// `2**24 - 1` bytes can be sealed with a single nonce.
+const AES_CCM_MAX_INPUT_LEN: u64 = (1 << (8 * (15 - NONCE_LEN))) - 1;
+
+const NONCE_LEN_L2: usize = 104 / 8;
+
+// With 104-bit nonces, the length field is two bytes long, so no more than
+// `2**16 - 1` bytes can be sealed with a single nonce.
+const AES_CCM_L2_MAX_INPUT_LEN: u64 = (1 << (8 * (15 - NONCE_LEN_L2))) - 1;
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn max_input_len_test() {
+        // RFC 6655 Section 3: "the maximum size of the plaintext is 2^24 - 1
+        // octets".
+        assert_eq!(super::AES_128_CCM.max_input_len, 16_777_215u64);
+        assert_eq!(super::AES_256_CCM_8.max_input_len, 16_777_215u64);
+
+        // RFC 3610 Section 2: "0 <= l(m) < 2^(8L)".
+        assert_eq!(super::AES_128_CCM_L2.max_input_len, 65_535u64);
+        assert_eq!(super::AES_128_CCM_4_L2.max_input_len, 65_535u64);
+    }
+}
@@ -13,6 +13,13 @@
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
 use {aead, bssl, c, error, polyfill};
+use super::aes::{AES_128_KEY_LEN, AES_256_KEY_LEN};
+
+#[cfg(feature = "pure_rust")]
+use core;
+
+#[cfg(feature = "pure_rust")]
+use super::{aes, gcm};
 
 /// AES-128 in GCM mode with 128-bit tags and 96 bit nonces.
 ///
@@ -21,6 +28,8 @@ use {aead, bssl, c, error, polyfill};
 /// Go analog: [`crypto.aes`](https://golang.org/pkg/crypto/aes/)
 pub static AES_128_GCM: aead::Algorithm = aead::Algorithm {
     key_len: AES_128_KEY_LEN,
+    nonce_len: NONCE_LEN,
+    tag_len: TAG_LEN,
     init: aes_gcm_init,
     seal: aes_gcm_seal,
     open: aes_gcm_open,
@@ -35,6 +44,8 @@ pub static AES_128_GCM: aead::Algorithm = aead::Algorithm {
 /// Go analog: [`crypto.aes`](https://golang.org/pkg/crypto/aes/)
 pub static AES_256_GCM: aead::Algorithm = aead::Algorithm {
     key_len: AES_256_KEY_LEN,
+    nonce_len: NONCE_LEN,
+    tag_len: TAG_LEN,
     init: aes_gcm_init,
     seal: aes_gcm_seal,
     open: aes_gcm_open,
@@ -50,46 +61,48 @@ fn aes_gcm_init(ctx_buf: &mut [u8], key: &[u8])
     })
 }
 
-fn aes_gcm_seal(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS],
-                nonce: &[u8; aead::NONCE_LEN], ad: &[u8], in_out: &mut [u8],
-                tag: &mut [u8; aead::TAG_LEN])
+// `tag_out` may be shorter than `TAG_LEN`, in which case the tag is truncated.
+fn aes_gcm_seal(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS], nonce: &[u8], ad: &[u8],
+                in_out: &mut [u8], tag_out: &mut [u8])
                 -> Result<(), error::Unspecified> {
     let ctx = polyfill::slice::u64_as_u8(ctx);
+    let nonce = slice_as_array_ref!(nonce, NONCE_LEN)?;
+    let mut tag = [0u8; TAG_LEN];
     bssl::map_result(unsafe {
-        GFp_aes_gcm_seal(ctx.as_ptr(), in_out.as_mut_ptr(), in_out.len(), tag,
-                         nonce, ad.as_ptr(), ad.len())
-    })
+        GFp_aes_gcm_seal(ctx.as_ptr(), in_out.as_mut_ptr(), in_out.len(),
+                         &mut tag, nonce, ad.as_ptr(), ad.len())
+    })?;
+    tag_out.copy_from_slice(&tag[..tag_out.len()]);
+    Ok(())
 }
 
-fn aes_gcm_open(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS],
-                nonce: &[u8; aead::NONCE_LEN], ad: &[u8], in_prefix_len: usize,
-                in_out: &mut [u8], tag_out: &mut [u8; aead::TAG_LEN])
+fn aes_gcm_open(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS], nonce: &[u8], ad: &[u8],
+                in_prefix_len: usize, in_out: &mut [u8], tag_out: &mut [u8])
                 -> Result<(), error::Unspecified> {
     let ctx = polyfill::slice::u64_as_u8(ctx);
+    let nonce = slice_as_array_ref!(nonce, NONCE_LEN)?;
+    let mut tag = [0u8; TAG_LEN];
     bssl::map_result(unsafe {
         GFp_aes_gcm_open(ctx.as_ptr(), in_out.as_mut_ptr(),
-                         in_out.len() - in_prefix_len, tag_out, nonce,
+                         in_out.len() - in_prefix_len, &mut tag, nonce,
                          in_out[in_prefix_len..].as_ptr(), ad.as_ptr(),
                          ad.len())
-    })
+    })?;
+    tag_out.copy_from_slice(&tag[..tag_out.len()]);
+    Ok(())
 }
 
 
-const AES_128_KEY_LEN: usize = 128 / 8;
-const AES_256_KEY_LEN: usize = 32; // 256 / 8
-
-pub const AES_KEY_CTX_BUF_LEN: usize = AES_KEY_BUF_LEN + GCM128_SERIALIZED_LEN;
+pub const AES_KEY_CTX_BUF_LEN: usize =
+    super::aes::KEY_BUF_LEN + GCM128_SERIALIZED_LEN;
 
-// Keep this in sync with `AES_KEY` in aes.h.
-const AES_KEY_BUF_LEN: usize = (4 * 4 * (AES_MAX_ROUNDS + 1)) + 8;
+const NONCE_LEN: usize = 96 / 8;
+const TAG_LEN: usize = 128 / 8;
 
 const AES_BLOCK_LEN: u64 = 16;
 const AES_GCM_OVERHEAD_BLOCKS_PER_NONCE: u64 = 2;
 const AES_GCM_MAX_INPUT_LEN: u64 = max_input_len!(AES_BLOCK_LEN, AES_GCM_OVERHEAD_BLOCKS_PER_NONCE);
 
-// Keep this in sync with `AES_MAXNR` in aes.h.
-const AES_MAX_ROUNDS: usize = 14;
-
 // Keep this in sync with `GCM128_SERIALIZED_LEN` in gcm.h.
 // TODO: test.
 // TODO: some implementations of GCM don't require the buffer to be this big.
@@ -97,29 +110,182 @@ const AES_MAX_ROUNDS: usize = 14;
 const GCM128_SERIALIZED_LEN: usize = 16 * 16;
 
 
+#[cfg(not(feature = "pure_rust"))]
 extern {
     fn GFp_aes_gcm_init(ctx_buf: *mut u8, ctx_buf_len: c::size_t,
                         key: *const u8, key_len: c::size_t) -> c::int;
 
     fn GFp_aes_gcm_seal(ctx_buf: *const u8, in_out: *mut u8,
                         in_out_len: c::size_t,
-                        tag_out: &mut [u8; aead::TAG_LEN],
-                        nonce: &[u8; aead::NONCE_LEN], ad: *const u8,
+                        tag_out: &mut [u8; TAG_LEN],
+                        nonce: &[u8; NONCE_LEN], ad: *const u8,
                         ad_len: c::size_t) -> c::int;
 
     fn GFp_aes_gcm_open(ctx_buf: *const u8, out: *mut u8,
                         in_out_len: c::size_t,
-                        tag_out: &mut [u8; aead::TAG_LEN],
-                        nonce: &[u8; aead::NONCE_LEN], in_: *const u8,
+                        tag_out: &mut [u8; TAG_LEN],
+                        nonce: &[u8; NONCE_LEN], in_: *const u8,
                         ad: *const u8, ad_len: c::size_t) -> c::int;
 }
 
+// Portable implementations of the C functions, for builds with the
+// `pure_rust` feature. The GHASH key is stored in `ctx_buf` right after the
+// AES key schedule.
+
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe fn GFp_aes_gcm_init(ctx_buf: *mut u8, ctx_buf_len: c::size_t,
+                           key: *const u8, key_len: c::size_t) -> c::int {
+    if ctx_buf_len < AES_KEY_CTX_BUF_LEN {
+        return 0;
+    }
+    let key = core::slice::from_raw_parts(key, key_len);
+    let aes_key = core::slice::from_raw_parts_mut(ctx_buf, aes::KEY_BUF_LEN);
+    if aes::init(aes_key, key).is_err() {
+        return 0;
+    }
+    let mut h = [0u8; aes::BLOCK_LEN];
+    aes::encrypt_block(portable_aes_key(ctx_buf), &mut h);
+    core::ptr::copy_nonoverlapping(
+        h.as_ptr(), ctx_buf.offset(aes::KEY_BUF_LEN as isize), aes::BLOCK_LEN);
+    1
+}
+
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe fn GFp_aes_gcm_seal(ctx_buf: *const u8, in_out: *mut u8,
+                           in_out_len: c::size_t,
+                           tag_out: &mut [u8; TAG_LEN],
+                           nonce: &[u8; NONCE_LEN], ad: *const u8,
+                           ad_len: c::size_t) -> c::int {
+    let in_out = core::slice::from_raw_parts_mut(in_out, in_out_len);
+    let ad = core::slice::from_raw_parts(ad, ad_len);
+    let (aes_key, mut ghash) = portable_init(ctx_buf, ad);
+
+    let mut counter = Counter::new(nonce);
+    for chunk in in_out.chunks_mut(CHUNK_LEN) {
+        counter.xor_keystream(aes_key, chunk);
+        ghash.update(chunk);
+    }
+
+    portable_finish(aes_key, ghash, nonce, ad_len, in_out_len, tag_out);
+    1
+}
+
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe fn GFp_aes_gcm_open(ctx_buf: *const u8, out: *mut u8,
+                           in_out_len: c::size_t,
+                           tag_out: &mut [u8; TAG_LEN],
+                           nonce: &[u8; NONCE_LEN], in_: *const u8,
+                           ad: *const u8, ad_len: c::size_t) -> c::int {
+    let ad = core::slice::from_raw_parts(ad, ad_len);
+    let (aes_key, mut ghash) = portable_init(ctx_buf, ad);
+
+    // `out` may overlap `in_` as long as `out <= in_`, so each chunk of the
+    // ciphertext is copied before the plaintext is written.
+    let mut counter = Counter::new(nonce);
+    let mut offset = 0;
+    while offset < in_out_len {
+        let len = core::cmp::min(CHUNK_LEN, in_out_len - offset);
+        let mut chunk = [0u8; CHUNK_LEN];
+        core::ptr::copy(in_.offset(offset as isize), chunk.as_mut_ptr(), len);
+        ghash.update(&chunk[..len]);
+        counter.xor_keystream(aes_key, &mut chunk[..len]);
+        core::ptr::copy(chunk.as_ptr(), out.offset(offset as isize), len);
+        offset += len;
+    }
+
+    portable_finish(aes_key, ghash, nonce, ad_len, in_out_len, tag_out);
+    1
+}
+
+#[cfg(feature = "pure_rust")]
+unsafe fn portable_aes_key<'a>(ctx_buf: *const u8) -> &'a [u64] {
+    core::slice::from_raw_parts(ctx_buf as *const u64, aes::KEY_BUF_ELEMS)
+}
+
+#[cfg(feature = "pure_rust")]
+unsafe fn portable_init<'a>(ctx_buf: *const u8, ad: &[u8])
+                            -> (&'a [u64], gcm::Context) {
+    let h = core::slice::from_raw_parts(ctx_buf.offset(aes::KEY_BUF_LEN as isize),
+                                        aes::BLOCK_LEN);
+    let mut ghash =
+        gcm::Context::new(slice_as_array_ref!(h, aes::BLOCK_LEN).unwrap());
+    ghash.update(ad);
+    (portable_aes_key(ctx_buf), ghash)
+}
+
+#[cfg(feature = "pure_rust")]
+fn portable_finish(aes_key: &[u64], mut ghash: gcm::Context,
+                   nonce: &[u8; NONCE_LEN], ad_len: usize, in_out_len: usize,
+                   tag_out: &mut [u8; TAG_LEN]) {
+    let mut lengths = [0u8; aes::BLOCK_LEN];
+    lengths[..8].copy_from_slice(&polyfill::slice::be_u8_from_u64(
+        polyfill::u64_from_usize(ad_len) * 8));
+    lengths[8..].copy_from_slice(&polyfill::slice::be_u8_from_u64(
+        polyfill::u64_from_usize(in_out_len) * 8));
+    ghash.update_block(&lengths);
+
+    // The tag is encrypted with the counter block for counter value 1.
+    let mut tag = ghash.finish();
+    let mut j0 = [0u8; aes::BLOCK_LEN];
+    j0[..NONCE_LEN].copy_from_slice(nonce);
+    j0[aes::BLOCK_LEN - 1] = 1;
+    aes::encrypt_block(aes_key, &mut j0);
+    for (t, k) in tag.iter_mut().zip(j0.iter()) {
+        *t ^= *k;
+    }
+    tag_out.copy_from_slice(&tag);
+}
+
+// The bitsliced AES encrypts four blocks at a time.
+#[cfg(feature = "pure_rust")]
+const CHUNK_LEN: usize = 4 * aes::BLOCK_LEN;
+
+// AES in counter mode with a 32-bit big-endian counter, starting at 2.
+#[cfg(feature = "pure_rust")]
+struct Counter {
+    block: aes::Block,
+    counter: u32,
+}
+
+#[cfg(feature = "pure_rust")]
+impl Counter {
+    fn new(nonce: &[u8; NONCE_LEN]) -> Counter {
+        let mut block = [0u8; aes::BLOCK_LEN];
+        block[..NONCE_LEN].copy_from_slice(nonce);
+        Counter { block, counter: 2 }
+    }
+
+    fn xor_keystream(&mut self, aes_key: &[u64], in_out: &mut [u8]) {
+        debug_assert!(in_out.len() <= CHUNK_LEN);
+        let blocks = (in_out.len() + aes::BLOCK_LEN - 1) / aes::BLOCK_LEN;
+        let mut counter = self.block;
+        counter[NONCE_LEN..].copy_from_slice(
+            &polyfill::slice::be_u8_from_u32(self.counter));
+        let mut keystream = [0u8; CHUNK_LEN];
+        aes::ctr32_encrypt_blocks(aes_key,
+                                  &mut keystream[..(blocks * aes::BLOCK_LEN)],
+                                  &counter);
+        for (b, k) in in_out.iter_mut().zip(keystream.iter()) {
+            *b ^= *k;
+        }
+        self.counter = self.counter.wrapping_add(blocks as u32);
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
+    #[cfg(not(feature = "pure_rust"))]
     use {c, test};
-    use super::AES_MAX_ROUNDS;
+    #[cfg(not(feature = "pure_rust"))]
+    use super::super::aes::MAX_ROUNDS as AES_MAX_ROUNDS;
 
+    // This tests the C code's AES implementation directly. With the
+    // `pure_rust` feature, `aes::tests` tests the Rust implementation.
+    #[cfg(not(feature = "pure_rust"))]
     #[test]
     pub fn test_aes() {
         test::from_file("src/aead/aes_tests.txt", |section, test_case| {
@@ -162,21 +328,71 @@ mod tests {
         })
     }
 
+    #[cfg(not(feature = "pure_rust"))]
     const AES_BLOCK_SIZE: usize = 16;
 
     // Keep this in sync with AES_KEY in aes.h.
+    #[cfg(not(feature = "pure_rust"))]
     #[repr(C)]
     pub struct AES_KEY {
         pub rd_key: [u32; 4 * (AES_MAX_ROUNDS + 1)],
         pub rounds: usize,
     }
 
+    #[cfg(not(feature = "pure_rust"))]
     extern "C" {
         fn GFp_AES_set_encrypt_key(key: *const u8, bits: usize,
                                    aes_key: *mut AES_KEY) -> c::int;
         fn GFp_AES_encrypt(in_: *const u8, out: *mut u8, key: *const AES_KEY);
     }
 
+    // The `Algorithm` vtable lets a GCM variant with truncated tags reuse
+    // `aes_gcm_seal` and `aes_gcm_open`.
+    #[test]
+    fn truncated_tag_test() {
+        use aead;
+
+        static AES_128_GCM_96_BIT_TAG: aead::Algorithm = aead::Algorithm {
+            key_len: super::AES_128_KEY_LEN,
+            nonce_len: super::NONCE_LEN,
+            tag_len: 12,
+            init: super::aes_gcm_init,
+            seal: super::aes_gcm_seal,
+            open: super::aes_gcm_open,
+            id: aead::AlgorithmID::AES_128_GCM,
+            max_input_len: super::AES_GCM_MAX_INPUT_LEN,
+        };
+
+        let key_bytes = [1u8; 16];
+        let nonce = [2u8; 12];
+        let ad = b"ad";
+
+        let mut full = b"plaintext".to_vec();
+        full.extend_from_slice(&[0u8; 16]);
+        let key =
+            aead::SealingKey::new(&super::AES_128_GCM, &key_bytes).unwrap();
+        assert_eq!(aead::seal_in_place(&key, &nonce, ad, &mut full, 16),
+                   Ok(9 + 16));
+
+        let mut truncated = b"plaintext".to_vec();
+        truncated.extend_from_slice(&[0u8; 12]);
+        let key =
+            aead::SealingKey::new(&AES_128_GCM_96_BIT_TAG, &key_bytes).unwrap();
+        assert_eq!(aead::seal_in_place(&key, &nonce, ad, &mut truncated, 12),
+                   Ok(9 + 12));
+        assert_eq!(&truncated[..], &full[..(9 + 12)]);
+
+        let key =
+            aead::OpeningKey::new(&AES_128_GCM_96_BIT_TAG, &key_bytes).unwrap();
+        assert_eq!(aead::open_in_place(&key, &nonce, ad, 0,
+                                       &mut truncated.clone()).unwrap(),
+                   b"plaintext");
+        assert!(aead::open_in_place(&key, &nonce, ad, 0, &mut full).is_err());
+        truncated[9 + 11] ^= 1;
+        assert!(aead::open_in_place(&key, &nonce, ad, 0, &mut truncated)
+                    .is_err());
+    }
+
     #[test]
     fn max_input_len_test() {
         // [NIST SP800-38D] Section 5.2.1.1. Note that [RFC 5116 Section 5.1] and
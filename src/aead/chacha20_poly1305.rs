@@ -21,6 +21,8 @@ use {aead, chacha, error, poly1305, polyfill};
 /// [RFC 7539]: https://tools.ietf.org/html/rfc7539
 pub static CHACHA20_POLY1305: aead::Algorithm = aead::Algorithm {
     key_len: chacha::KEY_LEN_IN_BYTES,
+    nonce_len: chacha::NONCE_LEN,
+    tag_len: poly1305::TAG_LEN,
     init: chacha20_poly1305_init,
     seal: chacha20_poly1305_seal,
     open: chacha20_poly1305_open,
@@ -38,11 +40,12 @@ pub fn chacha20_poly1305_init(ctx_buf: &mut [u8], key: &[u8])
     Ok(())
 }
 
-fn chacha20_poly1305_seal(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS],
-                          nonce: &[u8; aead::NONCE_LEN], ad: &[u8],
-                          in_out: &mut [u8], tag_out: &mut [u8; aead::TAG_LEN])
+fn chacha20_poly1305_seal(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS], nonce: &[u8],
+                          ad: &[u8], in_out: &mut [u8], tag_out: &mut [u8])
                           -> Result<(), error::Unspecified> {
     let chacha20_key = ctx_as_key(ctx)?;
+    let nonce = slice_as_array_ref!(nonce, chacha::NONCE_LEN)?;
+    let tag_out = slice_as_array_ref_mut!(tag_out, poly1305::TAG_LEN)?;
     let mut counter = chacha::make_counter(nonce, 1);
     chacha::chacha20_xor_in_place(&chacha20_key, &counter, in_out);
     counter[0] = 0;
@@ -50,12 +53,13 @@ fn chacha20_poly1305_seal(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS],
     Ok(())
 }
 
-fn chacha20_poly1305_open(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS],
-                          nonce: &[u8; aead::NONCE_LEN], ad: &[u8],
-                          in_prefix_len: usize, in_out: &mut [u8],
-                          tag_out: &mut [u8; aead::TAG_LEN])
+fn chacha20_poly1305_open(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS], nonce: &[u8],
+                          ad: &[u8], in_prefix_len: usize, in_out: &mut [u8],
+                          tag_out: &mut [u8])
                           -> Result<(), error::Unspecified> {
     let chacha20_key = ctx_as_key(ctx)?;
+    let nonce = slice_as_array_ref!(nonce, chacha::NONCE_LEN)?;
+    let tag_out = slice_as_array_ref_mut!(tag_out, poly1305::TAG_LEN)?;
     let mut counter = chacha::make_counter(nonce, 0);
     {
         let ciphertext = &in_out[in_prefix_len..];
@@ -74,7 +78,7 @@ fn ctx_as_key(ctx: &[u64; aead::KEY_CTX_BUF_ELEMS])
         chacha::KEY_LEN_IN_BYTES / 4)
 }
 
-fn aead_poly1305(tag_out: &mut [u8; aead::TAG_LEN], chacha20_key: &chacha::Key,
+fn aead_poly1305(tag_out: &mut poly1305::Tag, chacha20_key: &chacha::Key,
                  counter: &chacha::Counter, ad: &[u8], ciphertext: &[u8]) {
     debug_assert_eq!(counter[0], 0);
     let key = poly1305::Key::derive_using_chacha(chacha20_key, counter);
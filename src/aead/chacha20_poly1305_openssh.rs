@@ -29,7 +29,7 @@
 //!    http://cvsweb.openbsd.org/cgi-bin/cvsweb/src/usr.bin/ssh/PROTOCOL.chacha20poly1305?annotate=HEAD
 //! [RFC 4253]: https://tools.ietf.org/html/rfc4253
 
-use {chacha, error, poly1305};
+use {chacha, error, poly1305, zeroize};
 
 /// A key for sealing packets.
 pub struct SealingKey {
@@ -135,6 +135,13 @@ struct Key {
     k_2: chacha::Key,
 }
 
+impl Drop for Key {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.k_1);
+        zeroize::zeroize(&mut self.k_2);
+    }
+}
+
 impl Key {
     pub fn new(key_material: &[u8; KEY_LEN]) -> Key {
         // The first half becomes K_2 and the second half becomes K_1.
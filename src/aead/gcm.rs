@@ -0,0 +1,127 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! A portable, constant-time GHASH, for builds with the `pure_rust` feature.
+//!
+//! The carryless multiplication uses integer multiplication with "holes" in
+//! the operands, so that the carries of each partial product land in bits that
+//! are masked away, as in BearSSL's `ghash_ctmul64`. It doesn't branch on or
+//! index memory with secret values, assuming that integer multiplication is
+//! constant-time.
+
+use polyfill;
+use super::aes::{Block, BLOCK_LEN};
+
+pub struct Context {
+    // `h` and `y` are stored as big-endian (high, low) pairs.
+    h: (u64, u64),
+    y: (u64, u64),
+}
+
+impl Context {
+    /// Starts a GHASH computation with the hash key `h`.
+    pub fn new(h: &Block) -> Context {
+        Context { h: from_block(h), y: (0, 0) }
+    }
+
+    /// Updates the hash with `data`, padding it with zeros to a whole number
+    /// of blocks.
+    pub fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(BLOCK_LEN) {
+            let mut block = [0u8; BLOCK_LEN];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.update_block(&block);
+        }
+    }
+
+    pub fn update_block(&mut self, block: &Block) {
+        let (x1, x0) = from_block(block);
+        let (y1, y0) = (self.y.0 ^ x1, self.y.1 ^ x0);
+        let (h1, h0) = self.h;
+
+        // Karatsuba multiplication. `bmul64` only computes the low half of
+        // each 64x64-bit product; the high half is the bit-reversal of the
+        // low half of the product of the bit-reversed operands.
+        let (h0r, h1r) = (h0.reverse_bits(), h1.reverse_bits());
+        let (y0r, y1r) = (y0.reverse_bits(), y1.reverse_bits());
+        let (h2, h2r) = (h0 ^ h1, h0r ^ h1r);
+        let (y2, y2r) = (y0 ^ y1, y0r ^ y1r);
+
+        let z0 = bmul64(y0, h0);
+        let z1 = bmul64(y1, h1);
+        let mut z2 = bmul64(y2, h2);
+        let mut z0h = bmul64(y0r, h0r);
+        let mut z1h = bmul64(y1r, h1r);
+        let mut z2h = bmul64(y2r, h2r);
+        z2 ^= z0 ^ z1;
+        z2h ^= z0h ^ z1h;
+        z0h = z0h.reverse_bits() >> 1;
+        z1h = z1h.reverse_bits() >> 1;
+        z2h = z2h.reverse_bits() >> 1;
+
+        let mut v0 = z0;
+        let mut v1 = z0h ^ z2;
+        let mut v2 = z1 ^ z2h;
+        let mut v3 = z1h;
+
+        // GHASH's bit order is reflected, so the 255-bit product is shifted
+        // left by one bit before it is reduced.
+        v3 = (v3 << 1) | (v2 >> 63);
+        v2 = (v2 << 1) | (v1 >> 63);
+        v1 = (v1 << 1) | (v0 >> 63);
+        v0 <<= 1;
+
+        // Reduce modulo x**128 + x**7 + x**2 + x + 1.
+        v2 ^= v0 ^ (v0 >> 1) ^ (v0 >> 2) ^ (v0 >> 7);
+        v1 ^= (v0 << 63) ^ (v0 << 62) ^ (v0 << 57);
+        v3 ^= v1 ^ (v1 >> 1) ^ (v1 >> 2) ^ (v1 >> 7);
+        v2 ^= (v1 << 63) ^ (v1 << 62) ^ (v1 << 57);
+
+        self.y = (v3, v2);
+    }
+
+    pub fn finish(self) -> Block {
+        let mut r = [0u8; BLOCK_LEN];
+        r[..8].copy_from_slice(&polyfill::slice::be_u8_from_u64(self.y.0));
+        r[8..].copy_from_slice(&polyfill::slice::be_u8_from_u64(self.y.1));
+        r
+    }
+}
+
+fn from_block(block: &Block) -> (u64, u64) {
+    let word = |i: usize| {
+        let hi = slice_as_array_ref!(&block[(i * 8)..][..4], 4).unwrap();
+        let lo = slice_as_array_ref!(&block[(i * 8 + 4)..][..4], 4).unwrap();
+        (u64::from(polyfill::slice::u32_from_be_u8(hi)) << 32) |
+            u64::from(polyfill::slice::u32_from_be_u8(lo))
+    };
+    (word(0), word(1))
+}
+
+// Returns the low 64 bits of the carryless product of `x` and `y`.
+fn bmul64(x: u64, y: u64) -> u64 {
+    const M0: u64 = 0x1111_1111_1111_1111;
+    const M1: u64 = 0x2222_2222_2222_2222;
+    const M2: u64 = 0x4444_4444_4444_4444;
+    const M3: u64 = 0x8888_8888_8888_8888;
+
+    let (x0, x1, x2, x3) = (x & M0, x & M1, x & M2, x & M3);
+    let (y0, y1, y2, y3) = (y & M0, y & M1, y & M2, y & M3);
+    let mul = |a: u64, b: u64| a.wrapping_mul(b);
+    let z0 = mul(x0, y0) ^ mul(x1, y3) ^ mul(x2, y2) ^ mul(x3, y1);
+    let z1 = mul(x0, y1) ^ mul(x1, y0) ^ mul(x2, y3) ^ mul(x3, y2);
+    let z2 = mul(x0, y2) ^ mul(x1, y1) ^ mul(x2, y0) ^ mul(x3, y3);
+    let z3 = mul(x0, y3) ^ mul(x1, y2) ^ mul(x2, y1) ^ mul(x3, y0);
+    (z0 & M0) | (z1 & M1) | (z2 & M2) | (z3 & M3)
+}
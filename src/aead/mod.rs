@@ -25,10 +25,19 @@
 //! [AEAD]: http://www-cse.ucsd.edu/~mihir/papers/oem.html
 //! [`crypto.cipher.AEAD`]: https://golang.org/pkg/crypto/cipher/#AEAD
 
-use {constant_time, error, init, poly1305, polyfill};
+use {constant_time, error, hkdf, init, polyfill, zeroize};
 
 pub use self::chacha20_poly1305::CHACHA20_POLY1305;
 pub use self::aes_gcm::{AES_128_GCM, AES_256_GCM};
+pub use self::aes_ccm::{AES_128_CCM, AES_256_CCM, AES_128_CCM_8, AES_256_CCM_8,
+                        AES_128_CCM_L2, AES_128_CCM_8_L2, AES_128_CCM_4_L2};
+pub use self::nonce::{
+    CounterNonceSequence,
+    NonceSequence,
+    RandomPrefixNonceSequence,
+    SequencedOpeningKey,
+    SequencedSealingKey,
+};
 
 /// A key for authenticating and decrypting (“opening”) AEAD-protected data.
 ///
@@ -65,6 +74,12 @@ impl OpeningKey {
     pub fn algorithm(&self) -> &'static Algorithm { self.key.algorithm() }
 }
 
+impl<'a> From<hkdf::Okm<'a, &'static Algorithm>> for OpeningKey {
+    fn from(okm: hkdf::Okm<'a, &'static Algorithm>) -> Self {
+        OpeningKey { key: Key::from(okm) }
+    }
+}
+
 /// Authenticates and decrypts (“opens”) data in place. When
 ///
 /// The input may have a prefix that is `in_prefix_len` bytes long; any such
@@ -117,20 +132,24 @@ pub fn open_in_place<'a>(key: &OpeningKey, nonce: &[u8], ad: &[u8],
                          in_prefix_len: usize,
                          ciphertext_and_tag_modified_in_place: &'a mut [u8])
                          -> Result<&'a mut [u8], error::Unspecified> {
-    let nonce = slice_as_array_ref!(nonce, NONCE_LEN)?;
+    if nonce.len() != key.key.algorithm.nonce_len() {
+        return Err(error::Unspecified);
+    }
     let ciphertext_and_tag_len =
         ciphertext_and_tag_modified_in_place.len()
                 .checked_sub(in_prefix_len).ok_or(error::Unspecified)?;
+    let tag_len = key.key.algorithm.tag_len();
     let ciphertext_len =
-        ciphertext_and_tag_len.checked_sub(TAG_LEN).ok_or(error::Unspecified)?;
+        ciphertext_and_tag_len.checked_sub(tag_len).ok_or(error::Unspecified)?;
     check_per_nonce_max_bytes(key.key.algorithm, ciphertext_len)?;
     let (in_out, received_tag) =
         ciphertext_and_tag_modified_in_place
             .split_at_mut(in_prefix_len + ciphertext_len);
-    let mut calculated_tag = [0u8; TAG_LEN];
+    let mut calculated_tag = [0u8; MAX_TAG_LEN];
+    let calculated_tag = &mut calculated_tag[..tag_len];
     (key.key.algorithm.open)(&key.key.ctx_buf, nonce, &ad, in_prefix_len,
-                             in_out, &mut calculated_tag)?;
-    if constant_time::verify_slices_are_equal(&calculated_tag, received_tag)
+                             in_out, calculated_tag)?;
+    if constant_time::verify_slices_are_equal(calculated_tag, received_tag)
             .is_err() {
         // Zero out the plaintext so that it isn't accidentally leaked or used
         // after verification fails. It would be safest if we could check the
@@ -176,9 +195,16 @@ impl SealingKey {
     pub fn algorithm(&self) -> &'static Algorithm { self.key.algorithm() }
 }
 
+impl<'a> From<hkdf::Okm<'a, &'static Algorithm>> for SealingKey {
+    fn from(okm: hkdf::Okm<'a, &'static Algorithm>) -> Self {
+        SealingKey { key: Key::from(okm) }
+    }
+}
+
 /// Encrypts and signs (“seals”) data in place.
 ///
-/// `nonce` must be unique for every use of the key to seal data.
+/// `nonce` must be unique for every use of the key to seal data. See
+/// `SequencedSealingKey` for a key that manages its own nonces.
 ///
 /// The input is `in_out[..(in_out.len() - out_suffix_capacity)]`; i.e. the
 /// input is the part of `in_out` that precedes the suffix. When
@@ -200,17 +226,20 @@ impl SealingKey {
 pub fn seal_in_place(key: &SealingKey, nonce: &[u8], ad: &[u8],
                      in_out: &mut [u8], out_suffix_capacity: usize)
                      -> Result<usize, error::Unspecified> {
-    if out_suffix_capacity < key.key.algorithm.tag_len() {
+    let tag_len = key.key.algorithm.tag_len();
+    if out_suffix_capacity < tag_len {
+        return Err(error::Unspecified);
+    }
+    if nonce.len() != key.key.algorithm.nonce_len() {
         return Err(error::Unspecified);
     }
-    let nonce = slice_as_array_ref!(nonce, NONCE_LEN)?;
     let in_out_len =
         in_out.len().checked_sub(out_suffix_capacity).ok_or(error::Unspecified)?;
     check_per_nonce_max_bytes(key.key.algorithm, in_out_len)?;
     let (in_out, tag_out) = in_out.split_at_mut(in_out_len);
-    let tag_out = slice_as_array_ref_mut!(tag_out, TAG_LEN)?;
-    (key.key.algorithm.seal)(&key.key.ctx_buf, nonce, ad, in_out, tag_out)?;
-    Ok(in_out_len + TAG_LEN)
+    (key.key.algorithm.seal)(&key.key.ctx_buf, nonce, ad, in_out,
+                             &mut tag_out[..tag_len])?;
+    Ok(in_out_len + tag_len)
 }
 
 /// `OpeningKey` and `SealingKey` are type-safety wrappers around `Key`, which
@@ -222,6 +251,12 @@ struct Key {
     algorithm: &'static Algorithm,
 }
 
+impl Drop for Key {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.ctx_buf);
+    }
+}
+
 const KEY_CTX_BUF_ELEMS: usize = (KEY_CTX_BUF_LEN + 7) / 8;
 
 // Keep this in sync with `aead_aes_gcm_ctx` in e_aes.c.
@@ -252,6 +287,16 @@ impl Key {
     fn algorithm(&self) -> &'static Algorithm { self.algorithm }
 }
 
+impl<'a> From<hkdf::Okm<'a, &'static Algorithm>> for Key {
+    fn from(okm: hkdf::Okm<'a, &'static Algorithm>) -> Self {
+        let algorithm = *okm.len();
+        let mut key_bytes = [0; MAX_KEY_LEN];
+        let key_bytes = &mut key_bytes[..algorithm.key_len()];
+        okm.fill(key_bytes).unwrap();
+        Key::new(algorithm, key_bytes).unwrap()
+    }
+}
+
 /// An AEAD Algorithm.
 ///
 /// C analog: `EVP_AEAD`
@@ -261,17 +306,22 @@ impl Key {
 pub struct Algorithm {
     init: fn(ctx_buf: &mut [u8], key: &[u8]) -> Result<(), error::Unspecified>,
 
-    seal: fn(ctx: &[u64; KEY_CTX_BUF_ELEMS], nonce: &[u8; NONCE_LEN], ad: &[u8],
-             in_out: &mut [u8], tag_out: &mut [u8; TAG_LEN])
+    // The callers of `seal` and `open` ensure that `nonce` is exactly
+    // `nonce_len` bytes long and `tag_out` is exactly `tag_len` bytes long.
+    seal: fn(ctx: &[u64; KEY_CTX_BUF_ELEMS], nonce: &[u8], ad: &[u8],
+             in_out: &mut [u8], tag_out: &mut [u8])
+             -> Result<(), error::Unspecified>,
+    open: fn(ctx: &[u64; KEY_CTX_BUF_ELEMS], nonce: &[u8], ad: &[u8],
+             in_prefix_len: usize, in_out: &mut [u8], tag_out: &mut [u8])
              -> Result<(), error::Unspecified>,
-    open: fn(ctx: &[u64; KEY_CTX_BUF_ELEMS], nonce: &[u8; NONCE_LEN],
-             ad: &[u8], in_prefix_len: usize, in_out: &mut [u8],
-             tag_out: &mut [u8; TAG_LEN]) -> Result<(), error::Unspecified>,
 
     key_len: usize,
+    nonce_len: usize,
+    tag_len: usize,
     id: AlgorithmID,
 
-    /// Use `max_input_len!()` to initialize this.
+    /// Use `max_input_len!()` to initialize this for AEADs with a 32-bit block
+    /// counter.
     // TODO: Make this `usize`.
     max_input_len: u64,
 }
@@ -301,16 +351,23 @@ impl Algorithm {
     /// Go analog:
     ///   [`crypto.cipher.AEAD.Overhead`](https://golang.org/pkg/crypto/cipher/#AEAD)
     #[inline(always)]
-    pub fn tag_len(&self) -> usize { TAG_LEN }
+    pub fn tag_len(&self) -> usize { self.tag_len }
 
     /// The length of the nonces.
     ///
+    /// See also `MAX_NONCE_LEN`.
+    ///
     /// C analog: `EVP_AEAD_nonce_length`
     ///
     /// Go analog:
     ///   [`crypto.cipher.AEAD.NonceSize`](https://golang.org/pkg/crypto/cipher/#AEAD)
     #[inline(always)]
-    pub fn nonce_len(&self) -> usize { NONCE_LEN }
+    pub fn nonce_len(&self) -> usize { self.nonce_len }
+}
+
+impl hkdf::KeyType for &'static Algorithm {
+    #[inline]
+    fn len(&self) -> usize { self.key_len() }
 }
 
 derive_debug_from_field!(Algorithm, id);
@@ -320,6 +377,13 @@ derive_debug_from_field!(Algorithm, id);
 enum AlgorithmID {
     AES_128_GCM,
     AES_256_GCM,
+    AES_128_CCM,
+    AES_256_CCM,
+    AES_128_CCM_8,
+    AES_256_CCM_8,
+    AES_128_CCM_L2,
+    AES_128_CCM_8_L2,
+    AES_128_CCM_4_L2,
     CHACHA20_POLY1305,
 }
 
@@ -329,14 +393,14 @@ impl PartialEq for Algorithm {
 
 impl Eq for Algorithm {}
 
-/// The maximum length of a tag for the algorithms in this module.
-pub const MAX_TAG_LEN: usize = TAG_LEN;
+// The maximum length of a key for the algorithms in this module.
+const MAX_KEY_LEN: usize = 256 / 8;
 
-// All the AEADs we support use 128-bit tags.
-const TAG_LEN: usize = poly1305::TAG_LEN;
+/// The maximum length of a tag for the algorithms in this module.
+pub const MAX_TAG_LEN: usize = 128 / 8;
 
-// All the AEADs we support use 96-bit nonces.
-const NONCE_LEN: usize = 96 / 8;
+/// The maximum length of a nonce for the algorithms in this module.
+pub const MAX_NONCE_LEN: usize = 104 / 8;
 
 
 fn check_per_nonce_max_bytes(alg: &Algorithm, in_out_len: usize)
@@ -349,4 +413,12 @@ fn check_per_nonce_max_bytes(alg: &Algorithm, in_out_len: usize)
 
 pub mod chacha20_poly1305_openssh;
 mod chacha20_poly1305;
+pub(crate) mod aes;
+mod aes_ccm;
 mod aes_gcm;
+
+#[cfg(feature = "pure_rust")]
+mod gcm;
+
+mod nonce;
+pub mod quic;
@@ -0,0 +1,244 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Nonce sequences, and keys that take their nonces from them.
+
+use {error, polyfill, rand};
+use super::{Algorithm, OpeningKey, SealingKey, MAX_NONCE_LEN};
+
+/// A sequence of unique nonces.
+///
+/// A given `NonceSequence` must never produce the same nonce twice, and it
+/// should be used with at most one key.
+pub trait NonceSequence {
+    /// Fills `nonce` with the next nonce in the sequence.
+    ///
+    /// `nonce.len()` is the nonce length of the algorithm the nonce will be
+    /// used with. `advance` must fail if the sequence is exhausted or if it
+    /// cannot produce a nonce of that length.
+    fn advance(&mut self, nonce: &mut [u8]) -> Result<(), error::Unspecified>;
+}
+
+/// A `NonceSequence` of big-endian 64-bit counter values, left-padded with
+/// zeros to the nonce length.
+///
+/// The sequence is exhausted once the counter would wrap around.
+pub struct CounterNonceSequence {
+    next: Option<u64>,
+}
+
+impl CounterNonceSequence {
+    /// Constructs a sequence whose first nonce encodes `first`.
+    #[inline]
+    pub fn new(first: u64) -> CounterNonceSequence {
+        CounterNonceSequence { next: Some(first) }
+    }
+}
+
+impl NonceSequence for CounterNonceSequence {
+    fn advance(&mut self, nonce: &mut [u8]) -> Result<(), error::Unspecified> {
+        let counter = self.next.ok_or(error::Unspecified)?;
+        let padding_len =
+            nonce.len().checked_sub(COUNTER_LEN).ok_or(error::Unspecified)?;
+        let (padding, counter_out) = nonce.split_at_mut(padding_len);
+        polyfill::slice::fill(padding, 0);
+        counter_out.copy_from_slice(&polyfill::slice::be_u8_from_u64(counter));
+        self.next = counter.checked_add(1);
+        Ok(())
+    }
+}
+
+/// A `NonceSequence` of nonces made of a fixed random prefix followed by a
+/// big-endian 64-bit counter, as recommended in [RFC 5116 Section 3.2].
+///
+/// Giving each sender its own random prefix lets several senders share a key
+/// without coordinating their counters, at the cost of a small chance that two
+/// senders pick the same prefix. Only 96-bit nonces are supported.
+///
+/// [RFC 5116 Section 3.2]: https://tools.ietf.org/html/rfc5116#section-3.2
+pub struct RandomPrefixNonceSequence {
+    prefix: [u8; PREFIX_LEN],
+    counter: CounterNonceSequence,
+}
+
+impl RandomPrefixNonceSequence {
+    /// Constructs a sequence with a prefix generated by `rng` and a counter
+    /// starting at zero.
+    pub fn new(rng: &rand::SecureRandom)
+               -> Result<RandomPrefixNonceSequence, error::Unspecified> {
+        let mut prefix = [0u8; PREFIX_LEN];
+        rng.fill(&mut prefix)?;
+        Ok(RandomPrefixNonceSequence {
+            prefix,
+            counter: CounterNonceSequence::new(0),
+        })
+    }
+}
+
+impl NonceSequence for RandomPrefixNonceSequence {
+    fn advance(&mut self, nonce: &mut [u8]) -> Result<(), error::Unspecified> {
+        if nonce.len() != NONCE_LEN {
+            return Err(error::Unspecified);
+        }
+        self.counter.advance(nonce)?;
+        nonce[..PREFIX_LEN].copy_from_slice(&self.prefix);
+        Ok(())
+    }
+}
+
+const NONCE_LEN: usize = 96 / 8;
+const COUNTER_LEN: usize = 8;
+const PREFIX_LEN: usize = NONCE_LEN - COUNTER_LEN;
+
+/// A `SealingKey` that takes the nonce for each sealing operation from a
+/// `NonceSequence`.
+///
+/// # Examples
+///
+/// ```
+/// use ring::aead;
+///
+/// # fn main() { f().unwrap() }
+/// # fn f() -> Result<(), ring::error::Unspecified> {
+/// let key_bytes = [0u8; 32];
+/// let key = aead::SealingKey::new(&aead::CHACHA20_POLY1305, &key_bytes)?;
+/// let mut key =
+///     aead::SequencedSealingKey::new(key, aead::CounterNonceSequence::new(0));
+///
+/// let mut in_out = b"hello".to_vec();
+/// in_out.extend_from_slice(&[0u8; aead::MAX_TAG_LEN]);
+/// let out_len = key.seal_in_place(b"", &mut in_out, aead::MAX_TAG_LEN)?;
+/// assert_eq!(out_len, 5 + aead::CHACHA20_POLY1305.tag_len());
+/// # Ok(())
+/// # }
+/// ```
+pub struct SequencedSealingKey<N: NonceSequence> {
+    key: SealingKey,
+    nonce_sequence: N,
+    invocations_remaining: u64,
+}
+
+impl<N: NonceSequence> SequencedSealingKey<N> {
+    /// Constructs a key that takes its nonces from `nonce_sequence`.
+    ///
+    /// The key can be used until `nonce_sequence` is exhausted.
+    #[inline]
+    pub fn new(key: SealingKey, nonce_sequence: N) -> Self {
+        Self::with_max_invocations(key, nonce_sequence, u64::max_value())
+    }
+
+    /// Like `new`, but the key also refuses to seal anything after it has
+    /// been used `max_invocations` times. Use this to enforce an
+    /// algorithm's or protocol's limit on how much data one key may protect.
+    #[inline]
+    pub fn with_max_invocations(key: SealingKey, nonce_sequence: N,
+                                max_invocations: u64) -> Self {
+        SequencedSealingKey {
+            key,
+            nonce_sequence,
+            invocations_remaining: max_invocations,
+        }
+    }
+
+    /// The key's AEAD algorithm.
+    #[inline(always)]
+    pub fn algorithm(&self) -> &'static Algorithm { self.key.algorithm() }
+
+    /// Like `aead::seal_in_place`, using the next nonce in the key's nonce
+    /// sequence.
+    ///
+    /// Every call consumes a nonce, even one that fails. Once the nonce
+    /// sequence or the key's invocation limit is exhausted, every call fails.
+    pub fn seal_in_place(&mut self, ad: &[u8], in_out: &mut [u8],
+                         out_suffix_capacity: usize)
+                         -> Result<usize, error::Unspecified> {
+        let mut nonce = [0u8; MAX_NONCE_LEN];
+        let nonce = next_nonce(&mut self.nonce_sequence,
+                               &mut self.invocations_remaining,
+                               self.key.algorithm(), &mut nonce)?;
+        super::seal_in_place(&self.key, nonce, ad, in_out, out_suffix_capacity)
+    }
+}
+
+/// An `OpeningKey` that takes the nonce for each opening operation from a
+/// `NonceSequence`.
+///
+/// The opening side must use a nonce sequence that produces the same nonces,
+/// in the same order, as the sealing side's.
+pub struct SequencedOpeningKey<N: NonceSequence> {
+    key: OpeningKey,
+    nonce_sequence: N,
+    invocations_remaining: u64,
+}
+
+impl<N: NonceSequence> SequencedOpeningKey<N> {
+    /// Constructs a key that takes its nonces from `nonce_sequence`.
+    ///
+    /// The key can be used until `nonce_sequence` is exhausted.
+    #[inline]
+    pub fn new(key: OpeningKey, nonce_sequence: N) -> Self {
+        Self::with_max_invocations(key, nonce_sequence, u64::max_value())
+    }
+
+    /// Like `new`, but the key also refuses to open anything after it has
+    /// been used `max_invocations` times.
+    #[inline]
+    pub fn with_max_invocations(key: OpeningKey, nonce_sequence: N,
+                                max_invocations: u64) -> Self {
+        SequencedOpeningKey {
+            key,
+            nonce_sequence,
+            invocations_remaining: max_invocations,
+        }
+    }
+
+    /// The key's AEAD algorithm.
+    #[inline(always)]
+    pub fn algorithm(&self) -> &'static Algorithm { self.key.algorithm() }
+
+    /// Like `aead::open_in_place`, using the next nonce in the key's nonce
+    /// sequence.
+    ///
+    /// Every call consumes a nonce, even one that fails. Once the nonce
+    /// sequence or the key's invocation limit is exhausted, every call fails.
+    pub fn open_in_place<'a>(&mut self, ad: &[u8], in_prefix_len: usize,
+                             ciphertext_and_tag_modified_in_place: &'a mut [u8])
+                             -> Result<&'a mut [u8], error::Unspecified> {
+        let mut nonce = [0u8; MAX_NONCE_LEN];
+        let nonce = next_nonce(&mut self.nonce_sequence,
+                               &mut self.invocations_remaining,
+                               self.key.algorithm(), &mut nonce)?;
+        super::open_in_place(&self.key, nonce, ad, in_prefix_len,
+                             ciphertext_and_tag_modified_in_place)
+    }
+}
+
+fn next_nonce<'a, N: NonceSequence>(nonce_sequence: &mut N,
+                                    invocations_remaining: &mut u64,
+                                    algorithm: &Algorithm,
+                                    nonce: &'a mut [u8; MAX_NONCE_LEN])
+                                    -> Result<&'a [u8], error::Unspecified> {
+    if *invocations_remaining == 0 {
+        return Err(error::Unspecified);
+    }
+    let nonce = &mut nonce[..algorithm.nonce_len()];
+    if nonce_sequence.advance(nonce).is_err() {
+        // Never consult an exhausted sequence again, in case it would
+        // (incorrectly) start over.
+        *invocations_remaining = 0;
+        return Err(error::Unspecified);
+    }
+    *invocations_remaining -= 1;
+    Ok(nonce)
+}
@@ -0,0 +1,201 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! QUIC header protection, as specified in [RFC 9001 Section 5.4].
+//!
+//! QUIC protects packet payloads with the AEADs in `ring::aead`, and protects
+//! the packet number and some bits of the first byte of the header with a
+//! mask computed from a sample of the protected payload. The header
+//! protection algorithm depends on the packet protection AEAD:
+//!
+//! | Packet protection                   | Header protection |
+//! |-------------------------------------|-------------------|
+//! | `aead::AES_128_GCM`                 | `quic::AES_128`   |
+//! | `aead::AES_256_GCM`                 | `quic::AES_256`   |
+//! | `aead::CHACHA20_POLY1305`           | `quic::CHACHA20`  |
+//!
+//! The header protection key is derived along with the packet protection key,
+//! e.g. using `tls13::hkdf_expand_label` with the label "quic hp".
+//!
+//! [RFC 9001 Section 5.4]: https://tools.ietf.org/html/rfc9001#section-5.4
+
+use {chacha, error, hkdf, init, polyfill, zeroize};
+use super::aes;
+
+/// A key for generating QUIC header protection masks.
+pub struct HeaderProtectionKey {
+    ctx_buf: [u64; aes::KEY_BUF_ELEMS],
+    algorithm: &'static Algorithm,
+}
+
+impl Drop for HeaderProtectionKey {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.ctx_buf);
+    }
+}
+
+impl HeaderProtectionKey {
+    /// Constructs a new header protection key.
+    ///
+    /// `key_bytes` must be exactly `algorithm.key_len()` bytes long.
+    pub fn new(algorithm: &'static Algorithm, key_bytes: &[u8])
+               -> Result<HeaderProtectionKey, error::Unspecified> {
+        if key_bytes.len() != algorithm.key_len() {
+            return Err(error::Unspecified);
+        }
+        let mut key = HeaderProtectionKey {
+            ctx_buf: [0; aes::KEY_BUF_ELEMS],
+            algorithm,
+        };
+        init::init_once();
+        (algorithm.init)(polyfill::slice::u64_as_u8_mut(&mut key.ctx_buf),
+                         key_bytes)?;
+        Ok(key)
+    }
+
+    /// Computes the header protection mask for the packet that `sample` was
+    /// taken from.
+    ///
+    /// `sample` must be exactly `self.algorithm().sample_len()` bytes long.
+    /// The first byte of the mask is applied to the first byte of the header,
+    /// and the remaining bytes are applied to the packet number.
+    pub fn new_mask(&self, sample: &[u8])
+                    -> Result<[u8; MASK_LEN], error::Unspecified> {
+        let sample = slice_as_array_ref!(sample, SAMPLE_LEN)?;
+        Ok((self.algorithm.new_mask)(&self.ctx_buf, sample))
+    }
+
+    /// The key's algorithm.
+    #[inline(always)]
+    pub fn algorithm(&self) -> &'static Algorithm { self.algorithm }
+}
+
+impl<'a> From<hkdf::Okm<'a, &'static Algorithm>> for HeaderProtectionKey {
+    fn from(okm: hkdf::Okm<'a, &'static Algorithm>) -> Self {
+        let algorithm = *okm.len();
+        let mut key_bytes = [0; super::MAX_KEY_LEN];
+        let key_bytes = &mut key_bytes[..algorithm.key_len()];
+        okm.fill(key_bytes).unwrap();
+        HeaderProtectionKey::new(algorithm, key_bytes).unwrap()
+    }
+}
+
+/// A QUIC header protection algorithm.
+pub struct Algorithm {
+    init: fn(ctx_buf: &mut [u8], key: &[u8]) -> Result<(), error::Unspecified>,
+    new_mask: fn(ctx: &[u64; aes::KEY_BUF_ELEMS], sample: &Sample)
+                 -> [u8; MASK_LEN],
+    key_len: usize,
+    id: AlgorithmID,
+}
+
+impl Algorithm {
+    /// The length of the key.
+    #[inline(always)]
+    pub fn key_len(&self) -> usize { self.key_len }
+
+    /// The length of the sample that the mask is computed from.
+    #[inline(always)]
+    pub fn sample_len(&self) -> usize { SAMPLE_LEN }
+}
+
+impl hkdf::KeyType for &'static Algorithm {
+    #[inline]
+    fn len(&self) -> usize { self.key_len() }
+}
+
+derive_debug_from_field!(Algorithm, id);
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Eq, PartialEq)]
+enum AlgorithmID {
+    AES_128,
+    AES_256,
+    CHACHA20,
+}
+
+impl PartialEq for Algorithm {
+    fn eq(&self, other: &Self) -> bool { self.id == other.id }
+}
+
+impl Eq for Algorithm {}
+
+/// AES-128 header protection, for use with `aead::AES_128_GCM`.
+pub static AES_128: Algorithm = Algorithm {
+    init: aes::init,
+    new_mask: aes_new_mask,
+    key_len: aes::AES_128_KEY_LEN,
+    id: AlgorithmID::AES_128,
+};
+
+/// AES-256 header protection, for use with `aead::AES_256_GCM`.
+pub static AES_256: Algorithm = Algorithm {
+    init: aes::init,
+    new_mask: aes_new_mask,
+    key_len: aes::AES_256_KEY_LEN,
+    id: AlgorithmID::AES_256,
+};
+
+/// ChaCha20 header protection, for use with `aead::CHACHA20_POLY1305`.
+pub static CHACHA20: Algorithm = Algorithm {
+    init: chacha20_init,
+    new_mask: chacha20_new_mask,
+    key_len: chacha::KEY_LEN_IN_BYTES,
+    id: AlgorithmID::CHACHA20,
+};
+
+// RFC 9001 Section 5.4.3: "mask = AES-ECB(hp_key, sample)".
+fn aes_new_mask(ctx: &[u64; aes::KEY_BUF_ELEMS], sample: &Sample)
+                -> [u8; MASK_LEN] {
+    let mut block = *sample;
+    aes::encrypt_block(ctx, &mut block);
+    let mut mask = [0u8; MASK_LEN];
+    mask.copy_from_slice(&block[..MASK_LEN]);
+    mask
+}
+
+// The ChaCha20 key is stored at the start of the context buffer.
+fn chacha20_init(ctx_buf: &mut [u8], key: &[u8])
+                 -> Result<(), error::Unspecified> {
+    ctx_buf[..chacha::KEY_LEN_IN_BYTES].copy_from_slice(key);
+    Ok(())
+}
+
+// RFC 9001 Section 5.4.4: The first four bytes of the sample are the block
+// counter, in little-endian byte order, and the rest are the nonce; the mask
+// is the encryption of five zero bytes.
+fn chacha20_new_mask(ctx: &[u64; aes::KEY_BUF_ELEMS], sample: &Sample)
+                     -> [u8; MASK_LEN] {
+    let key_bytes = &polyfill::slice::u64_as_u8(ctx)
+        [..chacha::KEY_LEN_IN_BYTES];
+    let mut key = chacha::key_from_bytes(
+        slice_as_array_ref!(key_bytes, chacha::KEY_LEN_IN_BYTES).unwrap());
+    let (counter, nonce) = sample.split_at(4);
+    let counter = chacha::make_counter(
+        slice_as_array_ref!(nonce, chacha::NONCE_LEN).unwrap(),
+        polyfill::slice::u32_from_le_u8(
+            slice_as_array_ref!(counter, 4).unwrap()));
+    let mut mask = [0u8; MASK_LEN];
+    chacha::chacha20_xor_in_place(&key, &counter, &mut mask);
+    zeroize::zeroize(&mut key);
+    mask
+}
+
+/// The length of a header protection mask.
+pub const MASK_LEN: usize = 5;
+
+const SAMPLE_LEN: usize = 16;
+
+/// A sample of a protected packet's payload.
+type Sample = [u8; SAMPLE_LEN];
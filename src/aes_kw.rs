@@ -0,0 +1,262 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! AES Key Wrap, as specified in [RFC 3394], and AES Key Wrap with Padding, as
+//! specified in [RFC 5649].
+//!
+//! These wrap (encrypt) key data under a key-encryption key (KEK) such that
+//! any modification of the wrapped key is detected when it is unwrapped. The
+//! KEK is a `cipher::EncryptingKey` for wrapping and a
+//! `cipher::DecryptingKey` for unwrapping.
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::{aes_kw, cipher};
+//!
+//! # fn main() { f().unwrap() }
+//! # fn f() -> Result<(), ring::error::Unspecified> {
+//! let kek_bytes = [0x42; 32];
+//! let key_data = [0x24; 20];
+//!
+//! let kek = cipher::EncryptingKey::new(&cipher::AES_256, &kek_bytes)?;
+//! let mut wrapped = [0u8; 24 + aes_kw::SEMIBLOCK_LEN];
+//! let wrapped = aes_kw::wrap_with_padding(&kek, &key_data, &mut wrapped)?;
+//!
+//! let kek = cipher::DecryptingKey::new(&cipher::AES_256, &kek_bytes)?;
+//! let mut unwrapped = [0u8; 24];
+//! let unwrapped = aes_kw::unwrap_with_padding(&kek, wrapped, &mut unwrapped)?;
+//! assert_eq!(unwrapped, &key_data[..]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [RFC 3394]: https://tools.ietf.org/html/rfc3394
+//! [RFC 5649]: https://tools.ietf.org/html/rfc5649
+
+use {cipher, constant_time, error, polyfill};
+
+/// The length of a semiblock, half of an AES block.
+///
+/// Wrapped key data is one semiblock longer than the key data (after padding,
+/// for `wrap_with_padding`).
+pub const SEMIBLOCK_LEN: usize = cipher::BLOCK_LEN / 2;
+
+/// Wraps `key_data` using AES-KW.
+///
+/// `key_data` must be a multiple of `SEMIBLOCK_LEN` bytes long, and at least
+/// two semiblocks long. `out` must be at least
+/// `key_data.len() + SEMIBLOCK_LEN` bytes long. Returns the wrapped key, which
+/// is written to the start of `out`.
+pub fn wrap<'a>(kek: &cipher::EncryptingKey, key_data: &[u8],
+                out: &'a mut [u8]) -> Result<&'a [u8], error::Unspecified> {
+    if key_data.len() < 2 * SEMIBLOCK_LEN || key_data.len() % SEMIBLOCK_LEN != 0
+    {
+        return Err(error::Unspecified);
+    }
+    let wrapped_len = key_data.len() + SEMIBLOCK_LEN;
+    if out.len() < wrapped_len {
+        return Err(error::Unspecified);
+    }
+    let out = &mut out[..wrapped_len];
+    out[SEMIBLOCK_LEN..].copy_from_slice(key_data);
+    wrap_in_place(kek, &KW_IV, out);
+    Ok(out)
+}
+
+/// Unwraps `wrapped` using AES-KW, checking its integrity; the inverse of
+/// `wrap`.
+///
+/// `out` must be at least `wrapped.len() - SEMIBLOCK_LEN` bytes long. Returns
+/// the key data, which is written to the start of `out`. When `unwrap`
+/// returns `Err(..)`, none of the unwrapped data is left in `out`.
+pub fn unwrap<'a>(kek: &cipher::DecryptingKey, wrapped: &[u8],
+                  out: &'a mut [u8]) -> Result<&'a [u8], error::Unspecified> {
+    if wrapped.len() < 3 * SEMIBLOCK_LEN {
+        return Err(error::Unspecified);
+    }
+    let out = unwrap_prefix(out, wrapped.len())?;
+    let a = unwrap_in_place(kek, wrapped, out);
+    if constant_time::verify_slices_are_equal(&a, &KW_IV).is_err() {
+        polyfill::slice::fill(out, 0);
+        return Err(error::Unspecified);
+    }
+    Ok(out)
+}
+
+/// Wraps `key_data` using AES-KWP.
+///
+/// `key_data` must be between 1 and `2**32 - 1` bytes long. `out` must have
+/// room for `key_data` padded to a multiple of `SEMIBLOCK_LEN` bytes, plus
+/// another `SEMIBLOCK_LEN` bytes. Returns the wrapped key, which is written to
+/// the start of `out`.
+pub fn wrap_with_padding<'a>(kek: &cipher::EncryptingKey, key_data: &[u8],
+                             out: &'a mut [u8])
+                             -> Result<&'a [u8], error::Unspecified> {
+    let mli = polyfill::u64_from_usize(key_data.len());
+    if mli == 0 || mli > 0xffff_ffff {
+        return Err(error::Unspecified);
+    }
+    let padded_len = padded_len(key_data.len());
+    let wrapped_len = padded_len + SEMIBLOCK_LEN;
+    if out.len() < wrapped_len {
+        return Err(error::Unspecified);
+    }
+    let out = &mut out[..wrapped_len];
+
+    // RFC 5649 Section 3.
+    let mut aiv = [0u8; SEMIBLOCK_LEN];
+    aiv[..4].copy_from_slice(&KWP_IV_PREFIX);
+    aiv[4..].copy_from_slice(&polyfill::slice::be_u8_from_u64(mli)[4..]);
+    let (padded, padding) =
+        out[SEMIBLOCK_LEN..].split_at_mut(key_data.len());
+    padded.copy_from_slice(key_data);
+    polyfill::slice::fill(padding, 0);
+
+    // RFC 5649 Section 4.1.
+    if padded_len == SEMIBLOCK_LEN {
+        let block = slice_as_array_ref_mut!(out, cipher::BLOCK_LEN).unwrap();
+        block[..SEMIBLOCK_LEN].copy_from_slice(&aiv);
+        kek.encrypt_block(block);
+    } else {
+        wrap_in_place(kek, &aiv, out);
+    }
+    Ok(out)
+}
+
+/// Unwraps `wrapped` using AES-KWP, checking its integrity; the inverse of
+/// `wrap_with_padding`.
+///
+/// `out` must be at least `wrapped.len() - SEMIBLOCK_LEN` bytes long. Returns
+/// the key data, which is written to the start of `out`. When
+/// `unwrap_with_padding` returns `Err(..)`, none of the unwrapped data is left
+/// in `out`.
+pub fn unwrap_with_padding<'a>(kek: &cipher::DecryptingKey, wrapped: &[u8],
+                               out: &'a mut [u8])
+                               -> Result<&'a [u8], error::Unspecified> {
+    if wrapped.len() < 2 * SEMIBLOCK_LEN {
+        return Err(error::Unspecified);
+    }
+    let out = unwrap_prefix(out, wrapped.len())?;
+
+    // RFC 5649 Section 4.2.
+    let a = if wrapped.len() == 2 * SEMIBLOCK_LEN {
+        let mut block = [0u8; cipher::BLOCK_LEN];
+        block.copy_from_slice(wrapped);
+        kek.decrypt_block(&mut block);
+        out.copy_from_slice(&block[SEMIBLOCK_LEN..]);
+        let mut a = [0u8; SEMIBLOCK_LEN];
+        a.copy_from_slice(&block[..SEMIBLOCK_LEN]);
+        a
+    } else {
+        unwrap_in_place(kek, wrapped, out)
+    };
+
+    // RFC 5649 Section 3. None of these checks branch on the unwrapped data,
+    // so the time taken doesn't reveal which check failed.
+    let padded_len = polyfill::u64_from_usize(out.len());
+    let mli = a[4..].iter().fold(0u64, |acc, b| (acc << 8) | u64::from(*b));
+    let mut bad = constant_time::verify_slices_are_equal(&a[..4],
+                                                         &KWP_IV_PREFIX)
+                      .is_err();
+    bad |= mli <= padded_len - SEMIBLOCK_LEN as u64;
+    bad |= mli > padded_len;
+    let mut padding = 0u8;
+    for (i, b) in out.iter().enumerate() {
+        let in_padding = polyfill::u64_from_usize(i) >= mli;
+        padding |= *b & 0u8.wrapping_sub(in_padding as u8);
+    }
+    bad |= padding != 0;
+    if bad {
+        polyfill::slice::fill(out, 0);
+        return Err(error::Unspecified);
+    }
+
+    Ok(&out[..(mli as usize)])
+}
+
+// RFC 3394 Section 2.2.3.1.
+const KW_IV: [u8; SEMIBLOCK_LEN] = [0xa6; SEMIBLOCK_LEN];
+
+// RFC 5649 Section 3.
+const KWP_IV_PREFIX: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+
+fn padded_len(len: usize) -> usize {
+    (len + (SEMIBLOCK_LEN - 1)) / SEMIBLOCK_LEN * SEMIBLOCK_LEN
+}
+
+// Returns the part of `out` that `wrapped_len` bytes of wrapped key data are
+// unwrapped into.
+fn unwrap_prefix(out: &mut [u8], wrapped_len: usize)
+                 -> Result<&mut [u8], error::Unspecified> {
+    if wrapped_len % SEMIBLOCK_LEN != 0 {
+        return Err(error::Unspecified);
+    }
+    let unwrapped_len = wrapped_len - SEMIBLOCK_LEN;
+    if out.len() < unwrapped_len {
+        return Err(error::Unspecified);
+    }
+    Ok(&mut out[..unwrapped_len])
+}
+
+// The wrapping process `W` of RFC 3394 Section 2.2.1 (in its alternative,
+// indexed form), with the initial value `iv`. `in_out[SEMIBLOCK_LEN..]` is
+// the input; the output overwrites all of `in_out`.
+fn wrap_in_place(kek: &cipher::EncryptingKey, iv: &[u8; SEMIBLOCK_LEN],
+                 in_out: &mut [u8]) {
+    let mut b = [0u8; cipher::BLOCK_LEN];
+    b[..SEMIBLOCK_LEN].copy_from_slice(iv);
+    let mut t = 0u64;
+    for _ in 0..6 {
+        for r in in_out[SEMIBLOCK_LEN..].chunks_mut(SEMIBLOCK_LEN) {
+            t += 1;
+            b[SEMIBLOCK_LEN..].copy_from_slice(r);
+            kek.encrypt_block(&mut b);
+            xor_t(&mut b, t);
+            r.copy_from_slice(&b[SEMIBLOCK_LEN..]);
+        }
+    }
+    in_out[..SEMIBLOCK_LEN].copy_from_slice(&b[..SEMIBLOCK_LEN]);
+}
+
+// The unwrapping process `W**-1` of RFC 3394 Section 2.2.2, without the
+// integrity check. `out` must be one semiblock shorter than `wrapped`.
+// Returns the recovered initial value.
+fn unwrap_in_place(kek: &cipher::DecryptingKey, wrapped: &[u8],
+                   out: &mut [u8]) -> [u8; SEMIBLOCK_LEN] {
+    debug_assert_eq!(out.len() + SEMIBLOCK_LEN, wrapped.len());
+    let mut b = [0u8; cipher::BLOCK_LEN];
+    b[..SEMIBLOCK_LEN].copy_from_slice(&wrapped[..SEMIBLOCK_LEN]);
+    out.copy_from_slice(&wrapped[SEMIBLOCK_LEN..]);
+    let mut t = 6 * polyfill::u64_from_usize(out.len() / SEMIBLOCK_LEN);
+    for _ in 0..6 {
+        for r in out.chunks_mut(SEMIBLOCK_LEN).rev() {
+            xor_t(&mut b, t);
+            t -= 1;
+            b[SEMIBLOCK_LEN..].copy_from_slice(r);
+            kek.decrypt_block(&mut b);
+            r.copy_from_slice(&b[SEMIBLOCK_LEN..]);
+        }
+    }
+    let mut a = [0u8; SEMIBLOCK_LEN];
+    a.copy_from_slice(&b[..SEMIBLOCK_LEN]);
+    a
+}
+
+fn xor_t(b: &mut [u8; cipher::BLOCK_LEN], t: u64) {
+    let t = polyfill::slice::be_u8_from_u64(t);
+    for (a, t) in b[..SEMIBLOCK_LEN].iter_mut().zip(t.iter()) {
+        *a ^= *t;
+    }
+}
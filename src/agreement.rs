@@ -79,6 +79,9 @@
 use {ec, error, rand};
 use untrusted;
 
+#[cfg(feature = "self_tests")]
+use self_test;
+
 
 pub use ec::PUBLIC_KEY_MAX_LEN;
 
@@ -112,7 +115,9 @@ impl<'a> EphemeralPrivateKey {
         // This only handles the key generation part of step 1. The rest of
         // step one is done by `compute_public_key()`.
         let private_key = ec::PrivateKey::generate(&alg.i.curve, rng)?;
-        Ok(EphemeralPrivateKey { private_key, alg })
+        let private_key = EphemeralPrivateKey { private_key, alg };
+        private_key.check_consistency()?;
+        Ok(private_key)
     }
 
     /// The key exchange algorithm.
@@ -138,6 +143,26 @@ impl<'a> EphemeralPrivateKey {
         self.private_key.compute_public_key(&self.alg.i.curve, out)
     }
 
+    // A pairwise consistency test of a generated key: the public key must be
+    // accepted as the peer's public key in a key agreement with itself.
+    #[cfg(feature = "self_tests")]
+    fn check_consistency(&self) -> Result<(), error::Unspecified> {
+        let mut public_key = [0u8; PUBLIC_KEY_MAX_LEN];
+        let public_key = &mut public_key[..self.public_key_len()];
+        let mut shared_key = [0u8; ec::ELEM_MAX_BYTES];
+        let shared_key =
+            &mut shared_key[..self.alg.i.curve.elem_and_scalar_len];
+        let result = self.compute_public_key(public_key).and_then(|()| {
+            (self.alg.i.ecdh)(shared_key, &self.private_key,
+                              untrusted::Input::from(public_key))
+        });
+        self_test::consistency_check_result(result)
+    }
+
+    #[cfg(not(feature = "self_tests"))]
+    #[inline(always)]
+    fn check_consistency(&self) -> Result<(), error::Unspecified> { Ok(()) }
+
     #[cfg(test)]
     pub fn bytes(&'a self, curve: &ec::Curve) -> &'a [u8] {
         self.private_key.bytes(curve)
@@ -0,0 +1,195 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Unkeyed BLAKE2b, as specified in [RFC 7693], for use by Argon2.
+//!
+//! [RFC 7693]: https://tools.ietf.org/html/rfc7693
+
+use {core, polyfill, zeroize};
+
+pub const BLOCK_LEN: usize = 128;
+pub const MAX_OUTPUT_LEN: usize = 64;
+
+pub struct Context {
+    h: [u64; 8],
+    t: [u64; 2],
+    buf: [u8; BLOCK_LEN],
+    buf_len: usize,
+    output_len: usize,
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.h);
+        zeroize::zeroize(&mut self.buf);
+    }
+}
+
+impl Context {
+    pub fn new(output_len: usize) -> Context {
+        assert!(output_len >= 1 && output_len <= MAX_OUTPUT_LEN);
+        let mut h = IV;
+        h[0] ^= 0x0101_0000 ^ (output_len as u64);
+        Context {
+            h,
+            t: [0, 0],
+            buf: [0u8; BLOCK_LEN],
+            buf_len: 0,
+            output_len,
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // The last block is processed differently, so a full buffer is
+            // only compressed once it is known that more data follows.
+            if self.buf_len == BLOCK_LEN {
+                self.increment_counter(BLOCK_LEN);
+                compress(&mut self.h, &self.buf, &self.t, false);
+                self.buf_len = 0;
+            }
+            let n = core::cmp::min(BLOCK_LEN - self.buf_len, data.len());
+            self.buf[self.buf_len..][..n].copy_from_slice(&data[..n]);
+            self.buf_len += n;
+            data = &data[n..];
+        }
+    }
+
+    pub fn finish(mut self, out: &mut [u8]) {
+        assert_eq!(out.len(), self.output_len);
+        let buf_len = self.buf_len;
+        self.increment_counter(buf_len);
+        polyfill::slice::fill(&mut self.buf[buf_len..], 0);
+        compress(&mut self.h, &self.buf, &self.t, true);
+
+        for (out, h) in out.chunks_mut(8).zip(self.h.iter()) {
+            let h = polyfill::slice::le_u8_from_u64(*h);
+            out.copy_from_slice(&h[..out.len()]);
+        }
+    }
+
+    fn increment_counter(&mut self, len: usize) {
+        let (t0, carry) = self.t[0].overflowing_add(len as u64);
+        self.t[0] = t0;
+        if carry {
+            self.t[1] = self.t[1].wrapping_add(1);
+        }
+    }
+}
+
+fn compress(h: &mut [u64; 8], block: &[u8; BLOCK_LEN], t: &[u64; 2],
+            last: bool) {
+    let mut m = [0u64; 16];
+    for (m, b) in m.iter_mut().zip(block.chunks(8)) {
+        *m = polyfill::slice::u64_from_le_u8(slice_as_array_ref!(b, 8).unwrap());
+    }
+
+    let mut v = [0u64; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&IV);
+    v[12] ^= t[0];
+    v[13] ^= t[1];
+    if last {
+        v[14] = !v[14];
+    }
+
+    for round in 0..12 {
+        let s = &SIGMA[round % 10];
+        g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+        g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
+
+#[inline(always)]
+fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64,
+     y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+#[cfg(test)]
+mod tests {
+    use test;
+    use std::vec::Vec;
+
+    fn blake2b(output_len: usize, input: &[u8]) -> Vec<u8> {
+        let mut ctx = super::Context::new(output_len);
+        // Feed the input in uneven pieces to exercise the buffering.
+        for chunk in input.chunks(7) {
+            ctx.update(chunk);
+        }
+        let mut out = vec![0u8; output_len];
+        ctx.finish(&mut out);
+        out
+    }
+
+    #[test]
+    fn test_blake2b() {
+        let long_input: Vec<u8> =
+            (0..512).map(|i| (i % 256) as u8).collect();
+        let vectors: &[(usize, &[u8], &str)] = &[
+            (64, b"",
+             "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419\
+              d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce"),
+            // RFC 7693 Appendix A.
+            (64, b"abc",
+             "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+              7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"),
+            (20, &long_input[..128],
+             "e6992372ab022447b34f6d6032fbab707a11adef"),
+            (32, &long_input[..],
+             "540b20132d8aeae54057cb69c24f95d26a1c472cc700dd450defe9bb796d4f14"),
+        ];
+        for &(output_len, input, expected) in vectors {
+            assert_eq!(blake2b(output_len, input),
+                       test::from_hex(expected).unwrap());
+        }
+    }
+}
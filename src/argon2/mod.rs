@@ -0,0 +1,449 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Argon2id derivation and verification.
+//!
+//! Use `derive` to derive Argon2id outputs. Use `verify` to verify secret
+//! against previously-derived outputs.
+//!
+//! Argon2 is specified in [RFC 9106], which also gives the test vectors. Only
+//! the Argon2id variant, version 0x13, is implemented. Argon2id is
+//! memory-hard: `derive` and `verify` allocate `memory_kib` KiB of memory.
+//! The lanes are computed sequentially, so `parallelism` affects the result
+//! but not the speed of the computation.
+//!
+//! [RFC 9106]: https://tools.ietf.org/html/rfc9106
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::argon2;
+//!
+//! # fn main() { f().unwrap() }
+//! # fn f() -> Result<(), ring::error::Unspecified> {
+//! // 19 MiB of memory, 2 passes, 1 lane.
+//! let params = argon2::Params::new(19 * 1024, 2, 1)?;
+//! let salt = b"unique salt for this user";
+//!
+//! let mut credential = [0u8; 32];
+//! argon2::derive(&params, salt, b"password", &mut credential);
+//!
+//! assert!(argon2::verify(&params, salt, b"password", &credential).is_ok());
+//! assert!(argon2::verify(&params, salt, b"passw0rd", &credential).is_err());
+//! # Ok(())
+//! # }
+//! ```
+
+use {constant_time, error, polyfill, zeroize};
+
+mod blake2b;
+
+/// Argon2id parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Params {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+impl Params {
+    /// Validates and constructs Argon2id parameters.
+    ///
+    /// `memory_kib` is the memory size `m` in KiB, `iterations` is the number
+    /// of passes `t`, and `parallelism` is the number of lanes `p`.
+    /// `parallelism` must be between 1 and `2**24 - 1`, `iterations` must be
+    /// at least 1, `memory_kib` must be at least `8 * parallelism`, and the
+    /// memory must be addressable.
+    pub fn new(memory_kib: u32, iterations: u32, parallelism: u32)
+               -> Result<Params, error::Unspecified> {
+        if parallelism < 1 || parallelism > 0x00ff_ffff || iterations < 1 {
+            return Err(error::Unspecified);
+        }
+        if u64::from(memory_kib) < 8 * u64::from(parallelism) {
+            return Err(error::Unspecified);
+        }
+        let params = Params { memory_kib, iterations, parallelism };
+        let _ = (params.memory_blocks() as usize).checked_mul(BLOCK_LEN)
+            .ok_or(error::Unspecified)?;
+        Ok(params)
+    }
+
+    /// The memory size `m`, in KiB.
+    #[inline]
+    pub fn memory_kib(&self) -> u32 { self.memory_kib }
+
+    /// The number of passes `t`.
+    #[inline]
+    pub fn iterations(&self) -> u32 { self.iterations }
+
+    /// The number of lanes `p`.
+    #[inline]
+    pub fn parallelism(&self) -> u32 { self.parallelism }
+
+    // m', the number of blocks actually used.
+    fn memory_blocks(&self) -> u32 {
+        self.lane_len() * self.parallelism
+    }
+
+    // q, the number of blocks in each lane.
+    fn lane_len(&self) -> u32 {
+        SYNC_POINTS * (self.memory_kib / (SYNC_POINTS * self.parallelism))
+    }
+}
+
+/// Fills `out` with the key derived using Argon2id with the given inputs.
+///
+/// # Panics
+///
+/// `derive` panics if `out.len()` is less than 4, if `salt.len()` is less
+/// than 8, or if any input is longer than `2**32 - 1` bytes.
+pub fn derive(params: &Params, salt: &[u8], secret: &[u8], out: &mut [u8]) {
+    derive_keyed(params, &[], &[], salt, secret, out)
+}
+
+/// Like `derive`, but also with the optional secret value `key` (K) and
+/// `associated_data` (X) inputs of Argon2.
+///
+/// # Panics
+///
+/// `derive_keyed` panics under the same conditions as `derive`.
+pub fn derive_keyed(params: &Params, key: &[u8], associated_data: &[u8],
+                    salt: &[u8], secret: &[u8], out: &mut [u8]) {
+    assert!(out.len() >= 4);
+    assert!(salt.len() >= 8);
+
+    let lanes = params.parallelism as usize;
+    let lane_len = params.lane_len() as usize;
+
+    // H_0, followed by space for the block and lane indexes.
+    let mut h0 = [0u8; blake2b::MAX_OUTPUT_LEN + 8];
+    {
+        let mut ctx = blake2b::Context::new(blake2b::MAX_OUTPUT_LEN);
+        ctx.update(&le_u32(params.parallelism));
+        ctx.update(&le_len(out));
+        ctx.update(&le_u32(params.memory_kib));
+        ctx.update(&le_u32(params.iterations));
+        ctx.update(&le_u32(VERSION));
+        ctx.update(&le_u32(ARGON2ID));
+        for input in &[secret, salt, key, associated_data] {
+            ctx.update(&le_len(input));
+            ctx.update(input);
+        }
+        ctx.finish(&mut h0[..blake2b::MAX_OUTPUT_LEN]);
+    }
+
+    let mut memory = vec![0u64; lanes * lane_len * BLOCK_WORDS];
+
+    let mut block_bytes = [0u8; BLOCK_LEN];
+    for lane in 0..lanes {
+        for i in 0..2 {
+            h0[64..68].copy_from_slice(&le_u32(i as u32));
+            h0[68..72].copy_from_slice(&le_u32(lane as u32));
+            h_prime(&h0, &mut block_bytes);
+            let block = block_mut(&mut memory, lane * lane_len + i);
+            for (w, b) in block.iter_mut().zip(block_bytes.chunks(8)) {
+                *w = polyfill::slice::u64_from_le_u8(
+                    slice_as_array_ref!(b, 8).unwrap());
+            }
+        }
+    }
+
+    for pass in 0..params.iterations {
+        for slice in 0..SYNC_POINTS {
+            for lane in 0..lanes {
+                let position = Position { pass, slice, lane };
+                fill_segment(params, &position, &mut memory);
+            }
+        }
+    }
+
+    let mut c = [0u64; BLOCK_WORDS];
+    for lane in 0..lanes {
+        let last = block(&memory, lane * lane_len + lane_len - 1);
+        for (c, w) in c.iter_mut().zip(last.iter()) {
+            *c ^= *w;
+        }
+    }
+    for (b, c) in block_bytes.chunks_mut(8).zip(c.iter()) {
+        b.copy_from_slice(&polyfill::slice::le_u8_from_u64(*c));
+    }
+    h_prime(&block_bytes, out);
+
+    // All of these were derived from the password.
+    zeroize::zeroize(&mut memory);
+    zeroize::zeroize(&mut c);
+    zeroize::zeroize(&mut block_bytes);
+    zeroize::zeroize(&mut h0);
+}
+
+/// Verifies that a previously-derived (e.g., using `derive`) Argon2id key
+/// matches the Argon2id key derived from the other inputs.
+///
+/// The comparison is done in constant time to prevent timing attacks. The
+/// comparison will fail if `previously_derived` is shorter than 4 bytes or if
+/// `salt` is shorter than 8 bytes.
+pub fn verify(params: &Params, salt: &[u8], secret: &[u8],
+              previously_derived: &[u8]) -> Result<(), error::Unspecified> {
+    verify_keyed(params, &[], &[], salt, secret, previously_derived)
+}
+
+/// Like `verify`, but also with the optional secret value `key` (K) and
+/// `associated_data` (X) inputs of Argon2.
+pub fn verify_keyed(params: &Params, key: &[u8], associated_data: &[u8],
+                    salt: &[u8], secret: &[u8], previously_derived: &[u8])
+                    -> Result<(), error::Unspecified> {
+    if previously_derived.len() < 4 || salt.len() < 8 {
+        return Err(error::Unspecified);
+    }
+    for input in &[key, associated_data, salt, secret, previously_derived] {
+        if input.len() as u64 > 0xffff_ffff {
+            return Err(error::Unspecified);
+        }
+    }
+    let mut derived = vec![0u8; previously_derived.len()];
+    derive_keyed(params, key, associated_data, salt, secret, &mut derived);
+    constant_time::verify_slices_are_equal(&derived, previously_derived)
+}
+
+struct Position {
+    pass: u32,
+    slice: u32,
+    lane: usize,
+}
+
+fn fill_segment(params: &Params, position: &Position, memory: &mut [u64]) {
+    let lanes = params.parallelism as usize;
+    let lane_len = params.lane_len() as usize;
+    let segment_len = lane_len / (SYNC_POINTS as usize);
+
+    // Argon2id uses data-independent addressing for the first half of the
+    // first pass and data-dependent addressing otherwise.
+    let data_independent = position.pass == 0 && position.slice < 2;
+
+    let zero_block = [0u64; BLOCK_WORDS];
+    let mut input_block = [0u64; BLOCK_WORDS];
+    let mut address_block = [0u64; BLOCK_WORDS];
+    if data_independent {
+        input_block[0] = u64::from(position.pass);
+        input_block[1] = position.lane as u64;
+        input_block[2] = u64::from(position.slice);
+        input_block[3] = u64::from(params.memory_blocks());
+        input_block[4] = u64::from(params.iterations);
+        input_block[5] = u64::from(ARGON2ID);
+    }
+
+    // The first two blocks of each lane were filled from H_0.
+    let start = if position.pass == 0 && position.slice == 0 {
+        if data_independent {
+            next_addresses(&mut address_block, &mut input_block, &zero_block);
+        }
+        2
+    } else {
+        0
+    };
+
+    let lane_start = position.lane * lane_len;
+    for index in start..segment_len {
+        let curr = (position.slice as usize) * segment_len + index;
+        let prev = if curr == 0 { lane_len - 1 } else { curr - 1 };
+
+        let pseudo_rand = if data_independent {
+            if index % BLOCK_WORDS == 0 {
+                next_addresses(&mut address_block, &mut input_block,
+                               &zero_block);
+            }
+            address_block[index % BLOCK_WORDS]
+        } else {
+            block(memory, lane_start + prev)[0]
+        };
+
+        let ref_lane = if position.pass == 0 && position.slice == 0 {
+            position.lane
+        } else {
+            ((pseudo_rand >> 32) as usize) % lanes
+        };
+        let ref_index = index_alpha(position, index, segment_len, lane_len,
+                                    pseudo_rand & 0xffff_ffff,
+                                    ref_lane == position.lane);
+
+        let new_block = compress(block(memory, lane_start + prev),
+                                 block(memory, ref_lane * lane_len + ref_index));
+        let curr = block_mut(memory, lane_start + curr);
+        if position.pass == 0 {
+            curr.copy_from_slice(&new_block);
+        } else {
+            for (c, n) in curr.iter_mut().zip(new_block.iter()) {
+                *c ^= *n;
+            }
+        }
+    }
+}
+
+// Maps the pseudo-random value `j1` to the index of the reference block
+// within its lane.
+fn index_alpha(position: &Position, index: usize, segment_len: usize,
+               lane_len: usize, j1: u64, same_lane: bool) -> usize {
+    let slice = position.slice as usize;
+
+    // The number of blocks that may be referenced. The block being computed
+    // and the block before it are never referenced; if the reference is to
+    // another lane then its current segment may not be referenced either.
+    let finished_blocks = if position.pass == 0 {
+        slice * segment_len
+    } else {
+        lane_len - segment_len
+    };
+    let reference_area_size = if same_lane {
+        finished_blocks + index - 1
+    } else if index == 0 {
+        finished_blocks - 1
+    } else {
+        finished_blocks
+    };
+
+    let x = (j1 * j1) >> 32;
+    let y = ((reference_area_size as u64) * x) >> 32;
+    let relative_position = reference_area_size - 1 - (y as usize);
+
+    let start_position =
+        if position.pass == 0 || position.slice == SYNC_POINTS - 1 {
+            0
+        } else {
+            (slice + 1) * segment_len
+        };
+
+    (start_position + relative_position) % lane_len
+}
+
+fn next_addresses(address_block: &mut [u64; BLOCK_WORDS],
+                  input_block: &mut [u64; BLOCK_WORDS],
+                  zero_block: &[u64; BLOCK_WORDS]) {
+    input_block[6] += 1;
+    let tmp = compress(zero_block, input_block);
+    *address_block = compress(zero_block, &tmp);
+}
+
+// The compression function G.
+fn compress(x: &[u64], y: &[u64]) -> [u64; BLOCK_WORDS] {
+    let mut r = [0u64; BLOCK_WORDS];
+    for ((r, x), y) in r.iter_mut().zip(x.iter()).zip(y.iter()) {
+        *r = *x ^ *y;
+    }
+
+    let mut z = r;
+    for i in 0..8 {
+        let row = [16 * i, 16 * i + 1, 16 * i + 2, 16 * i + 3,
+                   16 * i + 4, 16 * i + 5, 16 * i + 6, 16 * i + 7,
+                   16 * i + 8, 16 * i + 9, 16 * i + 10, 16 * i + 11,
+                   16 * i + 12, 16 * i + 13, 16 * i + 14, 16 * i + 15];
+        permute(&mut z, &row);
+    }
+    for i in 0..8 {
+        let column = [2 * i, 2 * i + 1, 2 * i + 16, 2 * i + 17,
+                      2 * i + 32, 2 * i + 33, 2 * i + 48, 2 * i + 49,
+                      2 * i + 64, 2 * i + 65, 2 * i + 80, 2 * i + 81,
+                      2 * i + 96, 2 * i + 97, 2 * i + 112, 2 * i + 113];
+        permute(&mut z, &column);
+    }
+
+    for (z, r) in z.iter_mut().zip(r.iter()) {
+        *z ^= *r;
+    }
+    z
+}
+
+// The permutation P, applied to the 16 words of `v` at the indexes `i`.
+fn permute(v: &mut [u64; BLOCK_WORDS], i: &[usize; 16]) {
+    gb(v, i[0], i[4], i[8], i[12]);
+    gb(v, i[1], i[5], i[9], i[13]);
+    gb(v, i[2], i[6], i[10], i[14]);
+    gb(v, i[3], i[7], i[11], i[15]);
+    gb(v, i[0], i[5], i[10], i[15]);
+    gb(v, i[1], i[6], i[11], i[12]);
+    gb(v, i[2], i[7], i[8], i[13]);
+    gb(v, i[3], i[4], i[9], i[14]);
+}
+
+#[inline(always)]
+fn gb(v: &mut [u64; BLOCK_WORDS], a: usize, b: usize, c: usize, d: usize) {
+    v[a] = bla_mka(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = bla_mka(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = bla_mka(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = bla_mka(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+#[inline(always)]
+fn bla_mka(x: u64, y: u64) -> u64 {
+    let xy = (x & 0xffff_ffff) * (y & 0xffff_ffff);
+    x.wrapping_add(y).wrapping_add(xy.wrapping_mul(2))
+}
+
+// The variable-length hash function H'.
+fn h_prime(input: &[u8], out: &mut [u8]) {
+    if out.len() <= blake2b::MAX_OUTPUT_LEN {
+        let mut ctx = blake2b::Context::new(out.len());
+        ctx.update(&le_len(out));
+        ctx.update(input);
+        ctx.finish(out);
+        return;
+    }
+
+    const HALF_LEN: usize = blake2b::MAX_OUTPUT_LEN / 2;
+
+    let mut v = [0u8; blake2b::MAX_OUTPUT_LEN];
+    let mut ctx = blake2b::Context::new(blake2b::MAX_OUTPUT_LEN);
+    ctx.update(&le_len(out));
+    ctx.update(input);
+    ctx.finish(&mut v);
+    out[..HALF_LEN].copy_from_slice(&v[..HALF_LEN]);
+
+    let mut pos = HALF_LEN;
+    while out.len() - pos > blake2b::MAX_OUTPUT_LEN {
+        let mut ctx = blake2b::Context::new(blake2b::MAX_OUTPUT_LEN);
+        ctx.update(&v);
+        ctx.finish(&mut v);
+        out[pos..][..HALF_LEN].copy_from_slice(&v[..HALF_LEN]);
+        pos += HALF_LEN;
+    }
+
+    let mut ctx = blake2b::Context::new(out.len() - pos);
+    ctx.update(&v);
+    ctx.finish(&mut out[pos..]);
+}
+
+fn block(memory: &[u64], i: usize) -> &[u64] {
+    &memory[(i * BLOCK_WORDS)..][..BLOCK_WORDS]
+}
+
+fn block_mut(memory: &mut [u64], i: usize) -> &mut [u64] {
+    &mut memory[(i * BLOCK_WORDS)..][..BLOCK_WORDS]
+}
+
+fn le_u32(value: u32) -> [u8; 4] { polyfill::slice::le_u8_from_u32(value) }
+
+// The length of `input` as a `LE32`.
+fn le_len(input: &[u8]) -> [u8; 4] {
+    assert!(input.len() as u64 <= 0xffff_ffff);
+    le_u32(input.len() as u32)
+}
+
+const BLOCK_WORDS: usize = 128;
+const BLOCK_LEN: usize = BLOCK_WORDS * 8;
+const SYNC_POINTS: u32 = 4;
+const VERSION: u32 = 0x13;
+const ARGON2ID: u32 = 2;
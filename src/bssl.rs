@@ -27,7 +27,7 @@ pub fn map_result(bssl_result: c::int) -> Result<(), error::Unspecified> {
 // were all executables with their own `main` functions. Those main functions
 // have been replaced with uniquely-named functions so that they can all be
 // linked into the same executable.
-#[cfg(test)]
+#[cfg(all(test, not(feature = "pure_rust")))]
 macro_rules! bssl_test {
     ( $fn_name:ident, $bssl_test_main_fn_name:ident ) => {
         #[test]
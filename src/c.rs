@@ -40,7 +40,8 @@ macro_rules! define_metrics_tests {
     ( $name:ident, $test_c_metrics:ident, $c_align:ident, $c_size:ident,
       $expected_align_factor:expr ) =>
     {
-        #[cfg(test)]
+        // The `pure_rust` build has no C code to compare against.
+        #[cfg(all(test, not(feature = "pure_rust")))]
         extern {
             // We can't use `size_t` because we need to test that our
             // definition of `size_t` is correct using this code! We use `u16`
@@ -51,7 +52,7 @@ macro_rules! define_metrics_tests {
             static $c_size: u16;
         }
 
-        #[cfg(test)]
+        #[cfg(all(test, not(feature = "pure_rust")))]
         #[test]
         fn $test_c_metrics() {
             use std::mem;
@@ -149,7 +150,7 @@ define_metrics_tests!(i32, test_i32_metrics, GFp_int32_t_align,
 define_metrics_tests!(u32, test_u32_metrics, GFp_uint32_t_align,
                       GFp_uint32_t_size);
 
-#[cfg(all(test, not(any(
+#[cfg(all(test, not(feature = "pure_rust"), not(any(
             all(target_arch = "x86", target_os = "linux"),
             all(target_arch = "x86", target_os = "macos"),
             all(target_arch = "x86", target_os = "ios"),
@@ -157,7 +158,7 @@ define_metrics_tests!(u32, test_u32_metrics, GFp_uint32_t_align,
 ))))]
 const SIXTY_FOUR_BIT_ALIGNMENT_FACTOR: usize = 1;
 
-#[cfg(all(test, any(
+#[cfg(all(test, not(feature = "pure_rust"), any(
             all(target_arch = "x86", target_os = "linux"),
             all(target_arch = "x86", target_os = "macos"),
             all(target_arch = "x86", target_os = "ios"),
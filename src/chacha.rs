@@ -13,13 +13,130 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use c;
+//! The ChaCha20 stream cipher, as specified in [RFC 7539], with a 96-bit nonce
+//! and a 32-bit block counter.
+//!
+//! **ChaCha20 by itself doesn't detect tampering with the ciphertext.** Use
+//! `aead::CHACHA20_POLY1305` unless the data is authenticated some other way,
+//! or unless the keystream is used for something other than encryption, such
+//! as a deterministic random number generator.
+//!
+//! The keystream for a key and nonce is 2**32 blocks of `BLOCK_LEN` bytes.
+//! Any range of it can be used, in any order, by choosing the starting block
+//! counter or byte offset; this allows random access to, e.g., the sectors of
+//! an encrypted disk. A given key, nonce, and offset must never be used to
+//! encrypt two different things.
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::chacha;
+//!
+//! # fn main() { f().unwrap() }
+//! # fn f() -> Result<(), ring::error::Unspecified> {
+//! let key = chacha::ChaCha20Key::new(&[0x42; chacha::KEY_LEN])?;
+//! let nonce = [0x24; chacha::NONCE_LEN];
+//!
+//! // Encrypt a message in one step...
+//! let mut message = *b"hello, world";
+//! key.xor_in_place(&nonce, 0, &mut message)?;
+//!
+//! // ...and decrypt it in two, starting in the middle of the keystream.
+//! let (hello, world) = message.split_at_mut(5);
+//! key.xor_in_place_at(&nonce, 5, world)?;
+//! key.xor_in_place_at(&nonce, 0, hello)?;
+//! assert_eq!(&message, b"hello, world");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [RFC 7539]: https://tools.ietf.org/html/rfc7539
+
+use {c, error, polyfill, zeroize};
 use core;
 use polyfill::slice::u32_from_le_u8;
 
-pub type Key = [u32; KEY_LEN_IN_BYTES / 4];
+/// The length of a ChaCha20 key.
+pub const KEY_LEN: usize = KEY_LEN_IN_BYTES;
+
+/// The length of a ChaCha20 block, which is the amount of keystream generated
+/// for each value of the block counter.
+pub const BLOCK_LEN: usize = 64;
+
+/// A ChaCha20 key.
+pub struct ChaCha20Key {
+    key: Key,
+}
+
+impl Drop for ChaCha20Key {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.key);
+    }
+}
+
+impl ChaCha20Key {
+    /// Constructs a key from `key_bytes`, which must be exactly `KEY_LEN`
+    /// bytes long.
+    pub fn new(key_bytes: &[u8]) -> Result<ChaCha20Key, error::Unspecified> {
+        let key_bytes = slice_as_array_ref!(key_bytes, KEY_LEN)?;
+        Ok(ChaCha20Key { key: key_from_bytes(key_bytes) })
+    }
 
-pub fn key_from_bytes(key_bytes: &[u8; KEY_LEN_IN_BYTES]) -> Key {
+    /// XORs the keystream for `nonce`, starting at the beginning of block
+    /// `counter`, into `in_out`.
+    ///
+    /// Fails, without modifying `in_out`, if `in_out` extends past the end of
+    /// the keystream.
+    pub fn xor_in_place(&self, nonce: &[u8; NONCE_LEN], counter: u32,
+                        in_out: &mut [u8]) -> Result<(), error::Unspecified> {
+        self.xor_in_place_at(nonce, u64::from(counter) * (BLOCK_LEN as u64),
+                             in_out)
+    }
+
+    /// XORs the keystream for `nonce`, starting at byte `offset` of the
+    /// keystream, into `in_out`.
+    ///
+    /// `offset` need not be a multiple of `BLOCK_LEN`. Fails, without
+    /// modifying `in_out`, if `in_out` extends past the end of the keystream.
+    pub fn xor_in_place_at(&self, nonce: &[u8; NONCE_LEN], offset: u64,
+                           in_out: &mut [u8])
+                           -> Result<(), error::Unspecified> {
+        let end = offset.checked_add(polyfill::u64_from_usize(in_out.len()))
+            .ok_or(error::Unspecified)?;
+        if end > MAX_KEYSTREAM_LEN {
+            return Err(error::Unspecified);
+        }
+
+        let block = (offset / (BLOCK_LEN as u64)) as u32;
+        let skip = (offset % (BLOCK_LEN as u64)) as usize;
+        let mut in_out = in_out;
+        let mut counter = make_counter(nonce, block);
+        if skip != 0 {
+            // Generate the whole first block and use only its end.
+            let mut keystream = [0u8; BLOCK_LEN];
+            chacha20_xor_in_place(&self.key, &counter, &mut keystream);
+            let n = core::cmp::min(BLOCK_LEN - skip, in_out.len());
+            for (b, k) in in_out[..n].iter_mut().zip(&keystream[skip..]) {
+                *b ^= *k;
+            }
+            zeroize::zeroize(&mut keystream);
+            in_out = &mut {in_out}[n..];
+            if in_out.is_empty() {
+                return Ok(());
+            }
+            counter = make_counter(nonce, block + 1);
+        }
+        chacha20_xor_in_place(&self.key, &counter, in_out);
+        Ok(())
+    }
+}
+
+// The 32-bit block counter must not wrap around.
+const MAX_KEYSTREAM_LEN: u64 = (1 << 32) * (BLOCK_LEN as u64);
+
+pub(crate) type Key = [u32; KEY_LEN_IN_BYTES / 4];
+
+pub(crate) fn key_from_bytes(key_bytes: &[u8; KEY_LEN_IN_BYTES]) -> Key {
     let mut key = [0u32; KEY_LEN_IN_BYTES / 4];
     for (key_u32, key_u8_4) in key.iter_mut().zip(key_bytes.chunks(4)) {
         *key_u32 = u32_from_le_u8(slice_as_array_ref!(key_u8_4, 4).unwrap());
@@ -28,13 +145,15 @@ pub fn key_from_bytes(key_bytes: &[u8; KEY_LEN_IN_BYTES]) -> Key {
 }
 
 #[inline]
-pub fn chacha20_xor_in_place(key: &Key, counter: &Counter, in_out: &mut [u8]) {
+pub(crate) fn chacha20_xor_in_place(key: &Key, counter: &Counter,
+                                    in_out: &mut [u8]) {
     chacha20_xor_inner(key, counter, in_out.as_ptr(), in_out.len(),
                        in_out.as_mut_ptr());
 }
 
-pub fn chacha20_xor_overlapping(key: &Key, counter: &Counter,
-                                in_out: &mut [u8], in_prefix_len: usize) {
+pub(crate) fn chacha20_xor_overlapping(key: &Key, counter: &Counter,
+                                       in_out: &mut [u8],
+                                       in_prefix_len: usize) {
     // XXX: The x86 and at least one branch of the ARM assembly language
     // code doesn't allow overlapping input and output unless they are
     // exactly overlapping. TODO: Figure out which branch of the ARM code
@@ -56,8 +175,9 @@ pub fn chacha20_xor_overlapping(key: &Key, counter: &Counter,
 }
 
 #[inline]
-pub fn chacha20_xor_inner(key: &Key, counter: &Counter, input: *const u8,
-                          in_out_len: usize, output: *mut u8) {
+pub(crate) fn chacha20_xor_inner(key: &Key, counter: &Counter,
+                                 input: *const u8, in_out_len: usize,
+                                 output: *mut u8) {
     debug_assert!(core::mem::align_of_val(key) >= 4);
     debug_assert!(core::mem::align_of_val(counter) >= 4);
     unsafe {
@@ -65,23 +185,86 @@ pub fn chacha20_xor_inner(key: &Key, counter: &Counter, input: *const u8,
     }
 }
 
-pub type Counter = [u32; 4];
+pub(crate) type Counter = [u32; 4];
 
 #[inline]
-pub fn make_counter(nonce: &[u8; NONCE_LEN], counter: u32) -> Counter {
+pub(crate) fn make_counter(nonce: &[u8; NONCE_LEN], counter: u32) -> Counter {
     [counter.to_le(),
      u32_from_le_u8(slice_as_array_ref!(&nonce[0..4], 4).unwrap()),
      u32_from_le_u8(slice_as_array_ref!(&nonce[4..8], 4).unwrap()),
      u32_from_le_u8(slice_as_array_ref!(&nonce[8..12], 4).unwrap())]
 }
 
+#[cfg(not(feature = "pure_rust"))]
 extern {
     fn GFp_ChaCha20_ctr32(out: *mut u8, in_: *const u8, in_len: c::size_t,
                           key: &Key, counter: &Counter);
 }
 
-pub const KEY_LEN_IN_BYTES: usize = 256 / 8;
+// A portable implementation of `GFp_ChaCha20_ctr32`, for builds with the
+// `pure_rust` feature. Like the assembly language code, it allows `out` to
+// overlap `in_` as long as `out <= in_`: each block of input is read before
+// the corresponding block of output is written.
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe fn GFp_ChaCha20_ctr32(out: *mut u8, in_: *const u8, in_len: c::size_t,
+                             key: &Key, counter: &Counter) {
+    let mut state = [
+        0x61707865, 0x3320646e, 0x79622d32, 0x6b206574,
+        key[0], key[1], key[2], key[3],
+        key[4], key[5], key[6], key[7],
+        u32::from_le(counter[0]), counter[1], counter[2], counter[3],
+    ];
+
+    let mut offset = 0;
+    while offset < in_len {
+        let mut x = state;
+        for _ in 0..10 {
+            quarter_round(&mut x, 0, 4, 8, 12);
+            quarter_round(&mut x, 1, 5, 9, 13);
+            quarter_round(&mut x, 2, 6, 10, 14);
+            quarter_round(&mut x, 3, 7, 11, 15);
+            quarter_round(&mut x, 0, 5, 10, 15);
+            quarter_round(&mut x, 1, 6, 11, 12);
+            quarter_round(&mut x, 2, 7, 8, 13);
+            quarter_round(&mut x, 3, 4, 9, 14);
+        }
+
+        let mut keystream = [0u8; BLOCK_LEN];
+        for (i, (x, s)) in x.iter().zip(state.iter()).enumerate() {
+            keystream[(i * 4)..][..4].copy_from_slice(
+                &polyfill::slice::le_u8_from_u32(x.wrapping_add(*s)));
+        }
+
+        let n = core::cmp::min(BLOCK_LEN, in_len - offset);
+        let mut block = [0u8; BLOCK_LEN];
+        core::ptr::copy(in_.offset(offset as isize), block.as_mut_ptr(), n);
+        for (b, k) in block[..n].iter_mut().zip(keystream.iter()) {
+            *b ^= *k;
+        }
+        core::ptr::copy(block.as_ptr(), out.offset(offset as isize), n);
+
+        state[12] = state[12].wrapping_add(1);
+        offset += n;
+    }
+}
+
+#[cfg(feature = "pure_rust")]
+#[inline(always)]
+fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(16);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(12);
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(8);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(7);
+}
+
+pub(crate) const KEY_LEN_IN_BYTES: usize = 256 / 8;
 
+/// The length of a ChaCha20 nonce.
 pub const NONCE_LEN: usize = 12; /* 96 bits */
 
 #[cfg(test)]
@@ -102,7 +285,7 @@ mod tests {
     // problem spreads to other platforms.
     #[test]
     pub fn chacha20_tests() {
-        test::from_file("src/chacha_tests.txt", |section, test_case| {
+        test::from_file("tests/chacha_tests.txt", |section, test_case| {
             assert_eq!(section, "");
 
             let key = test_case.consume_bytes("Key");
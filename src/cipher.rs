@@ -0,0 +1,358 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Unauthenticated AES encryption: CTR mode, CBC mode with PKCS#7 padding,
+//! and the raw block cipher.
+//!
+//! **Nothing in this module detects tampering with the ciphertext.** These
+//! modes exist only for interoperability with legacy protocols and formats,
+//! such as CMS, SSH's `aes128-ctr`, and disk encryption formats, that
+//! authenticate their data some other way, if at all. Use `ring::aead` for
+//! anything else.
+//!
+//! Encryption uses the same AES implementation as `aead::AES_128_GCM` and
+//! `aead::AES_256_GCM`. Decryption (`DecryptingKey`) uses a constant-time
+//! implementation that is much slower.
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::cipher;
+//!
+//! # fn main() { f().unwrap() }
+//! # fn f() -> Result<(), ring::error::Unspecified> {
+//! let key_bytes = [0x42; 16];
+//! let iv = [0x24; cipher::BLOCK_LEN];
+//!
+//! let mut in_out = b"legacy data".to_vec();
+//! let plaintext_len = in_out.len();
+//! in_out.extend_from_slice(&[0u8; cipher::BLOCK_LEN]);
+//!
+//! let key = cipher::EncryptingKey::new(&cipher::AES_128, &key_bytes)?;
+//! let ciphertext_len =
+//!     cipher::cbc_encrypt_in_place(&key, &iv, &mut in_out, plaintext_len)?;
+//!
+//! let key = cipher::DecryptingKey::new(&cipher::AES_128, &key_bytes)?;
+//! let plaintext =
+//!     cipher::cbc_decrypt_in_place(&key, &iv, &mut in_out[..ciphertext_len])?;
+//! assert_eq!(plaintext, b"legacy data");
+//! # Ok(())
+//! # }
+//! ```
+
+use {core, error, init, polyfill, zeroize};
+use aead::aes;
+
+/// The length of an AES block, and of CTR and CBC initialization vectors.
+pub const BLOCK_LEN: usize = aes::BLOCK_LEN;
+
+/// A block cipher algorithm.
+pub struct Algorithm {
+    key_len: usize,
+    id: AlgorithmID,
+}
+
+/// AES with 128-bit keys.
+pub static AES_128: Algorithm = Algorithm {
+    key_len: aes::AES_128_KEY_LEN,
+    id: AlgorithmID::AES_128,
+};
+
+/// AES with 256-bit keys.
+pub static AES_256: Algorithm = Algorithm {
+    key_len: aes::AES_256_KEY_LEN,
+    id: AlgorithmID::AES_256,
+};
+
+impl Algorithm {
+    /// The length of the key.
+    #[inline(always)]
+    pub fn key_len(&self) -> usize { self.key_len }
+}
+
+derive_debug_from_field!(Algorithm, id);
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Eq, PartialEq)]
+enum AlgorithmID {
+    AES_128,
+    AES_256,
+}
+
+impl PartialEq for Algorithm {
+    fn eq(&self, other: &Self) -> bool { self.id == other.id }
+}
+
+impl Eq for Algorithm {}
+
+/// A key for encrypting blocks, for CTR mode (in both directions), and for
+/// CBC encryption.
+pub struct EncryptingKey {
+    ctx_buf: [u64; aes::KEY_BUF_ELEMS],
+    algorithm: &'static Algorithm,
+}
+
+impl Drop for EncryptingKey {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.ctx_buf);
+    }
+}
+
+impl EncryptingKey {
+    /// Constructs a new key.
+    ///
+    /// `key_bytes` must be exactly `algorithm.key_len()` bytes long.
+    pub fn new(algorithm: &'static Algorithm, key_bytes: &[u8])
+               -> Result<EncryptingKey, error::Unspecified> {
+        if key_bytes.len() != algorithm.key_len() {
+            return Err(error::Unspecified);
+        }
+        let mut key = EncryptingKey {
+            ctx_buf: [0; aes::KEY_BUF_ELEMS],
+            algorithm,
+        };
+        init::init_once();
+        aes::init(polyfill::slice::u64_as_u8_mut(&mut key.ctx_buf),
+                  key_bytes)?;
+        Ok(key)
+    }
+
+    /// The key's algorithm.
+    #[inline(always)]
+    pub fn algorithm(&self) -> &'static Algorithm { self.algorithm }
+
+    /// Encrypts a single block in place, with no mode of operation.
+    #[inline]
+    pub fn encrypt_block(&self, block: &mut [u8; BLOCK_LEN]) {
+        aes::encrypt_block(&self.ctx_buf, block);
+    }
+}
+
+/// A key for decrypting blocks and for CBC decryption.
+pub struct DecryptingKey {
+    key: aes::DecryptKey,
+    algorithm: &'static Algorithm,
+}
+
+impl DecryptingKey {
+    /// Constructs a new key.
+    ///
+    /// `key_bytes` must be exactly `algorithm.key_len()` bytes long.
+    pub fn new(algorithm: &'static Algorithm, key_bytes: &[u8])
+               -> Result<DecryptingKey, error::Unspecified> {
+        if key_bytes.len() != algorithm.key_len() {
+            return Err(error::Unspecified);
+        }
+        Ok(DecryptingKey {
+            key: aes::DecryptKey::new(key_bytes)?,
+            algorithm,
+        })
+    }
+
+    /// The key's algorithm.
+    #[inline(always)]
+    pub fn algorithm(&self) -> &'static Algorithm { self.algorithm }
+
+    /// Decrypts a single block in place, with no mode of operation.
+    #[inline]
+    pub fn decrypt_block(&self, block: &mut [u8; BLOCK_LEN]) {
+        self.key.decrypt_block(block)
+    }
+}
+
+/// A CTR mode keystream, as specified in [NIST SP 800-38A] Section 6.5.
+///
+/// The whole 128-bit counter block is incremented as a big-endian integer, as
+/// in SSH's `aes128-ctr` and `aes256-ctr` ([RFC 4344]). Encryption and
+/// decryption are the same operation.
+///
+/// [NIST SP 800-38A]: http://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38a.pdf
+/// [RFC 4344]: https://tools.ietf.org/html/rfc4344
+pub struct CtrContext<'a> {
+    key: &'a EncryptingKey,
+    counter: [u8; BLOCK_LEN],
+    keystream: [u8; BLOCK_LEN],
+    keystream_used: usize,
+}
+
+impl<'a> CtrContext<'a> {
+    /// Starts a keystream with the initial counter block `iv`.
+    ///
+    /// The same key and initial counter block must never be used to encrypt
+    /// two different messages, and the counter ranges used with a key must
+    /// never overlap.
+    pub fn new(key: &'a EncryptingKey, iv: &[u8; BLOCK_LEN]) -> Self {
+        CtrContext {
+            key,
+            counter: *iv,
+            keystream: [0; BLOCK_LEN],
+            keystream_used: BLOCK_LEN,
+        }
+    }
+
+    /// XORs the next `in_out.len()` bytes of the keystream into `in_out`.
+    ///
+    /// A message may be processed in pieces of any length by calling this
+    /// repeatedly.
+    pub fn xor_in_place(&mut self, in_out: &mut [u8]) {
+        let mut in_out = in_out;
+        while !in_out.is_empty() {
+            if self.keystream_used == BLOCK_LEN && in_out.len() >= BLOCK_LEN {
+                // Encrypt whole blocks together, stopping before the block
+                // whose counter ends in 0xffffffff, because
+                // `aes::ctr32_encrypt_blocks` increments only the last 32
+                // bits of the counter.
+                let low = polyfill::slice::u32_from_be_u8(
+                    slice_as_array_ref!(&self.counter[12..], 4).unwrap());
+                let blocks = core::cmp::min(in_out.len() / BLOCK_LEN,
+                                            (0xffff_ffff - low) as usize);
+                if blocks > 0 {
+                    let (whole, rest) =
+                        {in_out}.split_at_mut(blocks * BLOCK_LEN);
+                    aes::ctr32_encrypt_blocks(&self.key.ctx_buf, whole,
+                                              &self.counter);
+                    add_be(&mut self.counter, blocks as u32);
+                    in_out = rest;
+                    continue;
+                }
+            }
+            if self.keystream_used == BLOCK_LEN {
+                self.keystream = self.counter;
+                self.key.encrypt_block(&mut self.keystream);
+                add_be(&mut self.counter, 1);
+                self.keystream_used = 0;
+            }
+            let n = core::cmp::min(BLOCK_LEN - self.keystream_used,
+                                   in_out.len());
+            let keystream = &self.keystream[self.keystream_used..][..n];
+            for (b, k) in in_out[..n].iter_mut().zip(keystream.iter()) {
+                *b ^= *k;
+            }
+            self.keystream_used += n;
+            in_out = &mut {in_out}[n..];
+        }
+    }
+}
+
+fn add_be(counter: &mut [u8; BLOCK_LEN], n: u32) {
+    let mut carry = u64::from(n);
+    for b in counter.iter_mut().rev() {
+        carry += u64::from(*b);
+        *b = carry as u8;
+        carry >>= 8;
+    }
+}
+
+/// Encrypts a message in place in CBC mode, as specified in
+/// [NIST SP 800-38A] Section 6.2, after padding it as specified in
+/// [RFC 5652 Section 6.3] (PKCS#7).
+///
+/// The message is `in_out[..plaintext_len]`; the rest of `in_out` must have
+/// room for the padding, which is between 1 and `BLOCK_LEN` bytes long. When
+/// `cbc_encrypt_in_place` returns `Ok(ciphertext_len)`, the ciphertext is
+/// `in_out[..ciphertext_len]`.
+///
+/// `iv` must be unpredictable (e.g. generated with a `rand::SecureRandom`)
+/// and must not be reused with the same key.
+///
+/// [NIST SP 800-38A]: http://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38a.pdf
+/// [RFC 5652 Section 6.3]: https://tools.ietf.org/html/rfc5652#section-6.3
+pub fn cbc_encrypt_in_place(key: &EncryptingKey, iv: &[u8; BLOCK_LEN],
+                            in_out: &mut [u8], plaintext_len: usize)
+                            -> Result<usize, error::Unspecified> {
+    let padding_len = BLOCK_LEN - (plaintext_len % BLOCK_LEN);
+    let ciphertext_len =
+        plaintext_len.checked_add(padding_len).ok_or(error::Unspecified)?;
+    if ciphertext_len > in_out.len() {
+        return Err(error::Unspecified);
+    }
+    let in_out = &mut in_out[..ciphertext_len];
+    polyfill::slice::fill(&mut in_out[plaintext_len..], padding_len as u8);
+
+    let mut chain = *iv;
+    for block in in_out.chunks_mut(BLOCK_LEN) {
+        for (c, b) in chain.iter_mut().zip(block.iter()) {
+            *c ^= *b;
+        }
+        key.encrypt_block(&mut chain);
+        block.copy_from_slice(&chain);
+    }
+
+    Ok(ciphertext_len)
+}
+
+/// Decrypts a message in place in CBC mode and removes its PKCS#7 padding;
+/// the inverse of `cbc_encrypt_in_place`.
+///
+/// `ciphertext` must be a non-zero multiple of `BLOCK_LEN` bytes long. When
+/// `cbc_decrypt_in_place` returns `Ok(plaintext)`, `plaintext` is
+/// `&mut ciphertext[..plaintext.len()]`.
+///
+/// The padding is checked in constant time, so the time taken doesn't reveal
+/// why the padding is invalid. Still, the result of the check is revealed by
+/// the return value: a peer that can submit modified ciphertexts and learn
+/// whether they were accepted can decrypt them (a “padding oracle” attack).
+/// Authenticate the ciphertext before decrypting it.
+///
+/// When `cbc_decrypt_in_place` returns `Err(..)`, `ciphertext` has been
+/// zeroed.
+pub fn cbc_decrypt_in_place<'a>(key: &DecryptingKey, iv: &[u8; BLOCK_LEN],
+                                ciphertext: &'a mut [u8])
+                                -> Result<&'a mut [u8], error::Unspecified> {
+    if ciphertext.is_empty() || ciphertext.len() % BLOCK_LEN != 0 {
+        return Err(error::Unspecified);
+    }
+
+    let mut chain = *iv;
+    for block in ciphertext.chunks_mut(BLOCK_LEN) {
+        let mut decrypted = [0u8; BLOCK_LEN];
+        decrypted.copy_from_slice(block);
+        key.decrypt_block(&mut decrypted);
+        for ((b, d), c) in
+                block.iter_mut().zip(decrypted.iter()).zip(chain.iter_mut()) {
+            let ciphertext_byte = *b;
+            *b = *d ^ *c;
+            *c = ciphertext_byte;
+        }
+    }
+
+    let last_block = &ciphertext[(ciphertext.len() - BLOCK_LEN)..];
+    let padding_len = last_block[BLOCK_LEN - 1];
+    let mut bad = is_zero_mask(padding_len) |
+                  !lt_mask(padding_len, (BLOCK_LEN + 1) as u8);
+    for (i, b) in last_block.iter().rev().enumerate() {
+        // Check the last `padding_len` bytes, without branching on it.
+        let in_padding = lt_mask(i as u8, padding_len);
+        bad |= in_padding & !is_zero_mask(*b ^ padding_len);
+    }
+    if bad != 0 {
+        polyfill::slice::fill(ciphertext, 0);
+        return Err(error::Unspecified);
+    }
+
+    let plaintext_len = ciphertext.len() - usize::from(padding_len);
+    Ok(&mut ciphertext[..plaintext_len])
+}
+
+// Returns 0xff if `a == 0` and 0 otherwise, in constant time.
+#[inline(always)]
+fn is_zero_mask(a: u8) -> u8 {
+    ((u32::from(a).wrapping_sub(1)) >> 8) as u8
+}
+
+// Returns 0xff if `a < b` and 0 otherwise, in constant time.
+#[inline(always)]
+fn lt_mask(a: u8, b: u8) -> u8 {
+    ((u32::from(a).wrapping_sub(u32::from(b))) >> 8) as u8
+}
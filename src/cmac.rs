@@ -0,0 +1,280 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! AES-CMAC message authentication, as specified in [RFC 4493] and
+//! [NIST SP 800-38B].
+//!
+//! The API mirrors `ring::hmac`: a `SigningKey` is used with `sign` or a
+//! `SigningContext` to calculate a MAC, and a `VerificationKey` is used with
+//! `verify` to check one. Keys are `cipher::AES_128` or `cipher::AES_256`
+//! keys.
+//!
+//! Some protocols, e.g. AUTOSAR SecOC, transmit only a prefix of the MAC.
+//! `verify` only accepts whole MACs; to verify a truncated MAC, compare it to
+//! the same-length prefix of the result of `sign` using
+//! `constant_time::verify_slices_are_equal`.
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::{cipher, cmac};
+//!
+//! # fn main() { f().unwrap() }
+//! # fn f() -> Result<(), ring::error::Unspecified> {
+//! let key_value = [0x42; 16];
+//! let msg = "hello, world";
+//!
+//! let s_key = cmac::SigningKey::new(&cipher::AES_128, &key_value)?;
+//! let signature = cmac::sign(&s_key, msg.as_bytes());
+//!
+//! let v_key = cmac::VerificationKey::new(&cipher::AES_128, &key_value)?;
+//! cmac::verify(&v_key, msg.as_bytes(), signature.as_ref())?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [RFC 4493]: https://tools.ietf.org/html/rfc4493
+//! [NIST SP 800-38B]: http://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-38B.pdf
+
+use {cipher, constant_time, core, error, rand, zeroize};
+
+/// The length of an AES-CMAC signature.
+pub const SIGNATURE_LEN: usize = cipher::BLOCK_LEN;
+
+/// An AES-CMAC signature.
+///
+/// For a given signature `s`, use `s.as_ref()` to get the signature value as
+/// a byte slice.
+#[derive(Clone, Copy, Debug)]
+pub struct Signature([u8; SIGNATURE_LEN]);
+
+impl AsRef<[u8]> for Signature {
+    #[inline] fn as_ref(&self) -> &[u8] { &self.0[..] }
+}
+
+/// A key to use for AES-CMAC signing.
+pub struct SigningKey {
+    key: cipher::EncryptingKey,
+    k1: [u8; cipher::BLOCK_LEN],
+    k2: [u8; cipher::BLOCK_LEN],
+}
+
+impl Drop for SigningKey {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.k1);
+        zeroize::zeroize(&mut self.k2);
+    }
+}
+
+impl SigningKey {
+    /// Generates an AES-CMAC signing key for `algorithm` with a random value
+    /// generated from `rng`.
+    pub fn generate(algorithm: &'static cipher::Algorithm,
+                    rng: &rand::SecureRandom)
+                    -> Result<SigningKey, error::Unspecified> {
+        let mut key_bytes = [0u8; MAX_KEY_LEN];
+        let key_bytes = &mut key_bytes[..algorithm.key_len()];
+        rng.fill(key_bytes)?;
+        SigningKey::new(algorithm, key_bytes)
+    }
+
+    /// Constructs an AES-CMAC signing key from `key_value`, which must be
+    /// exactly `algorithm.key_len()` bytes long.
+    pub fn new(algorithm: &'static cipher::Algorithm, key_value: &[u8])
+               -> Result<SigningKey, error::Unspecified> {
+        let key = cipher::EncryptingKey::new(algorithm, key_value)?;
+
+        // RFC 4493 Section 2.3.
+        let mut l = [0u8; cipher::BLOCK_LEN];
+        key.encrypt_block(&mut l);
+        let k1 = dbl(&l);
+        let k2 = dbl(&k1);
+
+        Ok(SigningKey { key, k1, k2 })
+    }
+
+    /// The key's algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static cipher::Algorithm {
+        self.key.algorithm()
+    }
+}
+
+// Multiplies `block` by `x` in GF(2**128), as in RFC 4493 Section 2.3.
+fn dbl(block: &[u8; cipher::BLOCK_LEN]) -> [u8; cipher::BLOCK_LEN] {
+    let mut r = [0u8; cipher::BLOCK_LEN];
+    for i in 0..(cipher::BLOCK_LEN - 1) {
+        r[i] = (block[i] << 1) | (block[i + 1] >> 7);
+    }
+    let msb = block[0] >> 7;
+    r[cipher::BLOCK_LEN - 1] = (block[cipher::BLOCK_LEN - 1] << 1) ^
+                               (0x87 & 0u8.wrapping_sub(msb));
+    r
+}
+
+/// A context for multi-step (Init-Update-Finish) AES-CMAC signing.
+///
+/// Use `sign` for single-step AES-CMAC signing.
+pub struct SigningContext<'a> {
+    key: &'a SigningKey,
+    x: [u8; cipher::BLOCK_LEN],
+
+    // The last block of input seen so far, which is processed differently
+    // from the others if it turns out to be the last block of the message.
+    pending: [u8; cipher::BLOCK_LEN],
+    pending_len: usize,
+}
+
+impl<'a> Drop for SigningContext<'a> {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.x);
+        zeroize::zeroize(&mut self.pending);
+    }
+}
+
+impl<'a> SigningContext<'a> {
+    /// Constructs a new AES-CMAC signing context using the given key.
+    pub fn with_key(signing_key: &'a SigningKey) -> SigningContext<'a> {
+        SigningContext {
+            key: signing_key,
+            x: [0u8; cipher::BLOCK_LEN],
+            pending: [0u8; cipher::BLOCK_LEN],
+            pending_len: 0,
+        }
+    }
+
+    /// Updates the AES-CMAC with all the data in `data`. `update` may be
+    /// called zero or more times until `sign` is called.
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+        while !data.is_empty() {
+            if self.pending_len == cipher::BLOCK_LEN {
+                // More input follows, so the pending block isn't the last.
+                xor_block(&mut self.x, &self.pending);
+                self.key.key.encrypt_block(&mut self.x);
+                self.pending_len = 0;
+            }
+            let n = core::cmp::min(cipher::BLOCK_LEN - self.pending_len,
+                                   data.len());
+            self.pending[self.pending_len..][..n].copy_from_slice(&data[..n]);
+            self.pending_len += n;
+            data = &data[n..];
+        }
+    }
+
+    /// Finalizes the AES-CMAC calculation and returns the AES-CMAC value.
+    /// `sign` consumes the context so it cannot be (mis-)used after `sign` has
+    /// been called.
+    ///
+    /// It is generally not safe to implement AES-CMAC verification by
+    /// comparing the return value of `sign` to a signature. Use `verify` for
+    /// verification instead.
+    pub fn sign(mut self) -> Signature {
+        // RFC 4493 Section 2.4, steps 4 through 6.
+        let subkey = if self.pending_len == cipher::BLOCK_LEN {
+            &self.key.k1
+        } else {
+            self.pending[self.pending_len] = 0x80;
+            for b in &mut self.pending[(self.pending_len + 1)..] {
+                *b = 0;
+            }
+            &self.key.k2
+        };
+        xor_block(&mut self.pending, subkey);
+        xor_block(&mut self.x, &self.pending);
+        self.key.key.encrypt_block(&mut self.x);
+        Signature(self.x)
+    }
+}
+
+fn xor_block(a: &mut [u8; cipher::BLOCK_LEN], b: &[u8; cipher::BLOCK_LEN]) {
+    for (a, b) in a.iter_mut().zip(b.iter()) {
+        *a ^= *b;
+    }
+}
+
+/// Calculates the AES-CMAC of `data` using the key `key` in one step.
+///
+/// Use `SigningContext` to calculate AES-CMACs where the input is in multiple
+/// parts.
+///
+/// It is generally not safe to implement AES-CMAC verification by comparing
+/// the return value of `sign` to a signature. Use `verify` for verification
+/// instead.
+pub fn sign(key: &SigningKey, data: &[u8]) -> Signature {
+    let mut ctx = SigningContext::with_key(key);
+    ctx.update(data);
+    ctx.sign()
+}
+
+/// A key to use for AES-CMAC authentication.
+pub struct VerificationKey {
+    wrapped: SigningKey,
+}
+
+impl VerificationKey {
+    /// Constructs an AES-CMAC verification key from `key_value`, which must
+    /// be exactly `algorithm.key_len()` bytes long.
+    #[inline]
+    pub fn new(algorithm: &'static cipher::Algorithm, key_value: &[u8])
+               -> Result<VerificationKey, error::Unspecified> {
+        Ok(VerificationKey { wrapped: SigningKey::new(algorithm, key_value)? })
+    }
+
+    /// The key's algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static cipher::Algorithm {
+        self.wrapped.algorithm()
+    }
+}
+
+/// Calculates the AES-CMAC of `data` using the key `key`, and verifies
+/// whether the resultant value equals `signature`, in one step.
+///
+/// The verification will be done in constant time to prevent timing attacks.
+#[inline(always)]
+pub fn verify(key: &VerificationKey, data: &[u8], signature: &[u8])
+              -> Result<(), error::Unspecified> {
+    verify_with_own_key(&key.wrapped, data, signature)
+}
+
+/// Calculates the AES-CMAC of `data` using the signing key `key`, and
+/// verifies whether the resultant value equals `signature`, in one step.
+///
+/// This is logically equivalent to, but more efficient than, constructing a
+/// `VerificationKey` with the same value as `key` and then using `verify`.
+///
+/// The verification will be done in constant time to prevent timing attacks.
+pub fn verify_with_own_key(key: &SigningKey, data: &[u8], signature: &[u8])
+                           -> Result<(), error::Unspecified> {
+    constant_time::verify_slices_are_equal(sign(key, data).as_ref(), signature)
+}
+
+const MAX_KEY_LEN: usize = 32;
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn dbl_test() {
+        // RFC 4493 Section 4, subkey generation.
+        let l = [0x7d, 0xf7, 0x6b, 0x0c, 0x1a, 0xb8, 0x99, 0xb3,
+                 0x3e, 0x42, 0xf0, 0x47, 0xb9, 0x1b, 0x54, 0x6f];
+        let k1 = [0xfb, 0xee, 0xd6, 0x18, 0x35, 0x71, 0x33, 0x66,
+                  0x7c, 0x85, 0xe0, 0x8f, 0x72, 0x36, 0xa8, 0xde];
+        let k2 = [0xf7, 0xdd, 0xac, 0x30, 0x6a, 0xe2, 0x66, 0xcc,
+                  0xf9, 0x0b, 0xc1, 0x1e, 0xe4, 0x6d, 0x51, 0x3b];
+        assert_eq!(super::dbl(&l), k1);
+        assert_eq!(super::dbl(&k1), k2);
+    }
+}
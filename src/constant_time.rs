@@ -16,6 +16,9 @@
 
 use {c, error};
 
+#[cfg(feature = "pure_rust")]
+use core;
+
 /// Returns `Ok(())` if `a == b` and `Err(error::Unspecified)` otherwise.
 /// The comparison of `a` and `b` is done in constant time with respect to the
 /// contents of each, but NOT in constant time with respect to the lengths of
@@ -32,6 +35,16 @@ pub fn verify_slices_are_equal(a: &[u8], b: &[u8])
     }
 }
 
+#[cfg(not(feature = "pure_rust"))]
 extern {
     fn GFp_memcmp(a: *const u8, b: *const u8, len: c::size_t) -> c::int;
 }
+
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe fn GFp_memcmp(a: *const u8, b: *const u8, len: c::size_t) -> c::int {
+    let a = core::slice::from_raw_parts(a, len);
+    let b = core::slice::from_raw_parts(b, len);
+    let x = a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b));
+    c::int::from(x)
+}
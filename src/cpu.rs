@@ -0,0 +1,486 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! CPU feature detection and implementation selection.
+//!
+//! *ring*'s assembly language code chooses an implementation of each
+//! primitive at runtime based on the optional features of the CPU it is
+//! running on, e.g. AES-NI and AVX2 on x86-64 or NEON and the ARMv8 crypto
+//! extensions on ARM. `features()` reports which of those features were
+//! detected and `implementation()` reports which implementation of each
+//! primitive is used as a result.
+//!
+//! `force_portable()` makes *ring* ignore the optional features, so that the
+//! implementations that don't need them are used on every CPU. That is useful
+//! for testing those implementations on machines that would otherwise never
+//! use them.
+//!
+//! Builds with the `pure_rust` feature don't detect any features and use the
+//! portable implementations on every CPU.
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::cpu;
+//!
+//! println!("{:?}", cpu::features());
+//! println!("AES: {}", cpu::implementation(cpu::Primitive::AES));
+//! if cpu::features().has(cpu::Feature::AES) {
+//!     assert_ne!(cpu::implementation(cpu::Primitive::AES), cpu::PORTABLE);
+//! }
+//! ```
+
+use {error, init, std};
+use core::fmt;
+
+/// An optional CPU feature that *ring* can use.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Feature {
+    /// The AES-NI instructions on x86 and x86-64, or the ARMv8 AES
+    /// instructions on ARM and AArch64.
+    AES,
+
+    /// Carry-less multiplication (`PCLMULQDQ`) on x86 and x86-64.
+    PCLMULQDQ,
+
+    /// SSSE3 on x86 and x86-64.
+    SSSE3,
+
+    /// AVX on x86 and x86-64.
+    AVX,
+
+    /// AVX2 on x86 and x86-64.
+    AVX2,
+
+    /// The `MOVBE` instruction on x86 and x86-64.
+    MOVBE,
+
+    /// BMI1 on x86 and x86-64.
+    BMI1,
+
+    /// BMI2 on x86 and x86-64.
+    BMI2,
+
+    /// The SHA extensions on x86 and x86-64.
+    SHA,
+
+    /// NEON on ARM and AArch64.
+    NEON,
+
+    /// The ARMv8 `PMULL` polynomial multiplication instructions.
+    PMULL,
+
+    /// The ARMv8 SHA-1 instructions.
+    SHA1,
+
+    /// The ARMv8 SHA-256 instructions.
+    SHA256,
+}
+
+const ALL_FEATURES: [Feature; 13] = [
+    Feature::AES,
+    Feature::PCLMULQDQ,
+    Feature::SSSE3,
+    Feature::AVX,
+    Feature::AVX2,
+    Feature::MOVBE,
+    Feature::BMI1,
+    Feature::BMI2,
+    Feature::SHA,
+    Feature::NEON,
+    Feature::PMULL,
+    Feature::SHA1,
+    Feature::SHA256,
+];
+
+/// The optional features of the CPU that *ring* uses.
+///
+/// The `Debug` implementation lists them.
+#[derive(Clone, Copy)]
+pub struct Features(());
+
+impl Features {
+    /// Whether `feature` was detected. This is always false after
+    /// `force_portable()` succeeded.
+    #[inline]
+    pub fn has(&self, feature: Feature) -> bool { arch::has(feature) }
+}
+
+impl fmt::Debug for Features {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entries(ALL_FEATURES.iter().filter(|&&feature| self.has(feature)))
+            .finish()
+    }
+}
+
+/// The optional features of the CPU that *ring* uses, detecting them first
+/// if that hasn't been done already.
+pub fn features() -> Features {
+    init::init_once();
+    Features(())
+}
+
+/// A primitive that has more than one implementation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Primitive {
+    /// The AES block cipher applied to one block at a time, as by AES-CTR,
+    /// AES-CBC, AES-CCM and the other AES modes, and by AES-GCM for inputs
+    /// that `AESBulk`'s implementation doesn't handle.
+    AES,
+
+    /// The AES block cipher in counter mode, as used by AES-GCM for the bulk
+    /// of its input.
+    AESBulk,
+
+    /// GHASH, as used by AES-GCM and AES-GMAC.
+    GHASH,
+
+    /// The ChaCha20 stream cipher.
+    ChaCha20,
+
+    /// The Poly1305 one-time authenticator.
+    Poly1305,
+
+    /// The SHA-256 compression function.
+    SHA256,
+
+    /// The SHA-512 compression function, also used by SHA-384 and
+    /// SHA-512/256.
+    SHA512,
+
+    /// The P-256 field and group arithmetic.
+    P256,
+}
+
+/// The name `implementation()` returns for the implementation of a primitive
+/// that doesn't use any optional CPU feature.
+///
+/// 32-bit x86 builds of *ring* require SSE2, so on x86 the implementations
+/// that use SSE2 but nothing newer are portable.
+pub const PORTABLE: &'static str = "portable";
+
+/// The name of the implementation of `primitive` that is used, e.g.
+/// `"aesni"` or `"avx2"`, or `PORTABLE`.
+///
+/// Some of the implementations fall back to a simpler one for short inputs.
+pub fn implementation(primitive: Primitive) -> &'static str {
+    arch::implementation(features(), primitive)
+}
+
+/// Makes *ring* ignore the optional features of the CPU, so that the portable
+/// implementation of every primitive is used.
+///
+/// Keys are set up for the implementation that is in use when they are
+/// constructed, so this can't change the implementation once *ring* has
+/// detected the CPU's features, which it does the first time it is used. It
+/// fails if that has already happened. Programs that test the portable
+/// implementations should call it before doing anything else.
+///
+/// On 32-bit ARM, when the C code is compiled with NEON enabled, the
+/// `AESBulk` and GHASH implementations still use NEON.
+pub fn force_portable() -> Result<(), error::Unspecified> {
+    let mut forced = false;
+    INIT.call_once(|| {
+        unsafe {
+            detect();
+            arch::mask_all();
+        }
+        forced = true;
+    });
+    if forced { Ok(()) } else { Err(error::Unspecified) }
+}
+
+/// Detects the CPU's features, if that hasn't been done already.
+#[inline(always)]
+pub(crate) fn init_once() {
+    INIT.call_once(|| unsafe { detect() });
+}
+
+static INIT: std::sync::Once = std::sync::ONCE_INIT;
+
+unsafe fn detect() {
+    // iOS uses the static ARM configuration in crypto/crypto.c.
+    #[cfg(not(any(target_os = "ios", feature = "pure_rust")))]
+    {
+        extern { fn GFp_cpuid_setup(); }
+        GFp_cpuid_setup();
+    }
+}
+
+// Keep `implementation()` in sync with the dispatching in the C and assembly
+// language code, e.g. `aes_block()` and `aes_ctr()` in crypto/cipher/e_aes.c
+// and `gcm128_init_gmult_ghash()` in crypto/fipsmodule/modes/gcm.c.
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"),
+          not(feature = "pure_rust")))]
+mod arch {
+    use super::{Feature, Features, PORTABLE, Primitive};
+
+    // See include/GFp/cpu.h.
+    extern { static mut GFp_ia32cap_P: [u32; 4]; }
+
+    fn cap(word: usize, bit: u32) -> bool {
+        unsafe { GFp_ia32cap_P[word] & (1 << bit) != 0 }
+    }
+
+    // Bits that the assembly language code checks in addition to `Feature`s.
+    fn fxsr() -> bool { cap(0, 24) }
+    fn intel() -> bool { cap(0, 30) }
+
+    pub fn has(feature: Feature) -> bool {
+        match feature {
+            Feature::AES => cap(1, 25),
+            Feature::PCLMULQDQ => cap(1, 1),
+            Feature::SSSE3 => cap(1, 9),
+            Feature::AVX => cap(1, 28),
+            Feature::AVX2 => cap(2, 5),
+            Feature::MOVBE => cap(1, 22),
+            Feature::BMI1 => cap(2, 3),
+            Feature::BMI2 => cap(2, 8),
+            Feature::SHA => cap(2, 29),
+            _ => false,
+        }
+    }
+
+    pub fn implementation(features: Features, primitive: Primitive)
+                          -> &'static str {
+        let has = |feature| features.has(feature);
+        let x86_64 = cfg!(target_arch = "x86_64");
+        match primitive {
+            // bsaes only handles eight or more blocks at a time, and its key
+            // schedule isn't vpaes's, so single blocks are encrypted with the
+            // portable code alongside it.
+            Primitive::AES => {
+                if has(Feature::AES) {
+                    "aesni"
+                } else if !x86_64 && has(Feature::SSSE3) {
+                    "vpaes"
+                } else {
+                    PORTABLE
+                }
+            },
+            Primitive::AESBulk => {
+                if has(Feature::AES) {
+                    "aesni"
+                } else if has(Feature::SSSE3) {
+                    if x86_64 { "bsaes" } else { "vpaes" }
+                } else {
+                    PORTABLE
+                }
+            },
+            Primitive::GHASH => {
+                if fxsr() && has(Feature::PCLMULQDQ) {
+                    if x86_64 && has(Feature::AVX) && has(Feature::MOVBE) {
+                        "avx"
+                    } else {
+                        "clmul"
+                    }
+                } else {
+                    PORTABLE
+                }
+            },
+            Primitive::ChaCha20 => {
+                if x86_64 && has(Feature::SSSE3) && has(Feature::AVX2) {
+                    "avx2"
+                } else if (x86_64 || fxsr()) && has(Feature::SSSE3) {
+                    "ssse3"
+                } else {
+                    PORTABLE
+                }
+            },
+            Primitive::Poly1305 => {
+                if x86_64 && has(Feature::AVX2) {
+                    "avx2"
+                } else if x86_64 && has(Feature::AVX) {
+                    "avx"
+                } else {
+                    PORTABLE
+                }
+            },
+            Primitive::SHA256 => {
+                if !x86_64 && !fxsr() {
+                    PORTABLE
+                } else if x86_64 && has(Feature::SHA) {
+                    "shaext"
+                } else if x86_64 && has(Feature::AVX2) &&
+                          has(Feature::BMI1) && has(Feature::BMI2) {
+                    "avx2"
+                } else if intel() && has(Feature::AVX) &&
+                          (!x86_64 || has(Feature::SSSE3)) {
+                    "avx"
+                } else if has(Feature::SSSE3) {
+                    "ssse3"
+                } else {
+                    PORTABLE
+                }
+            },
+            Primitive::SHA512 => {
+                if x86_64 && has(Feature::AVX2) && has(Feature::BMI1) &&
+                   has(Feature::BMI2) {
+                    "avx2"
+                } else if x86_64 && intel() && has(Feature::AVX) &&
+                          has(Feature::SSSE3) {
+                    "avx"
+                } else if !x86_64 && fxsr() && has(Feature::SSSE3) {
+                    "ssse3"
+                } else {
+                    PORTABLE
+                }
+            },
+            Primitive::P256 => PORTABLE,
+        }
+    }
+
+    // Clears the bits of everything newer than SSE2. The other bits of the
+    // first word describe the CPU rather than optional features.
+    pub unsafe fn mask_all() {
+        GFp_ia32cap_P[1] = 0;
+        GFp_ia32cap_P[2] = 0;
+    }
+}
+
+#[cfg(all(any(target_arch = "arm", target_arch = "aarch64"),
+          not(feature = "pure_rust")))]
+mod arch {
+    use super::{Feature, Features, PORTABLE, Primitive};
+
+    // See include/GFp/arm_arch.h.
+    extern { static mut GFp_armcap_P: u32; }
+
+    const ARMV7_NEON: u32 = 1 << 0;
+    const ARMV8_AES: u32 = 1 << 2;
+    const ARMV8_SHA1: u32 = 1 << 3;
+    const ARMV8_SHA256: u32 = 1 << 4;
+    const ARMV8_PMULL: u32 = 1 << 5;
+
+    pub fn has(feature: Feature) -> bool {
+        let bit = match feature {
+            Feature::NEON => ARMV7_NEON,
+            Feature::AES => ARMV8_AES,
+            Feature::SHA1 => ARMV8_SHA1,
+            Feature::SHA256 => ARMV8_SHA256,
+            Feature::PMULL => ARMV8_PMULL,
+            _ => { return false; },
+        };
+        unsafe { GFp_armcap_P & bit != 0 }
+    }
+
+    pub fn implementation(features: Features, primitive: Primitive)
+                          -> &'static str {
+        let has = |feature| features.has(feature);
+        let arm = cfg!(target_arch = "arm");
+
+        // `GFp_is_NEON_capable()` in include/GFp/cpu.h, which the C code
+        // uses, ignores `GFp_armcap_P` when NEON is enabled at compile time.
+        let neon_in_c = has(Feature::NEON) ||
+            cfg!(all(target_feature = "neon", not(target_os = "android")));
+
+        match primitive {
+            Primitive::AES => {
+                if has(Feature::AES) { "aes_hw" } else { PORTABLE }
+            },
+            Primitive::AESBulk => {
+                if has(Feature::AES) {
+                    "aes_hw"
+                } else if arm && neon_in_c {
+                    "bsaes"
+                } else {
+                    PORTABLE
+                }
+            },
+            Primitive::GHASH => {
+                if has(Feature::PMULL) {
+                    "pmull"
+                } else if arm && neon_in_c {
+                    "neon"
+                } else {
+                    PORTABLE
+                }
+            },
+            Primitive::ChaCha20 | Primitive::Poly1305 => {
+                if has(Feature::NEON) { "neon" } else { PORTABLE }
+            },
+            Primitive::SHA256 => {
+                if has(Feature::SHA256) {
+                    "armv8"
+                } else if arm && has(Feature::NEON) {
+                    "neon"
+                } else {
+                    PORTABLE
+                }
+            },
+            Primitive::SHA512 => {
+                if arm && has(Feature::NEON) { "neon" } else { PORTABLE }
+            },
+            Primitive::P256 => PORTABLE,
+        }
+    }
+
+    pub unsafe fn mask_all() {
+        GFp_armcap_P = 0;
+    }
+}
+
+// The `pure_rust` build has only the portable implementations.
+#[cfg(any(not(any(target_arch = "x86", target_arch = "x86_64",
+                  target_arch = "arm", target_arch = "aarch64")),
+          feature = "pure_rust"))]
+mod arch {
+    use super::{Feature, Features, PORTABLE, Primitive};
+
+    pub fn has(_feature: Feature) -> bool { false }
+
+    pub fn implementation(_features: Features, _primitive: Primitive)
+                          -> &'static str {
+        PORTABLE
+    }
+
+    pub unsafe fn mask_all() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_force_portable_after_init() {
+        let _ = features();
+        assert!(force_portable().is_err());
+    }
+
+    #[test]
+    fn test_implementations_follow_features() {
+        let features = features();
+        let all = [Primitive::AES, Primitive::AESBulk, Primitive::GHASH,
+                   Primitive::ChaCha20, Primitive::Poly1305, Primitive::SHA256,
+                   Primitive::SHA512, Primitive::P256];
+        if ALL_FEATURES.iter().all(|&feature| !features.has(feature)) {
+            if cfg!(any(target_arch = "x86_64", target_arch = "aarch64")) {
+                for &primitive in all.iter() {
+                    assert_eq!(implementation(primitive), PORTABLE);
+                }
+            }
+        }
+        if features.has(Feature::AES) {
+            assert_ne!(implementation(Primitive::AES), PORTABLE);
+            assert_ne!(implementation(Primitive::AESBulk), PORTABLE);
+        }
+        if features.has(Feature::NEON) || features.has(Feature::AVX2) {
+            assert_ne!(implementation(Primitive::ChaCha20), PORTABLE);
+            assert_ne!(implementation(Primitive::Poly1305), PORTABLE);
+        }
+        assert_eq!(implementation(Primitive::P256), PORTABLE);
+    }
+}
@@ -24,7 +24,7 @@
 // The goal for this implementation is to drive the overhead as close to zero
 // as possible.
 
-use {c, init, polyfill};
+use {c, init, polyfill, zeroize};
 use core;
 
 // XXX: Replace with `const fn` when `const fn` is stable:
@@ -37,6 +37,9 @@ macro_rules! u32x2 {
 
 mod sha1;
 
+#[cfg(feature = "pure_rust")]
+mod sha2;
+
 /// A context for multi-step (Init-Update-Finish) digest calculations.
 ///
 /// C analog: `EVP_MD_CTX`.
@@ -72,6 +75,13 @@ pub struct Context {
     pub algorithm: &'static Algorithm,
 }
 
+impl Drop for Context {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.state);
+        zeroize::zeroize(&mut self.pending);
+    }
+}
+
 impl Context {
     /// Constructs a new context.
     ///
@@ -209,6 +219,65 @@ impl Clone for Context {
     }
 }
 
+/// Finishes copies of a `Context` that has processed a whole number of blocks
+/// with a message of a fixed length that fits, with its padding, in a single
+/// block. The padding is computed once in `new`, so each `finish` is just one
+/// call to the block function.
+pub(crate) struct FixedLenFinisher {
+    state: State,
+    block: [u8; MAX_BLOCK_LEN],
+    msg_len: usize,
+    algorithm: &'static Algorithm,
+}
+
+impl FixedLenFinisher {
+    pub(crate) fn new(ctx: &Context, msg_len: usize) -> FixedLenFinisher {
+        let algorithm = ctx.algorithm;
+        assert_eq!(ctx.num_pending, 0);
+        assert!(msg_len + 1 + algorithm.len_len <= algorithm.block_len);
+
+        let mut block = [0u8; MAX_BLOCK_LEN];
+        block[msg_len] = 0x80;
+
+        // Output the length, in bits, in big endian order.
+        let mut completed_data_bits: u64 = ctx.completed_data_blocks
+            .checked_mul(polyfill::u64_from_usize(algorithm.block_len))
+            .unwrap()
+            .checked_add(polyfill::u64_from_usize(msg_len)).unwrap()
+            .checked_mul(8).unwrap();
+        for b in (&mut block[(algorithm.block_len - 8)..algorithm.block_len])
+                    .into_iter().rev() {
+            *b = completed_data_bits as u8;
+            completed_data_bits /= 0x100;
+        }
+
+        FixedLenFinisher {
+            state: ctx.state,
+            block,
+            msg_len,
+            algorithm,
+        }
+    }
+
+    /// The message to be hashed by `finish`.
+    #[inline]
+    pub(crate) fn msg_mut(&mut self) -> &mut [u8] {
+        &mut self.block[..self.msg_len]
+    }
+
+    pub(crate) fn finish(&self) -> Digest {
+        let mut state = self.state;
+        unsafe {
+            (self.algorithm.block_data_order)(&mut state, self.block.as_ptr(),
+                                              1);
+        }
+        Digest {
+            algorithm: self.algorithm,
+            value: (self.algorithm.format_output)(&state),
+        }
+    }
+}
+
 /// Returns the digest of `data` using the given digest algorithm.
 ///
 /// C analog: `EVP_Digest`
@@ -490,6 +559,7 @@ const SHA512_BLOCK_LEN: usize = 1024 / 8;
 /// The length of the length field for SHA-512-based algorithms, in bytes.
 const SHA512_LEN_LEN: usize = 128 / 8;
 
+#[cfg(not(feature = "pure_rust"))]
 extern {
     fn GFp_sha256_block_data_order(state: &mut State, data: *const u8,
                                    num: c::size_t);
@@ -497,6 +567,12 @@ extern {
                                    num: c::size_t);
 }
 
+#[cfg(feature = "pure_rust")]
+use self::sha2::{
+    block_data_order_256 as GFp_sha256_block_data_order,
+    block_data_order_512 as GFp_sha512_block_data_order,
+};
+
 
 #[cfg(test)]
 pub mod test_util {
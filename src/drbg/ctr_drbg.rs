@@ -0,0 +1,152 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! CTR_DRBG using AES-256 without a derivation function, as specified in
+//! [NIST SP 800-90A Rev. 1 Section 10.2.1].
+//!
+//! Without a derivation function, the entropy input must be full-entropy and
+//! exactly `SEED_LEN` bytes long, and the personalization string and the
+//! additional input can't be longer than `SEED_LEN` bytes.
+//!
+//! [NIST SP 800-90A Rev. 1 Section 10.2.1]:
+//!     http://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-90Ar1.pdf
+
+use {polyfill, zeroize};
+use aead::aes;
+use super::Mechanism;
+
+pub struct State {
+    key: [u64; aes::KEY_BUF_ELEMS],
+    v: aes::Block,
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.key);
+        zeroize::zeroize(&mut self.v);
+    }
+}
+
+impl State {
+    pub fn instantiate(entropy_input: &[u8], personalization_string: &[u8])
+                       -> State {
+        let mut state = State {
+            key: [0u64; aes::KEY_BUF_ELEMS],
+            v: [0u8; aes::BLOCK_LEN],
+        };
+        state.set_key(&[0u8; KEY_LEN]);
+        state.reseed(entropy_input, personalization_string);
+        state
+    }
+
+    fn set_key(&mut self, key: &[u8]) {
+        aes::init(polyfill::slice::u64_as_u8_mut(&mut self.key), key).unwrap();
+    }
+
+    // `V = (V + 1) mod 2**blocklen`, followed by the encryption of `V`.
+    fn next_block(&mut self) -> aes::Block {
+        for v in self.v.iter_mut().rev() {
+            *v = v.wrapping_add(1);
+            if *v != 0 {
+                break;
+            }
+        }
+        let mut block = self.v;
+        aes::encrypt_block(&self.key, &mut block);
+        block
+    }
+
+    // CTR_DRBG_Update. `provided_data` is implicitly padded with zeros to
+    // `SEED_LEN` bytes.
+    fn update(&mut self, provided_data: &[u8]) {
+        let mut temp = [0u8; SEED_LEN];
+        for chunk in temp.chunks_mut(aes::BLOCK_LEN) {
+            chunk.copy_from_slice(&self.next_block());
+        }
+        for (t, p) in temp.iter_mut().zip(provided_data) {
+            *t ^= *p;
+        }
+        let (key, v) = temp.split_at(KEY_LEN);
+        self.set_key(key);
+        self.v.copy_from_slice(v);
+    }
+}
+
+impl Mechanism for State {
+    // The entropy input and the additional input are combined by XORing them
+    // together.
+    fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) {
+        assert_eq!(entropy_input.len(), SEED_LEN);
+        assert!(additional_input.len() <= SEED_LEN);
+        let mut seed_material = [0u8; SEED_LEN];
+        seed_material.copy_from_slice(entropy_input);
+        for (s, a) in seed_material.iter_mut().zip(additional_input) {
+            *s ^= *a;
+        }
+        self.update(&seed_material);
+    }
+
+    fn generate(&mut self, out: &mut [u8], additional_input: &[u8]) {
+        assert!(additional_input.len() <= SEED_LEN);
+        if !additional_input.is_empty() {
+            self.update(additional_input);
+        }
+        for chunk in out.chunks_mut(aes::BLOCK_LEN) {
+            let block = self.next_block();
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+        self.update(additional_input);
+    }
+}
+
+const KEY_LEN: usize = aes::AES_256_KEY_LEN;
+
+/// The length of the seed: the key length plus the block length.
+pub const SEED_LEN: usize = KEY_LEN + aes::BLOCK_LEN;
+
+#[cfg(test)]
+mod tests {
+    use test;
+    use super::State;
+    use super::super::Mechanism;
+
+    #[test]
+    fn test_ctr_drbg() {
+        test::from_file("src/drbg/ctr_drbg_tests.txt", |section, test_case| {
+            assert_eq!(section, "");
+            let entropy_input = test_case.consume_bytes("EntropyInput");
+            let personalization_string =
+                test_case.consume_bytes("PersonalizationString");
+            let entropy_input_reseed =
+                test_case.consume_bytes("EntropyInputReseed");
+            let additional_input_reseed =
+                test_case.consume_bytes("AdditionalInputReseed");
+            let additional_input_1 =
+                test_case.consume_bytes("AdditionalInput1");
+            let additional_input_2 =
+                test_case.consume_bytes("AdditionalInput2");
+            let expected = test_case.consume_bytes("ReturnedBits");
+
+            let mut state =
+                State::instantiate(&entropy_input, &personalization_string);
+            state.reseed(&entropy_input_reseed, &additional_input_reseed);
+            let mut actual = vec![0u8; expected.len()];
+            state.generate(&mut actual, &additional_input_1);
+            state.generate(&mut actual, &additional_input_2);
+            assert_eq!(actual, expected);
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,155 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! HMAC_DRBG, as specified in [NIST SP 800-90A Rev. 1 Section 10.1.2].
+//!
+//! [NIST SP 800-90A Rev. 1 Section 10.1.2]:
+//!     http://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-90Ar1.pdf
+
+use {digest, hmac, zeroize};
+use super::Mechanism;
+
+pub struct State {
+    k: hmac::SigningKey,
+    v: [u8; digest::MAX_OUTPUT_LEN],
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.v);
+    }
+}
+
+impl State {
+    pub fn instantiate(digest_alg: &'static digest::Algorithm,
+                       entropy_input: &[u8], nonce: &[u8],
+                       personalization_string: &[u8]) -> State {
+        let mut state = State {
+            k: hmac::SigningKey::new(digest_alg,
+                                     &[0u8; digest::MAX_OUTPUT_LEN]
+                                        [..digest_alg.output_len]),
+            v: [0u8; digest::MAX_OUTPUT_LEN],
+        };
+        for v in state.v[..digest_alg.output_len].iter_mut() {
+            *v = 0x01;
+        }
+        state.update(&[entropy_input, nonce, personalization_string]);
+        state
+    }
+
+    // HMAC_DRBG_Update.
+    fn update(&mut self, provided_data: &[&[u8]]) {
+        self.update_round(0x00, provided_data);
+        if provided_data.iter().all(|data| data.is_empty()) {
+            return;
+        }
+        self.update_round(0x01, provided_data);
+    }
+
+    // `K = HMAC(K, V || round || provided_data)` and then `V = HMAC(K, V)`.
+    fn update_round(&mut self, round: u8, provided_data: &[&[u8]]) {
+        let digest_alg = self.k.digest_algorithm();
+        let k = {
+            let mut ctx = hmac::SigningContext::with_key(&self.k);
+            ctx.update(&self.v[..digest_alg.output_len]);
+            ctx.update(&[round]);
+            for data in provided_data {
+                ctx.update(data);
+            }
+            ctx.sign()
+        };
+        self.k = hmac::SigningKey::new(digest_alg, k.as_ref());
+        self.next_v();
+    }
+
+    // `V = HMAC(K, V)`.
+    fn next_v(&mut self) {
+        let output_len = self.k.digest_algorithm().output_len;
+        let v = hmac::sign(&self.k, &self.v[..output_len]);
+        self.v[..output_len].copy_from_slice(v.as_ref());
+    }
+}
+
+impl Mechanism for State {
+    fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) {
+        self.update(&[entropy_input, additional_input]);
+    }
+
+    fn generate(&mut self, out: &mut [u8], additional_input: &[u8]) {
+        if !additional_input.is_empty() {
+            self.update(&[additional_input]);
+        }
+        let output_len = self.k.digest_algorithm().output_len;
+        for chunk in out.chunks_mut(output_len) {
+            self.next_v();
+            chunk.copy_from_slice(&self.v[..chunk.len()]);
+        }
+        self.update(&[additional_input]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test;
+    use super::State;
+    use super::super::Mechanism;
+
+    #[test]
+    fn test_hmac_drbg() {
+        test::from_file("src/drbg/hmac_drbg_tests.txt", |section, test_case| {
+            let digest_alg = test_case.consume_digest_alg("Digest").unwrap();
+            let entropy_input = test_case.consume_bytes("EntropyInput");
+            let nonce = test_case.consume_bytes("Nonce");
+            let personalization_string =
+                test_case.consume_bytes("PersonalizationString");
+
+            let mut state = State::instantiate(digest_alg, &entropy_input,
+                                               &nonce,
+                                               &personalization_string);
+            let expected = test_case.consume_bytes("ReturnedBits");
+            let mut actual = vec![0u8; expected.len()];
+            match section {
+                "NoPredictionResistance" => {
+                    let entropy_input_reseed =
+                        test_case.consume_bytes("EntropyInputReseed");
+                    let additional_input_reseed =
+                        test_case.consume_bytes("AdditionalInputReseed");
+                    state.reseed(&entropy_input_reseed,
+                                 &additional_input_reseed);
+                    for name in &["AdditionalInput1", "AdditionalInput2"] {
+                        let additional_input = test_case.consume_bytes(name);
+                        state.generate(&mut actual, &additional_input);
+                    }
+                },
+                "PredictionResistance" => {
+                    // The additional input is used when reseeding, before
+                    // each generation.
+                    for &(name, additional_input_name) in
+                            &[("EntropyInputPR1", "AdditionalInput1"),
+                              ("EntropyInputPR2", "AdditionalInput2")] {
+                        let entropy_input = test_case.consume_bytes(name);
+                        let additional_input =
+                            test_case.consume_bytes(additional_input_name);
+                        state.reseed(&entropy_input, &additional_input);
+                        state.generate(&mut actual, &[]);
+                    }
+                },
+                _ => unreachable!(),
+            }
+            assert_eq!(actual, expected);
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,363 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Deterministic random bit generators (DRBGs), as specified in
+//! [NIST SP 800-90A Rev. 1].
+//!
+//! `HmacDrbg` and `CtrDrbg` implement `rand::SecureRandom`. Each one is
+//! seeded from `rand::SystemRandom` when it is constructed, and reseeds itself
+//! from `SystemRandom` according to its `ReseedPolicy`. Between reseeds no
+//! system calls are made, so a DRBG is much faster than `SystemRandom` when
+//! many small random values are needed. A DRBG may be shared between threads;
+//! concurrent calls to `fill` are serialized.
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::{digest, drbg, rand};
+//! use ring::rand::SecureRandom;
+//!
+//! # fn main() { f().unwrap() }
+//! # fn f() -> Result<(), ring::error::Unspecified> {
+//! let rng = drbg::HmacDrbg::with_reseed_policy(
+//!     &digest::SHA256, b"my application", drbg::ReseedPolicy {
+//!         reseed_interval: 1000,
+//!         prediction_resistance: false,
+//!     })?;
+//!
+//! let mut nonce = [0u8; 12];
+//! rng.fill(&mut nonce)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [NIST SP 800-90A Rev. 1]:
+//!     http://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-90Ar1.pdf
+
+use {digest, error, init, rand};
+use std;
+
+/// When a DRBG reseeds itself from `rand::SystemRandom`.
+#[derive(Clone, Copy, Debug)]
+pub struct ReseedPolicy {
+    /// The maximum number of requests for random bytes between reseeds. It
+    /// must be between 1 and `MAX_RESEED_INTERVAL`, inclusive. Each call to
+    /// `fill` is one request for every `MAX_REQUEST_LEN` bytes or part
+    /// thereof.
+    pub reseed_interval: u64,
+
+    /// Whether the DRBG reseeds itself before every request, for
+    /// prediction resistance (NIST SP 800-90A Rev. 1 Section 8.8). This makes
+    /// the DRBG about as expensive as `SystemRandom`.
+    pub prediction_resistance: bool,
+}
+
+impl Default for ReseedPolicy {
+    fn default() -> Self {
+        ReseedPolicy {
+            reseed_interval: DEFAULT_RESEED_INTERVAL,
+            prediction_resistance: false,
+        }
+    }
+}
+
+/// The reseed interval of the default `ReseedPolicy`.
+pub const DEFAULT_RESEED_INTERVAL: u64 = 1 << 16;
+
+/// The largest reseed interval that NIST SP 800-90A allows.
+pub const MAX_RESEED_INTERVAL: u64 = 1 << 48;
+
+/// The maximum number of bytes that are generated per request. Longer outputs
+/// are generated by several consecutive requests.
+pub const MAX_REQUEST_LEN: usize = (1 << 19) / 8;
+
+/// HMAC_DRBG, as specified in NIST SP 800-90A Rev. 1 Section 10.1.2.
+pub struct HmacDrbg {
+    drbg: Drbg<hmac_drbg::State>,
+}
+
+impl HmacDrbg {
+    /// Constructs a new `HmacDrbg` with the default `ReseedPolicy`.
+    ///
+    /// See `with_reseed_policy`.
+    #[inline]
+    pub fn new(digest_alg: &'static digest::Algorithm,
+               personalization_string: &[u8])
+               -> Result<HmacDrbg, error::Unspecified> {
+        Self::with_reseed_policy(digest_alg, personalization_string,
+                                 ReseedPolicy::default())
+    }
+
+    /// Constructs a new `HmacDrbg` that uses HMAC with `digest_alg`, seeded
+    /// from `rand::SystemRandom`.
+    ///
+    /// `digest_alg` must have an output length of at least 256 bits, so
+    /// SHA-1 isn't supported. The DRBG's security strength is 256 bits. The
+    /// `personalization_string`, which may be empty, should distinguish this
+    /// DRBG from other instances.
+    pub fn with_reseed_policy(digest_alg: &'static digest::Algorithm,
+                              personalization_string: &[u8],
+                              reseed_policy: ReseedPolicy)
+                              -> Result<HmacDrbg, error::Unspecified> {
+        if digest_alg.output_len < HMAC_DRBG_ENTROPY_LEN {
+            return Err(error::Unspecified);
+        }
+        let drbg = Drbg::new(reseed_policy, HMAC_DRBG_ENTROPY_LEN,
+                             HMAC_DRBG_NONCE_LEN, &rand::SystemRandom::new(),
+                             |entropy_input, nonce| {
+            hmac_drbg::State::instantiate(digest_alg, entropy_input, nonce,
+                                          personalization_string)
+        })?;
+        Ok(HmacDrbg { drbg })
+    }
+}
+
+impl rand::SecureRandom for HmacDrbg {
+    fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+        self.drbg.fill(dest, &rand::SystemRandom::new())
+    }
+}
+
+/// CTR_DRBG using AES-256 without a derivation function, as specified in
+/// NIST SP 800-90A Rev. 1 Section 10.2.1.
+pub struct CtrDrbg {
+    drbg: Drbg<ctr_drbg::State>,
+}
+
+impl CtrDrbg {
+    /// Constructs a new `CtrDrbg` with the default `ReseedPolicy`.
+    ///
+    /// See `with_reseed_policy`.
+    #[inline]
+    pub fn new(personalization_string: &[u8])
+               -> Result<CtrDrbg, error::Unspecified> {
+        Self::with_reseed_policy(personalization_string,
+                                 ReseedPolicy::default())
+    }
+
+    /// Constructs a new `CtrDrbg`, seeded from `rand::SystemRandom`.
+    ///
+    /// The DRBG's security strength is 256 bits. The
+    /// `personalization_string`, which may be empty, should distinguish this
+    /// DRBG from other instances; it can't be longer than 48 bytes.
+    pub fn with_reseed_policy(personalization_string: &[u8],
+                              reseed_policy: ReseedPolicy)
+                              -> Result<CtrDrbg, error::Unspecified> {
+        if personalization_string.len() > ctr_drbg::SEED_LEN {
+            return Err(error::Unspecified);
+        }
+        let drbg = Drbg::new(reseed_policy, ctr_drbg::SEED_LEN, 0,
+                             &rand::SystemRandom::new(),
+                             |entropy_input, _| {
+            ctr_drbg::State::instantiate(entropy_input, personalization_string)
+        })?;
+        Ok(CtrDrbg { drbg })
+    }
+}
+
+impl rand::SecureRandom for CtrDrbg {
+    fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+        self.drbg.fill(dest, &rand::SystemRandom::new())
+    }
+}
+
+// A DRBG mechanism's reseed and generate functions.
+trait Mechanism {
+    fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]);
+
+    // `out` must not be longer than `MAX_REQUEST_LEN`.
+    fn generate(&mut self, out: &mut [u8], additional_input: &[u8]);
+}
+
+// The parts of a DRBG that don't depend on the mechanism: the reseed policy,
+// the reseed counter, and the lock.
+struct Drbg<M: Mechanism> {
+    state: std::sync::Mutex<State<M>>,
+    reseed_policy: ReseedPolicy,
+    entropy_len: usize,
+}
+
+struct State<M: Mechanism> {
+    mechanism: M,
+
+    // The number of requests since the last (re)seeding.
+    requests: u64,
+}
+
+impl<M: Mechanism> Drbg<M> {
+    fn new<F>(reseed_policy: ReseedPolicy, entropy_len: usize,
+              nonce_len: usize, entropy_source: &rand::SecureRandom,
+              instantiate: F) -> Result<Drbg<M>, error::Unspecified>
+              where F: FnOnce(&[u8], &[u8]) -> M {
+        if reseed_policy.reseed_interval < 1 ||
+           reseed_policy.reseed_interval > MAX_RESEED_INTERVAL {
+            return Err(error::Unspecified);
+        }
+        init::init_once();
+
+        let mut seed = [0u8; MAX_ENTROPY_LEN];
+        let seed = &mut seed[..(entropy_len + nonce_len)];
+        entropy_source.fill(seed)?;
+        let (entropy_input, nonce) = seed.split_at(entropy_len);
+        Ok(Drbg {
+            state: std::sync::Mutex::new(State {
+                mechanism: instantiate(entropy_input, nonce),
+                requests: 0,
+            }),
+            reseed_policy,
+            entropy_len,
+        })
+    }
+
+    fn fill(&self, dest: &mut [u8], entropy_source: &rand::SecureRandom)
+            -> Result<(), error::Unspecified> {
+        let mut state = self.state.lock().map_err(|_| error::Unspecified)?;
+        for out in dest.chunks_mut(MAX_REQUEST_LEN) {
+            if self.reseed_policy.prediction_resistance ||
+               state.requests >= self.reseed_policy.reseed_interval {
+                let mut entropy_input = [0u8; MAX_ENTROPY_LEN];
+                let entropy_input = &mut entropy_input[..self.entropy_len];
+                entropy_source.fill(entropy_input)?;
+                state.mechanism.reseed(entropy_input, &[]);
+                state.requests = 0;
+            }
+            state.mechanism.generate(out, &[]);
+            state.requests += 1;
+        }
+        Ok(())
+    }
+}
+
+// NIST SP 800-90A Rev. 1 Section 8.6.7: The nonce must have at least half the
+// security strength in bits of entropy.
+const HMAC_DRBG_ENTROPY_LEN: usize = 256 / 8;
+const HMAC_DRBG_NONCE_LEN: usize = HMAC_DRBG_ENTROPY_LEN / 2;
+
+const MAX_ENTROPY_LEN: usize = ctr_drbg::SEED_LEN;
+
+mod ctr_drbg;
+mod hmac_drbg;
+
+#[cfg(test)]
+mod tests {
+    use {digest, error, rand, std, test};
+    use super::*;
+
+    // A mechanism that records how it was (re)seeded.
+    struct Recorder {
+        seeds: std::vec::Vec<std::vec::Vec<u8>>,
+    }
+
+    impl Mechanism for Recorder {
+        fn reseed(&mut self, entropy_input: &[u8], _: &[u8]) {
+            self.seeds.push(entropy_input.to_vec());
+        }
+
+        fn generate(&mut self, out: &mut [u8], _: &[u8]) {
+            assert!(out.len() <= MAX_REQUEST_LEN);
+        }
+    }
+
+    fn recorder(reseed_policy: ReseedPolicy)
+                -> Result<Drbg<Recorder>, error::Unspecified> {
+        let rng = test::rand::FixedByteRandom { byte: 0 };
+        Drbg::new(reseed_policy, 3, 1, &rng, |entropy_input, nonce| {
+            assert_eq!(entropy_input, &[0, 0, 0]);
+            assert_eq!(nonce, &[0]);
+            Recorder { seeds: vec![] }
+        })
+    }
+
+    fn seeds(drbg: &Drbg<Recorder>) -> usize {
+        drbg.state.lock().unwrap().mechanism.seeds.len()
+    }
+
+    #[test]
+    fn test_reseed_interval() {
+        let drbg = recorder(ReseedPolicy {
+            reseed_interval: 2,
+            prediction_resistance: false,
+        }).unwrap();
+        let rng = test::rand::FixedByteRandom { byte: 1 };
+        let mut buf = [0u8; 1];
+        drbg.fill(&mut buf, &rng).unwrap();
+        drbg.fill(&mut buf, &rng).unwrap();
+        assert_eq!(seeds(&drbg), 0);
+        drbg.fill(&mut buf, &rng).unwrap();
+        assert_eq!(seeds(&drbg), 1);
+        assert_eq!(drbg.state.lock().unwrap().mechanism.seeds[0],
+                   vec![1, 1, 1]);
+
+        // Long outputs are generated by several requests.
+        let mut buf = vec![0u8; (2 * MAX_REQUEST_LEN) + 1];
+        drbg.fill(&mut buf, &rng).unwrap();
+        assert_eq!(seeds(&drbg), 2);
+        drbg.fill(&mut buf[..1], &rng).unwrap();
+        assert_eq!(seeds(&drbg), 3);
+    }
+
+    #[test]
+    fn test_prediction_resistance() {
+        let drbg = recorder(ReseedPolicy {
+            reseed_interval: MAX_RESEED_INTERVAL,
+            prediction_resistance: true,
+        }).unwrap();
+        let rng = test::rand::FixedByteRandom { byte: 1 };
+        let mut buf = [0u8; 1];
+        for i in 1..4 {
+            drbg.fill(&mut buf, &rng).unwrap();
+            assert_eq!(seeds(&drbg), i);
+        }
+        drbg.fill(&mut [], &rng).unwrap();
+        assert_eq!(seeds(&drbg), 3);
+    }
+
+    #[test]
+    fn test_reseed_policy_validation() {
+        for &(reseed_interval, ok) in &[(0, false), (1, true),
+                                        (MAX_RESEED_INTERVAL, true),
+                                        (MAX_RESEED_INTERVAL + 1, false)] {
+            let reseed_policy = ReseedPolicy {
+                reseed_interval,
+                prediction_resistance: false,
+            };
+            assert_eq!(recorder(reseed_policy).is_ok(), ok);
+            assert_eq!(HmacDrbg::with_reseed_policy(&digest::SHA256, b"",
+                                                    reseed_policy).is_ok(),
+                       ok);
+            assert_eq!(CtrDrbg::with_reseed_policy(b"", reseed_policy).is_ok(),
+                       ok);
+        }
+    }
+
+    #[test]
+    fn test_entropy_source_failure() {
+        struct Failing;
+        impl rand::SecureRandom for Failing {
+            fn fill(&self, _: &mut [u8]) -> Result<(), error::Unspecified> {
+                Err(error::Unspecified)
+            }
+        }
+        let drbg = recorder(ReseedPolicy {
+            reseed_interval: 1,
+            prediction_resistance: false,
+        }).unwrap();
+        let mut buf = [0u8; 1];
+        drbg.fill(&mut buf, &Failing).unwrap();
+        assert!(drbg.fill(&mut buf, &Failing).is_err());
+
+        assert!(Drbg::new(ReseedPolicy::default(), 3, 1, &Failing,
+                          |_, _| Recorder { seeds: vec![] }).is_err());
+    }
+}
@@ -15,10 +15,17 @@
 //! EdDSA Signatures.
 
 use core;
-use {der, digest, error, pkcs8, private, rand, signature, signature_impl};
+use {der, digest, error, pkcs8, private, rand, signature, signature_impl,
+     zeroize};
 use super::ops::*;
 use untrusted;
 
+#[cfg(all(feature = "use_heap", target_os = "linux"))]
+use secure_mem;
+
+#[cfg(feature = "self_tests")]
+use self_test;
+
 /// Parameters for EdDSA signing and verification.
 pub struct EdDSAParameters;
 
@@ -40,6 +47,29 @@ pub struct Ed25519KeyPair {
     public_key: PublicKey,
 }
 
+impl Drop for Ed25519KeyPair {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.private_scalar);
+        zeroize::zeroize(&mut self.private_prefix);
+    }
+}
+
+#[cfg(all(feature = "use_heap", target_os = "linux"))]
+impl secure_mem::Relocate for Ed25519KeyPair {
+    fn locked_len(&self) -> usize { 0 }
+
+    fn relocate(&mut self, _region: &mut secure_mem::Region)
+                -> Result<(), error::Unspecified> {
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "use_heap", target_os = "linux"))]
+impl secure_mem::Lockable for Ed25519KeyPair {}
+
+#[cfg(all(feature = "use_heap", target_os = "linux"))]
+impl private::Private for Ed25519KeyPair {}
+
 impl<'a> Ed25519KeyPair {
     /// Generates a new key pair and returns the key pair serialized as a
     /// PKCS#8 document.
@@ -54,6 +84,7 @@ impl<'a> Ed25519KeyPair {
         let mut seed = [0u8; SEED_LEN];
         rng.fill(&mut seed)?;
         let key_pair = Ed25519KeyPair::from_seed_(&seed);
+        key_pair.check_consistency()?;
         // TODO: Replace this with `wrap_key()` and return a `PKCS8Document`.
         let mut bytes = [0; ED25519_PKCS8_V2_LEN];
         pkcs8::wrap_key_(&PKCS8_TEMPLATE, &seed[..], key_pair.public_key_bytes(),
@@ -72,7 +103,7 @@ impl<'a> Ed25519KeyPair {
     /// If you need to parse PKCS#8 v1 files (without the public key) then use
     /// `Ed25519KeyPair::from_pkcs8_maybe_unchecked()` instead.
     pub fn from_pkcs8(input: untrusted::Input)
-                      -> Result<Ed25519KeyPair, error::Unspecified> {
+                      -> Result<Ed25519KeyPair, error::KeyRejected> {
         let (seed, public_key) = unwrap_pkcs8(pkcs8::Version::V2Only, input)?;
         Self::from_seed_and_public_key(seed, public_key.unwrap())
     }
@@ -90,7 +121,7 @@ impl<'a> Ed25519KeyPair {
     ///
     /// PKCS#8 v2 files are parsed exactly like `Ed25519KeyPair::from_pkcs8()`.
     pub fn from_pkcs8_maybe_unchecked(input: untrusted::Input)
-            -> Result<Ed25519KeyPair, error::Unspecified> {
+            -> Result<Ed25519KeyPair, error::KeyRejected> {
         let (seed, public_key) = unwrap_pkcs8(pkcs8::Version::V1OrV2, input)?;
         if let Some(public_key) = public_key {
             Self::from_seed_and_public_key(seed, public_key)
@@ -111,14 +142,17 @@ impl<'a> Ed25519KeyPair {
     /// key.
     pub fn from_seed_and_public_key(seed: untrusted::Input,
                                     public_key: untrusted::Input)
-            -> Result<Ed25519KeyPair, error::Unspecified> {
+            -> Result<Ed25519KeyPair, error::KeyRejected> {
         let pair = Self::from_seed_unchecked(seed)?;
 
-        // This implicitly verifies that `public_key` is the right length.
+        if public_key.len() != PUBLIC_KEY_LEN {
+            return Err(error::KeyRejected::invalid_encoding());
+        }
+
         // XXX: This rejects ~18 keys when they are partially reduced, though
         // those keys are virtually impossible to find.
         if public_key != pair.public_key_bytes() {
-            return Err(error::Unspecified);
+            return Err(error::KeyRejected::inconsistent_components());
         }
 
         Ok(pair)
@@ -134,8 +168,11 @@ impl<'a> Ed25519KeyPair {
     /// the private key. It is not possible to detect misuse or corruption of
     /// the private key since the public key isn't given as input.
     pub fn from_seed_unchecked(seed: untrusted::Input)
-                               -> Result<Ed25519KeyPair, error::Unspecified> {
-        let seed = slice_as_array_ref!(seed.as_slice_less_safe(), SEED_LEN)?;
+                               -> Result<Ed25519KeyPair, error::KeyRejected> {
+        let seed = slice_as_array_ref!(seed.as_slice_less_safe(), SEED_LEN)
+            .map_err(|error::Unspecified| {
+                error::KeyRejected::invalid_encoding()
+            })?;
         Ok(Self::from_seed_(seed))
     }
 
@@ -200,16 +237,31 @@ impl<'a> Ed25519KeyPair {
         }
         signature_impl::signature_from_bytes(&signature_bytes)
     }
+
+    // A pairwise consistency test of a generated key pair.
+    #[cfg(feature = "self_tests")]
+    fn check_consistency(&self) -> Result<(), error::Unspecified> {
+        const MESSAGE: &'static [u8] = b"pairwise consistency test";
+        let signature = self.sign(MESSAGE);
+        self_test::consistency_check_result(signature::verify(
+            &ED25519, untrusted::Input::from(self.public_key_bytes()),
+            untrusted::Input::from(MESSAGE),
+            untrusted::Input::from(signature.as_ref())))
+    }
+
+    #[cfg(not(feature = "self_tests"))]
+    #[inline(always)]
+    fn check_consistency(&self) -> Result<(), error::Unspecified> { Ok(()) }
 }
 
 fn unwrap_pkcs8(version: pkcs8::Version, input: untrusted::Input)
         -> Result<(untrusted::Input, Option<untrusted::Input>),
-                  error::Unspecified> {
+                  error::KeyRejected> {
     let (private_key, public_key) =
         pkcs8::unwrap_key(&PKCS8_TEMPLATE, version, input)?;
     let private_key = private_key.read_all(error::Unspecified, |input| {
         der::expect_tag_and_get_value(input, der::Tag::OctetString)
-    })?;
+    }).map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
     Ok((private_key, public_key))
 }
 
@@ -283,6 +335,13 @@ fn digest_scalar(digest: digest::Digest) -> Scalar {
     scalar
 }
 
+#[cfg(feature = "pure_rust")]
+use super::portable::{GFp_curve25519_scalar_mask,
+                      GFp_ge_double_scalarmult_vartime,
+                      GFp_x25519_ge_scalarmult_base, GFp_x25519_sc_muladd,
+                      GFp_x25519_sc_reduce};
+
+#[cfg(not(feature = "pure_rust"))]
 extern  {
     fn GFp_curve25519_scalar_mask(a: &mut Scalar);
     fn GFp_ge_double_scalarmult_vartime(r: &mut Point, a_coeff: &Scalar,
@@ -18,3 +18,6 @@ pub mod ed25519;
 pub mod x25519;
 
 mod ops;
+
+#[cfg(feature = "pure_rust")]
+mod portable;
@@ -15,7 +15,10 @@
 //! Elliptic curve operations on the birationally equivalent curves Curve25519
 //! and Edwards25519.
 
-use {bssl, c, error};
+use {bssl, error};
+
+#[cfg(not(feature = "pure_rust"))]
+use c;
 
 // Keep this in sync with `fe` in curve25519/internal.h.
 pub type Elem = [i32; ELEM_LIMBS];
@@ -39,10 +42,10 @@ const UNREDUCED_SCALAR_LEN: usize = SCALAR_LEN * 2;
 // Keep this in sync with `ge_p3` in curve25519/internal.h.
 #[repr(C)]
 pub struct ExtPoint {
-    x: Elem,
-    y: Elem,
-    z: Elem,
-    t: Elem,
+    pub(super) x: Elem,
+    pub(super) y: Elem,
+    pub(super) z: Elem,
+    pub(super) t: Elem,
 }
 
 impl ExtPoint {
@@ -81,9 +84,9 @@ impl ExtPoint {
 // Keep this in sync with `ge_p2` in curve25519/internal.h.
 #[repr(C)]
 pub struct Point {
-    x: Elem,
-    y: Elem,
-    z: Elem,
+    pub(super) x: Elem,
+    pub(super) y: Elem,
+    pub(super) z: Elem,
 }
 
 impl Point {
@@ -121,6 +124,11 @@ fn encode_point(x: Elem, y: Elem, z: Elem) -> EncodedPoint {
     bytes
 }
 
+#[cfg(feature = "pure_rust")]
+use super::portable::{GFp_fe_invert, GFp_fe_isnegative, GFp_fe_mul,
+                      GFp_fe_tobytes, GFp_x25519_ge_frombytes_vartime};
+
+#[cfg(not(feature = "pure_rust"))]
 extern {
     fn GFp_fe_invert(out: &mut Elem, z: &Elem);
     fn GFp_fe_isnegative(elem: &Elem) -> u8;
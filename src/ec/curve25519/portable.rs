@@ -0,0 +1,820 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Portable implementations of the functions in crypto/curve25519, for builds
+//! with the `pure_rust` feature.
+//!
+//! The functions have the same names and signatures as the C functions so
+//! that `ops`, `x25519` and `ed25519` don't need to care which ones are used.
+//! This is a port of curve25519.c as it is built with `OPENSSL_SMALL`: the
+//! ref10 field arithmetic with ten 25.5-bit limbs, the ref10 scalar
+//! arithmetic with twelve 21-bit limbs, and the 960-byte base point table.
+//! The unrolled C code is written as loops here, but the operations are done
+//! in the same order so that the limbs stay within the same bounds.
+
+#![allow(non_snake_case)]
+
+use c;
+use super::ops::{Elem, EncodedPoint, ExtPoint, Point, Scalar,
+                 UnreducedScalar};
+
+const ZERO: Elem = [0; 10];
+const ONE: Elem = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+// d, 2*d and sqrt(-1), from curve25519.c.
+const D: Elem = [-10913610, 13857413, -15372611, 6949391, 114729, -8787816,
+                 -6275908, -3247719, -18696448, -12055116];
+const D2: Elem = [-21827239, -5839606, -30745221, 13898782, 229458, 15978800,
+                  -12551817, -6495438, 29715968, 9444199];
+const SQRTM1: Elem = [-32595792, -7943725, 9377950, 3500415, 12389472,
+                      -272473, -25146209, -2005654, 326686, 11406482];
+
+// Limb `i` of a field element is scaled by 2**ceil(25.5 * i).
+const FE_WIDTHS: [u32; 10] = [26, 25, 26, 25, 26, 25, 26, 25, 26, 25];
+
+const SC_WIDTHS: [u32; 24] = [21; 24];
+
+// Reads the (up to 32) bits of `a` starting at bit `start`.
+fn load_bits(a: &[u8], start: usize) -> u64 {
+    let mut v = 0u64;
+    for k in 0..4 {
+        if let Some(b) = a.get((start / 8) + k) {
+            v |= u64::from(*b) << (8 * k);
+        }
+    }
+    v >> (start % 8)
+}
+
+// Splits `a` into limbs of the given widths. The last limb gets all the
+// remaining bits unless `mask_last` is set.
+fn load(a: &[u8], widths: &[u32], mask_last: bool, out: &mut [i64]) {
+    let last = out.len() - 1;
+    let mut start = 0;
+    for (i, (out, width)) in out.iter_mut().zip(widths).enumerate() {
+        let mut v = load_bits(a, start);
+        if mask_last || i < last {
+            v &= (1 << width) - 1;
+        }
+        *out = v as i64;
+        start += *width as usize;
+    }
+}
+
+// The inverse of `load`. The limbs must be non-negative and all but the last
+// must fit in their widths.
+fn store(out: &mut [u8], limbs: &[i64], widths: &[u32]) {
+    let mut acc = 0u64;
+    let mut bits = 0;
+    let mut bytes = out.iter_mut();
+    for (limb, width) in limbs.iter().zip(widths) {
+        acc |= (*limb as u64) << bits;
+        bits += width;
+        while bits >= 8 {
+            if let Some(b) = bytes.next() {
+                *b = acc as u8;
+            }
+            acc >>= 8;
+            bits -= 8;
+        }
+    }
+    for b in bytes {
+        *b = acc as u8;
+        acc >>= 8;
+    }
+}
+
+fn fe_frombytes(s: &[u8]) -> Elem {
+    // Ignores the top bit of `s`.
+    let mut h = [0; 10];
+    load(s, &FE_WIDTHS, true, &mut h);
+    fe_carry(h)
+}
+
+// The carry chain of `fe_mul` in curve25519.c.
+fn fe_carry(mut h: [i64; 10]) -> Elem {
+    for &i in &[0, 4, 1, 5, 2, 6, 3, 7, 4, 8, 9, 0] {
+        let width = FE_WIDTHS[i];
+        let carry = (h[i] + (1 << (width - 1))) >> width;
+        if i == 9 {
+            h[0] += carry * 19;
+        } else {
+            h[i + 1] += carry;
+        }
+        h[i] -= carry << width;
+    }
+    let mut r = ZERO;
+    for (r, h) in r.iter_mut().zip(h.iter()) {
+        *r = *h as i32;
+    }
+    r
+}
+
+fn fe_add(f: &Elem, g: &Elem) -> Elem {
+    let mut h = ZERO;
+    for i in 0..10 {
+        h[i] = f[i] + g[i];
+    }
+    h
+}
+
+fn fe_sub(f: &Elem, g: &Elem) -> Elem {
+    let mut h = ZERO;
+    for i in 0..10 {
+        h[i] = f[i] - g[i];
+    }
+    h
+}
+
+fn fe_neg(f: &Elem) -> Elem { fe_sub(&ZERO, f) }
+
+// The product before carrying. When both limbs are odd-numbered their scales
+// add up to one bit more than the scale of limb `i + j`, and 2**255 == 19.
+fn fe_mul_wide(f: &Elem, g: &Elem) -> [i64; 10] {
+    let mut h = [0i64; 10];
+    for i in 0..10 {
+        for j in 0..10 {
+            let mut p = i64::from(f[i]) * i64::from(g[j]);
+            if i % 2 == 1 && j % 2 == 1 {
+                p *= 2;
+            }
+            if i + j < 10 {
+                h[i + j] += p;
+            } else {
+                h[i + j - 10] += 19 * p;
+            }
+        }
+    }
+    h
+}
+
+fn fe_mul(f: &Elem, g: &Elem) -> Elem { fe_carry(fe_mul_wide(f, g)) }
+
+fn fe_sq(f: &Elem) -> Elem { fe_mul(f, f) }
+
+// 2 * f * f.
+fn fe_sq2(f: &Elem) -> Elem {
+    let mut h = fe_mul_wide(f, f);
+    for h in h.iter_mut() {
+        *h *= 2;
+    }
+    fe_carry(h)
+}
+
+fn fe_sq_times(f: &Elem, n: usize) -> Elem {
+    let mut h = fe_sq(f);
+    for _ in 1..n {
+        h = fe_sq(&h);
+    }
+    h
+}
+
+fn fe_mul121666(f: &Elem) -> Elem {
+    let mut h = [0i64; 10];
+    for i in 0..10 {
+        h[i] = i64::from(f[i]) * 121666;
+    }
+    fe_carry(h)
+}
+
+// Replaces `f` with `g` if `b == 1`. `b` must be 0 or 1.
+fn fe_cmov(f: &mut Elem, g: &Elem, b: u32) {
+    let mask = 0u32.wrapping_sub(b) as i32;
+    for i in 0..10 {
+        f[i] ^= (f[i] ^ g[i]) & mask;
+    }
+}
+
+// Swaps `f` and `g` if `b == 1`. `b` must be 0 or 1.
+fn fe_cswap(f: &mut Elem, g: &mut Elem, b: u32) {
+    let mask = 0u32.wrapping_sub(b) as i32;
+    for i in 0..10 {
+        let x = (f[i] ^ g[i]) & mask;
+        f[i] ^= x;
+        g[i] ^= x;
+    }
+}
+
+fn fe_isnonzero(f: &Elem) -> bool {
+    let mut s = [0u8; 32];
+    unsafe { GFp_fe_tobytes(&mut s, f) };
+    s.iter().fold(0, |acc, b| acc | b) != 0
+}
+
+fn fe_pow22523(z: &Elem) -> Elem {
+    let t0 = fe_sq(z);
+    let t1 = fe_sq_times(&t0, 2);
+    let t1 = fe_mul(z, &t1);
+    let t0 = fe_mul(&t0, &t1);
+    let t0 = fe_sq(&t0);
+    let t0 = fe_mul(&t1, &t0);
+    let t1 = fe_sq_times(&t0, 5);
+    let t0 = fe_mul(&t1, &t0);
+    let t1 = fe_sq_times(&t0, 10);
+    let t1 = fe_mul(&t1, &t0);
+    let t2 = fe_sq_times(&t1, 20);
+    let t1 = fe_mul(&t2, &t1);
+    let t1 = fe_sq_times(&t1, 10);
+    let t0 = fe_mul(&t1, &t0);
+    let t1 = fe_sq_times(&t0, 50);
+    let t1 = fe_mul(&t1, &t0);
+    let t2 = fe_sq_times(&t1, 100);
+    let t1 = fe_mul(&t2, &t1);
+    let t1 = fe_sq_times(&t1, 50);
+    let t0 = fe_mul(&t1, &t0);
+    let t0 = fe_sq_times(&t0, 2);
+    fe_mul(&t0, z)
+}
+
+pub unsafe extern fn GFp_fe_invert(out: &mut Elem, z: &Elem) {
+    let t0 = fe_sq(z);
+    let t1 = fe_sq_times(&t0, 2);
+    let t1 = fe_mul(z, &t1);
+    let t0 = fe_mul(&t0, &t1);
+    let t2 = fe_sq(&t0);
+    let t1 = fe_mul(&t1, &t2);
+    let t2 = fe_sq_times(&t1, 5);
+    let t1 = fe_mul(&t2, &t1);
+    let t2 = fe_sq_times(&t1, 10);
+    let t2 = fe_mul(&t2, &t1);
+    let t3 = fe_sq_times(&t2, 20);
+    let t2 = fe_mul(&t3, &t2);
+    let t2 = fe_sq_times(&t2, 10);
+    let t1 = fe_mul(&t2, &t1);
+    let t2 = fe_sq_times(&t1, 50);
+    let t2 = fe_mul(&t2, &t1);
+    let t3 = fe_sq_times(&t2, 100);
+    let t2 = fe_mul(&t3, &t2);
+    let t2 = fe_sq_times(&t2, 50);
+    let t1 = fe_mul(&t2, &t1);
+    let t1 = fe_sq_times(&t1, 5);
+    *out = fe_mul(&t1, &t0);
+}
+
+pub unsafe extern fn GFp_fe_isnegative(f: &Elem) -> u8 {
+    let mut s = [0u8; 32];
+    GFp_fe_tobytes(&mut s, f);
+    s[0] & 1
+}
+
+pub unsafe extern fn GFp_fe_mul(h: &mut Elem, f: &Elem, g: &Elem) {
+    *h = fe_mul(f, g);
+}
+
+pub unsafe extern fn GFp_fe_tobytes(s: &mut EncodedPoint, h: &Elem) {
+    let mut h = {
+        let mut wide = [0i64; 10];
+        for (wide, h) in wide.iter_mut().zip(h.iter()) {
+            *wide = i64::from(*h);
+        }
+        wide
+    };
+
+    // q = floor(h / p). See the proof in curve25519.c.
+    let mut q = ((19 * h[9]) + (1 << 24)) >> 25;
+    for i in 0..10 {
+        q = (h[i] + q) >> FE_WIDTHS[i];
+    }
+
+    // h - (2**255 - 19) * q, which is between 0 and 2**255 - 20.
+    h[0] += 19 * q;
+    for i in 0..9 {
+        let width = FE_WIDTHS[i];
+        h[i + 1] += h[i] >> width;
+        h[i] &= (1 << width) - 1;
+    }
+    h[9] &= (1 << FE_WIDTHS[9]) - 1;
+
+    store(s, &h, &FE_WIDTHS);
+}
+
+// Keep these in sync with `ge_p1p1` and `ge_cached` in curve25519/internal.h.
+// Unlike the C code, the base point multiples are also stored as `Cached`,
+// with `z == 1`, instead of as `ge_precomp`.
+struct P1P1 {
+    x: Elem,
+    y: Elem,
+    z: Elem,
+    t: Elem,
+}
+
+#[derive(Clone, Copy)]
+struct Cached {
+    yplusx: Elem,
+    yminusx: Elem,
+    z: Elem,
+    t2d: Elem,
+}
+
+const CACHED_IDENTITY: Cached = Cached {
+    yplusx: ONE,
+    yminusx: ONE,
+    z: ONE,
+    t2d: ZERO,
+};
+
+fn p3_identity() -> ExtPoint {
+    ExtPoint { x: ZERO, y: ONE, z: ONE, t: ZERO }
+}
+
+fn p3_to_cached(p: &ExtPoint) -> Cached {
+    Cached {
+        yplusx: fe_add(&p.y, &p.x),
+        yminusx: fe_sub(&p.y, &p.x),
+        z: p.z,
+        t2d: fe_mul(&p.t, &D2),
+    }
+}
+
+fn p1p1_to_p2(p: &P1P1) -> Point {
+    Point {
+        x: fe_mul(&p.x, &p.t),
+        y: fe_mul(&p.y, &p.z),
+        z: fe_mul(&p.z, &p.t),
+    }
+}
+
+fn p1p1_to_p3(p: &P1P1) -> ExtPoint {
+    ExtPoint {
+        x: fe_mul(&p.x, &p.t),
+        y: fe_mul(&p.y, &p.z),
+        z: fe_mul(&p.z, &p.t),
+        t: fe_mul(&p.x, &p.y),
+    }
+}
+
+fn p2_dbl(p: &Point) -> P1P1 {
+    let x = fe_sq(&p.x);
+    let z = fe_sq(&p.y);
+    let t = fe_sq2(&p.z);
+    let t0 = fe_sq(&fe_add(&p.x, &p.y));
+    let y = fe_add(&z, &x);
+    let z = fe_sub(&z, &x);
+    P1P1 {
+        x: fe_sub(&t0, &y),
+        y,
+        t: fe_sub(&t, &z),
+        z,
+    }
+}
+
+fn p3_dbl(p: &ExtPoint) -> P1P1 {
+    p2_dbl(&Point { x: p.x, y: p.y, z: p.z })
+}
+
+fn ge_add(p: &ExtPoint, q: &Cached) -> P1P1 {
+    let a = fe_mul(&fe_add(&p.y, &p.x), &q.yplusx);
+    let b = fe_mul(&fe_sub(&p.y, &p.x), &q.yminusx);
+    let c = fe_mul(&q.t2d, &p.t);
+    let zz = fe_mul(&p.z, &q.z);
+    let d = fe_add(&zz, &zz);
+    P1P1 {
+        x: fe_sub(&a, &b),
+        y: fe_add(&a, &b),
+        z: fe_add(&d, &c),
+        t: fe_sub(&d, &c),
+    }
+}
+
+// -(x, y) == (-x, y), so this is equivalent to `x25519_ge_sub`.
+fn cached_neg(q: &Cached) -> Cached {
+    Cached {
+        yplusx: q.yminusx,
+        yminusx: q.yplusx,
+        z: q.z,
+        t2d: fe_neg(&q.t2d),
+    }
+}
+
+fn cached_cmov(t: &mut Cached, u: &Cached, b: u32) {
+    fe_cmov(&mut t.yplusx, &u.yplusx, b);
+    fe_cmov(&mut t.yminusx, &u.yminusx, b);
+    fe_cmov(&mut t.z, &u.z, b);
+    fe_cmov(&mut t.t2d, &u.t2d, b);
+}
+
+// 1 if `b == c`, 0 otherwise.
+fn equal(b: u8, c: u8) -> u32 { u32::from(b ^ c).wrapping_sub(1) >> 31 }
+
+pub unsafe extern fn GFp_x25519_ge_frombytes_vartime(h: &mut ExtPoint,
+                                                     s: &EncodedPoint)
+                                                     -> c::int {
+    h.y = fe_frombytes(s);
+    h.z = ONE;
+    let u = fe_sq(&h.y);
+    let v = fe_mul(&u, &D);
+    let u = fe_sub(&u, &h.z); // u = y^2-1
+    let v = fe_add(&v, &h.z); // v = dy^2+1
+
+    let v3 = fe_mul(&fe_sq(&v), &v); // v3 = v^3
+    h.x = fe_mul(&fe_mul(&fe_sq(&v3), &v), &u); // x = uv^7
+
+    h.x = fe_pow22523(&h.x); // x = (uv^7)^((q-5)/8)
+    h.x = fe_mul(&fe_mul(&h.x, &v3), &u); // x = uv^3(uv^7)^((q-5)/8)
+
+    let vxx = fe_mul(&fe_sq(&h.x), &v);
+    if fe_isnonzero(&fe_sub(&vxx, &u)) { // vx^2-u
+        if fe_isnonzero(&fe_add(&vxx, &u)) { // vx^2+u
+            return 0;
+        }
+        h.x = fe_mul(&h.x, &SQRTM1);
+    }
+
+    if GFp_fe_isnegative(&h.x) != (s[31] >> 7) {
+        h.x = fe_neg(&h.x);
+    }
+
+    h.t = fe_mul(&h.x, &h.y);
+    1
+}
+
+// Entry `i` is (x, y) of ((i + 1) as a four-bit number (i_0, i_1, i_2, i_3),
+// with i_0 the most significant bit) (i_0*2^192 + i_1*2^128 + i_2*2^64 +
+// i_3)G, each serialized with `GFp_fe_tobytes`. This is `k25519SmallPrecomp`
+// from curve25519.c.
+const SMALL_PRECOMP: [u8; 15 * 2 * 32] = [
+    0x1a, 0xd5, 0x25, 0x8f, 0x60, 0x2d, 0x56, 0xc9, 0xb2, 0xa7, 0x25, 0x95,
+    0x60, 0xc7, 0x2c, 0x69, 0x5c, 0xdc, 0xd6, 0xfd, 0x31, 0xe2, 0xa4, 0xc0,
+    0xfe, 0x53, 0x6e, 0xcd, 0xd3, 0x36, 0x69, 0x21, 0x58, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x02, 0xa2, 0xed, 0xf4, 0x8f, 0x6b, 0x0b, 0x3e,
+    0xeb, 0x35, 0x1a, 0xd5, 0x7e, 0xdb, 0x78, 0x00, 0x96, 0x8a, 0xa0, 0xb4,
+    0xcf, 0x60, 0x4b, 0xd4, 0xd5, 0xf9, 0x2d, 0xbf, 0x88, 0xbd, 0x22, 0x62,
+    0x13, 0x53, 0xe4, 0x82, 0x57, 0xfa, 0x1e, 0x8f, 0x06, 0x2b, 0x90, 0xba,
+    0x08, 0xb6, 0x10, 0x54, 0x4f, 0x7c, 0x1b, 0x26, 0xed, 0xda, 0x6b, 0xdd,
+    0x25, 0xd0, 0x4e, 0xea, 0x42, 0xbb, 0x25, 0x03, 0xa2, 0xfb, 0xcc, 0x61,
+    0x67, 0x06, 0x70, 0x1a, 0xc4, 0x78, 0x3a, 0xff, 0x32, 0x62, 0xdd, 0x2c,
+    0xab, 0x50, 0x19, 0x3b, 0xf2, 0x9b, 0x7d, 0xb8, 0xfd, 0x4f, 0x29, 0x9c,
+    0xa7, 0x91, 0xba, 0x0e, 0x46, 0x5e, 0x51, 0xfe, 0x1d, 0xbf, 0xe5, 0xe5,
+    0x9b, 0x95, 0x0d, 0x67, 0xf8, 0xd1, 0xb5, 0x5a, 0xa1, 0x93, 0x2c, 0xc3,
+    0xde, 0x0e, 0x97, 0x85, 0x2d, 0x7f, 0xea, 0xab, 0x3e, 0x47, 0x30, 0x18,
+    0x24, 0xe8, 0xb7, 0x60, 0xae, 0x47, 0x80, 0xfc, 0xe5, 0x23, 0xe7, 0xc2,
+    0xc9, 0x85, 0xe6, 0x98, 0xa0, 0x29, 0x4e, 0xe1, 0x84, 0x39, 0x2d, 0x95,
+    0x2c, 0xf3, 0x45, 0x3c, 0xff, 0xaf, 0x27, 0x4c, 0x6b, 0xa6, 0xf5, 0x4b,
+    0x11, 0xbd, 0xba, 0x5b, 0x9e, 0xc4, 0xa4, 0x51, 0x1e, 0xbe, 0xd0, 0x90,
+    0x3a, 0x9c, 0xc2, 0x26, 0xb6, 0x1e, 0xf1, 0x95, 0x7d, 0xc8, 0x6d, 0x52,
+    0xe6, 0x99, 0x2c, 0x5f, 0x9a, 0x96, 0x0c, 0x68, 0x29, 0xfd, 0xe2, 0xfb,
+    0xe6, 0xbc, 0xec, 0x31, 0x08, 0xec, 0xe6, 0xb0, 0x53, 0x60, 0xc3, 0x8c,
+    0xbe, 0xc1, 0xb3, 0x8a, 0x8f, 0xe4, 0x88, 0x2b, 0x55, 0xe5, 0x64, 0x6e,
+    0x9b, 0xd0, 0xaf, 0x7b, 0x64, 0x2a, 0x35, 0x25, 0x10, 0x52, 0xc5, 0x9e,
+    0x58, 0x11, 0x39, 0x36, 0x45, 0x51, 0xb8, 0x39, 0x93, 0xfc, 0x9d, 0x6a,
+    0xbe, 0x58, 0xcb, 0xa4, 0x0f, 0x51, 0x3c, 0x38, 0x05, 0xca, 0xab, 0x43,
+    0x63, 0x0e, 0xf3, 0x8b, 0x41, 0xa6, 0xf8, 0x9b, 0x53, 0x70, 0x80, 0x53,
+    0x86, 0x5e, 0x8f, 0xe3, 0xc3, 0x0d, 0x18, 0xc8, 0x4b, 0x34, 0x1f, 0xd8,
+    0x1d, 0xbc, 0xf2, 0x6d, 0x34, 0x3a, 0xbe, 0xdf, 0xd9, 0xf6, 0xf3, 0x89,
+    0xa1, 0xe1, 0x94, 0x9f, 0x5d, 0x4c, 0x5d, 0xe9, 0xa1, 0x49, 0x92, 0xef,
+    0x0e, 0x53, 0x81, 0x89, 0x58, 0x87, 0xa6, 0x37, 0xf1, 0xdd, 0x62, 0x60,
+    0x63, 0x5a, 0x9d, 0x1b, 0x8c, 0xc6, 0x7d, 0x52, 0xea, 0x70, 0x09, 0x6a,
+    0xe1, 0x32, 0xf3, 0x73, 0x21, 0x1f, 0x07, 0x7b, 0x7c, 0x9b, 0x49, 0xd8,
+    0xc0, 0xf3, 0x25, 0x72, 0x6f, 0x9d, 0xed, 0x31, 0x67, 0x36, 0x36, 0x54,
+    0x40, 0x92, 0x71, 0xe6, 0x11, 0x28, 0x11, 0xad, 0x93, 0x32, 0x85, 0x7b,
+    0x3e, 0xb7, 0x3b, 0x49, 0x13, 0x1c, 0x07, 0xb0, 0x2e, 0x93, 0xaa, 0xfd,
+    0xfd, 0x28, 0x47, 0x3d, 0x8d, 0xd2, 0xda, 0xc7, 0x44, 0xd6, 0x7a, 0xdb,
+    0x26, 0x7d, 0x1d, 0xb8, 0xe1, 0xde, 0x9d, 0x7a, 0x7d, 0x17, 0x7e, 0x1c,
+    0x37, 0x04, 0x8d, 0x2d, 0x7c, 0x5e, 0x18, 0x38, 0x1e, 0xaf, 0xc7, 0x1b,
+    0x33, 0x48, 0x31, 0x00, 0x59, 0xf6, 0xf2, 0xca, 0x0f, 0x27, 0x1b, 0x63,
+    0x12, 0x7e, 0x02, 0x1d, 0x49, 0xc0, 0x5d, 0x79, 0x87, 0xef, 0x5e, 0x7a,
+    0x2f, 0x1f, 0x66, 0x55, 0xd8, 0x09, 0xd9, 0x61, 0x38, 0x68, 0xb0, 0x07,
+    0xa3, 0xfc, 0xcc, 0x85, 0x10, 0x7f, 0x4c, 0x65, 0x65, 0xb3, 0xfa, 0xfa,
+    0xa5, 0x53, 0x6f, 0xdb, 0x74, 0x4c, 0x56, 0x46, 0x03, 0xe2, 0xd5, 0x7a,
+    0x29, 0x1c, 0xc6, 0x02, 0xbc, 0x59, 0xf2, 0x04, 0x75, 0x63, 0xc0, 0x84,
+    0x2f, 0x60, 0x1c, 0x67, 0x76, 0xfd, 0x63, 0x86, 0xf3, 0xfa, 0xbf, 0xdc,
+    0xd2, 0x2d, 0x90, 0x91, 0xbd, 0x33, 0xa9, 0xe5, 0x66, 0x0c, 0xda, 0x42,
+    0x27, 0xca, 0xf4, 0x66, 0xc2, 0xec, 0x92, 0x14, 0x57, 0x06, 0x63, 0xd0,
+    0x4d, 0x15, 0x06, 0xeb, 0x69, 0x58, 0x4f, 0x77, 0xc5, 0x8b, 0xc7, 0xf0,
+    0x8e, 0xed, 0x64, 0xa0, 0xb3, 0x3c, 0x66, 0x71, 0xc6, 0x2d, 0xda, 0x0a,
+    0x0d, 0xfe, 0x70, 0x27, 0x64, 0xf8, 0x27, 0xfa, 0xf6, 0x5f, 0x30, 0xa5,
+    0x0d, 0x6c, 0xda, 0xf2, 0x62, 0x5e, 0x78, 0x47, 0xd3, 0x66, 0x00, 0x1c,
+    0xfd, 0x56, 0x1f, 0x5d, 0x3f, 0x6f, 0xf4, 0x4c, 0xd8, 0xfd, 0x0e, 0x27,
+    0xc9, 0x5c, 0x2b, 0xbc, 0xc0, 0xa4, 0xe7, 0x23, 0x29, 0x02, 0x9f, 0x31,
+    0xd6, 0xe9, 0xd7, 0x96, 0xf4, 0xe0, 0x5e, 0x0b, 0x0e, 0x13, 0xee, 0x3c,
+    0x09, 0xed, 0xf2, 0x3d, 0x76, 0x91, 0xc3, 0xa4, 0x97, 0xae, 0xd4, 0x87,
+    0xd0, 0x5d, 0xf6, 0x18, 0x47, 0x1f, 0x1d, 0x67, 0xf2, 0xcf, 0x63, 0xa0,
+    0x91, 0x27, 0xf8, 0x93, 0x45, 0x75, 0x23, 0x3f, 0xd1, 0xf1, 0xad, 0x23,
+    0xdd, 0x64, 0x93, 0x96, 0x41, 0x70, 0x7f, 0xf7, 0xf5, 0xa9, 0x89, 0xa2,
+    0x34, 0xb0, 0x8d, 0x1b, 0xae, 0x19, 0x15, 0x49, 0x58, 0x23, 0x6d, 0x87,
+    0x15, 0x4f, 0x81, 0x76, 0xfb, 0x23, 0xb5, 0xea, 0xcf, 0xac, 0x54, 0x8d,
+    0x4e, 0x42, 0x2f, 0xeb, 0x0f, 0x63, 0xdb, 0x68, 0x37, 0xa8, 0xcf, 0x8b,
+    0xab, 0xf5, 0xa4, 0x6e, 0x96, 0x2a, 0xb2, 0xd6, 0xbe, 0x9e, 0xbd, 0x0d,
+    0xb4, 0x42, 0xa9, 0xcf, 0x01, 0x83, 0x8a, 0x17, 0x47, 0x76, 0xc4, 0xc6,
+    0x83, 0x04, 0x95, 0x0b, 0xfc, 0x11, 0xc9, 0x62, 0xb8, 0x0c, 0x76, 0x84,
+    0xd9, 0xb9, 0x37, 0xfa, 0xfc, 0x7c, 0xc2, 0x6d, 0x58, 0x3e, 0xb3, 0x04,
+    0xbb, 0x8c, 0x8f, 0x48, 0xbc, 0x91, 0x27, 0xcc, 0xf9, 0xb7, 0x22, 0x19,
+    0x83, 0x2e, 0x09, 0xb5, 0x72, 0xd9, 0x54, 0x1c, 0x4d, 0xa1, 0xea, 0x0b,
+    0xf1, 0xc6, 0x08, 0x72, 0x46, 0x87, 0x7a, 0x6e, 0x80, 0x56, 0x0a, 0x8a,
+    0xc0, 0xdd, 0x11, 0x6b, 0xd6, 0xdd, 0x47, 0xdf, 0x10, 0xd9, 0xd8, 0xea,
+    0x7c, 0xb0, 0x8f, 0x03, 0x00, 0x2e, 0xc1, 0x8f, 0x44, 0xa8, 0xd3, 0x30,
+    0x06, 0x89, 0xa2, 0xf9, 0x34, 0xad, 0xdc, 0x03, 0x85, 0xed, 0x51, 0xa7,
+    0x82, 0x9c, 0xe7, 0x5d, 0x52, 0x93, 0x0c, 0x32, 0x9a, 0x5b, 0xe1, 0xaa,
+    0xca, 0xb8, 0x02, 0x6d, 0x3a, 0xd4, 0xb1, 0x3a, 0xf0, 0x5f, 0xbe, 0xb5,
+    0x0d, 0x10, 0x6b, 0x38, 0x32, 0xac, 0x76, 0x80, 0xbd, 0xca, 0x94, 0x71,
+    0x7a, 0xf2, 0xc9, 0x35, 0x2a, 0xde, 0x9f, 0x42, 0x49, 0x18, 0x01, 0xab,
+    0xbc, 0xef, 0x7c, 0x64, 0x3f, 0x58, 0x3d, 0x92, 0x59, 0xdb, 0x13, 0xdb,
+    0x58, 0x6e, 0x0a, 0xe0, 0xb7, 0x91, 0x4a, 0x08, 0x20, 0xd6, 0x2e, 0x3c,
+    0x45, 0xc9, 0x8b, 0x17, 0x79, 0xe7, 0xc7, 0x90, 0x99, 0x3a, 0x18, 0x25,
+];
+
+fn small_precomp(i: usize) -> Cached {
+    let bytes = &SMALL_PRECOMP[(i * 2 * 32)..][..(2 * 32)];
+    let x = fe_frombytes(&bytes[..32]);
+    let y = fe_frombytes(&bytes[32..]);
+    Cached {
+        yplusx: fe_add(&y, &x),
+        yminusx: fe_sub(&y, &x),
+        z: ONE,
+        t2d: fe_mul(&fe_mul(&x, &y), &D2),
+    }
+}
+
+pub unsafe extern fn GFp_x25519_ge_scalarmult_base(h: &mut ExtPoint,
+                                                   a: &Scalar) {
+    let mut multiples = [CACHED_IDENTITY; 15];
+    for (i, multiple) in multiples.iter_mut().enumerate() {
+        *multiple = small_precomp(i);
+    }
+
+    // 64 doublings and 64 additions, as in
+    // `x25519_ge_scalarmult_small_precomp`.
+    *h = p3_identity();
+    for i in (0..64).rev() {
+        let mut index = 0u8;
+        for j in 0..4 {
+            let bit = 1 & (a[(8 * j) + (i / 8)] >> (i & 7));
+            index |= bit << j;
+        }
+
+        let mut e = CACHED_IDENTITY;
+        for (j, multiple) in multiples.iter().enumerate() {
+            cached_cmov(&mut e, multiple, equal(index, (j + 1) as u8));
+        }
+
+        let doubled = ge_add(h, &p3_to_cached(h));
+        *h = p1p1_to_p3(&doubled);
+        let sum = ge_add(h, &e);
+        *h = p1p1_to_p3(&sum);
+    }
+}
+
+// A, 3A, 5A, 7A, 9A, 11A, 13A, 15A.
+fn odd_multiples(a: &ExtPoint) -> [Cached; 8] {
+    let mut r = [p3_to_cached(a); 8];
+    let a2 = p1p1_to_p3(&p3_dbl(a));
+    for i in 1..8 {
+        r[i] = p3_to_cached(&p1p1_to_p3(&ge_add(&a2, &r[i - 1])));
+    }
+    r
+}
+
+fn slide(a: &Scalar) -> [i8; 256] {
+    let mut r = [0i8; 256];
+    for i in 0..256 {
+        r[i] = (1 & (a[i >> 3] >> (i & 7))) as i8;
+    }
+
+    for i in 0..256 {
+        if r[i] == 0 {
+            continue;
+        }
+        let mut b = 1;
+        while b <= 6 && i + b < 256 {
+            if r[i + b] != 0 {
+                let shifted = i32::from(r[i + b]) << b;
+                if i32::from(r[i]) + shifted <= 15 {
+                    r[i] = (i32::from(r[i]) + shifted) as i8;
+                    r[i + b] = 0;
+                } else if i32::from(r[i]) - shifted >= -15 {
+                    r[i] = (i32::from(r[i]) - shifted) as i8;
+                    for k in (i + b)..256 {
+                        if r[k] == 0 {
+                            r[k] = 1;
+                            break;
+                        }
+                        r[k] = 0;
+                    }
+                } else {
+                    break;
+                }
+            }
+            b += 1;
+        }
+    }
+
+    r
+}
+
+fn add_slide(t: P1P1, s: i8, multiples: &[Cached; 8]) -> P1P1 {
+    if s > 0 {
+        ge_add(&p1p1_to_p3(&t), &multiples[(s / 2) as usize])
+    } else if s < 0 {
+        ge_add(&p1p1_to_p3(&t), &cached_neg(&multiples[(-s / 2) as usize]))
+    } else {
+        t
+    }
+}
+
+// r = a * A + b * B, where B is the base point. Unlike the C code, this
+// computes the multiples of B on the fly instead of using a table.
+pub unsafe extern fn GFp_ge_double_scalarmult_vartime(r: &mut Point,
+                                                      a: &Scalar,
+                                                      A: &ExtPoint,
+                                                      b: &Scalar) {
+    let aslide = slide(a);
+    let bslide = slide(b);
+
+    let Ai = odd_multiples(A);
+    let B = {
+        let x = fe_frombytes(&SMALL_PRECOMP[..32]);
+        let y = fe_frombytes(&SMALL_PRECOMP[32..64]);
+        ExtPoint { x, y, z: ONE, t: fe_mul(&x, &y) }
+    };
+    let Bi = odd_multiples(&B);
+
+    *r = Point { x: ZERO, y: ONE, z: ONE };
+
+    let top = match (0..256).rev().find(|&i| aslide[i] != 0 ||
+                                             bslide[i] != 0) {
+        Some(i) => i,
+        None => { return; },
+    };
+
+    for i in (0..(top + 1)).rev() {
+        let t = p2_dbl(r);
+        let t = add_slide(t, aslide[i], &Ai);
+        let t = add_slide(t, bslide[i], &Bi);
+        *r = p1p1_to_p2(&t);
+    }
+}
+
+// The scalar arithmetic works modulo
+// l = 2^252 + 27742317777372353535851937790883648493. Limb `i` is scaled by
+// 2^(21*i).
+
+// 2^252 * 2^(21*i) == -FOLD * 2^(21*(i - 12)) (mod l), where `FOLD` holds the
+// 21-bit limbs of 27742317777372353535851937790883648493, negated.
+const FOLD: [i64; 6] = [666643, 470296, 654183, -997805, 136657, -683901];
+
+fn sc_fold(s: &mut [i64; 24], i: usize) {
+    for (j, f) in FOLD.iter().enumerate() {
+        s[i - 12 + j] += s[i] * f;
+    }
+    s[i] = 0;
+}
+
+fn sc_carry(s: &mut [i64; 24], i: usize) {
+    let carry = (s[i] + (1 << 20)) >> 21;
+    s[i + 1] += carry;
+    s[i] -= carry << 21;
+}
+
+fn sc_carry_floor(s: &mut [i64; 24], i: usize) {
+    let carry = s[i] >> 21;
+    s[i + 1] += carry;
+    s[i] -= carry << 21;
+}
+
+// The reduction shared by `GFp_x25519_sc_reduce` and `GFp_x25519_sc_muladd`.
+fn sc_reduce(mut s: [i64; 24], out: &mut [u8]) {
+    for i in (18..24).rev() {
+        sc_fold(&mut s, i);
+    }
+    for i in (6..17).step_by(2) {
+        sc_carry(&mut s, i);
+    }
+    for i in (7..16).step_by(2) {
+        sc_carry(&mut s, i);
+    }
+
+    for i in (12..18).rev() {
+        sc_fold(&mut s, i);
+    }
+    for i in (0..11).step_by(2) {
+        sc_carry(&mut s, i);
+    }
+    for i in (1..12).step_by(2) {
+        sc_carry(&mut s, i);
+    }
+
+    sc_fold(&mut s, 12);
+    for i in 0..12 {
+        sc_carry_floor(&mut s, i);
+    }
+
+    sc_fold(&mut s, 12);
+    for i in 0..11 {
+        sc_carry_floor(&mut s, i);
+    }
+
+    store(&mut out[..32], &s[..12], &SC_WIDTHS[..12]);
+}
+
+pub unsafe extern fn GFp_x25519_sc_reduce(s: &mut UnreducedScalar) {
+    let mut limbs = [0; 24];
+    load(&s[..], &SC_WIDTHS, false, &mut limbs);
+    sc_reduce(limbs, &mut s[..]);
+}
+
+// s = (a * b + c) mod l.
+pub unsafe extern fn GFp_x25519_sc_muladd(s: &mut Scalar, a: &Scalar,
+                                          b: &Scalar, c: &Scalar) {
+    let mut a_limbs = [0; 12];
+    let mut b_limbs = [0; 12];
+    let mut limbs = [0; 24];
+    load(a, &SC_WIDTHS[..12], false, &mut a_limbs);
+    load(b, &SC_WIDTHS[..12], false, &mut b_limbs);
+    load(c, &SC_WIDTHS[..12], false, &mut limbs[..12]);
+    for i in 0..12 {
+        for j in 0..12 {
+            limbs[i + j] += a_limbs[i] * b_limbs[j];
+        }
+    }
+
+    for i in (0..23).step_by(2) {
+        sc_carry(&mut limbs, i);
+    }
+    for i in (1..22).step_by(2) {
+        sc_carry(&mut limbs, i);
+    }
+
+    sc_reduce(limbs, &mut s[..]);
+}
+
+pub unsafe extern fn GFp_curve25519_scalar_mask(a: &mut Scalar) {
+    a[0] &= 248;
+    a[31] &= 127;
+    a[31] |= 64;
+}
+
+// The Montgomery ladder of `x25519_scalar_mult_generic`.
+pub unsafe extern fn GFp_x25519_scalar_mult(out: &mut EncodedPoint,
+                                            scalar: &Scalar,
+                                            point: &EncodedPoint) {
+    let mut e = *scalar;
+    GFp_curve25519_scalar_mask(&mut e);
+    let x1 = fe_frombytes(point);
+    let mut x2 = ONE;
+    let mut z2 = ZERO;
+    let mut x3 = x1;
+    let mut z3 = ONE;
+
+    let mut swap = 0;
+    for pos in (0..255).rev() {
+        let b = u32::from(1 & (e[pos / 8] >> (pos & 7)));
+        swap ^= b;
+        fe_cswap(&mut x2, &mut x3, swap);
+        fe_cswap(&mut z2, &mut z3, swap);
+        swap = b;
+
+        let tmp0 = fe_sub(&x3, &z3);
+        let tmp1 = fe_sub(&x2, &z2);
+        x2 = fe_add(&x2, &z2);
+        z2 = fe_add(&x3, &z3);
+        z3 = fe_mul(&tmp0, &x2);
+        z2 = fe_mul(&z2, &tmp1);
+        let tmp0 = fe_sq(&tmp1);
+        let tmp1 = fe_sq(&x2);
+        x3 = fe_add(&z3, &z2);
+        z2 = fe_sub(&z3, &z2);
+        x2 = fe_mul(&tmp1, &tmp0);
+        let tmp1 = fe_sub(&tmp1, &tmp0);
+        z2 = fe_sq(&z2);
+        z3 = fe_mul121666(&tmp1);
+        x3 = fe_sq(&x3);
+        let tmp0 = fe_add(&tmp0, &z3);
+        z3 = fe_mul(&x1, &z2);
+        z2 = fe_mul(&tmp1, &tmp0);
+    }
+    fe_cswap(&mut x2, &mut x3, swap);
+    fe_cswap(&mut z2, &mut z3, swap);
+
+    let mut z2_inv = ZERO;
+    GFp_fe_invert(&mut z2_inv, &z2);
+    GFp_fe_tobytes(out, &fe_mul(&x2, &z2_inv));
+}
+
+pub unsafe extern fn GFp_x25519_public_from_private(public_key_out: &mut EncodedPoint,
+                                                    private_key: &Scalar) {
+    let mut e = *private_key;
+    GFp_curve25519_scalar_mask(&mut e);
+
+    let mut a = p3_identity();
+    GFp_x25519_ge_scalarmult_base(&mut a, &e);
+
+    // We only need the u-coordinate of the curve25519 point. The map is
+    // u=(y+1)/(1-y). Since y=Y/Z, this gives u=(Z+Y)/(Z-Y).
+    let zplusy = fe_add(&a.z, &a.y);
+    let zminusy = fe_sub(&a.z, &a.y);
+    let mut zminusy_inv = ZERO;
+    GFp_fe_invert(&mut zminusy_inv, &zminusy);
+    GFp_fe_tobytes(public_key_out, &fe_mul(&zplusy, &zminusy_inv));
+}
@@ -114,6 +114,10 @@ type SharedSecret = [u8; SHARED_SECRET_LEN];
 const SHARED_SECRET_LEN: usize = ELEM_AND_SCALAR_LEN;
 
 
+#[cfg(feature = "pure_rust")]
+use super::portable::{GFp_x25519_public_from_private, GFp_x25519_scalar_mult};
+
+#[cfg(not(feature = "pure_rust"))]
 extern {
     fn GFp_x25519_public_from_private(public_key_out: &mut PublicKey,
                                       private_key: &PrivateKey);
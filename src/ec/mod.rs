@@ -12,7 +12,7 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use {error, init, rand};
+use {error, init, rand, zeroize};
 use untrusted;
 
 /// A key agreement algorithm.
@@ -62,6 +62,12 @@ pub struct PrivateKey {
     bytes: [u8; SCALAR_MAX_BYTES],
 }
 
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.bytes);
+    }
+}
+
 impl<'a> PrivateKey {
     pub fn generate(curve: &Curve, rng: &rand::SecureRandom)
                     -> Result<PrivateKey, error::Unspecified> {
@@ -22,6 +22,12 @@ use super::ops::*;
 use super::public_key::*;
 use untrusted;
 
+#[cfg(all(feature = "use_heap", target_os = "linux"))]
+use secure_mem;
+
+#[cfg(feature = "self_tests")]
+use self_test;
+
 /// An ECDSA signing algorithm.
 pub struct ECDSASigningAlgorithm {
     curve: &'static ec::Curve,
@@ -189,6 +195,22 @@ pub struct ECDSAKeyPair {
     alg: &'static ECDSASigningAlgorithm,
 }
 
+#[cfg(all(feature = "use_heap", target_os = "linux"))]
+impl secure_mem::Relocate for ECDSAKeyPair {
+    fn locked_len(&self) -> usize { 0 }
+
+    fn relocate(&mut self, _region: &mut secure_mem::Region)
+                -> Result<(), error::Unspecified> {
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "use_heap", target_os = "linux"))]
+impl secure_mem::Lockable for ECDSAKeyPair {}
+
+#[cfg(all(feature = "use_heap", target_os = "linux"))]
+impl private::Private for ECDSAKeyPair {}
+
 impl<'a> ECDSAKeyPair {
     /// Generates a new key pair and returns the key pair serialized as a
     /// PKCS#8 document.
@@ -208,6 +230,7 @@ impl<'a> ECDSAKeyPair {
         let mut public_key_bytes = [0; ec::PUBLIC_KEY_MAX_LEN];
         let public_key_bytes = &mut public_key_bytes[..alg.curve.public_key_len];
         (alg.curve.public_from_private)(public_key_bytes, &private_key)?;
+        check_consistency(alg, &private_key, public_key_bytes)?;
         Ok(pkcs8::wrap_key(&alg.pkcs8_template, private_key.bytes(alg.curve),
                            public_key_bytes))
     }
@@ -224,7 +247,7 @@ impl<'a> ECDSAKeyPair {
     /// algorithm identifier in the PKCS#8 header.
     pub fn from_pkcs8(alg: &'static ECDSASigningAlgorithm,
                       input: untrusted::Input)
-                      -> Result<ECDSAKeyPair, error::Unspecified> {
+                      -> Result<ECDSAKeyPair, error::KeyRejected> {
         let key_pair = ec::suite_b::key_pair_from_pkcs8(alg.curve,
             alg.pkcs8_template, input)?;
         Ok(ECDSAKeyPair { key_pair, alg })
@@ -239,13 +262,35 @@ impl<'a> ECDSAKeyPair {
     pub fn from_private_key_and_public_key(alg: &'static ECDSASigningAlgorithm,
                                            private_key: untrusted::Input,
                                            public_key: untrusted::Input)
-                      -> Result<ECDSAKeyPair, error::Unspecified> {
+                      -> Result<ECDSAKeyPair, error::KeyRejected> {
         let key_pair = ec::suite_b::key_pair_from_bytes(
             alg.curve, private_key, public_key)?;
         Ok(ECDSAKeyPair { key_pair, alg })
     }
 }
 
+// A consistency test of a generated key pair. Signing isn't implemented yet, so
+// this checks that the key pair would be accepted by
+// `ECDSAKeyPair::from_private_key_and_public_key()`.
+#[cfg(feature = "self_tests")]
+fn check_consistency(alg: &'static ECDSASigningAlgorithm,
+                     private_key: &ec::PrivateKey, public_key: &[u8])
+                     -> Result<(), error::Unspecified> {
+    let result = ec::suite_b::key_pair_from_bytes(
+        alg.curve, untrusted::Input::from(private_key.bytes(alg.curve)),
+        untrusted::Input::from(public_key));
+    self_test::consistency_check_result(
+        result.map(|_| ()).map_err(error::Unspecified::from))
+}
+
+#[cfg(not(feature = "self_tests"))]
+#[inline(always)]
+fn check_consistency(_alg: &'static ECDSASigningAlgorithm,
+                     _private_key: &ec::PrivateKey, _public_key: &[u8])
+                     -> Result<(), error::Unspecified> {
+    Ok(())
+}
+
 fn split_rs_fixed<'a>(
         ops: &'static ScalarOps, input: &mut untrusted::Reader<'a>)
         -> Result<(untrusted::Input<'a>, untrusted::Input<'a>),
@@ -473,6 +473,9 @@ mod tests {
 
     #[test]
     fn p384_elem_sub_test() {
+        #[cfg(feature = "pure_rust")]
+        use super::nistz::GFp_p384_elem_sub;
+        #[cfg(not(feature = "pure_rust"))]
         extern {
             fn GFp_p384_elem_sub(r: *mut Limb, a: *const Limb, b: *const Limb);
         }
@@ -514,6 +517,9 @@ mod tests {
 
     #[test]
     fn p384_elem_div_by_2_test() {
+        #[cfg(feature = "pure_rust")]
+        use super::nistz::GFp_p384_elem_div_by_2;
+        #[cfg(not(feature = "pure_rust"))]
         extern {
             fn GFp_p384_elem_div_by_2(r: *mut Limb, a: *const Limb);
         }
@@ -545,6 +551,9 @@ mod tests {
     // TODO: Add test vectors that test the range of values above `q`.
     #[test]
     fn p256_elem_neg_test() {
+        #[cfg(feature = "pure_rust")]
+        use super::nistz::GFp_nistz256_neg;
+        #[cfg(not(feature = "pure_rust"))]
         extern {
             fn GFp_nistz256_neg(r: *mut Limb, a: *const Limb);
         }
@@ -554,6 +563,9 @@ mod tests {
 
     #[test]
     fn p384_elem_neg_test() {
+        #[cfg(feature = "pure_rust")]
+        use super::nistz::GFp_p384_elem_neg;
+        #[cfg(not(feature = "pure_rust"))]
         extern {
             fn GFp_p384_elem_neg(r: *mut Limb, a: *const Limb);
         }
@@ -647,6 +659,9 @@ mod tests {
 
     #[test]
     fn p256_scalar_square_test() {
+        #[cfg(feature = "pure_rust")]
+        use super::nistz::GFp_p256_scalar_sqr_rep_mont;
+        #[cfg(not(feature = "pure_rust"))]
         extern {
             fn GFp_p256_scalar_sqr_rep_mont(r: *mut Limb, a: *const Limb,
                                             rep: c::int);
@@ -722,6 +737,9 @@ mod tests {
 
     #[test]
     fn p256_point_sum_mixed_test() {
+        #[cfg(feature = "pure_rust")]
+        use super::nistz::GFp_nistz256_point_add_affine;
+        #[cfg(not(feature = "pure_rust"))]
         extern {
             fn GFp_nistz256_point_add_affine(
                 r: *mut Limb/*[p256::COMMON_OPS.num_limbs*3]*/,
@@ -763,6 +781,9 @@ mod tests {
 
     #[test]
     fn p256_point_double_test() {
+        #[cfg(feature = "pure_rust")]
+        use super::nistz::GFp_nistz256_point_double;
+        #[cfg(not(feature = "pure_rust"))]
         extern {
             fn GFp_nistz256_point_double(
                 r: *mut Limb/*[p256::COMMON_OPS.num_limbs*3]*/,
@@ -774,6 +795,9 @@ mod tests {
 
     #[test]
     fn p384_point_double_test() {
+        #[cfg(feature = "pure_rust")]
+        use super::nistz::GFp_nistz384_point_double;
+        #[cfg(not(feature = "pure_rust"))]
         extern {
             fn GFp_nistz384_point_double(
                 r: *mut Limb/*[p384::COMMON_OPS.num_limbs*3]*/,
@@ -1147,3 +1171,6 @@ macro_rules! bench_curve {
 pub mod p256;
 pub mod p384;
 mod elem;
+
+#[cfg(feature = "pure_rust")]
+mod nistz;
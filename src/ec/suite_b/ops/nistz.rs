@@ -0,0 +1,531 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Portable implementations of the P-256 and P-384 functions in crypto/ec,
+//! for builds with the `pure_rust` feature.
+//!
+//! The functions have the same names and signatures as the C and assembly
+//! language functions so that `p256` and `p384` don't need to care which ones
+//! are used. The field and scalar arithmetic is the generic Montgomery
+//! arithmetic in `limb`; the point arithmetic follows ecp_nistz384.inl, which
+//! the C code uses for both curves.
+
+#![allow(non_snake_case)]
+
+use c;
+use core;
+use limb::{limb_is_zero, limbs_add, limbs_copy_conditional, limbs_mont_mul,
+           limbs_sub};
+use super::{CommonOps, Limb, LIMB_BITS, LIMB_BYTES, MAX_LIMBS, p256, p384};
+
+type E = [Limb; MAX_LIMBS];
+
+// The point at infinity has Z == 0.
+type P = [E; 3];
+
+struct Curve {
+    ops: &'static CommonOps,
+
+    // -q**-1 (mod 2**LIMB_BITS) and -n**-1 (mod 2**LIMB_BITS).
+    q_n0: Limb,
+    n_n0: Limb,
+
+    // 1, Montgomery-encoded.
+    one: E,
+}
+
+#[derive(Clone, Copy)]
+struct Modulus<'a> {
+    m: &'a [Limb],
+    n0: Limb,
+}
+
+impl Curve {
+    fn q(&self) -> Modulus {
+        Modulus { m: &self.ops.q.p[..self.ops.num_limbs], n0: self.q_n0 }
+    }
+
+    fn n(&self) -> Modulus {
+        Modulus { m: &self.ops.n.limbs[..self.ops.num_limbs], n0: self.n_n0 }
+    }
+}
+
+static P256: Curve = Curve {
+    ops: &p256::COMMON_OPS,
+    q_n0: 1,
+    #[cfg(target_pointer_width = "64")]
+    n_n0: 0xccd1c8aa_ee00bc4f,
+    #[cfg(target_pointer_width = "32")]
+    n_n0: 0xee00bc4f,
+    one: limbs![0, 0, 0, 0,
+                0x00000000, 0xfffffffe, 0xffffffff, 0xffffffff,
+                0xffffffff, 0x00000000, 0x00000000, 0x00000001],
+};
+
+static P384: Curve = Curve {
+    ops: &p384::COMMON_OPS,
+    #[cfg(target_pointer_width = "64")]
+    q_n0: 0x00000001_00000001,
+    #[cfg(target_pointer_width = "32")]
+    q_n0: 1,
+    #[cfg(target_pointer_width = "64")]
+    n_n0: 0x6ed46089_e88fdc45,
+    #[cfg(target_pointer_width = "32")]
+    n_n0: 0xe88fdc45,
+    one: limbs![0x00000000, 0x00000000, 0x00000000, 0x00000000,
+                0x00000000, 0x00000000, 0x00000000, 0x00000001,
+                0x00000000, 0xffffffff, 0xffffffff, 0x00000001],
+};
+
+// The elements are fully reduced modulo `m`. Only the first `m.len()` limbs
+// of each one are used; the rest are zero.
+impl<'a> Modulus<'a> {
+    fn add(&self, a: &E, b: &E) -> E {
+        let n = self.m.len();
+        let mut r = [0; MAX_LIMBS];
+        let carry = limbs_add(&mut r[..n], &a[..n], &b[..n]);
+        let mut reduced = [0; MAX_LIMBS];
+        let borrow = limbs_sub(&mut reduced[..n], &r[..n], self.m);
+        // Use `a + b - m` if `a + b` overflowed or wasn't less than `m`.
+        let use_reduced = (carry | (borrow ^ 1)).wrapping_neg();
+        limbs_copy_conditional(&mut r[..n], &reduced[..n], use_reduced);
+        r
+    }
+
+    fn sub(&self, a: &E, b: &E) -> E {
+        let n = self.m.len();
+        let mut r = [0; MAX_LIMBS];
+        let borrow = limbs_sub(&mut r[..n], &a[..n], &b[..n]);
+        let mut m = [0; MAX_LIMBS];
+        limbs_copy_conditional(&mut m[..n], self.m, borrow.wrapping_neg());
+        let mut sum = [0; MAX_LIMBS];
+        let _ = limbs_add(&mut sum[..n], &r[..n], &m[..n]);
+        sum
+    }
+
+    fn neg(&self, a: &E) -> E {
+        self.sub(&[0; MAX_LIMBS], a)
+    }
+
+    fn mul_by_2(&self, a: &E) -> E { self.add(a, a) }
+
+    fn mul_by_3(&self, a: &E) -> E { self.add(&self.add(a, a), a) }
+
+    fn div_by_2(&self, a: &E) -> E {
+        let n = self.m.len();
+        // If `a` is odd then `a + m` is even, and (a + m) / 2 == a / 2 (mod m).
+        let mut m = [0; MAX_LIMBS];
+        limbs_copy_conditional(&mut m[..n], self.m, (a[0] & 1).wrapping_neg());
+        let mut sum = [0; MAX_LIMBS];
+        let carry = limbs_add(&mut sum[..n], &a[..n], &m[..n]);
+        let mut r = [0; MAX_LIMBS];
+        for i in 0..n {
+            let hi = if i + 1 < n { sum[i + 1] } else { carry };
+            r[i] = (sum[i] >> 1) | (hi << (LIMB_BITS - 1));
+        }
+        r
+    }
+
+    fn mul(&self, a: &E, b: &E) -> E {
+        let n = self.m.len();
+        let mut r = [0; MAX_LIMBS];
+        limbs_mont_mul(&mut r[..n], &a[..n], &b[..n], self.m, self.n0);
+        r
+    }
+
+    fn sqr(&self, a: &E) -> E { self.mul(a, a) }
+
+    fn is_zero(&self, a: &E) -> Limb {
+        limb_is_zero(a[..self.m.len()].iter().fold(0, |acc, a| acc | a))
+    }
+
+    fn is_equal(&self, a: &E, b: &E) -> bool {
+        a[..self.m.len()] == b[..self.m.len()]
+    }
+}
+
+fn point_double(curve: &Curve, a: &P) -> P {
+    let q = curve.q();
+    let (in_x, in_y, in_z) = (&a[0], &a[1], &a[2]);
+
+    let mut s = q.mul_by_2(in_y);
+    let mut z_sqr = q.sqr(in_z);
+    s = q.sqr(&s);
+    let res_z = q.mul_by_2(&q.mul(in_z, in_y));
+
+    let mut m = q.add(in_x, &z_sqr);
+    z_sqr = q.sub(in_x, &z_sqr);
+
+    let mut res_y = q.div_by_2(&q.sqr(&s));
+
+    m = q.mul_by_3(&q.mul(&m, &z_sqr));
+
+    s = q.mul(&s, in_x);
+    let tmp0 = q.mul_by_2(&s);
+
+    let res_x = q.sub(&q.sqr(&m), &tmp0);
+    s = q.sub(&s, &res_x);
+
+    res_y = q.sub(&q.mul(&s, &m), &res_y);
+
+    [res_x, res_y, res_z]
+}
+
+fn point_add(curve: &Curve, a: &P, b: &P) -> P {
+    let q = curve.q();
+    let (in1_x, in1_y, in1_z) = (&a[0], &a[1], &a[2]);
+    let (in2_x, in2_y, in2_z) = (&b[0], &b[1], &b[2]);
+
+    let in1_infty = q.is_zero(in1_z);
+    let in2_infty = q.is_zero(in2_z);
+
+    let z2_sqr = q.sqr(in2_z);
+    let z1_sqr = q.sqr(in1_z);
+
+    let s1 = q.mul(&q.mul(&z2_sqr, in2_z), in1_y); // S1 = Y1*Z2^3
+    let s2 = q.mul(&q.mul(&z1_sqr, in1_z), in2_y); // S2 = Y2*Z1^3
+    let r = q.sub(&s2, &s1); // R = S2 - S1
+
+    let u1 = q.mul(in1_x, &z2_sqr); // U1 = X1*Z2^2
+    let u2 = q.mul(in2_x, &z1_sqr); // U2 = X2*Z1^2
+    let h = q.sub(&u2, &u1); // H = U2 - U1
+
+    // This should not happen during signing or ECDH, so branching here isn't
+    // a timing side channel.
+    if q.is_equal(&u1, &u2) && in1_infty == 0 && in2_infty == 0 {
+        if q.is_equal(&s1, &s2) {
+            return point_double(curve, a);
+        }
+        return [[0; MAX_LIMBS]; 3];
+    }
+
+    let r_sqr = q.sqr(&r);
+    let mut res_z = q.mul(&q.mul(&h, in1_z), in2_z); // Z3 = H*Z1*Z2
+    let h_sqr = q.sqr(&h);
+    let h_cub = q.mul(&h_sqr, &h);
+
+    let u2 = q.mul(&u1, &h_sqr); // U1*H^2
+    let mut res_x = q.sub(&q.sub(&r_sqr, &q.mul_by_2(&u2)), &h_cub);
+    let mut res_y = q.sub(&q.mul(&r, &q.sub(&u2, &res_x)),
+                          &q.mul(&s1, &h_cub));
+
+    let n = q.m.len();
+    limbs_copy_conditional(&mut res_x[..n], &in2_x[..n], in1_infty);
+    limbs_copy_conditional(&mut res_y[..n], &in2_y[..n], in1_infty);
+    limbs_copy_conditional(&mut res_z[..n], &in2_z[..n], in1_infty);
+
+    limbs_copy_conditional(&mut res_x[..n], &in1_x[..n], in2_infty);
+    limbs_copy_conditional(&mut res_y[..n], &in1_y[..n], in2_infty);
+    limbs_copy_conditional(&mut res_z[..n], &in1_z[..n], in2_infty);
+
+    [res_x, res_y, res_z]
+}
+
+const WINDOW_BITS: usize = 5;
+
+// Returns `table[index - 1]`, or the point at infinity if `index` is zero,
+// without branching on or indexing memory with `index`.
+fn select_w5(table: &[P; 1 << (WINDOW_BITS - 1)], index: usize) -> P {
+    let mut r = [[0; MAX_LIMBS]; 3];
+    for (i, entry) in table.iter().enumerate() {
+        let mask = limb_is_zero(((i + 1) ^ index) as Limb);
+        for (r, entry) in r.iter_mut().zip(entry.iter()) {
+            limbs_copy_conditional(r, entry, mask);
+        }
+    }
+    r
+}
+
+// Returns (`digit`, `is_negative`) for the (`WINDOW_BITS` + 1)-bit window
+// `window`, in constant time. See `booth_recode` in crypto/ec/ecp_nistz.h.
+fn booth_recode(window: usize) -> (usize, Limb) {
+    let s = !((window >> WINDOW_BITS).wrapping_sub(1));
+    let mut d = (((1 << (WINDOW_BITS + 1)) - window - 1) & s) | (window & !s);
+    d = (d >> 1) + (d & 1);
+    (d, ((s & 1) as Limb).wrapping_neg())
+}
+
+// `r = p * p_scalar`, using a signed fixed window so that the same sequence
+// of doublings and additions is done for every scalar.
+fn point_mul(curve: &Curve, p_scalar: &E, p_x: &E, p_y: &E) -> P {
+    const MASK: usize = (1 << (WINDOW_BITS + 1)) - 1;
+
+    let q = curve.q();
+    let num_limbs = curve.ops.num_limbs;
+    let bits = num_limbs * LIMB_BITS;
+    debug_assert!(bits % WINDOW_BITS != 0);
+
+    // The little-endian encoding of the scalar, with an extra zero byte at
+    // the end so that every window can be read from two consecutive bytes.
+    let mut p_str = [0u8; (MAX_LIMBS * LIMB_BYTES) + 1];
+    for (i, limb) in p_scalar[..num_limbs].iter().enumerate() {
+        for j in 0..LIMB_BYTES {
+            p_str[(i * LIMB_BYTES) + j] = (limb >> (8 * j)) as u8;
+        }
+    }
+    let window = |index: usize| {
+        let off = (index - 1) / 8;
+        let w = usize::from(p_str[off]) | (usize::from(p_str[off + 1]) << 8);
+        (w >> ((index - 1) % 8)) & MASK
+    };
+
+    // `table[i]` is `(i + 1) * p`.
+    let mut table = [[[0; MAX_LIMBS]; 3]; 1 << (WINDOW_BITS - 1)];
+    table[0] = [*p_x, *p_y, curve.one];
+    for i in 1..table.len() {
+        table[i] = if i % 2 == 1 {
+            point_double(curve, &table[i / 2])
+        } else {
+            point_add(curve, &table[i - 1], &table[0])
+        };
+    }
+
+    let add_precomputed_w5 = |r: &P, window: usize| {
+        let (digit, is_negative) = booth_recode(window);
+        let mut h = select_w5(&table, digit);
+        let neg_y = q.neg(&h[1]);
+        limbs_copy_conditional(&mut h[1], &neg_y, is_negative);
+        point_add(curve, r, &h)
+    };
+
+    let start_index = bits - (bits % WINDOW_BITS);
+    let mut index = start_index;
+
+    let (digit, is_negative) = booth_recode(window(index));
+    debug_assert_eq!(is_negative, 0);
+    let mut r = select_w5(&table, digit);
+
+    while index >= WINDOW_BITS {
+        if index != start_index {
+            r = add_precomputed_w5(&r, window(index));
+        }
+
+        index -= WINDOW_BITS;
+
+        for _ in 0..WINDOW_BITS {
+            r = point_double(curve, &r);
+        }
+    }
+
+    // Final window.
+    add_precomputed_w5(&r, (usize::from(p_str[0]) << 1) & MASK)
+}
+
+unsafe fn load_elems(a: *const Limb, num_limbs: usize, out: &mut [E]) {
+    let a = core::slice::from_raw_parts(a, out.len() * num_limbs);
+    for (out, a) in out.iter_mut().zip(a.chunks(num_limbs)) {
+        out[..num_limbs].copy_from_slice(a);
+    }
+}
+
+unsafe fn load_elem(curve: &Curve, a: *const Limb) -> E {
+    let mut r = [[0; MAX_LIMBS]; 1];
+    load_elems(a, curve.ops.num_limbs, &mut r);
+    r[0]
+}
+
+unsafe fn load_point(curve: &Curve, a: *const Limb) -> P {
+    let mut r = [[0; MAX_LIMBS]; 3];
+    load_elems(a, curve.ops.num_limbs, &mut r);
+    r
+}
+
+unsafe fn store_elems(r: *mut Limb, num_limbs: usize, elems: &[E]) {
+    let r = core::slice::from_raw_parts_mut(r, elems.len() * num_limbs);
+    for (r, elem) in r.chunks_mut(num_limbs).zip(elems.iter()) {
+        r.copy_from_slice(&elem[..num_limbs]);
+    }
+}
+
+// The inputs are copied before the output is written because the callers
+// may pass the same pointer for `r` and `a` or `b`.
+
+unsafe fn elem_op1<F>(curve: &Curve, r: *mut Limb, a: *const Limb, f: F)
+        where F: Fn(&E) -> E {
+    let a = load_elem(curve, a);
+    store_elems(r, curve.ops.num_limbs, &[f(&a)]);
+}
+
+unsafe fn elem_op2<F>(curve: &Curve, r: *mut Limb, a: *const Limb,
+                      b: *const Limb, f: F)
+        where F: Fn(&E, &E) -> E {
+    let a = load_elem(curve, a);
+    let b = load_elem(curve, b);
+    store_elems(r, curve.ops.num_limbs, &[f(&a, &b)]);
+}
+
+#[cfg(test)]
+unsafe fn point_op1<F>(curve: &Curve, r: *mut Limb, a: *const Limb, f: F)
+        where F: Fn(&P) -> P {
+    let a = load_point(curve, a);
+    store_elems(r, curve.ops.num_limbs, &f(&a));
+}
+
+unsafe fn point_op2<F>(curve: &Curve, r: *mut Limb, a: *const Limb,
+                       b: *const Limb, f: F)
+        where F: Fn(&P, &P) -> P {
+    let a = load_point(curve, a);
+    let b = load_point(curve, b);
+    store_elems(r, curve.ops.num_limbs, &f(&a, &b));
+}
+
+unsafe fn point_mul_raw(curve: &Curve, r: *mut Limb, p_scalar: *const Limb,
+                        p_x: *const Limb, p_y: *const Limb) {
+    let p_scalar = load_elem(curve, p_scalar);
+    let p_x = load_elem(curve, p_x);
+    let p_y = load_elem(curve, p_y);
+    store_elems(r, curve.ops.num_limbs,
+                &point_mul(curve, &p_scalar, &p_x, &p_y));
+}
+
+
+pub unsafe extern fn GFp_nistz256_add(r: *mut Limb, a: *const Limb,
+                                      b: *const Limb) {
+    elem_op2(&P256, r, a, b, |a, b| P256.q().add(a, b))
+}
+
+pub unsafe extern fn GFp_nistz256_mul_mont(r: *mut Limb, a: *const Limb,
+                                           b: *const Limb) {
+    elem_op2(&P256, r, a, b, |a, b| P256.q().mul(a, b))
+}
+
+pub unsafe extern fn GFp_nistz256_sqr_mont(r: *mut Limb, a: *const Limb) {
+    elem_op1(&P256, r, a, |a| P256.q().sqr(a))
+}
+
+#[cfg(test)]
+pub unsafe extern fn GFp_nistz256_neg(r: *mut Limb, a: *const Limb) {
+    elem_op1(&P256, r, a, |a| P256.q().neg(a))
+}
+
+pub unsafe extern fn GFp_nistz256_point_add(r: *mut Limb, a: *const Limb,
+                                            b: *const Limb) {
+    point_op2(&P256, r, a, b, |a, b| point_add(&P256, a, b))
+}
+
+// `b` is affine; (0, 0) is the point at infinity.
+#[cfg(test)]
+pub unsafe extern fn GFp_nistz256_point_add_affine(r: *mut Limb,
+                                                   a: *const Limb,
+                                                   b: *const Limb) {
+    let a = load_point(&P256, a);
+    let mut b_xy = [[0; MAX_LIMBS]; 2];
+    load_elems(b, P256.ops.num_limbs, &mut b_xy);
+    let q = P256.q();
+    let mut b_z = P256.one;
+    let b_infty = q.is_zero(&b_xy[0]) & q.is_zero(&b_xy[1]);
+    limbs_copy_conditional(&mut b_z, &[0; MAX_LIMBS], b_infty);
+    let b = [b_xy[0], b_xy[1], b_z];
+    store_elems(r, P256.ops.num_limbs, &point_add(&P256, &a, &b));
+}
+
+#[cfg(test)]
+pub unsafe extern fn GFp_nistz256_point_double(r: *mut Limb, a: *const Limb) {
+    point_op1(&P256, r, a, |a| point_double(&P256, a))
+}
+
+pub unsafe extern fn GFp_nistz256_point_mul(r: *mut Limb,
+                                            p_scalar: *const Limb,
+                                            p_x: *const Limb,
+                                            p_y: *const Limb) {
+    point_mul_raw(&P256, r, p_scalar, p_x, p_y)
+}
+
+pub unsafe extern fn GFp_nistz256_point_mul_base(r: *mut Limb,
+                                                 g_scalar: *const Limb) {
+    // The generator, Montgomery-encoded.
+    static G: [E; 2] = [
+        limbs![0, 0, 0, 0,
+               0x18905f76, 0xa53755c6, 0x79fb732b, 0x77622510,
+               0x75ba95fc, 0x5fedb601, 0x79e730d4, 0x18a9143c],
+        limbs![0, 0, 0, 0,
+               0x8571ff18, 0x25885d85, 0xd2e88688, 0xdd21f325,
+               0x8b4ab8e4, 0xba19e45c, 0xddf25357, 0xce95560a],
+    ];
+    let g_scalar = load_elem(&P256, g_scalar);
+    store_elems(r, P256.ops.num_limbs,
+                &point_mul(&P256, &g_scalar, &G[0], &G[1]));
+}
+
+pub unsafe extern fn GFp_p256_scalar_mul_mont(r: *mut Limb, a: *const Limb,
+                                              b: *const Limb) {
+    elem_op2(&P256, r, a, b, |a, b| P256.n().mul(a, b))
+}
+
+pub unsafe extern fn GFp_p256_scalar_sqr_mont(r: *mut Limb, a: *const Limb) {
+    elem_op1(&P256, r, a, |a| P256.n().sqr(a))
+}
+
+pub unsafe extern fn GFp_p256_scalar_sqr_rep_mont(r: *mut Limb,
+                                                  a: *const Limb,
+                                                  rep: c::int) {
+    debug_assert!(rep >= 1);
+    elem_op1(&P256, r, a, |a| {
+        let n = P256.n();
+        let mut r = n.sqr(a);
+        for _ in 1..rep {
+            r = n.sqr(&r);
+        }
+        r
+    })
+}
+
+
+pub unsafe extern fn GFp_p384_elem_add(r: *mut Limb, a: *const Limb,
+                                       b: *const Limb) {
+    elem_op2(&P384, r, a, b, |a, b| P384.q().add(a, b))
+}
+
+#[cfg(test)]
+pub unsafe extern fn GFp_p384_elem_sub(r: *mut Limb, a: *const Limb,
+                                       b: *const Limb) {
+    elem_op2(&P384, r, a, b, |a, b| P384.q().sub(a, b))
+}
+
+#[cfg(test)]
+pub unsafe extern fn GFp_p384_elem_div_by_2(r: *mut Limb, a: *const Limb) {
+    elem_op1(&P384, r, a, |a| P384.q().div_by_2(a))
+}
+
+#[cfg(test)]
+pub unsafe extern fn GFp_p384_elem_neg(r: *mut Limb, a: *const Limb) {
+    elem_op1(&P384, r, a, |a| P384.q().neg(a))
+}
+
+pub unsafe extern fn GFp_p384_elem_mul_mont(r: *mut Limb, a: *const Limb,
+                                            b: *const Limb) {
+    elem_op2(&P384, r, a, b, |a, b| P384.q().mul(a, b))
+}
+
+pub unsafe extern fn GFp_nistz384_point_add(r: *mut Limb, a: *const Limb,
+                                            b: *const Limb) {
+    point_op2(&P384, r, a, b, |a, b| point_add(&P384, a, b))
+}
+
+#[cfg(test)]
+pub unsafe extern fn GFp_nistz384_point_double(r: *mut Limb, a: *const Limb) {
+    point_op1(&P384, r, a, |a| point_double(&P384, a))
+}
+
+pub unsafe extern fn GFp_nistz384_point_mul(r: *mut Limb,
+                                            p_scalar: *const Limb,
+                                            p_x: *const Limb,
+                                            p_y: *const Limb) {
+    point_mul_raw(&P384, r, p_scalar, p_x, p_y)
+}
+
+pub unsafe extern fn GFp_p384_scalar_mul_mont(r: *mut Limb, a: *const Limb,
+                                              b: *const Limb) {
+    elem_op2(&P384, r, a, b, |a, b| P384.n().mul(a, b))
+}
@@ -277,6 +277,14 @@ fn p256_scalar_inv_to_mont(a: &Scalar<Unencoded>) -> Scalar<R> {
 }
 
 
+#[cfg(feature = "pure_rust")]
+use super::nistz::{GFp_nistz256_add, GFp_nistz256_mul_mont,
+                   GFp_nistz256_sqr_mont, GFp_nistz256_point_add,
+                   GFp_nistz256_point_mul, GFp_nistz256_point_mul_base,
+                   GFp_p256_scalar_mul_mont, GFp_p256_scalar_sqr_mont,
+                   GFp_p256_scalar_sqr_rep_mont};
+
+#[cfg(not(feature = "pure_rust"))]
 extern {
     fn GFp_nistz256_add(r: *mut Limb/*[COMMON_OPS.num_limbs]*/,
                         a: *const Limb/*[COMMON_OPS.num_limbs]*/,
@@ -327,6 +327,12 @@ unsafe extern fn GFp_p384_elem_sqr_mont(
 }
 
 
+#[cfg(feature = "pure_rust")]
+use super::nistz::{GFp_p384_elem_add, GFp_p384_elem_mul_mont,
+                   GFp_nistz384_point_add, GFp_nistz384_point_mul,
+                   GFp_p384_scalar_mul_mont};
+
+#[cfg(not(feature = "pure_rust"))]
 extern {
     fn GFp_p384_elem_add(r: *mut Limb/*[COMMON_OPS.num_limbs]*/,
                          a: *const Limb/*[COMMON_OPS.num_limbs]*/,
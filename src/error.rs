@@ -102,3 +102,62 @@ impl std::error::Error for Unspecified {
 impl From<untrusted::EndOfInput> for Unspecified {
     fn from(_: untrusted::EndOfInput) -> Self { Unspecified }
 }
+
+/// An error parsing or validating a key.
+///
+/// The `Display` implementation and `description_()` return a short, static
+/// string that says why the key was rejected: one of `"InvalidEncoding"`,
+/// `"WrongAlgorithm"`, `"TooSmall"`, `"TooLarge"`, `"InconsistentComponents"`,
+/// or `"UnexpectedError"`. These are meant for logging and debugging; the set
+/// of reasons, and which reason is reported for a given key, may change over
+/// time, so don't make decisions based on them.
+///
+/// `KeyRejected` is only used by key constructors. Failures of operations on
+/// valid keys, such as signature verification, remain `Unspecified`.
+///
+/// `From<KeyRejected>` is implemented for `Unspecified`, so code that doesn't
+/// care about the reason can keep using `?` in functions that return
+/// `Result<T, Unspecified>`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyRejected(&'static str);
+
+impl KeyRejected {
+    /// The reason the key was rejected.
+    ///
+    /// This is named `description_` instead of `description` so that it
+    /// doesn't conflict with `std::error::Error::description()`.
+    pub fn description_(&self) -> &'static str { self.0 }
+
+    pub(crate) fn inconsistent_components() -> Self {
+        KeyRejected("InconsistentComponents")
+    }
+
+    pub(crate) fn invalid_encoding() -> Self { KeyRejected("InvalidEncoding") }
+
+    pub(crate) fn too_large() -> Self { KeyRejected("TooLarge") }
+
+    pub(crate) fn too_small() -> Self { KeyRejected("TooSmall") }
+
+    pub(crate) fn unexpected_error() -> Self { KeyRejected("UnexpectedError") }
+
+    pub(crate) fn wrong_algorithm() -> Self { KeyRejected("WrongAlgorithm") }
+}
+
+impl core::fmt::Display for KeyRejected {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(self.description_())
+    }
+}
+
+#[cfg(feature = "use_heap")]
+impl std::error::Error for KeyRejected {
+    #[inline]
+    fn cause(&self) -> Option<&std::error::Error> { None }
+
+    #[inline]
+    fn description(&self) -> &str { self.description_() }
+}
+
+impl From<KeyRejected> for Unspecified {
+    fn from(_: KeyRejected) -> Self { Unspecified }
+}
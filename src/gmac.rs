@@ -0,0 +1,168 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! AES-GMAC message authentication, as specified in [NIST SP 800-38D].
+//!
+//! GMAC is AES-GCM with an empty plaintext, where the message to be
+//! authenticated is the additional data. The API mirrors `ring::hmac`, except
+//! that every signing and verification operation takes a 96-bit nonce, and
+//! that there is no multi-step API; the whole message must be available at
+//! once.
+//!
+//! **Like AES-GCM, GMAC is completely insecure if a nonce is ever reused with
+//! the same key.** Prefer `ring::hmac` or `ring::cmac` unless a protocol
+//! requires GMAC.
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::{cipher, gmac};
+//!
+//! # fn main() { f().unwrap() }
+//! # fn f() -> Result<(), ring::error::Unspecified> {
+//! let key_value = [0x42; 32];
+//! let nonce = [0x24; gmac::NONCE_LEN];
+//! let msg = "hello, world";
+//!
+//! let s_key = gmac::SigningKey::new(&cipher::AES_256, &key_value)?;
+//! let signature = gmac::sign(&s_key, &nonce, msg.as_bytes())?;
+//!
+//! let v_key = gmac::VerificationKey::new(&cipher::AES_256, &key_value)?;
+//! gmac::verify(&v_key, &nonce, msg.as_bytes(), signature.as_ref())?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [NIST SP 800-38D]: http://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38d.pdf
+
+use {aead, cipher, constant_time, error, rand};
+
+/// The length of a GMAC nonce.
+pub const NONCE_LEN: usize = 96 / 8;
+
+/// The length of a GMAC signature.
+pub const SIGNATURE_LEN: usize = 128 / 8;
+
+/// An AES-GMAC signature.
+///
+/// For a given signature `s`, use `s.as_ref()` to get the signature value as
+/// a byte slice.
+#[derive(Clone, Copy, Debug)]
+pub struct Signature([u8; SIGNATURE_LEN]);
+
+impl AsRef<[u8]> for Signature {
+    #[inline] fn as_ref(&self) -> &[u8] { &self.0[..] }
+}
+
+/// A key to use for AES-GMAC signing.
+pub struct SigningKey {
+    key: aead::SealingKey,
+    algorithm: &'static cipher::Algorithm,
+}
+
+impl SigningKey {
+    /// Generates an AES-GMAC signing key for `algorithm` with a random value
+    /// generated from `rng`.
+    pub fn generate(algorithm: &'static cipher::Algorithm,
+                    rng: &rand::SecureRandom)
+                    -> Result<SigningKey, error::Unspecified> {
+        let mut key_bytes = [0u8; MAX_KEY_LEN];
+        let key_bytes = &mut key_bytes[..algorithm.key_len()];
+        rng.fill(key_bytes)?;
+        SigningKey::new(algorithm, key_bytes)
+    }
+
+    /// Constructs an AES-GMAC signing key from `key_value`, which must be
+    /// exactly `algorithm.key_len()` bytes long.
+    pub fn new(algorithm: &'static cipher::Algorithm, key_value: &[u8])
+               -> Result<SigningKey, error::Unspecified> {
+        let aead_algorithm = if algorithm == &cipher::AES_128 {
+            &aead::AES_128_GCM
+        } else {
+            &aead::AES_256_GCM
+        };
+        Ok(SigningKey {
+            key: aead::SealingKey::new(aead_algorithm, key_value)?,
+            algorithm,
+        })
+    }
+
+    /// The key's algorithm.
+    #[inline(always)]
+    pub fn algorithm(&self) -> &'static cipher::Algorithm { self.algorithm }
+}
+
+/// Calculates the AES-GMAC of `data` using the key `key` and the nonce
+/// `nonce`.
+///
+/// It is generally not safe to implement AES-GMAC verification by comparing
+/// the return value of `sign` to a signature. Use `verify` for verification
+/// instead.
+pub fn sign(key: &SigningKey, nonce: &[u8; NONCE_LEN], data: &[u8])
+            -> Result<Signature, error::Unspecified> {
+    let mut tag = [0u8; SIGNATURE_LEN];
+    let tag_len =
+        aead::seal_in_place(&key.key, nonce, data, &mut tag, SIGNATURE_LEN)?;
+    debug_assert_eq!(tag_len, SIGNATURE_LEN);
+    Ok(Signature(tag))
+}
+
+/// A key to use for AES-GMAC authentication.
+pub struct VerificationKey {
+    wrapped: SigningKey,
+}
+
+impl VerificationKey {
+    /// Constructs an AES-GMAC verification key from `key_value`, which must
+    /// be exactly `algorithm.key_len()` bytes long.
+    #[inline]
+    pub fn new(algorithm: &'static cipher::Algorithm, key_value: &[u8])
+               -> Result<VerificationKey, error::Unspecified> {
+        Ok(VerificationKey { wrapped: SigningKey::new(algorithm, key_value)? })
+    }
+
+    /// The key's algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static cipher::Algorithm {
+        self.wrapped.algorithm()
+    }
+}
+
+/// Calculates the AES-GMAC of `data` using the key `key` and the nonce
+/// `nonce`, and verifies whether the resultant value equals `signature`, in
+/// one step.
+///
+/// The verification will be done in constant time to prevent timing attacks.
+#[inline(always)]
+pub fn verify(key: &VerificationKey, nonce: &[u8; NONCE_LEN], data: &[u8],
+              signature: &[u8]) -> Result<(), error::Unspecified> {
+    verify_with_own_key(&key.wrapped, nonce, data, signature)
+}
+
+/// Calculates the AES-GMAC of `data` using the signing key `key` and the
+/// nonce `nonce`, and verifies whether the resultant value equals
+/// `signature`, in one step.
+///
+/// This is logically equivalent to, but more efficient than, constructing a
+/// `VerificationKey` with the same value as `key` and then using `verify`.
+///
+/// The verification will be done in constant time to prevent timing attacks.
+pub fn verify_with_own_key(key: &SigningKey, nonce: &[u8; NONCE_LEN],
+                           data: &[u8], signature: &[u8])
+                           -> Result<(), error::Unspecified> {
+    constant_time::verify_slices_are_equal(sign(key, nonce, data)?.as_ref(),
+                                           signature)
+}
+
+const MAX_KEY_LEN: usize = 32;
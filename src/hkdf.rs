@@ -16,21 +16,169 @@
 //!
 //! HKDF is specified in [RFC 5869].
 //!
-//! In most situations, it is best to use `extract_and_expand` to do both the
-//! HKDF-Extract and HKDF-Expand as one atomic operation. It is only necessary
-//! to use the separate `expand` and `extract` functions if a single derived
-//! `PRK` (defined in RFC 5869) is used more than once.
+//! A `Salt` is used to extract a `Prk` (pseudo-random key) from the input
+//! keying material. The `Prk` is then expanded into one or more `Okm`
+//! (output keying material) values. An `Okm` doesn't contain the output
+//! itself; instead, it knows how long the output must be, via its `KeyType`,
+//! and it can be converted directly into a key for another *ring* API. For
+//! example, an `Okm<&'static aead::Algorithm>` can be converted into an
+//! `aead::SealingKey` or `aead::OpeningKey`, and an
+//! `Okm<&'static digest::Algorithm>` can be converted into an
+//! `hmac::SigningKey`, an `hmac::VerificationKey`, a `Salt`, or a `Prk`. This
+//! way the raw key material never needs to be exposed to the caller.
 //!
-//! Salts have type `hmac::SigningKey` instead of `&[u8]` because they are
-//! frequently used for multiple HKDF operations, and it is more efficient to
-//! construct the `SigningKey` once and reuse it. Given a digest algorithm
-//! `digest_alg` and a salt `salt: &[u8]`, the `SigningKey` should be
-//! constructed as `hmac::SigningKey::new(digest_alg, salt)`.
+//! ```
+//! use ring::{aead, digest, hkdf};
+//!
+//! # fn main_with_result() -> Result<(), ring::error::Unspecified> {
+//! let salt = hkdf::Salt::new(&digest::SHA256, b"salt value");
+//! let prk = salt.extract(b"input keying material");
+//! let info = [&b"client key"[..]];
+//! let okm = prk.expand(&info, &aead::AES_128_GCM)?;
+//! let _key = aead::SealingKey::from(okm);
+//! #
+//! # Ok(())
+//! # }
+//! #
+//! # fn main() { main_with_result().unwrap() }
+//! ```
+//!
+//! The older `extract_and_expand`, `extract`, and `expand` functions operate
+//! on `hmac::SigningKey` values and raw output buffers. They panic when the
+//! requested output is too long, whereas `Prk::expand` returns an error.
 //!
 //! [RFC 5869]: https://tools.ietf.org/html/rfc5869
 
 
-use hmac;
+use {digest, error, hmac};
+
+/// A salt for HKDF operations.
+pub struct Salt(hmac::SigningKey);
+
+impl Salt {
+    /// Constructs a new `Salt` with the given value based on the given digest
+    /// algorithm.
+    ///
+    /// Constructing a `Salt` is relatively expensive so it is good to reuse a
+    /// `Salt` object instead of re-constructing `Salt`s with the same value.
+    pub fn new(digest_alg: &'static digest::Algorithm, value: &[u8]) -> Salt {
+        Salt(hmac::SigningKey::new(digest_alg, value))
+    }
+
+    /// The [HKDF-Extract] operation.
+    ///
+    /// | Parameter                 | RFC 5869 Term
+    /// |---------------------------|--------------
+    /// | `self.digest_algorithm()` | Hash
+    /// | `secret`                  | IKM (Input Keying Material)
+    /// | [return value]            | PRK
+    ///
+    /// [HKDF-Extract]: https://tools.ietf.org/html/rfc5869#section-2.2
+    pub fn extract(&self, secret: &[u8]) -> Prk {
+        Prk(extract(&self.0, secret))
+    }
+
+    /// The digest algorithm used by the salt.
+    #[inline]
+    pub fn digest_algorithm(&self) -> &'static digest::Algorithm {
+        self.0.digest_algorithm()
+    }
+}
+
+impl<'a> From<Okm<'a, &'static digest::Algorithm>> for Salt {
+    fn from(okm: Okm<'a, &'static digest::Algorithm>) -> Self {
+        Salt(hmac::SigningKey::from(okm))
+    }
+}
+
+/// The length of the OKM (Output Keying Material) for a `Prk::expand()` call.
+pub trait KeyType {
+    /// The length that `Prk::expand()` should expand its input to.
+    fn len(&self) -> usize;
+}
+
+/// The output length of a digest algorithm, for use as an HMAC key or as the
+/// value of a `Salt` or `Prk`.
+impl KeyType for &'static digest::Algorithm {
+    #[inline]
+    fn len(&self) -> usize { self.output_len }
+}
+
+/// A HKDF PRK (pseudorandom key).
+pub struct Prk(hmac::SigningKey);
+
+impl Prk {
+    /// Construct a new `Prk` directly with the given value.
+    ///
+    /// Usually one can avoid using this. It is useful when the application
+    /// intentionally wants to leak the PRK secret, e.g. to implement
+    /// `SSLKEYLOGFILE` functionality.
+    pub fn new_less_safe(digest_alg: &'static digest::Algorithm,
+                         value: &[u8]) -> Prk {
+        Prk(hmac::SigningKey::new(digest_alg, value))
+    }
+
+    /// The [HKDF-Expand] operation.
+    ///
+    /// | Parameter                 | RFC 5869 Term
+    /// |---------------------------|--------------
+    /// | `self`                    | PRK
+    /// | `info`                    | info (the concatenation of the slices)
+    /// | `len.len()`               | L (Length of output keying material)
+    ///
+    /// Fails if (and only if) `len.len()` is larger than 255 times the output
+    /// length of the digest algorithm.
+    ///
+    /// [HKDF-Expand]: https://tools.ietf.org/html/rfc5869#section-2.3
+    pub fn expand<'a, L: KeyType>(&'a self, info: &'a [&'a [u8]], len: L)
+                                  -> Result<Okm<'a, L>, error::Unspecified> {
+        if len.len() > 255 * self.0.digest_algorithm().output_len {
+            return Err(error::Unspecified);
+        }
+        Ok(Okm { prk: self, info, len })
+    }
+
+    /// The digest algorithm used by the PRK.
+    #[inline]
+    pub fn digest_algorithm(&self) -> &'static digest::Algorithm {
+        self.0.digest_algorithm()
+    }
+}
+
+impl<'a> From<Okm<'a, &'static digest::Algorithm>> for Prk {
+    fn from(okm: Okm<'a, &'static digest::Algorithm>) -> Self {
+        Prk(hmac::SigningKey::from(okm))
+    }
+}
+
+/// An HKDF OKM (Output Keying Material).
+///
+/// Intentionally not `Clone` or `Copy` as an OKM is generally only safe to
+/// use once.
+pub struct Okm<'a, L: KeyType> {
+    prk: &'a Prk,
+    info: &'a [&'a [u8]],
+    len: L,
+}
+
+impl<'a, L: KeyType> Okm<'a, L> {
+    /// The `KeyType` for this OKM, as given to `Prk::expand()`.
+    #[inline]
+    pub fn len(&self) -> &L { &self.len }
+
+    /// Fills `out` with the output of the HKDF-Expand operation for the given
+    /// inputs.
+    ///
+    /// Fails if (and only if) the requested output length is larger than 255
+    /// times the size of the digest algorithm's output, or if `out.len()`
+    /// isn't equal to `self.len().len()`.
+    pub fn fill(self, out: &mut [u8]) -> Result<(), error::Unspecified> {
+        if out.len() != self.len.len() {
+            return Err(error::Unspecified);
+        }
+        fill_okm(&self.prk.0, self.info, out)
+    }
+}
 
 /// Fills `out` with the output of the HKDF Extract-and-Expand operation for
 /// the given inputs.
@@ -95,37 +243,38 @@ pub fn extract(salt: &hmac::SigningKey, secret: &[u8]) -> hmac::SigningKey {
 /// imposed by the HKDF specification, and is necessary to prevent overflow of
 /// the 8-bit iteration counter in the expansion step.
 pub fn expand(prk: &hmac::SigningKey, info: &[u8], out: &mut [u8]) {
+    fill_okm(prk, &[info], out).unwrap()
+}
+
+fn fill_okm(prk: &hmac::SigningKey, info: &[&[u8]], out: &mut [u8])
+            -> Result<(), error::Unspecified> {
     let digest_alg = prk.digest_algorithm();
-    assert!(out.len() <= 255 * digest_alg.output_len);
-    assert!(digest_alg.block_len >= digest_alg.output_len);
+    if out.len() > 255 * digest_alg.output_len {
+        return Err(error::Unspecified);
+    }
+    debug_assert!(digest_alg.block_len >= digest_alg.output_len);
 
-    let mut ctx = hmac::SigningContext::with_key(prk);
+    let mut prev: Option<hmac::Signature> = None;
+    let mut n = 0u8;
+    for chunk in out.chunks_mut(digest_alg.output_len) {
+        // This can't overflow because of the length check above.
+        n += 1;
 
-    let mut n = 1u8;
-    let mut pos = 0;
-    loop {
-        ctx.update(info);
+        let mut ctx = hmac::SigningContext::with_key(prk);
+        if let Some(ref t) = prev {
+            ctx.update(t.as_ref());
+        }
+        for info in info {
+            ctx.update(info);
+        }
         ctx.update(&[n]);
 
         let t = ctx.sign();
 
         // Append `t` to the output.
-        let to_copy = if out.len() - pos < digest_alg.output_len {
-            out.len() - pos
-        } else {
-            digest_alg.output_len
-        };
-        let t_bytes = t.as_ref();
-        for i in 0..to_copy {
-            out[pos + i] = t_bytes[i];
-        }
-        if to_copy < digest_alg.output_len {
-            break;
-        }
-        pos += digest_alg.output_len;
-
-        ctx = hmac::SigningContext::with_key(prk);
-        ctx.update(t_bytes);
-        n += 1;
+        chunk.copy_from_slice(&t.as_ref()[..chunk.len()]);
+        prev = Some(t);
     }
+
+    Ok(())
 }
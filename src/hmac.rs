@@ -155,7 +155,7 @@
 //!     https://github.com/briansmith/ring/blob/master/src/hkdf.rs
 
 
-use {constant_time, digest, error, rand};
+use {constant_time, digest, error, hkdf, rand};
 
 /// An HMAC signature.
 ///
@@ -165,6 +165,7 @@ use {constant_time, digest, error, rand};
 pub struct Signature(digest::Digest);
 
 /// A key to use for HMAC signing.
+#[derive(Clone)]
 pub struct SigningKey {
     ctx_prototype: SigningContext,
 }
@@ -267,6 +268,18 @@ impl SigningKey {
     }
 }
 
+impl<'a> From<hkdf::Okm<'a, &'static digest::Algorithm>> for SigningKey {
+    /// Constructs a `SigningKey` from HKDF output keying material. The key
+    /// is `digest_alg.output_len` bytes long.
+    fn from(okm: hkdf::Okm<'a, &'static digest::Algorithm>) -> Self {
+        let digest_alg = *okm.len();
+        let mut key_bytes = [0u8; digest::MAX_OUTPUT_LEN];
+        let key_bytes = &mut key_bytes[..digest_alg.output_len];
+        okm.fill(key_bytes).unwrap();
+        SigningKey::new(digest_alg, key_bytes)
+    }
+}
+
 /// A context for multi-step (Init-Update-Finish) HMAC signing.
 ///
 /// Use `sign` for single-step HMAC signing.
@@ -327,6 +340,36 @@ pub fn sign(key: &SigningKey, data: &[u8]) -> Signature {
     ctx.sign()
 }
 
+/// Calculates HMACs of messages that are exactly as long as the digest
+/// algorithm's output, e.g. the chained values in PBKDF2. The inner and outer
+/// hashes each take only one call to the block function, with the padding
+/// precomputed.
+pub(crate) struct OutputLenSigner {
+    inner: digest::FixedLenFinisher,
+    outer: digest::FixedLenFinisher,
+}
+
+impl OutputLenSigner {
+    pub(crate) fn new(key: &SigningKey) -> OutputLenSigner {
+        let output_len = key.digest_algorithm().output_len;
+        OutputLenSigner {
+            inner: digest::FixedLenFinisher::new(&key.ctx_prototype.inner,
+                                                 output_len),
+            outer: digest::FixedLenFinisher::new(&key.ctx_prototype.outer,
+                                                 output_len),
+        }
+    }
+
+    /// Replaces `msg` with its HMAC. `msg.len()` must be the digest
+    /// algorithm's output length.
+    pub(crate) fn sign_in_place(&mut self, msg: &mut [u8]) {
+        self.inner.msg_mut().copy_from_slice(msg);
+        let inner = self.inner.finish();
+        self.outer.msg_mut().copy_from_slice(inner.as_ref());
+        msg.copy_from_slice(self.outer.finish().as_ref());
+    }
+}
+
 /// A key to use for HMAC authentication.
 pub struct VerificationKey {
     wrapped: SigningKey,
@@ -354,6 +397,15 @@ impl VerificationKey {
     }
 }
 
+impl<'a> From<hkdf::Okm<'a, &'static digest::Algorithm>> for VerificationKey {
+    /// Constructs a `VerificationKey` from HKDF output keying material. The
+    /// key is `digest_alg.output_len` bytes long.
+    #[inline]
+    fn from(okm: hkdf::Okm<'a, &'static digest::Algorithm>) -> Self {
+        VerificationKey { wrapped: SigningKey::from(okm) }
+    }
+}
+
 /// Calculates the HMAC of `data` using the key `key`, and verifies whether the
 /// resultant value equals `signature`, in one step.
 ///
@@ -467,6 +519,24 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn output_len_signer_test() {
+        for d in &digest::test_util::ALL_ALGORITHMS {
+            let key = hmac::SigningKey::new(d, b"key");
+            let mut signer = hmac::OutputLenSigner::new(&key);
+
+            let mut msg = vec![0u8; d.output_len];
+            for (i, b) in msg.iter_mut().enumerate() {
+                *b = i as u8;
+            }
+            for _ in 0..3 {
+                let expected = hmac::sign(&key, &msg);
+                signer.sign_in_place(&mut msg);
+                assert_eq!(&msg[..], expected.as_ref());
+            }
+        }
+    }
+
     // Test that `generate_serializable()` generates a key from the RNG, and
     // that the generated key fills the entire `key_bytes` parameter.
     #[test]
@@ -12,13 +12,10 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
+use {cpu, self_test};
+
 #[inline(always)]
 pub fn init_once() {
-    #[cfg(not(target_os = "ios"))]
-    {
-        use std;
-        extern { fn GFp_cpuid_setup(); }
-        static INIT: std::sync::Once = std::sync::ONCE_INIT;
-        INIT.call_once(|| unsafe { GFp_cpuid_setup() });
-    }
+    cpu::init_once();
+    self_test::check();
 }
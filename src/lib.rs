@@ -30,8 +30,16 @@
 //!         <code>dev_urandom_fallback</code> feature is disabled, such
 //!         fallbacks will not occur. See the documentation for
 //!         <code>rand::SystemRandom</code> for more details.
+//! <tr><td><code>pure_rust</code>
+//!     <td>Build without any C or assembly language code, using portable
+//!         Rust implementations of every primitive. See BUILDING.md for
+//!         details.
 //! <tr><td><code>rsa_signing</code>
 //!     <td>Enable RSA signing (<code>RSAKeyPair</code> and related things).
+//! <tr><td><code>self_tests</code>
+//!     <td>Run the known-answer self-tests the first time <i>ring</i> is
+//!         initialized, and check the consistency of each generated key
+//!         pair. See <code>ring::self_test</code> for details.
 //! </table>
 
 #![doc(html_root_url="https://briansmith.org/rustdoc/")]
@@ -97,39 +105,65 @@ mod bssl;
 mod polyfill;
 
 pub mod aead;
+pub mod aes_kw;
 pub mod agreement;
 
 #[cfg(feature = "use_heap")]
+pub mod argon2;
+
 mod bits;
 
 mod c;
-mod chacha;
+pub mod chacha;
+pub mod cipher;
+pub mod cmac;
 pub mod constant_time;
+pub mod cpu;
 
 #[doc(hidden)]
 pub mod der;
 
 pub mod digest;
+pub mod drbg;
 mod ec;
 pub mod error;
+pub mod gmac;
 pub mod hkdf;
 pub mod hmac;
 mod init;
 mod limb;
 pub mod pbkdf2;
+
+#[cfg(feature = "use_heap")]
+pub mod phc;
+
 mod pkcs8;
-mod poly1305;
+pub mod poly1305;
 pub mod rand;
 
-#[cfg(feature = "use_heap")]
 mod rsa;
 
+mod salsa20;
+
+#[cfg(feature = "use_heap")]
+pub mod scrypt;
+
+pub mod secretbox;
+
+#[cfg(all(feature = "use_heap", target_os = "linux"))]
+pub mod secure_mem;
+
+pub mod self_test;
 pub mod signature;
 mod signature_impl;
+pub mod ssh;
 
 #[cfg(any(feature = "use_heap", test))]
 pub mod test;
 
+pub mod tls13;
+mod zeroize;
+
 mod private {
     /// Traits that are designed to only be implemented internally in *ring*.
     //
@@ -146,7 +180,7 @@ mod private {
     pub trait Private {}
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "pure_rust")))]
 mod tests {
     bssl_test!(test_constant_time, bssl_constant_time_test_main);
 }
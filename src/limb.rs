@@ -18,10 +18,14 @@
 //! Limbs ordered least-significant-limb to most-significant-limb. The bits
 //! limbs use the native endianness.
 
-#![cfg_attr(not(feature = "use_heap"), allow(dead_code))]
+#![cfg_attr(any(not(feature = "use_heap"), feature = "pure_rust"),
+           allow(dead_code))]
 
 use {polyfill, c, error, untrusted};
 
+#[cfg(feature = "pure_rust")]
+use core;
+
 // XXX: Not correct for x32 ABIs.
 #[cfg(target_pointer_width = "64")] pub type Limb = u64;
 #[cfg(target_pointer_width = "32")] pub type Limb = u32;
@@ -209,6 +213,7 @@ pub fn big_endian_from_limbs_padded(limbs: &[Limb], out: &mut [u8]) {
     polyfill::slice::fill(to_zero, 0);
 }
 
+#[cfg(not(feature = "pure_rust"))]
 extern {
     fn LIMBS_are_zero(a: *const Limb, num_limbs: c::size_t) -> LimbMask;
     fn LIMBS_less_than(a: *const Limb, b: *const Limb, num_limbs: c::size_t)
@@ -216,6 +221,193 @@ extern {
     fn LIMBS_reduce_once(r: *mut Limb, m: *const Limb, num_limbs: c::size_t);
 }
 
+// Portable versions of the functions in crypto/limbs, for builds with the
+// `pure_rust` feature. They have the same signatures as the C functions so
+// that the callers above don't need to care which ones are used.
+
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe fn LIMBS_are_zero(a: *const Limb, num_limbs: c::size_t) -> LimbMask {
+    let a = core::slice::from_raw_parts(a, num_limbs);
+    limb_mask(limb_is_zero(a.iter().fold(0, |acc, a| acc | a)))
+}
+
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe fn LIMBS_less_than(a: *const Limb, b: *const Limb,
+                          num_limbs: c::size_t) -> LimbMask {
+    let a = core::slice::from_raw_parts(a, num_limbs);
+    let b = core::slice::from_raw_parts(b, num_limbs);
+    limb_mask(limbs_less_than(a, b))
+}
+
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe fn LIMBS_reduce_once(r: *mut Limb, m: *const Limb,
+                            num_limbs: c::size_t) {
+    let r = core::slice::from_raw_parts_mut(r, num_limbs);
+    let m = core::slice::from_raw_parts(m, num_limbs);
+    let lt = limbs_less_than(r, m);
+    let mut borrow = 0;
+    for (r, m) in r.iter_mut().zip(m) {
+        let (d, b) = limb_sbb(*r, *m & !lt, borrow);
+        *r = d;
+        borrow = b;
+    }
+    debug_assert_eq!(borrow, 0);
+}
+
+#[cfg(feature = "pure_rust")]
+fn limb_mask(mask: Limb) -> LimbMask {
+    debug_assert!(mask == 0 || mask == !0);
+    // `LimbMask` has the same representation as `Limb`, and `mask` is one of
+    // its two values.
+    unsafe { core::mem::transmute(mask) }
+}
+
+#[cfg(all(any(feature = "pure_rust", not(feature = "use_heap")),
+          target_pointer_width = "64"))]
+type DoubleLimb = u128;
+
+#[cfg(all(any(feature = "pure_rust", not(feature = "use_heap")),
+          target_pointer_width = "32"))]
+type DoubleLimb = u64;
+
+/// Returns all ones if `a` is zero and zero otherwise, in constant time.
+#[cfg(feature = "pure_rust")]
+#[inline]
+pub fn limb_is_zero(a: Limb) -> Limb {
+    ((!a & a.wrapping_sub(1)) >> (LIMB_BITS - 1)).wrapping_neg()
+}
+
+/// Returns `(a + b + carry, carry_out)`. `carry` must be 0 or 1.
+#[cfg(any(feature = "pure_rust", not(feature = "use_heap")))]
+#[inline(always)]
+pub fn limb_adc(a: Limb, b: Limb, carry: Limb) -> (Limb, Limb) {
+    let r = DoubleLimb::from(a) + DoubleLimb::from(b) +
+            DoubleLimb::from(carry);
+    (r as Limb, (r >> LIMB_BITS) as Limb)
+}
+
+/// Returns `(a - b - borrow, borrow_out)`. `borrow` must be 0 or 1.
+#[cfg(any(feature = "pure_rust", not(feature = "use_heap")))]
+#[inline(always)]
+pub fn limb_sbb(a: Limb, b: Limb, borrow: Limb) -> (Limb, Limb) {
+    let r = DoubleLimb::from(a).wrapping_sub(DoubleLimb::from(b))
+                               .wrapping_sub(DoubleLimb::from(borrow));
+    (r as Limb, ((r >> LIMB_BITS) as Limb) & 1)
+}
+
+/// Returns `(lo, hi)` of `a + (b * c) + carry`, which can't overflow.
+#[cfg(any(feature = "pure_rust", not(feature = "use_heap")))]
+#[inline(always)]
+pub fn limb_mac(a: Limb, b: Limb, c: Limb, carry: Limb) -> (Limb, Limb) {
+    let r = DoubleLimb::from(a) + (DoubleLimb::from(b) * DoubleLimb::from(c)) +
+            DoubleLimb::from(carry);
+    (r as Limb, (r >> LIMB_BITS) as Limb)
+}
+
+/// `r = a + b`, returning the carry.
+#[cfg(feature = "pure_rust")]
+pub fn limbs_add(r: &mut [Limb], a: &[Limb], b: &[Limb]) -> Limb {
+    debug_assert!(r.len() == a.len() && r.len() == b.len());
+    let mut carry = 0;
+    for i in 0..r.len() {
+        let (s, c) = limb_adc(a[i], b[i], carry);
+        r[i] = s;
+        carry = c;
+    }
+    carry
+}
+
+/// `r = a - b`, returning the borrow.
+#[cfg(feature = "pure_rust")]
+pub fn limbs_sub(r: &mut [Limb], a: &[Limb], b: &[Limb]) -> Limb {
+    debug_assert!(r.len() == a.len() && r.len() == b.len());
+    let mut borrow = 0;
+    for i in 0..r.len() {
+        let (d, b) = limb_sbb(a[i], b[i], borrow);
+        r[i] = d;
+        borrow = b;
+    }
+    borrow
+}
+
+/// Returns all ones if `a < b` and zero otherwise, in constant time.
+#[cfg(feature = "pure_rust")]
+pub fn limbs_less_than(a: &[Limb], b: &[Limb]) -> Limb {
+    debug_assert_eq!(a.len(), b.len());
+    let mut borrow = 0;
+    for (a, b) in a.iter().zip(b) {
+        let (_, b) = limb_sbb(*a, *b, borrow);
+        borrow = b;
+    }
+    borrow.wrapping_neg()
+}
+
+/// `r = mask ? a : r`, where `mask` is all ones or zero.
+#[cfg(any(feature = "pure_rust", not(feature = "use_heap")))]
+#[inline]
+pub fn limbs_copy_conditional(r: &mut [Limb], a: &[Limb], mask: Limb) {
+    for (r, a) in r.iter_mut().zip(a) {
+        *r = (*a & mask) | (*r & !mask);
+    }
+}
+
+/// `r = a * b * R**-1 (mod m)`, where `R = 2**(LIMB_BITS * m.len())`, `a`
+/// and `b` are less than `m`, and `n0` is `-m**-1 (mod 2**LIMB_BITS)`. The
+/// result is fully reduced. This is the CIOS method from "Analyzing and
+/// Comparing Montgomery Multiplication Algorithms" by Koç, Acar, and Kaliski.
+#[cfg(any(feature = "pure_rust", not(feature = "use_heap")))]
+pub fn limbs_mont_mul(r: &mut [Limb], a: &[Limb], b: &[Limb], m: &[Limb],
+                      n0: Limb) {
+    let num_limbs = m.len();
+    debug_assert!(r.len() == num_limbs && a.len() == num_limbs &&
+                  b.len() == num_limbs);
+
+    // The accumulator is `r` plus the two limbs `t_hi` and `t_top`.
+    for r in r.iter_mut() {
+        *r = 0;
+    }
+    let mut t_hi = 0;
+    for i in 0..num_limbs {
+        let mut carry = 0;
+        for j in 0..num_limbs {
+            let (lo, hi) = limb_mac(r[j], a[j], b[i], carry);
+            r[j] = lo;
+            carry = hi;
+        }
+        let (hi, t_top) = limb_adc(t_hi, carry, 0);
+
+        let q = r[0].wrapping_mul(n0);
+        let (_, mut carry) = limb_mac(r[0], q, m[0], 0);
+        for j in 1..num_limbs {
+            let (lo, hi) = limb_mac(r[j], q, m[j], carry);
+            r[j - 1] = lo;
+            carry = hi;
+        }
+        let (lo, c) = limb_adc(hi, carry, 0);
+        r[num_limbs - 1] = lo;
+        t_hi = t_top + c;
+    }
+
+    // The result is less than `2 * m`; subtract `m` unless that would make it
+    // negative.
+    let mut borrow = 0;
+    for j in 0..num_limbs {
+        let (d, b) = limb_sbb(r[j], m[j], borrow);
+        r[j] = d;
+        borrow = b;
+    }
+    let add_back = (borrow & !t_hi & 1).wrapping_neg();
+    let mut carry = 0;
+    for j in 0..num_limbs {
+        let (s, c) = limb_adc(r[j], m[j] & add_back, carry);
+        r[j] = s;
+        carry = c;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use untrusted;
@@ -17,6 +17,9 @@
 //! Use `derive` to derive PBKDF2 outputs. Use `verify` to verify secret
 //! against previously-derived outputs.
 //!
+//! `ring::phc` can store the digest algorithm, iteration count, and salt
+//! together with the derived output, so that they can't get out of sync.
+//!
 //! PBKDF2 is specified in [RFC 2898 Section 5.2] with test vectors given in
 //! [RFC 6070]. See also [NIST Special Publication 800-132].
 //!
@@ -114,6 +117,15 @@
 
 use {constant_time, digest, error, hmac, polyfill};
 
+#[cfg(feature = "use_heap")]
+use std;
+
+#[cfg(feature = "use_heap")]
+use std::vec::Vec;
+
+#[cfg(feature = "use_heap")]
+use zeroize;
+
 /// Fills `out` with the key derived using PBKDF2 with the given inputs.
 ///
 /// Do not use `derive` as part of verifying a secret; use `verify` instead, to
@@ -143,23 +155,29 @@ pub fn derive(digest_alg: &'static digest::Algorithm, iterations: u32,
               salt: &[u8], secret: &[u8], out: &mut [u8]) {
     assert!(iterations >= 1);
 
-    let output_len = digest_alg.output_len;
-
     // This implementation's performance is asymptotically optimal as described
-    // in https://jbp.io/2015/08/11/pbkdf2-performance-matters/. However, it
-    // hasn't been optimized to the same extent as fastpbkdf2. In particular,
-    // this implementation is probably doing a lot of unnecessary copying.
+    // in https://jbp.io/2015/08/11/pbkdf2-performance-matters/. Each iteration
+    // after the first costs just two calls to the digest algorithm's block
+    // function; see `derive_block`.
 
     let secret = hmac::SigningKey::new(digest_alg, secret);
 
     // Clear |out|.
     polyfill::slice::fill(out, 0);
 
-    let mut idx: u32 = 0;
+    derive_blocks(&secret, iterations, salt, 0, out);
+}
+
+// Derives the blocks of `out`, where `prev_idx` is the index of the block
+// before the first one in `out`.
+fn derive_blocks(secret: &hmac::SigningKey, iterations: u32, salt: &[u8],
+                 prev_idx: u32, out: &mut [u8]) {
+    let output_len = secret.digest_algorithm().output_len;
+    let mut idx = prev_idx;
 
     for chunk in out.chunks_mut(output_len) {
         idx = idx.checked_add(1).expect("derived key too long");
-        derive_block(&secret, iterations, salt, idx, chunk);
+        derive_block(secret, iterations, salt, idx, chunk);
     }
 }
 
@@ -169,12 +187,18 @@ fn derive_block(secret: &hmac::SigningKey, iterations: u32, salt: &[u8],
     ctx.update(salt);
     ctx.update(&polyfill::slice::be_u8_from_u32(idx));
 
-    let mut u = ctx.sign();
+    let mut u = [0u8; digest::MAX_OUTPUT_LEN];
+    let u = &mut u[..secret.digest_algorithm().output_len];
+    u.copy_from_slice(ctx.sign().as_ref());
+
+    // Each subsequent U is the HMAC of the previous U, which is exactly one
+    // digest output long, so the padding is the same every time.
+    let mut prf = hmac::OutputLenSigner::new(secret);
 
     let mut remaining = iterations;
     loop {
-        for i in 0..out.len() {
-            out[i] ^= u.as_ref()[i];
+        for (out, u) in out.iter_mut().zip(u.iter()) {
+            *out ^= *u;
         }
 
         if remaining == 1 {
@@ -182,7 +206,7 @@ fn derive_block(secret: &hmac::SigningKey, iterations: u32, salt: &[u8],
         }
         remaining -= 1;
 
-        u = hmac::sign(secret, u.as_ref());
+        prf.sign_in_place(u);
     }
 }
 
@@ -251,3 +275,90 @@ pub fn verify(digest_alg: &'static digest::Algorithm, iterations: u32,
 
     Ok(())
 }
+
+/// Like `derive`, but computes the output blocks on up to `max_threads`
+/// threads.
+///
+/// Each block of PBKDF2 output, which is as long as the digest algorithm's
+/// output, is computed independently of the others, so when `out` is longer
+/// than one block the blocks can be computed in parallel. The blocks are
+/// divided into at most `max_threads` contiguous groups, the first of which is
+/// computed on the calling thread. The result is the same as the result of
+/// `derive`.
+///
+/// # Panics
+///
+/// `derive_parallel` panics if `iterations < 1`, if `max_threads < 1`, or if
+/// a thread can't be spawned.
+///
+/// `derive_parallel` panics if `out.len()` is larger than (2**32 - 1) * the
+/// digest algorithm's output length, per the PBKDF2 specification.
+#[cfg(feature = "use_heap")]
+pub fn derive_parallel(digest_alg: &'static digest::Algorithm,
+                       iterations: u32, salt: &[u8], secret: &[u8],
+                       out: &mut [u8], max_threads: usize) {
+    assert!(iterations >= 1);
+    assert!(max_threads >= 1);
+
+    let output_len = digest_alg.output_len;
+    let num_blocks = (out.len() + output_len - 1) / output_len;
+    assert!(num_blocks as u64 <= 0xffff_ffff, "derived key too long");
+
+    let secret = hmac::SigningKey::new(digest_alg, secret);
+
+    // Clear |out|.
+    polyfill::slice::fill(out, 0);
+
+    if num_blocks == 0 {
+        return;
+    }
+    let blocks_per_thread = 1 + ((num_blocks - 1) / max_threads);
+
+    let mut chunks = out.chunks_mut(blocks_per_thread * output_len);
+    let first_chunk = chunks.next().unwrap();
+    let threads: Vec<_> = chunks.enumerate().map(|(i, chunk)| {
+        let prev_idx = ((i + 1) * blocks_per_thread) as u32;
+        let secret = secret.clone();
+        let salt = salt.to_vec();
+        let len = chunk.len();
+        let thread = std::thread::spawn(move || {
+            let mut out = vec![0u8; len];
+            derive_blocks(&secret, iterations, &salt, prev_idx, &mut out);
+            out
+        });
+        (chunk, thread)
+    }).collect();
+
+    derive_blocks(&secret, iterations, salt, 0, first_chunk);
+
+    for (chunk, thread) in threads {
+        let mut thread_out = thread.join().unwrap();
+        chunk.copy_from_slice(&thread_out);
+        zeroize::zeroize(&mut thread_out);
+    }
+}
+
+/// Like `verify`, but computes the PBKDF2 value using `derive_parallel` with
+/// up to `max_threads` threads.
+///
+/// The comparison is done in constant time to prevent timing attacks. The
+/// comparison will fail if `previously_derived` is empty (has a length of
+/// zero).
+///
+/// # Panics
+///
+/// `verify_parallel` panics under the same conditions as `derive_parallel`.
+#[cfg(feature = "use_heap")]
+pub fn verify_parallel(digest_alg: &'static digest::Algorithm,
+                       iterations: u32, salt: &[u8], secret: &[u8],
+                       previously_derived: &[u8], max_threads: usize)
+                       -> Result<(), error::Unspecified> {
+    if previously_derived.is_empty() {
+        return Err(error::Unspecified);
+    }
+
+    let mut derived = vec![0u8; previously_derived.len()];
+    derive_parallel(digest_alg, iterations, salt, secret, &mut derived,
+                    max_threads);
+    constant_time::verify_slices_are_equal(&derived, previously_derived)
+}
@@ -0,0 +1,459 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Password hashes stored as PHC strings.
+//!
+//! A [PHC string] records the password hashing function, its parameters, the
+//! salt, and the hash together, so that none of them can get out of sync with
+//! the others. These formats are supported:
+//!
+//! ```text
+//! $pbkdf2-sha256$i=100000$<salt>$<hash>
+//! $pbkdf2-sha384$i=100000$<salt>$<hash>
+//! $pbkdf2-sha512$i=100000$<salt>$<hash>
+//! $scrypt$ln=15,r=8,p=1$<salt>$<hash>
+//! $argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>
+//! ```
+//!
+//! The salt and the hash are encoded using the standard Base64 alphabet
+//! without padding. Parsing is strict: the parameters must be given in the
+//! order above, without leading zeros, and the Base64 encoding must be
+//! canonical, so each hash has exactly one encoding.
+//!
+//! Use `derive_encoded` to hash a new password and `verify_encoded` to verify
+//! a password against a stored PHC string. `verify_encoded` rejects hashes
+//! whose parameters are weaker than the `Policy`'s minimums or more expensive
+//! than its maximums, and reports when a hash's parameters differ from the
+//! preferred parameters so that the password can be rehashed after it is
+//! verified.
+//!
+//! [PHC string]:
+//!     https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::{digest, phc, rand, scrypt};
+//!
+//! # fn main() { f().unwrap() }
+//! # fn f() -> Result<(), ring::error::Unspecified> {
+//! let policy = phc::Policy {
+//!     preferred: phc::Params::Scrypt(scrypt::Params::new(15, 8, 1)?),
+//!     minimums: &[
+//!         phc::Params::Pbkdf2 { digest_alg: &digest::SHA256,
+//!                               iterations: 100_000 },
+//!         phc::Params::Scrypt(scrypt::Params::new(14, 8, 1)?),
+//!     ],
+//!     maximums: &[
+//!         phc::Params::Pbkdf2 { digest_alg: &digest::SHA256,
+//!                               iterations: 1_000_000 },
+//!         phc::Params::Scrypt(scrypt::Params::new(16, 8, 1)?),
+//!     ],
+//! };
+//!
+//! // A hash that was stored before the switch from PBKDF2 to scrypt.
+//! let rng = rand::SystemRandom::new();
+//! let old_params = phc::Params::Pbkdf2 { digest_alg: &digest::SHA256,
+//!                                        iterations: 100_000 };
+//! let stored = phc::derive_encoded(&old_params, &rng, b"password")?;
+//! assert!(stored.starts_with("$pbkdf2-sha256$i=100000$"));
+//!
+//! assert!(phc::verify_encoded(&policy, &stored, b"passw0rd").is_err());
+//! match phc::verify_encoded(&policy, &stored, b"password")? {
+//!     phc::Verified::UpToDate => unreachable!(),
+//!     phc::Verified::NeedsRehash => {
+//!         let stored = phc::derive_encoded(&policy.preferred, &rng,
+//!                                          b"password")?;
+//!         assert!(stored.starts_with("$scrypt$ln=15,r=8,p=1$"));
+//!         assert_eq!(phc::verify_encoded(&policy, &stored, b"password")?,
+//!                    phc::Verified::UpToDate);
+//!     },
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use {argon2, digest, error, pbkdf2, rand, scrypt};
+use std::string::String;
+use std::vec::Vec;
+
+/// The parameters of a password hashing function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Params {
+    /// PBKDF2, which is encoded as `pbkdf2-sha256`, `pbkdf2-sha384`, or
+    /// `pbkdf2-sha512`.
+    Pbkdf2 {
+        /// The digest algorithm used with HMAC. Only SHA-256, SHA-384, and
+        /// SHA-512 are supported.
+        digest_alg: &'static digest::Algorithm,
+
+        /// The iteration count, which must be at least 1.
+        iterations: u32,
+    },
+
+    /// scrypt, which is encoded as `scrypt`.
+    Scrypt(scrypt::Params),
+
+    /// Argon2id version 0x13, which is encoded as `argon2id`.
+    Argon2id(argon2::Params),
+}
+
+impl Params {
+    // Whether `self` is at least as strong as `minimum`.
+    fn meets(&self, minimum: &Params) -> bool {
+        match (self, minimum) {
+            (&Params::Pbkdf2 { digest_alg, iterations },
+             &Params::Pbkdf2 { digest_alg: min_digest_alg,
+                               iterations: min_iterations }) =>
+                digest_alg == min_digest_alg && iterations >= min_iterations,
+            (&Params::Scrypt(ref params), &Params::Scrypt(ref min)) =>
+                params.log_n() >= min.log_n() && params.r() >= min.r() &&
+                params.p() >= min.p(),
+            // Reducing the number of lanes while keeping the memory size the
+            // same doesn't make Argon2id weaker, so the parallelism isn't
+            // compared.
+            (&Params::Argon2id(ref params), &Params::Argon2id(ref min)) =>
+                params.memory_kib() >= min.memory_kib() &&
+                params.iterations() >= min.iterations(),
+            _ => false,
+        }
+    }
+
+    // Whether `self` is no more expensive than `maximum`.
+    fn within(&self, maximum: &Params) -> bool {
+        match (self, maximum) {
+            (&Params::Pbkdf2 { digest_alg, iterations },
+             &Params::Pbkdf2 { digest_alg: max_digest_alg,
+                               iterations: max_iterations }) =>
+                digest_alg == max_digest_alg && iterations <= max_iterations,
+            (&Params::Scrypt(ref params), &Params::Scrypt(ref max)) =>
+                params.log_n() <= max.log_n() && params.r() <= max.r() &&
+                params.p() <= max.p(),
+            (&Params::Argon2id(ref params), &Params::Argon2id(ref max)) =>
+                params.memory_kib() <= max.memory_kib() &&
+                params.iterations() <= max.iterations() &&
+                params.parallelism() <= max.parallelism(),
+            _ => false,
+        }
+    }
+}
+
+/// The policy that `verify_encoded` enforces.
+#[derive(Clone, Copy, Debug)]
+pub struct Policy<'a> {
+    /// The parameters for newly-derived hashes. A hash with any other
+    /// parameters is verified as `Verified::NeedsRehash`.
+    pub preferred: Params,
+
+    /// The weakest acceptable parameters. A hash is rejected unless its
+    /// parameters meet one of the minimums for the same algorithm: for
+    /// PBKDF2 the digest algorithm must be the same and the iteration count
+    /// must be at least as large; for scrypt each of `log_n`, `r`, and `p`
+    /// must be at least as large; for Argon2id the memory size and the
+    /// number of passes must be at least as large.
+    pub minimums: &'a [Params],
+
+    /// The most expensive acceptable parameters. A hash is rejected unless
+    /// its parameters are within one of the maximums for the same algorithm:
+    /// for PBKDF2 the digest algorithm must be the same and the iteration
+    /// count must be at most as large; for scrypt each of `log_n`, `r`, and
+    /// `p` must be at most as large; for Argon2id the memory size, the number
+    /// of passes, and the number of lanes must be at most as large. This
+    /// keeps a corrupt or malicious stored hash from making verification
+    /// exhaust the memory or the time available.
+    pub maximums: &'a [Params],
+}
+
+/// The result of a successful `verify_encoded`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verified {
+    /// The hash was derived with the policy's preferred parameters.
+    UpToDate,
+
+    /// The hash was derived with acceptable parameters other than the
+    /// policy's preferred parameters. The caller should replace it with the
+    /// result of `derive_encoded` using the preferred parameters while it has
+    /// the secret available.
+    NeedsRehash,
+}
+
+/// A decoded PHC string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decoded {
+    params: Params,
+    salt: Vec<u8>,
+    hash: Vec<u8>,
+}
+
+impl Decoded {
+    /// The parameters.
+    #[inline]
+    pub fn params(&self) -> &Params { &self.params }
+
+    /// The salt.
+    #[inline]
+    pub fn salt(&self) -> &[u8] { &self.salt }
+
+    /// The hash.
+    #[inline]
+    pub fn hash(&self) -> &[u8] { &self.hash }
+}
+
+/// The length of the salt generated by `derive_encoded`.
+pub const SALT_LEN: usize = 16;
+
+/// The length of the hash computed by `derive_encoded`.
+pub const HASH_LEN: usize = 32;
+
+/// Derives a hash of `secret` with a new random salt and returns it encoded
+/// as a PHC string.
+///
+/// The salt is `SALT_LEN` bytes and the hash is `HASH_LEN` bytes.
+pub fn derive_encoded(params: &Params, rng: &rand::SecureRandom,
+                      secret: &[u8]) -> Result<String, error::Unspecified> {
+    let mut salt = [0u8; SALT_LEN];
+    rng.fill(&mut salt)?;
+
+    // Check that `params` can be encoded before doing the expensive work.
+    let _ = encoded_id(params)?;
+
+    let mut hash = [0u8; HASH_LEN];
+    match *params {
+        Params::Pbkdf2 { digest_alg, iterations } =>
+            pbkdf2::derive(digest_alg, iterations, &salt, secret, &mut hash),
+        Params::Scrypt(ref params) =>
+            scrypt::derive(params, &salt, secret, &mut hash),
+        Params::Argon2id(ref params) =>
+            argon2::derive(params, &salt, secret, &mut hash),
+    }
+
+    encode(params, &salt, &hash)
+}
+
+/// Verifies `secret` against the PHC string `encoded` according to `policy`.
+///
+/// Verification fails if `encoded` is malformed, if its parameters don't
+/// meet any of `policy.minimums` or aren't within any of `policy.maximums`,
+/// or if `secret` doesn't match. The parameters are checked before the hash
+/// is computed. Otherwise, the result indicates whether the hash should be
+/// replaced with one that uses `policy.preferred`.
+///
+/// The comparison of the hashes is done in constant time.
+pub fn verify_encoded(policy: &Policy, encoded: &str, secret: &[u8])
+                      -> Result<Verified, error::Unspecified> {
+    let decoded = decode(encoded)?;
+    if !policy.minimums.iter().any(|minimum| decoded.params.meets(minimum)) {
+        return Err(error::Unspecified);
+    }
+    if !policy.maximums.iter().any(|maximum| decoded.params.within(maximum)) {
+        return Err(error::Unspecified);
+    }
+
+    match decoded.params {
+        Params::Pbkdf2 { digest_alg, iterations } =>
+            pbkdf2::verify(digest_alg, iterations, &decoded.salt, secret,
+                           &decoded.hash)?,
+        Params::Scrypt(ref params) =>
+            scrypt::verify(params, &decoded.salt, secret, &decoded.hash)?,
+        Params::Argon2id(ref params) =>
+            argon2::verify(params, &decoded.salt, secret, &decoded.hash)?,
+    }
+
+    if decoded.params == policy.preferred {
+        Ok(Verified::UpToDate)
+    } else {
+        Ok(Verified::NeedsRehash)
+    }
+}
+
+/// Encodes the given parameters, salt, and hash as a PHC string.
+///
+/// Fails if `params` uses a digest algorithm that can't be encoded, or if the
+/// salt or the hash is empty.
+pub fn encode(params: &Params, salt: &[u8], hash: &[u8])
+              -> Result<String, error::Unspecified> {
+    if salt.is_empty() || hash.is_empty() {
+        return Err(error::Unspecified);
+    }
+
+    let id = encoded_id(params)?;
+    let params = match *params {
+        Params::Pbkdf2 { iterations, .. } => format!("i={}", iterations),
+        Params::Scrypt(ref params) =>
+            format!("ln={},r={},p={}", params.log_n(), params.r(),
+                    params.p()),
+        Params::Argon2id(ref params) =>
+            format!("v={}$m={},t={},p={}", ARGON2_VERSION,
+                    params.memory_kib(), params.iterations(),
+                    params.parallelism()),
+    };
+    Ok(format!("${}${}${}${}", id, params, base64_encode(salt),
+               base64_encode(hash)))
+}
+
+/// Decodes a PHC string.
+pub fn decode(encoded: &str) -> Result<Decoded, error::Unspecified> {
+    let mut fields = encoded.split('$');
+    if fields.next() != Some("") {
+        return Err(error::Unspecified);
+    }
+    let mut next = || fields.next().ok_or(error::Unspecified);
+
+    let params = match next()? {
+        "pbkdf2-sha256" => pbkdf2_params(&digest::SHA256, next()?)?,
+        "pbkdf2-sha384" => pbkdf2_params(&digest::SHA384, next()?)?,
+        "pbkdf2-sha512" => pbkdf2_params(&digest::SHA512, next()?)?,
+        "scrypt" => {
+            let [log_n, r, p] = parse_params(next()?, &["ln", "r", "p"])?;
+            if log_n > 0xff {
+                return Err(error::Unspecified);
+            }
+            Params::Scrypt(scrypt::Params::new(log_n as u8, r, p)?)
+        },
+        "argon2id" => {
+            let [version] = parse_params(next()?, &["v"])?;
+            if version != ARGON2_VERSION {
+                return Err(error::Unspecified);
+            }
+            let [m, t, p] = parse_params(next()?, &["m", "t", "p"])?;
+            Params::Argon2id(argon2::Params::new(m, t, p)?)
+        },
+        _ => {
+            return Err(error::Unspecified);
+        },
+    };
+
+    let salt = base64_decode(next()?)?;
+    let hash = base64_decode(next()?)?;
+    if next().is_ok() || salt.is_empty() || hash.is_empty() {
+        return Err(error::Unspecified);
+    }
+
+    Ok(Decoded { params, salt, hash })
+}
+
+const ARGON2_VERSION: u32 = 0x13;
+
+fn encoded_id(params: &Params) -> Result<&'static str, error::Unspecified> {
+    match *params {
+        Params::Pbkdf2 { digest_alg, iterations } => {
+            if iterations < 1 {
+                return Err(error::Unspecified);
+            }
+            if *digest_alg == digest::SHA256 {
+                Ok("pbkdf2-sha256")
+            } else if *digest_alg == digest::SHA384 {
+                Ok("pbkdf2-sha384")
+            } else if *digest_alg == digest::SHA512 {
+                Ok("pbkdf2-sha512")
+            } else {
+                Err(error::Unspecified)
+            }
+        },
+        Params::Scrypt(_) => Ok("scrypt"),
+        Params::Argon2id(_) => Ok("argon2id"),
+    }
+}
+
+fn pbkdf2_params(digest_alg: &'static digest::Algorithm, params: &str)
+                 -> Result<Params, error::Unspecified> {
+    let [iterations] = parse_params(params, &["i"])?;
+    if iterations < 1 {
+        return Err(error::Unspecified);
+    }
+    Ok(Params::Pbkdf2 { digest_alg, iterations })
+}
+
+// Parses `name=value,...` where the names are exactly `names`, in order, and
+// the values are decimal numbers.
+fn parse_params<A>(params: &str, names: &[&str])
+                   -> Result<A, error::Unspecified>
+                   where A: Default + AsMut<[u32]> {
+    let mut values = A::default();
+    let mut params = params.split(',');
+    for (value, name) in values.as_mut().iter_mut().zip(names.iter()) {
+        let param = params.next().ok_or(error::Unspecified)?;
+        let mut param = param.splitn(2, '=');
+        if param.next() != Some(*name) {
+            return Err(error::Unspecified);
+        }
+        *value = parse_decimal(param.next().ok_or(error::Unspecified)?)?;
+    }
+    if params.next().is_some() {
+        return Err(error::Unspecified);
+    }
+    Ok(values)
+}
+
+fn parse_decimal(s: &str) -> Result<u32, error::Unspecified> {
+    let bytes = s.as_bytes();
+    if bytes.is_empty() || (bytes[0] == b'0' && bytes.len() > 1) {
+        return Err(error::Unspecified);
+    }
+    let mut value: u32 = 0;
+    for &b in bytes {
+        if b < b'0' || b > b'9' {
+            return Err(error::Unspecified);
+        }
+        value = value.checked_mul(10)
+            .and_then(|value| value.checked_add(u32::from(b - b'0')))
+            .ok_or(error::Unspecified)?;
+    }
+    Ok(value)
+}
+
+const BASE64_ALPHABET: &'static [u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Base64 with the standard alphabet and without padding.
+fn base64_encode(input: &[u8]) -> String {
+    let mut out = String::with_capacity((input.len() * 4 + 2) / 3);
+    for chunk in input.chunks(3) {
+        let mut block = [0u8; 3];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from(block[0]) << 16 | u32::from(block[1]) << 8 |
+                   u32::from(block[2]);
+        for i in 0..(chunk.len() + 1) {
+            let index = (bits >> (18 - (6 * i))) & 0x3f;
+            out.push(char::from(BASE64_ALPHABET[index as usize]));
+        }
+    }
+    out
+}
+
+fn base64_decode(input: &str) -> Result<Vec<u8>, error::Unspecified> {
+    let input = input.as_bytes();
+    if input.len() % 4 == 1 {
+        return Err(error::Unspecified);
+    }
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    for chunk in input.chunks(4) {
+        let mut bits: u32 = 0;
+        for (i, &c) in chunk.iter().enumerate() {
+            let value = BASE64_ALPHABET.iter().position(|&a| a == c)
+                .ok_or(error::Unspecified)?;
+            bits |= (value as u32) << (18 - (6 * i));
+        }
+        let len = chunk.len() - 1;
+        // Reject non-canonical encodings, where the unused low bits of the
+        // last character aren't zero.
+        if bits & (0x00ff_ffff >> (8 * len)) != 0 {
+            return Err(error::Unspecified);
+        }
+        for i in 0..len {
+            out.push((bits >> (16 - (8 * i))) as u8);
+        }
+    }
+    Ok(out)
+}
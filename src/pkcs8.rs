@@ -17,7 +17,7 @@
 //! [RFC 5958]: https://tools.ietf.org/html/rfc5958.
 
 use core;
-use {der, ec, error};
+use {der, ec, error, zeroize};
 use untrusted;
 
 pub enum Version {
@@ -68,7 +68,7 @@ impl Template {
 pub fn unwrap_key<'a>(template: &Template, version: Version,
                       input: untrusted::Input<'a>)
         -> Result<(untrusted::Input<'a>, Option<untrusted::Input<'a>>),
-                   error::Unspecified> {
+                   error::KeyRejected> {
     unwrap_key_(template.alg_id_value(), version, input)
 }
 
@@ -85,54 +85,79 @@ pub fn unwrap_key<'a>(template: &Template, version: Version,
 pub fn unwrap_key_<'a>(alg_id: &[u8], version: Version,
                        input: untrusted::Input<'a>)
         -> Result<(untrusted::Input<'a>, Option<untrusted::Input<'a>>),
-                  error::Unspecified> {
-    input.read_all(error::Unspecified, |input| {
-        der::nested(input, der::Tag::Sequence, error::Unspecified, |input| {
-            // Currently we only support algorithms that should only be encoded
-            // in v1 form, so reject v2 and any later form.
-            let require_public_key =
-                    match (der::small_nonnegative_integer(input)?, version) {
-                (0, Version::V1Only) => false,
-                (0, Version::V1OrV2) => false,
-                (1, Version::V1OrV2) |
-                (1, Version::V2Only) => true,
-                _ => { return Err(error::Unspecified); }
-            };
-
-            let actual_alg_id =
-                der::expect_tag_and_get_value(input, der::Tag::Sequence)?;
-            if actual_alg_id != alg_id {
-                return Err(error::Unspecified);
-            }
-
-            let private_key =
-                der::expect_tag_and_get_value(input, der::Tag::OctetString)?;
-
-            // Ignore any attributes that are present.
-            if input.peek(der::Tag::ContextSpecificConstructed0 as u8) {
-                let _ = der::expect_tag_and_get_value(input,
-                    der::Tag::ContextSpecificConstructed0)?;
-            }
-
-            let public_key = if require_public_key {
-                Some(der::nested(
-                    input, der::Tag::ContextSpecificConstructed1,
-                    error::Unspecified, der::bit_string_with_no_unused_bits)?)
-            } else {
-                None
-            };
-
-            Ok((private_key, public_key))
+                  error::KeyRejected> {
+    input.read_all(error::KeyRejected::invalid_encoding(), |input| {
+        der::nested(input, der::Tag::Sequence,
+                    error::KeyRejected::invalid_encoding(), |input| {
+            one_asymmetric_key_value(alg_id, version, input)
         })
     })
 }
 
+// Parses the contents of a `OneAsymmetricKey` (`PrivateKeyInfo` in v1).
+fn one_asymmetric_key_value<'a>(alg_id: &[u8], version: Version,
+                                input: &mut untrusted::Reader<'a>)
+        -> Result<(untrusted::Input<'a>, Option<untrusted::Input<'a>>),
+                  error::KeyRejected> {
+    let actual_version = der::small_nonnegative_integer(input)
+        .map_err(invalid_encoding)?;
+
+    // Currently we only support algorithms that should only be encoded
+    // in v1 form, so reject v2 and any later form.
+    let require_public_key = match (actual_version, version) {
+        (0, Version::V1Only) => false,
+        (0, Version::V1OrV2) => false,
+        (1, Version::V1OrV2) |
+        (1, Version::V2Only) => true,
+        _ => { return Err(error::KeyRejected::invalid_encoding()); }
+    };
+
+    let actual_alg_id =
+        der::expect_tag_and_get_value(input, der::Tag::Sequence)
+            .map_err(invalid_encoding)?;
+    if actual_alg_id != alg_id {
+        return Err(error::KeyRejected::wrong_algorithm());
+    }
+
+    let private_key =
+        der::expect_tag_and_get_value(input, der::Tag::OctetString)
+            .map_err(invalid_encoding)?;
+
+    // Ignore any attributes that are present.
+    if input.peek(der::Tag::ContextSpecificConstructed0 as u8) {
+        let _ = der::expect_tag_and_get_value(
+            input, der::Tag::ContextSpecificConstructed0)
+                .map_err(invalid_encoding)?;
+    }
+
+    let public_key = if require_public_key {
+        Some(der::nested(
+            input, der::Tag::ContextSpecificConstructed1, error::Unspecified,
+            der::bit_string_with_no_unused_bits)
+            .map_err(invalid_encoding)?)
+    } else {
+        None
+    };
+
+    Ok((private_key, public_key))
+}
+
+fn invalid_encoding(_: error::Unspecified) -> error::KeyRejected {
+    error::KeyRejected::invalid_encoding()
+}
+
 /// A generated PKCS#8 document.
 pub struct PKCS8Document {
     bytes: [u8; ec::PKCS8_DOCUMENT_MAX_LEN],
     len: usize,
 }
 
+impl Drop for PKCS8Document {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.bytes);
+    }
+}
+
 impl AsRef<[u8]> for PKCS8Document {
     #[inline]
     fn as_ref(&self) -> &[u8] { &self.bytes[..self.len] }
@@ -13,12 +13,41 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
+//! The Poly1305 one-time authenticator, as specified in [RFC 7539].
+//!
+//! **A Poly1305 key must never be used to authenticate more than one
+//! message.** An attacker who sees the tags of two messages authenticated
+//! with the same key can forge tags for other messages. To make that harder
+//! to do accidentally, `Key` can't be copied or cloned, and every operation
+//! that uses a key consumes it.
+//!
+//! Usually, the one-time key is derived for each message from a long-term
+//! key and a nonce, e.g. by a stream cipher, as in `secretbox` and
+//! `aead::CHACHA20_POLY1305`.
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::poly1305;
+//!
+//! let key_bytes = [0x42; poly1305::KEY_LEN];
+//! let msg = b"hello, world";
+//!
+//! let mut tag = [0u8; poly1305::TAG_LEN];
+//! poly1305::sign(poly1305::Key::new(&key_bytes), msg, &mut tag);
+//!
+//! let key = poly1305::Key::new(&key_bytes);
+//! assert!(poly1305::verify(key, msg, &tag).is_ok());
+//! ```
+//!
+//! [RFC 7539]: https://tools.ietf.org/html/rfc7539#section-2.5
+
 // TODO: enforce maximum input length.
 
 // Work around compiler bug?
 #![allow(non_shorthand_field_patterns)]
 
-use {c, chacha, constant_time, error, polyfill};
+use {c, chacha, constant_time, error, polyfill, zeroize};
 use core;
 
 // The assembly functions we call expect the state to be 8-byte aligned. We do
@@ -39,6 +68,7 @@ fn with_aligned<F>(opaque: &mut Opaque, f: F)
 }
 
 impl SigningContext {
+    /// Constructs a new signing context that uses, and consumes, `key`.
     #[inline]
     pub fn from_key(key: Key) -> SigningContext {
         #[inline]
@@ -88,6 +118,8 @@ impl SigningContext {
         ctx
     }
 
+    /// Updates the tag calculation with all the data in `input`. `update`
+    /// may be called zero or more times until `sign` is called.
     pub fn update(&mut self, mut input: &[u8]) {
         let &mut SigningContext {
             opaque: ref mut opaque,
@@ -125,6 +157,11 @@ impl SigningContext {
         });
     }
 
+    /// Finalizes the tag calculation and writes the tag to `tag_out`.
+    ///
+    /// It is generally not safe to implement Poly1305 verification by
+    /// comparing the tag calculated by `sign` to a received tag. Use `verify`
+    /// for verification instead.
     pub fn sign(mut self, tag_out: &mut Tag) {
         let &mut SigningContext {
             opaque: ref mut opaque,
@@ -147,6 +184,10 @@ impl SigningContext {
     }
 }
 
+/// Calculates the tag of `msg` using, and consuming, `key`, and verifies
+/// whether it equals `tag`, in one step.
+///
+/// The verification will be done in constant time to prevent timing attacks.
 pub fn verify(key: Key, msg: &[u8], tag: &Tag)
               -> Result<(), error::Unspecified> {
     let mut calculated_tag = [0u8; TAG_LEN];
@@ -154,6 +195,11 @@ pub fn verify(key: Key, msg: &[u8], tag: &Tag)
     constant_time::verify_slices_are_equal(&calculated_tag[..], tag)
 }
 
+/// Calculates the tag of `msg` using, and consuming, `key`, and writes it to
+/// `tag`.
+///
+/// Use `SigningContext` to calculate tags where the input is in multiple
+/// parts.
 pub fn sign(key: Key, msg: &[u8], tag: &mut Tag) {
     let mut ctx = SigningContext::from_key(key);
     ctx.update(msg);
@@ -161,7 +207,7 @@ pub fn sign(key: Key, msg: &[u8], tag: &mut Tag) {
 }
 
 #[cfg(test)]
-pub fn check_state_layout() {
+fn check_state_layout() {
     let required_state_size =
         if cfg!(target_arch = "x86") {
             // See comment above `_poly1305_init_sse2` in poly1305-x86.pl.
@@ -180,23 +226,34 @@ pub fn check_state_layout() {
     }
 }
 
-/// A Poly1305 key.
+/// A Poly1305 one-time key.
+///
+/// A `Key` is consumed by the operation that uses it, so each `Key` value
+/// can authenticate only one message.
 pub struct Key {
     bytes: KeyAndNonceBytes,
 }
 
+impl Drop for Key {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.bytes);
+    }
+}
+
 impl Key {
-    pub fn derive_using_chacha(chacha20_key: &chacha::Key,
-                               counter: &chacha::Counter) -> Key {
+    /// Constructs a key from `key_bytes`, which must be unique to the message
+    /// that the key will be used to authenticate.
+    #[inline]
+    pub fn new(key_bytes: &[u8; KEY_LEN]) -> Key {
+        Key { bytes: *key_bytes }
+    }
+
+    pub(crate) fn derive_using_chacha(chacha20_key: &chacha::Key,
+                                      counter: &chacha::Counter) -> Key {
         let mut bytes = [0u8; KEY_LEN];
         chacha::chacha20_xor_in_place(chacha20_key, counter, &mut bytes);
         Key { bytes }
     }
-
-    #[cfg(test)]
-    pub fn from_test_vector(bytes: &[u8; KEY_LEN]) -> Key {
-        Key { bytes: *bytes }
-    }
 }
 
 type KeyAndNonceBytes = [u8; 2 * BLOCK_LEN];
@@ -259,6 +316,9 @@ impl Funcs {
     }
 }
 
+/// A context for multi-step (Init-Update-Finish) Poly1305 signing.
+///
+/// Use `sign` for single-step signing.
 pub struct SigningContext {
     opaque: Opaque,
     nonce: [u32; 4],
@@ -267,6 +327,15 @@ pub struct SigningContext {
     func: Funcs
 }
 
+impl Drop for SigningContext {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.opaque);
+        zeroize::zeroize(&mut self.nonce);
+        zeroize::zeroize(&mut self.buf);
+    }
+}
+
+#[cfg(not(feature = "pure_rust"))]
 extern {
     fn GFp_poly1305_init_asm(state: &mut Opaque, key: &KeyBytes,
                              out_func: &mut Funcs) -> c::int;
@@ -275,6 +344,161 @@ extern {
     fn GFp_poly1305_emit(state: &mut Opaque, mac: &mut Tag, nonce: &Nonce);
 }
 
+// Portable implementations of the assembly language functions, for builds
+// with the `pure_rust` feature. This is poly1305-donna's 32-bit variant: the
+// accumulator and `r` are each kept in five 26-bit limbs, so that all the
+// products fit in 64 bits.
+
+#[cfg(feature = "pure_rust")]
+#[repr(C)]
+struct PortableState {
+    r: [u32; 5],
+    h: [u32; 5],
+}
+
+#[cfg(feature = "pure_rust")]
+fn portable_state(state: &mut Opaque) -> &mut PortableState {
+    debug_assert!(core::mem::size_of::<PortableState>() <= OPAQUE_LEN);
+    debug_assert_eq!(state.as_ptr() as usize %
+                         core::mem::align_of::<PortableState>(), 0);
+    unsafe { &mut *(state.as_mut_ptr() as *mut PortableState) }
+}
+
+#[cfg(feature = "pure_rust")]
+#[inline(always)]
+fn read_u32(buf: &[u8]) -> u32 {
+    polyfill::slice::u32_from_le_u8(slice_as_array_ref!(buf, 4).unwrap())
+}
+
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe fn GFp_poly1305_init_asm(state: &mut Opaque, key: &KeyBytes,
+                                _out_func: &mut Funcs) -> c::int {
+    let state = portable_state(state);
+    state.r = [
+        read_u32(&key[0..4]) & 0x3ffffff,
+        (read_u32(&key[3..7]) >> 2) & 0x3ffff03,
+        (read_u32(&key[6..10]) >> 4) & 0x3ffc0ff,
+        (read_u32(&key[9..13]) >> 6) & 0x3f03fff,
+        (read_u32(&key[12..16]) >> 8) & 0x00fffff,
+    ];
+    state.h = [0; 5];
+    0 // `out_func` wasn't initialized.
+}
+
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe extern fn GFp_poly1305_blocks(state: &mut Opaque, input: *const u8,
+                                     len: c::size_t, should_pad: Pad) {
+    let input = core::slice::from_raw_parts(input, len);
+    let state = portable_state(state);
+    let hibit = (should_pad as u32) << 24;
+
+    let r0 = u64::from(state.r[0]);
+    let r1 = u64::from(state.r[1]);
+    let r2 = u64::from(state.r[2]);
+    let r3 = u64::from(state.r[3]);
+    let r4 = u64::from(state.r[4]);
+    let (s1, s2, s3, s4) = (r1 * 5, r2 * 5, r3 * 5, r4 * 5);
+
+    let mut h0 = state.h[0];
+    let mut h1 = state.h[1];
+    let mut h2 = state.h[2];
+    let mut h3 = state.h[3];
+    let mut h4 = state.h[4];
+
+    for block in input.chunks(BLOCK_LEN) {
+        debug_assert_eq!(block.len(), BLOCK_LEN);
+        h0 += read_u32(&block[0..4]) & 0x3ffffff;
+        h1 += (read_u32(&block[3..7]) >> 2) & 0x3ffffff;
+        h2 += (read_u32(&block[6..10]) >> 4) & 0x3ffffff;
+        h3 += (read_u32(&block[9..13]) >> 6) & 0x3ffffff;
+        h4 += (read_u32(&block[12..16]) >> 8) | hibit;
+
+        let (x0, x1, x2, x3, x4) =
+            (u64::from(h0), u64::from(h1), u64::from(h2), u64::from(h3),
+             u64::from(h4));
+        let d0 = x0 * r0 + x1 * s4 + x2 * s3 + x3 * s2 + x4 * s1;
+        let mut d1 = x0 * r1 + x1 * r0 + x2 * s4 + x3 * s3 + x4 * s2;
+        let mut d2 = x0 * r2 + x1 * r1 + x2 * r0 + x3 * s4 + x4 * s3;
+        let mut d3 = x0 * r3 + x1 * r2 + x2 * r1 + x3 * r0 + x4 * s4;
+        let mut d4 = x0 * r4 + x1 * r3 + x2 * r2 + x3 * r1 + x4 * r0;
+
+        d1 += d0 >> 26;
+        h0 = (d0 as u32) & 0x3ffffff;
+        d2 += d1 >> 26;
+        h1 = (d1 as u32) & 0x3ffffff;
+        d3 += d2 >> 26;
+        h2 = (d2 as u32) & 0x3ffffff;
+        d4 += d3 >> 26;
+        h3 = (d3 as u32) & 0x3ffffff;
+        let c = (d4 >> 26) as u32;
+        h4 = (d4 as u32) & 0x3ffffff;
+        h0 += c * 5;
+        h1 += h0 >> 26;
+        h0 &= 0x3ffffff;
+    }
+
+    state.h = [h0, h1, h2, h3, h4];
+}
+
+#[cfg(feature = "pure_rust")]
+#[allow(non_snake_case)]
+unsafe extern fn GFp_poly1305_emit(state: &mut Opaque, mac: &mut Tag,
+                                   nonce: &Nonce) {
+    let state = portable_state(state);
+    let mut h0 = state.h[0];
+    let mut h1 = state.h[1];
+    let mut h2 = state.h[2];
+    let mut h3 = state.h[3];
+    let mut h4 = state.h[4];
+
+    // Fully carry `h`.
+    h2 += h1 >> 26;
+    h1 &= 0x3ffffff;
+    h3 += h2 >> 26;
+    h2 &= 0x3ffffff;
+    h4 += h3 >> 26;
+    h3 &= 0x3ffffff;
+    h0 += (h4 >> 26) * 5;
+    h4 &= 0x3ffffff;
+    h1 += h0 >> 26;
+    h0 &= 0x3ffffff;
+
+    // Compute `h - p` and select it, in constant time, if it isn't negative.
+    let mut g0 = h0.wrapping_add(5);
+    let mut g1 = h1.wrapping_add(g0 >> 26);
+    g0 &= 0x3ffffff;
+    let mut g2 = h2.wrapping_add(g1 >> 26);
+    g1 &= 0x3ffffff;
+    let mut g3 = h3.wrapping_add(g2 >> 26);
+    g2 &= 0x3ffffff;
+    let g4 = h4.wrapping_add(g3 >> 26).wrapping_sub(1 << 26);
+    g3 &= 0x3ffffff;
+
+    let use_g = (g4 >> 31).wrapping_sub(1);
+    h0 = (h0 & !use_g) | (g0 & use_g);
+    h1 = (h1 & !use_g) | (g1 & use_g);
+    h2 = (h2 & !use_g) | (g2 & use_g);
+    h3 = (h3 & !use_g) | (g3 & use_g);
+    h4 = (h4 & !use_g) | (g4 & use_g);
+
+    // `h % 2**128`, plus the nonce.
+    let h = [
+        h0 | (h1 << 26),
+        (h1 >> 6) | (h2 << 20),
+        (h2 >> 12) | (h3 << 14),
+        (h3 >> 18) | (h4 << 8),
+    ];
+    let mut carry = 0;
+    for (i, (h, n)) in h.iter().zip(nonce.iter()).enumerate() {
+        let f = u64::from(*h) + u64::from(*n) + carry;
+        mac[(i * 4)..][..4].copy_from_slice(
+            &polyfill::slice::le_u8_from_u32(f as u32));
+        carry = f >> 32;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use {error, test};
@@ -300,7 +524,7 @@ mod tests {
 
             // Test single-shot operation.
             {
-                let key = Key::from_test_vector(&key);
+                let key = Key::new(&key);
                 let mut ctx = SigningContext::from_key(key);
                 ctx.update(&input);
                 let mut actual_mac = [0; TAG_LEN];
@@ -308,19 +532,19 @@ mod tests {
                 assert_eq!(&expected_mac[..], &actual_mac[..]);
             }
             {
-                let key = Key::from_test_vector(&key);
+                let key = Key::new(&key);
                 let mut actual_mac = [0; TAG_LEN];
                 sign(key, &input, &mut actual_mac);
                 assert_eq!(&expected_mac[..], &actual_mac[..]);
             }
             {
-                let key = Key::from_test_vector(&key);
+                let key = Key::new(&key);
                 assert_eq!(Ok(()), verify(key, &input, &expected_mac));
             }
 
             // Test streaming byte-by-byte.
             {
-                let key = Key::from_test_vector(&key);
+                let key = Key::new(&key);
                 let mut ctx = SigningContext::from_key(key);
                 for chunk in input.chunks(1) {
                     ctx.update(chunk);
@@ -342,7 +566,7 @@ mod tests {
     fn test_poly1305_simd(excess: usize, key: &[u8; KEY_LEN], input: &[u8],
                           expected_mac: &[u8; TAG_LEN])
                           -> Result<(), error::Unspecified> {
-        let key = Key::from_test_vector(&key);
+        let key = Key::new(&key);
         let mut ctx = SigningContext::from_key(key);
 
         // Some implementations begin in non-SIMD mode and upgrade on demand.
@@ -19,7 +19,6 @@ use core;
 
 // A better name for the `&*` idiom for removing the mutability from a
 // reference.
-#[cfg(feature = "use_heap")]
 #[inline(always)]
 pub fn ref_from_mut_ref<'a, T: ?Sized>(x: &'a mut T) -> &'a T { x }
 
@@ -55,6 +54,40 @@ pub mod slice {
         u32::from(buffer[3]) << 24
     }
 
+    #[cfg(feature = "use_heap")]
+    #[inline(always)]
+    pub fn u64_from_le_u8(buffer: &[u8; 8]) -> u64 {
+        u64::from(buffer[0]) |
+        u64::from(buffer[1]) << 8 |
+        u64::from(buffer[2]) << 16 |
+        u64::from(buffer[3]) << 24 |
+        u64::from(buffer[4]) << 32 |
+        u64::from(buffer[5]) << 40 |
+        u64::from(buffer[6]) << 48 |
+        u64::from(buffer[7]) << 56
+    }
+
+    #[inline(always)]
+    pub fn le_u8_from_u32(value: u32) -> [u8; 4] {
+        [(value & 0xff) as u8,
+         ((value >> 8) & 0xff) as u8,
+         ((value >> 16) & 0xff) as u8,
+         ((value >> 24) & 0xff) as u8]
+    }
+
+    #[cfg(feature = "use_heap")]
+    #[inline(always)]
+    pub fn le_u8_from_u64(value: u64) -> [u8; 8] {
+        [(value & 0xff) as u8,
+         ((value >> 8) & 0xff) as u8,
+         ((value >> 16) & 0xff) as u8,
+         ((value >> 24) & 0xff) as u8,
+         ((value >> 32) & 0xff) as u8,
+         ((value >> 40) & 0xff) as u8,
+         ((value >> 48) & 0xff) as u8,
+         ((value >> 56) & 0xff) as u8]
+    }
+
     #[inline(always)]
     pub fn be_u8_from_u32(value: u32) -> [u8; 4] {
         [((value >> 24) & 0xff) as u8,
@@ -63,6 +96,18 @@ pub mod slice {
          (value & 0xff) as u8]
     }
 
+    #[inline(always)]
+    pub fn be_u8_from_u64(value: u64) -> [u8; 8] {
+        [((value >> 56) & 0xff) as u8,
+         ((value >> 48) & 0xff) as u8,
+         ((value >> 40) & 0xff) as u8,
+         ((value >> 32) & 0xff) as u8,
+         ((value >> 24) & 0xff) as u8,
+         ((value >> 16) & 0xff) as u8,
+         ((value >> 8) & 0xff) as u8,
+         (value & 0xff) as u8]
+    }
+
     // https://github.com/rust-lang/rust/issues/27750
     // https://internals.rust-lang.org/t/stabilizing-basic-functions-on-arrays-and-slices/2868
     #[inline(always)]
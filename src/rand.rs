@@ -25,7 +25,10 @@
 //! (seccomp filters on Linux in particular). See `SystemRandom`'s
 //! documentation for more details.
 
-use error;
+use {core, error};
+
+#[cfg(target_os = "linux")]
+use c;
 
 
 /// A secure random number generator.
@@ -48,11 +51,15 @@ pub trait SecureRandom {
 ///
 /// On Linux, `fill()` will use the [`getrandom`] syscall. If the kernel is too
 /// old to support `getrandom` then by default `fill()` falls back to reading
-/// from `/dev/urandom`. This decision is made the first time `fill`
-/// *succeeds*. The fallback to `/dev/urandom` can be disabled by disabling the
-/// `dev_urandom_fallback` default feature; this should be done whenever the
-/// target system is known to support `getrandom`. Library crates should avoid
-/// explicitly enabling the `dev_urandom_fallback` feature.
+/// from `/dev/urandom`. This decision is made the first time `fill` is
+/// called, without waiting for the kernel's entropy pool to be initialized,
+/// and `mechanism()` reports the result. The fallback to `/dev/urandom` can be
+/// disabled by disabling the `dev_urandom_fallback` default feature; this
+/// should be done whenever the target system is known to support `getrandom`.
+/// Library crates should avoid explicitly enabling the `dev_urandom_fallback`
+/// feature. An application can also refuse the fallback at runtime, and choose
+/// not to wait for the entropy pool, by using a `PolicySystemRandom` from
+/// `SystemRandom::with_policy`.
 ///
 /// On macOS and iOS, `fill()` is implemented using `SecRandomCopyBytes`.
 ///
@@ -94,49 +101,623 @@ impl SecureRandom for SystemRandom {
     }
 }
 
+/// How a Linux `PolicySystemRandom` behaves before the kernel's entropy pool
+/// has been initialized, and on kernels that don't support `getrandom`.
+///
+/// The default policy, which `SystemRandom` follows, waits for the entropy
+/// pool and, when the `dev_urandom_fallback` feature is enabled, falls back to
+/// `/dev/urandom`.
+///
+/// # Examples
+///
+/// ```
+/// use ring::rand;
+///
+/// # fn main() {
+/// let rng = rand::SystemRandom::with_policy(rand::SystemRandomPolicy {
+///     nonblocking: true,
+///     refuse_dev_urandom_fallback: true,
+/// });
+///
+/// let mut seed = [0u8; 32];
+/// match rng.try_fill(&mut seed) {
+///     Ok(()) => (),
+///     Err(rand::FillError::NotReady) => {
+///         // Too early in boot; try again later.
+///     },
+///     Err(_) => panic!("no secure randomness available"),
+/// }
+/// # }
+/// ```
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SystemRandomPolicy {
+    /// Whether `getrandom` is called with `GRND_NONBLOCK`, so that filling
+    /// fails with `FillError::NotReady` instead of waiting for the entropy
+    /// pool to be initialized. `/dev/urandom` never blocks, so this has no
+    /// effect when `/dev/urandom` is used; set `refuse_dev_urandom_fallback`
+    /// too to avoid that.
+    pub nonblocking: bool,
+
+    /// Whether filling fails with `FillError::FallbackRefused` instead of
+    /// reading from `/dev/urandom` when the kernel doesn't support
+    /// `getrandom`. This has no effect unless the `dev_urandom_fallback`
+    /// feature is enabled.
+    pub refuse_dev_urandom_fallback: bool,
+}
+
+/// The way a Linux `SystemRandom` or `PolicySystemRandom` gets random bytes
+/// from the kernel.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mechanism {
+    /// The [`getrandom`] syscall.
+    ///
+    /// [`getrandom`]: http://man7.org/linux/man-pages/man2/getrandom.2.html
+    Sysrand,
+
+    /// Reading from `/dev/urandom`.
+    DevURandom,
+}
+
+/// The reason `PolicySystemRandom::try_fill` failed.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FillError {
+    /// The policy is nonblocking and the kernel's entropy pool hasn't been
+    /// initialized yet.
+    NotReady,
+
+    /// The policy refuses to fall back to `/dev/urandom`, and the kernel
+    /// doesn't support `getrandom`.
+    FallbackRefused,
+
+    /// Any other failure.
+    Unspecified,
+}
+
+#[cfg(target_os = "linux")]
+impl From<FillError> for error::Unspecified {
+    fn from(_: FillError) -> Self { error::Unspecified }
+}
+
+#[cfg(target_os = "linux")]
+impl SystemRandom {
+    /// Constructs a new `PolicySystemRandom` that follows `policy`.
+    #[inline]
+    pub fn with_policy(policy: SystemRandomPolicy) -> PolicySystemRandom {
+        PolicySystemRandom { policy }
+    }
+
+    /// The mechanism every `SystemRandom` and `PolicySystemRandom` in the
+    /// process uses, which is `Mechanism::DevURandom` only when the
+    /// `dev_urandom_fallback` feature is enabled and the kernel doesn't
+    /// support `getrandom`.
+    ///
+    /// The first call may make a system call, which never blocks, to find
+    /// out.
+    #[inline]
+    pub fn mechanism(&self) -> Mechanism { mechanism() }
+}
+
+/// A Linux `SystemRandom` that follows a `SystemRandomPolicy` other than the
+/// default one, and that can report why filling failed.
+///
+/// Construct one with `SystemRandom::with_policy`. Like `SystemRandom`, a
+/// `PolicySystemRandom` is cheap to construct and may be shared across
+/// threads.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug)]
+pub struct PolicySystemRandom {
+    policy: SystemRandomPolicy,
+}
+
+#[cfg(target_os = "linux")]
+impl PolicySystemRandom {
+    /// The policy this `PolicySystemRandom` follows.
+    #[inline]
+    pub fn policy(&self) -> SystemRandomPolicy { self.policy }
+
+    /// The same as `SystemRandom::mechanism`.
+    #[inline]
+    pub fn mechanism(&self) -> Mechanism { mechanism() }
+
+    /// Like `fill`, but reports why it failed.
+    pub fn try_fill(&self, dest: &mut [u8]) -> Result<(), FillError> {
+        try_fill(self.policy, mechanism(), dest, &sysrand_chunk::chunk)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl SecureRandom for PolicySystemRandom {
+    #[inline(always)]
+    fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+        self.try_fill(dest).map_err(error::Unspecified::from)
+    }
+}
+
+// `chunk` is a parameter so that the tests can simulate failures of
+// `getrandom`.
+#[cfg(target_os = "linux")]
+fn try_fill(policy: SystemRandomPolicy, mechanism: Mechanism, dest: &mut [u8],
+            chunk: &Fn(&mut [u8], c::uint) -> Result<usize, FillError>)
+            -> Result<(), FillError> {
+    let flags = if policy.nonblocking {
+        sysrand_chunk::GRND_NONBLOCK
+    } else {
+        0
+    };
+    match mechanism {
+        Mechanism::Sysrand => sysrand::fill_with_flags(dest, flags, chunk),
+        Mechanism::DevURandom if policy.refuse_dev_urandom_fallback =>
+            Err(FillError::FallbackRefused),
+        Mechanism::DevURandom => fill_dev_urandom(dest),
+    }
+}
+
+/// A secure random number generator that serves small requests from a
+/// per-thread buffer of ChaCha20 keystream, instead of asking the operating
+/// system every time.
+///
+/// Each thread has its own 256-bit key, taken from `SystemRandom`. The
+/// keystream is generated using "fast key erasure": every time the buffer is
+/// refilled, the first 32 bytes of the new keystream replace the key, which is
+/// never used again, so a later compromise of the thread's state doesn't
+/// reveal output that was already returned. Output is also erased from the
+/// buffer as soon as it is returned. Requests larger than the buffer are
+/// filled directly from the keystream, with the same key erasure.
+///
+/// The key is replaced with a new one from `SystemRandom` after every 1 MiB
+/// of output, and whenever the process has forked since the key was chosen,
+/// so that a child process created with `fork()` never repeats its parent's
+/// output. On Unix-like systems forks are counted by a handler registered
+/// with `pthread_atfork`, so checking for a fork doesn't make a system call.
+/// Processes created without going through `fork()` (e.g. with a raw `clone`
+/// system call) aren't detected. Elsewhere, or if the handler can't be
+/// registered, the process ID is checked instead. (Like other fork detection
+/// that works by checking the process ID, this can be defeated if a process
+/// forks twice and a grandchild ends up with the original process ID while
+/// the parent's buffered state is still in memory; that can't happen while
+/// the original process is still alive.)
+///
+/// Like `SystemRandom`, a `ThreadLocalRandom` is cheap to construct and may be
+/// shared across threads; all instances on the same thread share the same
+/// state. If the thread's state isn't available because the thread is being
+/// destroyed, `fill()` uses `SystemRandom` directly.
+pub struct ThreadLocalRandom;
+
+impl ThreadLocalRandom {
+    /// Constructs a new `ThreadLocalRandom`.
+    #[inline(always)]
+    pub fn new() -> ThreadLocalRandom { ThreadLocalRandom }
+}
+
+impl SecureRandom for ThreadLocalRandom {
+    fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+        thread_local! {
+            static STATE: core::cell::RefCell<buffered::State> =
+                core::cell::RefCell::new(buffered::State::new());
+        }
+
+        let process = fork::process();
+        match STATE.try_with(|state| {
+            state.borrow_mut().fill(dest, process, &SystemRandom::new())
+        }) {
+            Ok(r) => r,
+            Err(_) => fill_impl(dest),
+        }
+    }
+}
+
+mod fork {
+    use std;
+
+    /// Identifies the process, as far as fork detection is concerned: the
+    /// result in a child process created by `fork()` is different from any
+    /// result in its parent, and from any result in a process that forked
+    /// earlier.
+    #[cfg(unix)]
+    pub fn process() -> u64 {
+        use core::sync::atomic::Ordering;
+
+        REGISTER.call_once(|| {
+            let registered =
+                unsafe { pthread_atfork(None, None, Some(count_fork)) } == 0;
+            REGISTERED.store(registered, Ordering::Release);
+        });
+        if REGISTERED.load(Ordering::Acquire) {
+            GENERATION.load(Ordering::Relaxed) as u64
+        } else {
+            by_pid()
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn process() -> u64 { by_pid() }
+
+    // The high bit keeps the process IDs apart from the fork generations.
+    fn by_pid() -> u64 { (1 << 63) | u64::from(std::process::id()) }
+
+    #[cfg(unix)]
+    use c;
+
+    #[cfg(unix)]
+    use core::sync::atomic::{AtomicBool, AtomicUsize};
+
+    #[cfg(unix)]
+    static REGISTER: std::sync::Once = std::sync::Once::new();
+
+    #[cfg(unix)]
+    static REGISTERED: AtomicBool = AtomicBool::new(false);
+
+    // The number of `fork()`s between the first call to `process()` and the
+    // creation of the current process.
+    #[cfg(unix)]
+    static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+    // Runs in the child, which only has the thread that called `fork()`.
+    #[cfg(unix)]
+    unsafe extern fn count_fork() {
+        use core::sync::atomic::Ordering;
+        let _ = GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[cfg(unix)]
+    extern {
+        fn pthread_atfork(prepare: Option<unsafe extern fn()>,
+                          parent: Option<unsafe extern fn()>,
+                          child: Option<unsafe extern fn()>) -> c::int;
+    }
+
+    #[cfg(all(test, unix))]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_fork_changes_process() {
+            let before = process();
+            assert_eq!(process(), before);
+            assert!(before < (1 << 63));
+
+            // Simulate the handler running in a child process.
+            unsafe { count_fork() };
+            let after = process();
+            assert!(after != before);
+            assert_eq!(process(), after);
+        }
+    }
+}
+
+mod buffered {
+    use {chacha, error, polyfill, zeroize};
+    use super::SecureRandom;
+
+    pub struct State {
+        key: chacha::Key,
+        buf: [u8; BUF_LEN],
+
+        // The unused output is `buf[(BUF_LEN - available)..]`. Everything
+        // before it is zero.
+        available: usize,
+
+        // The `fork::process()` that seeded the state. `None` until the state
+        // has been seeded, and after a reseed has failed.
+        seeded_by_process: Option<u64>,
+
+        bytes_until_reseed: usize,
+    }
+
+    impl State {
+        pub fn new() -> State {
+            State {
+                key: [0; chacha::KEY_LEN_IN_BYTES / 4],
+                buf: [0; BUF_LEN],
+                available: 0,
+                seeded_by_process: None,
+                bytes_until_reseed: 0,
+            }
+        }
+
+        pub fn fill(&mut self, dest: &mut [u8], process: u64,
+                    rng: &SecureRandom) -> Result<(), error::Unspecified> {
+            for dest in dest.chunks_mut(MAX_CHUNK_LEN) {
+                if self.seeded_by_process != Some(process) ||
+                   self.bytes_until_reseed < dest.len() {
+                    self.reseed(process, rng)?;
+                }
+                self.bytes_until_reseed -= dest.len();
+
+                if dest.len() > MAX_BUFFERED_LEN {
+                    self.fill_directly(dest);
+                    continue;
+                }
+                if self.available < dest.len() {
+                    self.refill();
+                }
+                let start = BUF_LEN - self.available;
+                let out = &mut self.buf[start..][..dest.len()];
+                dest.copy_from_slice(out);
+                zeroize::zeroize(out);
+                self.available -= dest.len();
+            }
+            Ok(())
+        }
+
+        fn reseed(&mut self, process: u64, rng: &SecureRandom)
+                  -> Result<(), error::Unspecified> {
+            // Forget everything before asking for the new key, so nothing is
+            // reused if that fails.
+            self.erase();
+
+            let mut seed = [0u8; chacha::KEY_LEN_IN_BYTES];
+            rng.fill(&mut seed)?;
+            self.key = chacha::key_from_bytes(&seed);
+            zeroize::zeroize(&mut seed);
+            self.seeded_by_process = Some(process);
+            self.bytes_until_reseed = RESEED_INTERVAL;
+            Ok(())
+        }
+
+        fn refill(&mut self) {
+            polyfill::slice::fill(&mut self.buf, 0);
+            chacha::chacha20_xor_in_place(&self.key, &counter(0),
+                                          &mut self.buf);
+            self.rekey_from_buf();
+            self.available = MAX_BUFFERED_LEN;
+        }
+
+        // The first block of the keystream becomes the new key and the rest
+        // is written to `dest`. The buffered output, which came from the
+        // previous key, is left alone.
+        fn fill_directly(&mut self, dest: &mut [u8]) {
+            debug_assert!(dest.len() <= MAX_CHUNK_LEN);
+            let mut block = [0u8; chacha::BLOCK_LEN];
+            chacha::chacha20_xor_in_place(&self.key, &counter(0), &mut block);
+            polyfill::slice::fill(dest, 0);
+            chacha::chacha20_xor_in_place(&self.key, &counter(1), dest);
+            self.key = new_key(&block);
+            zeroize::zeroize(&mut block);
+        }
+
+        fn rekey_from_buf(&mut self) {
+            self.key = new_key(&self.buf);
+            zeroize::zeroize(&mut self.buf[..chacha::KEY_LEN_IN_BYTES]);
+        }
+
+        fn erase(&mut self) {
+            zeroize::zeroize(&mut self.key);
+            zeroize::zeroize(&mut self.buf);
+            self.available = 0;
+            self.seeded_by_process = None;
+            self.bytes_until_reseed = 0;
+        }
+    }
+
+    impl Drop for State {
+        fn drop(&mut self) { self.erase(); }
+    }
+
+    fn new_key(keystream: &[u8]) -> chacha::Key {
+        chacha::key_from_bytes(
+            slice_as_array_ref!(&keystream[..chacha::KEY_LEN_IN_BYTES],
+                                chacha::KEY_LEN_IN_BYTES).unwrap())
+    }
+
+    // Each key is used for only one keystream, so the nonce is always zero.
+    fn counter(block: u32) -> chacha::Counter {
+        chacha::make_counter(&[0u8; chacha::NONCE_LEN], block)
+    }
+
+    // 16 ChaCha20 blocks.
+    const BUF_LEN: usize = 16 * chacha::BLOCK_LEN;
+
+    pub const MAX_BUFFERED_LEN: usize = BUF_LEN - chacha::KEY_LEN_IN_BYTES;
+
+    // Large requests are split into chunks of this size, each generated with
+    // its own key.
+    const MAX_CHUNK_LEN: usize = 64 * 1024;
+
+    const RESEED_INTERVAL: usize = 1024 * 1024;
+
+    #[cfg(test)]
+    mod tests {
+        use {error, polyfill, test};
+        use core;
+        use super::*;
+
+        // Counts the calls to `fill` and optionally fails them.
+        struct CountingRandom {
+            calls: core::cell::Cell<usize>,
+            fail: bool,
+        }
+
+        impl CountingRandom {
+            fn new() -> CountingRandom {
+                CountingRandom { calls: core::cell::Cell::new(0), fail: false }
+            }
+        }
+
+        impl SecureRandom for CountingRandom {
+            fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+                self.calls.set(self.calls.get() + 1);
+                if self.fail {
+                    return Err(error::Unspecified);
+                }
+                polyfill::slice::fill(dest, 1);
+                Ok(())
+            }
+        }
+
+        #[test]
+        fn test_output_is_erased() {
+            let rng = test::rand::FixedByteRandom { byte: 1 };
+            let mut state = State::new();
+            let mut a = [0u8; 32];
+            state.fill(&mut a, 1, &rng).unwrap();
+            assert!(a.iter().any(|b| *b != 0));
+            assert!(state.buf[..(BUF_LEN - state.available)]
+                .iter().all(|b| *b == 0));
+            assert_eq!(state.available, MAX_BUFFERED_LEN - a.len());
+
+            // The next output is different, even with a constant seed.
+            let mut b = [0u8; 32];
+            state.fill(&mut b, 1, &rng).unwrap();
+            assert!(a != b);
+            assert!(state.buf[..(BUF_LEN - state.available)]
+                .iter().all(|b| *b == 0));
+        }
+
+        #[test]
+        fn test_key_is_erased() {
+            let rng = test::rand::FixedByteRandom { byte: 1 };
+            let mut state = State::new();
+            let mut a = [0u8; 1];
+            state.fill(&mut a, 1, &rng).unwrap();
+            let key = state.key;
+
+            // Refilling the buffer, or filling a large request directly,
+            // replaces the key.
+            let mut rest = [0u8; MAX_BUFFERED_LEN];
+            state.fill(&mut rest, 1, &rng).unwrap();
+            assert!(state.key != key);
+            let key = state.key;
+            let mut large = [0u8; MAX_BUFFERED_LEN + 1];
+            state.fill(&mut large, 1, &rng).unwrap();
+            assert!(state.key != key);
+        }
+
+        #[test]
+        fn test_reseed_after_fork() {
+            let rng = CountingRandom::new();
+            let mut state = State::new();
+            let mut parent = [0u8; 32];
+            state.fill(&mut parent, 1, &rng).unwrap();
+            state.fill(&mut [0u8; 32], 1, &rng).unwrap();
+            assert_eq!(rng.calls.get(), 1);
+
+            // The seed is the same, so the child starts over with the
+            // parent's first output.
+            let mut child = [0u8; 32];
+            state.fill(&mut child, 2, &rng).unwrap();
+            assert_eq!(rng.calls.get(), 2);
+            assert_eq!(child, parent);
+        }
+
+        #[test]
+        fn test_failed_reseed_erases_state() {
+            let mut rng = CountingRandom::new();
+            let mut state = State::new();
+            state.fill(&mut [0u8; 32], 1, &rng).unwrap();
+
+            rng.fail = true;
+            let mut dest = [0u8; 32];
+            assert!(state.fill(&mut dest, 2, &rng).is_err());
+            assert!(state.seeded_by_process.is_none());
+            assert_eq!(state.available, 0);
+            assert!(state.buf.iter().all(|b| *b == 0));
+
+            // The parent doesn't keep using the erased state either.
+            assert!(state.fill(&mut dest, 1, &rng).is_err());
+            assert_eq!(rng.calls.get(), 3);
+        }
+
+        #[test]
+        fn test_reseed_interval() {
+            let rng = CountingRandom::new();
+            let mut state = State::new();
+            let mut dest = vec![0u8; RESEED_INTERVAL];
+            state.fill(&mut dest, 1, &rng).unwrap();
+            assert_eq!(rng.calls.get(), 1);
+            state.fill(&mut [0u8; 1], 1, &rng).unwrap();
+            assert_eq!(rng.calls.get(), 2);
+
+            let mut dest = vec![0u8; (2 * RESEED_INTERVAL) + 1];
+            state.fill(&mut dest, 1, &rng).unwrap();
+            assert_eq!(rng.calls.get(), 4);
+        }
+    }
+}
+
 #[cfg(not(any(target_os = "linux",
               target_os = "macos",
               target_os = "ios",
               windows)))]
 use self::urandom::fill as fill_impl;
 
-#[cfg(any(all(target_os = "linux", not(feature = "dev_urandom_fallback")),
-          windows))]
+#[cfg(windows)]
 use self::sysrand::fill as fill_impl;
 
+#[cfg(target_os = "linux")]
+fn fill_impl(dest: &mut [u8]) -> Result<(), error::Unspecified> {
+    try_fill(SystemRandomPolicy::default(), mechanism(), dest,
+             &sysrand_chunk::chunk)
+        .map_err(error::Unspecified::from)
+}
+
+#[cfg(all(target_os = "linux", not(feature = "dev_urandom_fallback")))]
+#[inline(always)]
+fn mechanism() -> Mechanism { Mechanism::Sysrand }
+
+#[cfg(all(target_os = "linux", feature = "dev_urandom_fallback"))]
+use self::sysrand_or_urandom::mechanism;
+
+#[cfg(all(target_os = "linux", not(feature = "dev_urandom_fallback")))]
+fn fill_dev_urandom(_: &mut [u8]) -> Result<(), FillError> {
+    unreachable!()
+}
+
 #[cfg(all(target_os = "linux", feature = "dev_urandom_fallback"))]
-use self::sysrand_or_urandom::fill as fill_impl;
+fn fill_dev_urandom(dest: &mut [u8]) -> Result<(), FillError> {
+    urandom::fill(dest).map_err(|_| FillError::Unspecified)
+}
 
 #[cfg(any(target_os = "macos", target_os = "ios"))]
 use self::darwin::fill as fill_impl;
 
 #[cfg(target_os = "linux")]
 mod sysrand_chunk {
-    use {c, error};
+    use c;
     use libc;
+    use super::FillError;
 
+    #[cfg(not(feature = "pure_rust"))]
     extern {
         static GFp_SYS_GETRANDOM: c::long;
     }
 
+    #[cfg(feature = "pure_rust")]
+    use libc::SYS_getrandom as GFp_SYS_GETRANDOM;
+
+    // Not all versions of the `libc` crate that we support define this.
+    pub const GRND_NONBLOCK: c::uint = 0x0001;
+
     #[inline]
-    pub fn chunk(dest: &mut [u8]) -> Result<usize, error::Unspecified> {
+    pub fn chunk(dest: &mut [u8], flags: c::uint)
+                 -> Result<usize, FillError> {
         let chunk_len: c::size_t = dest.len();
-        let flags: c::uint = 0;
         let r = unsafe {
             libc::syscall(GFp_SYS_GETRANDOM, dest.as_mut_ptr(), chunk_len, flags)
         };
         if r < 0 {
-            if unsafe { *libc::__errno_location() } == libc::EINTR {
-                // If an interrupt occurs while getrandom() is blocking to wait
-                // for the entropy pool, then EINTR is returned. Returning 0
-                // will cause the caller to try again.
-                return Ok(0);
-            }
-            return Err(error::Unspecified);
+            let errno = unsafe { *libc::__errno_location() };
+            return from_errno(errno, flags);
         }
         Ok(r as usize)
     }
+
+    pub fn from_errno(errno: c::int, flags: c::uint)
+                      -> Result<usize, FillError> {
+        if errno == libc::EINTR {
+            // If an interrupt occurs while getrandom() is blocking to wait
+            // for the entropy pool, then EINTR is returned. Returning 0
+            // will cause the caller to try again.
+            return Ok(0);
+        }
+        if errno == libc::EAGAIN && (flags & GRND_NONBLOCK) != 0 {
+            return Err(FillError::NotReady);
+        }
+        Err(FillError::Unspecified)
+    }
 }
 
 #[cfg(windows)]
@@ -169,7 +750,7 @@ mod sysrand_chunk {
     }
 }
 
-#[cfg(any(target_os = "linux", windows))]
+#[cfg(windows)]
 mod sysrand {
     use error;
     use super::sysrand_chunk::chunk;
@@ -184,6 +765,24 @@ mod sysrand {
     }
 }
 
+#[cfg(target_os = "linux")]
+mod sysrand {
+    use c;
+    use super::FillError;
+
+    pub fn fill_with_flags(
+            dest: &mut [u8], flags: c::uint,
+            chunk: &Fn(&mut [u8], c::uint) -> Result<usize, FillError>)
+            -> Result<(), FillError> {
+        let mut read_len = 0;
+        while read_len < dest.len() {
+            let chunk_len = chunk(&mut dest[read_len..], flags)?;
+            read_len += chunk_len;
+        }
+        Ok(())
+    }
+}
+
 // Keep the `cfg` conditions in sync with the conditions in lib.rs.
 #[cfg(all(any(target_os = "redox", unix),
           not(any(target_os = "macos", target_os = "ios")),
@@ -217,29 +816,23 @@ mod urandom {
 // Keep the `cfg` conditions in sync with the conditions in lib.rs.
 #[cfg(all(target_os = "linux", feature = "dev_urandom_fallback"))]
 mod sysrand_or_urandom {
-    use error;
+    use super::{FillError, Mechanism};
+    use super::sysrand_chunk::{chunk, GRND_NONBLOCK};
 
-    enum Mechanism {
-        Sysrand,
-        DevURandom,
-    }
-
-    pub fn fill(dest: &mut [u8]) -> Result<(), error::Unspecified> {
+    pub fn mechanism() -> Mechanism {
         lazy_static! {
             static ref MECHANISM: Mechanism = {
+                // Don't wait for the entropy pool; all that matters is
+                // whether `getrandom` is supported.
                 let mut dummy = [0u8; 1];
-                if super::sysrand_chunk::chunk(&mut dummy[..]).is_err() {
-                    Mechanism::DevURandom
-                } else {
-                    Mechanism::Sysrand
+                match chunk(&mut dummy[..], GRND_NONBLOCK) {
+                    Ok(_) | Err(FillError::NotReady) => Mechanism::Sysrand,
+                    Err(_) => Mechanism::DevURandom,
                 }
             };
         }
 
-        match *MECHANISM {
-            Mechanism::Sysrand => super::sysrand::fill(dest),
-            Mechanism::DevURandom => super::urandom::fill(dest),
-        }
+        *MECHANISM
     }
 }
 
@@ -302,4 +895,130 @@ mod tests {
             }
         }
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_system_random_policies() {
+        // The test machine's entropy pool has been initialized long ago and
+        // its kernel supports `getrandom`.
+        let policies = [
+            rand::SystemRandomPolicy::default(),
+            rand::SystemRandomPolicy {
+                nonblocking: true,
+                refuse_dev_urandom_fallback: false,
+            },
+            rand::SystemRandomPolicy {
+                nonblocking: false,
+                refuse_dev_urandom_fallback: true,
+            },
+            rand::SystemRandomPolicy {
+                nonblocking: true,
+                refuse_dev_urandom_fallback: true,
+            },
+        ];
+        for policy in policies.iter() {
+            let rng = rand::SystemRandom::with_policy(*policy);
+            assert_eq!(rng.policy(), *policy);
+            assert_eq!(rng.mechanism(), rand::Mechanism::Sysrand);
+
+            let mut buf = [0u8; 96];
+            assert_eq!(rng.try_fill(&mut buf), Ok(()));
+            assert!(buf.iter().any(|x| *x != 0));
+            assert!(rng.fill(&mut buf).is_ok());
+        }
+
+        assert_eq!(rand::SystemRandom::new().mechanism(),
+                   rand::Mechanism::Sysrand);
+        assert_eq!(rand::SystemRandomPolicy::default(),
+                   rand::SystemRandomPolicy {
+                       nonblocking: false,
+                       refuse_dev_urandom_fallback: false,
+                   });
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_system_random_policy_errors() {
+        use c;
+        use libc;
+        use rand::{FillError, Mechanism, SystemRandomPolicy};
+        use rand::sysrand_chunk::{from_errno, GRND_NONBLOCK};
+
+        assert_eq!(from_errno(libc::EINTR, 0), Ok(0));
+        assert_eq!(from_errno(libc::EINTR, GRND_NONBLOCK), Ok(0));
+        assert_eq!(from_errno(libc::EAGAIN, GRND_NONBLOCK),
+                   Err(FillError::NotReady));
+        assert_eq!(from_errno(libc::EAGAIN, 0), Err(FillError::Unspecified));
+        assert_eq!(from_errno(libc::ENOSYS, GRND_NONBLOCK),
+                   Err(FillError::Unspecified));
+
+        // A kernel whose entropy pool hasn't been initialized yet.
+        let not_ready = |_: &mut [u8], flags: c::uint| {
+            from_errno(libc::EAGAIN, flags)
+        };
+        let blocking = SystemRandomPolicy::default();
+        let nonblocking = SystemRandomPolicy {
+            nonblocking: true,
+            refuse_dev_urandom_fallback: false,
+        };
+        let mut buf = [0u8; 32];
+        assert_eq!(rand::try_fill(nonblocking, Mechanism::Sysrand, &mut buf,
+                                  &not_ready),
+                   Err(FillError::NotReady));
+        assert_eq!(rand::try_fill(blocking, Mechanism::Sysrand, &mut buf,
+                                  &not_ready),
+                   Err(FillError::Unspecified));
+
+        // A kernel that doesn't support `getrandom`. `chunk` must not be
+        // called.
+        let unreachable =
+            |_: &mut [u8], _: c::uint| -> Result<usize, FillError> {
+                unreachable!()
+            };
+        let refuse = SystemRandomPolicy {
+            nonblocking: false,
+            refuse_dev_urandom_fallback: true,
+        };
+        assert_eq!(rand::try_fill(refuse, Mechanism::DevURandom, &mut buf,
+                                  &unreachable),
+                   Err(FillError::FallbackRefused));
+    }
+
+    #[test]
+    fn test_thread_local_random_lengths() {
+        let lengths = [0, 1, 2, 3, 32, 96, 255, 256, 257, 991, 992, 993, 4096,
+                       65536, 65537];
+
+        let rng = rand::ThreadLocalRandom::new();
+        for len in lengths.iter() {
+            let mut buf = vec![0; *len];
+            assert!(rng.fill(&mut buf).is_ok());
+            if *len >= 96 {
+                assert!(buf.iter().any(|x| *x != 0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_thread_local_random_threads() {
+        use std;
+
+        // Each thread has its own key, so threads don't repeat each other's
+        // output.
+        let rng = std::sync::Arc::new(rand::ThreadLocalRandom::new());
+        let threads = (0..4).map(|_| {
+            let rng = rng.clone();
+            std::thread::spawn(move || {
+                let mut buf = [0u8; 32];
+                rng.fill(&mut buf).unwrap();
+                buf
+            })
+        }).collect::<std::vec::Vec<_>>();
+        let mut outputs =
+            threads.into_iter().map(|t| t.join().unwrap())
+                .collect::<std::vec::Vec<_>>();
+        outputs.sort();
+        outputs.dedup();
+        assert_eq!(outputs.len(), 4);
+    }
 }
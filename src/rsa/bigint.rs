@@ -111,6 +111,15 @@ impl Positive {
 pub struct OddPositive(Positive);
 
 impl OddPositive {
+    #[cfg(test)]
+    #[inline]
+    pub fn limbs(&self) -> &[limb::Limb] { (self.0).0.limbs() }
+
+    #[cfg(all(test, feature = "rsa_signing", not(feature = "pure_rust")))]
+    pub fn erase_for_test(&mut self) -> &[limb::Limb] {
+        (self.0).0.erase_for_test()
+    }
+
     #[cfg(feature = "rsa_signing")]
     #[inline]
     pub fn verify_less_than(&self, other: &Self)
@@ -242,6 +251,15 @@ unsafe impl<M> Send for Modulus<M> {}
 unsafe impl<M> Sync for Modulus<M> {}
 
 impl<M> Modulus<M> {
+    #[cfg(test)]
+    #[inline]
+    pub fn limbs(&self) -> &[limb::Limb] { (self.value.0).0.limbs() }
+
+    #[cfg(all(test, feature = "rsa_signing", not(feature = "pure_rust")))]
+    pub fn erase_for_test(&mut self) -> &[limb::Limb] {
+        (self.value.0).0.erase_for_test()
+    }
+
     fn new(n: OddPositive) -> Result<Self, error::Unspecified> {
         // A `Modulus` must be larger than 1.
         if n.bit_length() < bits::BitLength::from_usize_bits(2) {
@@ -293,6 +311,15 @@ pub struct Elem<M, E = Unencoded> {
 }
 
 impl<M, E> Elem<M, E> {
+    #[cfg(test)]
+    #[inline]
+    pub fn limbs(&self) -> &[limb::Limb] { self.value.limbs() }
+
+    #[cfg(all(test, feature = "rsa_signing", not(feature = "pure_rust")))]
+    pub fn erase_for_test(&mut self) -> &[limb::Limb] {
+        self.value.erase_for_test()
+    }
+
     // There's no need to convert `value` to the Montgomery domain since
     // 0 * R**2 (mod m) == 0, so the modulus isn't even needed to construct a
     // zero-valued element.
@@ -585,6 +612,60 @@ impl<M, E> One<M, E> {
     }
 }
 
+// A `secure_mem::SecureBox<RSAKeyPair>` moves the limbs of every value in the
+// key pair into its locked memory.
+#[cfg(all(feature = "rsa_signing", target_os = "linux"))]
+mod relocate {
+    use error;
+    use secure_mem::{Region, Relocate};
+    use super::{Elem, Modulus, Nonnegative, OddPositive, One};
+
+    impl Relocate for Nonnegative {
+        fn locked_len(&self) -> usize { self.0.locked_len() }
+
+        fn relocate(&mut self, region: &mut Region)
+                    -> Result<(), error::Unspecified> {
+            self.0.relocate(region)
+        }
+    }
+
+    impl Relocate for OddPositive {
+        fn locked_len(&self) -> usize { (self.0).0.locked_len() }
+
+        fn relocate(&mut self, region: &mut Region)
+                    -> Result<(), error::Unspecified> {
+            (self.0).0.relocate(region)
+        }
+    }
+
+    impl<M> Relocate for Modulus<M> {
+        fn locked_len(&self) -> usize { self.value.locked_len() }
+
+        fn relocate(&mut self, region: &mut Region)
+                    -> Result<(), error::Unspecified> {
+            self.value.relocate(region)
+        }
+    }
+
+    impl<M, E> Relocate for Elem<M, E> {
+        fn locked_len(&self) -> usize { self.value.locked_len() }
+
+        fn relocate(&mut self, region: &mut Region)
+                    -> Result<(), error::Unspecified> {
+            self.value.relocate(region)
+        }
+    }
+
+    impl<M, E> Relocate for One<M, E> {
+        fn locked_len(&self) -> usize { self.0.locked_len() }
+
+        fn relocate(&mut self, region: &mut Region)
+                    -> Result<(), error::Unspecified> {
+            self.0.relocate(region)
+        }
+    }
+}
+
 
 /// An non-secret odd positive value in the range
 /// [3, 2**PUBLIC_EXPONENT_MAX_BITS).
@@ -663,14 +744,14 @@ pub fn elem_exp_consttime<M>(
 
     // XXX: On x86-64 only, `GFp_BN_mod_exp_mont_consttime` dues the conversion
     // from Montgomery form itself using a special assembly-language reduction
-    // function. This means that at this point, whether `r` is Montgomery
+    // function, except in `pure_rust` builds. This means that at this point, whether `r` is Montgomery
     // encoded, and the exact type of `R` (in particular, its `E` type
     // parameter) depends on the platform. Type inference masks this.
     //
     // TODO: Get rid of that special assembly-language reduction function if
     // practical.
 
-    #[cfg(not(target_arch = "x86_64"))]
+    #[cfg(any(not(target_arch = "x86_64"), feature = "pure_rust"))]
     let r = r.into_unencoded(m)?;
 
     Ok(r)
@@ -904,6 +985,9 @@ struct Nonnegative(BIGNUM);
 unsafe impl Send for Nonnegative {}
 
 impl Nonnegative {
+    #[cfg(all(test, feature = "rsa_signing", not(feature = "pure_rust")))]
+    fn erase_for_test(&mut self) -> &[limb::Limb] { self.0.erase_for_test() }
+
     fn zero() -> Result<Self, error::Unspecified> {
         let r = Nonnegative(BIGNUM::zero());
         debug_assert!(r.is_zero());
@@ -924,8 +1008,8 @@ impl Nonnegative {
                                 -> Result<Self, error::Unspecified> {
         let mut r = Self::zero()?;
         r.0.make_limbs(
-            ((input.len() * limb::LIMB_BYTES) + limb::LIMB_BYTES - 1) /
-                limb::LIMB_BYTES, |limbs|  {
+            (input.len() + limb::LIMB_BYTES - 1) / limb::LIMB_BYTES,
+            |limbs| {
             // Rejects empty inputs.
             limb::parse_big_endian_and_pad_consttime(input, limbs)
         })?;
@@ -1045,11 +1129,15 @@ fn n0_from_u64(n0: u64) -> N0 {
 
 // `BIGNUM` is defined in its own submodule so that its private components are
 // not accessible.
+#[cfg(all(feature = "use_heap", not(feature = "pure_rust")))]
 mod repr_c {
-    use {bssl, c, error, limb};
+    use {bssl, c, error, limb, zeroize};
     use core;
     use libc;
 
+    #[cfg(all(feature = "rsa_signing", target_os = "linux"))]
+    use secure_mem;
+
     // Keep in sync with `bignum_st` in openss/bn.h.
     #[repr(C)]
     pub struct BIGNUM {
@@ -1059,20 +1147,52 @@ mod repr_c {
         flags: c::int,
     }
 
+    // Keep in sync with openss/bn.h.
+    #[cfg(all(feature = "rsa_signing", target_os = "linux"))]
+    const BN_FLG_STATIC_DATA: c::int = 0x02;
+
     impl Drop for BIGNUM {
         fn drop(&mut self) {
             // Keep this in sync with `GFp_BN_free()`.
 
-            // In particular, this doesn't work for `BN_FLG_STATIC_DATA`.
+            // The only flag used is `BN_FLG_STATIC_DATA`, for limbs that were
+            // moved into a `secure_mem::Region`.
+            #[cfg(not(all(feature = "rsa_signing", target_os = "linux")))]
             assert_eq!(self.flags, 0);
-            unsafe {
-                let d: *mut limb::Limb = self.d;
-                libc::free(d as *mut libc::c_void)
+            #[cfg(all(feature = "rsa_signing", target_os = "linux"))]
+            assert_eq!(self.flags & !BN_FLG_STATIC_DATA, 0);
+
+            self.erase();
+            if self.owns_limbs() {
+                unsafe { libc::free(self.d as *mut libc::c_void) }
             }
         }
     }
 
     impl BIGNUM {
+        // The limbs may hold a private key component.
+        fn erase(&mut self) {
+            if !self.d.is_null() {
+                zeroize::zeroize(unsafe {
+                    core::slice::from_raw_parts_mut(self.d, self.dmax as usize)
+                });
+            }
+        }
+
+        /// Erases the limbs as `drop()` does before freeing them, and returns
+        /// all of them, so that tests can check them while they're still
+        /// allocated.
+        #[cfg(all(test, feature = "rsa_signing"))]
+        pub fn erase_for_test(&mut self) -> &[limb::Limb] {
+            self.erase();
+            if self.d.is_null() {
+                return &[];
+            }
+            unsafe {
+                core::slice::from_raw_parts(self.d, self.dmax as usize)
+            }
+        }
+
         pub fn zero() -> Self {
             BIGNUM {
                 d: core::ptr::null_mut(),
@@ -1082,6 +1202,49 @@ mod repr_c {
             }
         }
 
+        #[cfg(not(all(feature = "rsa_signing", target_os = "linux")))]
+        #[inline]
+        fn owns_limbs(&self) -> bool { true }
+
+        #[cfg(all(feature = "rsa_signing", target_os = "linux"))]
+        #[inline]
+        fn owns_limbs(&self) -> bool {
+            (self.flags & BN_FLG_STATIC_DATA) == 0
+        }
+
+        #[cfg(all(feature = "rsa_signing", target_os = "linux"))]
+        pub fn locked_len(&self) -> usize {
+            let len = (self.top as usize) * limb::LIMB_BYTES;
+            (len + secure_mem::LOCKED_ALIGN - 1) &
+                !(secure_mem::LOCKED_ALIGN - 1)
+        }
+
+        /// Moves the limbs into `region`, erasing and freeing the old ones.
+        /// Afterwards the limbs can't grow, as `GFp_bn_wexpand()` refuses to
+        /// reallocate `BN_FLG_STATIC_DATA` limbs.
+        #[cfg(all(feature = "rsa_signing", target_os = "linux"))]
+        pub fn relocate(&mut self, region: &mut secure_mem::Region)
+                        -> Result<(), error::Unspecified> {
+            let top = self.top;
+            if top == 0 {
+                return Ok(());
+            }
+            let num_limbs = top as usize;
+            let d = region.alloc(num_limbs * limb::LIMB_BYTES,
+                                 core::mem::align_of::<limb::Limb>())?
+                as *mut limb::Limb;
+            unsafe {
+                core::ptr::copy_nonoverlapping(self.d, d, num_limbs);
+            }
+            let _ = core::mem::replace(self, BIGNUM {
+                d,
+                top,
+                dmax: top,
+                flags: BN_FLG_STATIC_DATA,
+            });
+            Ok(())
+        }
+
         #[inline]
         pub fn limbs(&self) -> &[limb::Limb] {
             unsafe {
@@ -1150,8 +1313,274 @@ mod repr_c {
     }
 }
 
+#[cfg(all(feature = "use_heap", not(feature = "pure_rust")))]
 pub use self::repr_c::BIGNUM;
 
+// Without `use_heap`, and in `pure_rust` builds, `BIGNUM` is a fixed-capacity
+// value that is stored inline, e.g. on the stack, instead of in heap-allocated
+// memory. It has the same interface as `repr_c::BIGNUM` but it can only hold
+// values up to `PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN` bytes long, which is enough
+// for verification and, with `rsa_signing`, for every supported private key.
+//
+// Each value takes `PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN` bytes, so
+// `verification::verify_rsa()` needs several kilobytes of stack.
+#[cfg(any(not(feature = "use_heap"), feature = "pure_rust"))]
+mod repr_fixed {
+    use {error, limb, zeroize};
+    use super::super::PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN;
+
+    #[cfg(all(feature = "rsa_signing", target_os = "linux"))]
+    use secure_mem;
+
+    const MAX_LIMBS: usize =
+        (PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN + limb::LIMB_BYTES - 1) /
+            limb::LIMB_BYTES;
+
+    // Invariant: `d[top..]` is all zeros.
+    pub struct BIGNUM {
+        d: [limb::Limb; MAX_LIMBS],
+        top: usize,
+    }
+
+    // The limbs may hold a private key component.
+    #[cfg(feature = "rsa_signing")]
+    impl Drop for BIGNUM {
+        fn drop(&mut self) { zeroize::zeroize(&mut self.d) }
+    }
+
+    impl BIGNUM {
+        pub fn zero() -> Self {
+            BIGNUM {
+                d: [0; MAX_LIMBS],
+                top: 0,
+            }
+        }
+
+        // The limbs are stored inline, so they are moved into the `Region`
+        // along with the value that contains them.
+        #[cfg(all(feature = "rsa_signing", target_os = "linux"))]
+        pub fn locked_len(&self) -> usize { 0 }
+
+        #[cfg(all(feature = "rsa_signing", target_os = "linux"))]
+        pub fn relocate(&mut self, _region: &mut secure_mem::Region)
+                        -> Result<(), error::Unspecified> {
+            Ok(())
+        }
+
+        #[inline]
+        pub fn limbs(&self) -> &[limb::Limb] { &self.d[..self.top] }
+
+        #[inline]
+        pub fn limbs_mut(&mut self) -> &mut [limb::Limb] {
+            &mut self.d[..self.top]
+        }
+
+        #[cfg(feature = "rsa_signing")]
+        pub fn grow_by_one_bit(&mut self) -> Result<(), error::Unspecified> {
+            if self.top == MAX_LIMBS {
+                return Err(error::Unspecified);
+            }
+            self.d[self.top] = 1;
+            self.top += 1;
+            Ok(())
+        }
+
+        #[cfg(feature = "rsa_signing")]
+        pub fn shrunk_by_at_most_one_bit(&mut self) {
+            if self.limbs().last().map_or(false, |last| *last == 0) {
+                self.top -= 1;
+            }
+        }
+
+        pub fn make_limbs<F>(&mut self, num_limbs: usize, f: F)
+                             -> Result<(), error::Unspecified>
+                where F: FnOnce(&mut [limb::Limb])
+                                -> Result<(), error::Unspecified> {
+            if num_limbs > MAX_LIMBS {
+                return Err(error::Unspecified);
+            }
+
+            // Zero the limbs being removed so the invariant holds. The new
+            // upper limbs are already zero.
+            if num_limbs < self.top {
+                for limb in &mut self.d[num_limbs..self.top] {
+                    *limb = 0;
+                }
+            }
+            self.top = num_limbs;
+
+            let result = f(self.limbs_mut());
+            self.correct_top();
+            result
+        }
+
+        pub fn copy_from(&mut self, a: &BIGNUM) {
+            self.d = a.d;
+            self.top = a.top;
+        }
+
+        /// Returns `a * b * R**-1 (mod n)`, where `R` is
+        /// `2**(LIMB_BITS * n.limbs().len())`. `a` and `b` must be less than
+        /// `n`.
+        pub fn mont_mul(a: &BIGNUM, b: &BIGNUM, n: &BIGNUM, n0: limb::Limb)
+                        -> Result<BIGNUM, error::Unspecified> {
+            let num_limbs = n.top;
+            if num_limbs == 0 || a.top > num_limbs || b.top > num_limbs {
+                return Err(error::Unspecified);
+            }
+            // Thanks to the invariant, `a` and `b` are zero-padded to the
+            // length of `n`.
+            let mut r = Self::zero();
+            limb::limbs_mont_mul(&mut r.d[..num_limbs], &a.d[..num_limbs],
+                                 &b.d[..num_limbs], &n.d[..num_limbs], n0);
+            r.top = num_limbs;
+            r.correct_top();
+            Ok(r)
+        }
+
+        /// Returns `a * R**-1 (mod n)`. `a` must be less than `n * R`, so it
+        /// may be up to twice as long as `n`, as when reducing an element of
+        /// ℤ/pqℤ modulo `p`.
+        pub fn from_montgomery(a: &BIGNUM, n: &BIGNUM, n0: limb::Limb)
+                               -> Result<BIGNUM, error::Unspecified> {
+            use limb::{limb_adc, limb_mac, limb_sbb};
+
+            let num_limbs = n.top;
+            if num_limbs == 0 || a.top > 2 * num_limbs {
+                return Err(error::Unspecified);
+            }
+
+            // Montgomery reduction (REDC), one limb at a time. The carry out
+            // of the top limb is kept in `carry`.
+            let mut t = [0; 2 * MAX_LIMBS];
+            t[..a.top].copy_from_slice(a.limbs());
+            let mut carry = 0;
+            for i in 0..num_limbs {
+                let q = t[i].wrapping_mul(n0);
+                let mut c = 0;
+                for j in 0..num_limbs {
+                    let (lo, hi) = limb_mac(t[i + j], q, n.d[j], c);
+                    t[i + j] = lo;
+                    c = hi;
+                }
+                let (s, c) = limb_adc(t[i + num_limbs], c, carry);
+                t[i + num_limbs] = s;
+                carry = c;
+            }
+
+            // The result is less than `2 * n`; subtract `n` unless that would
+            // make it negative.
+            let mut r = Self::zero();
+            let mut borrow = 0;
+            for j in 0..num_limbs {
+                let (d, b) = limb_sbb(t[num_limbs + j], n.d[j], borrow);
+                r.d[j] = d;
+                borrow = b;
+            }
+            let keep = (borrow & !carry & 1).wrapping_neg();
+            limb::limbs_copy_conditional(&mut r.d[..num_limbs],
+                                         &t[num_limbs..(2 * num_limbs)], keep);
+            zeroize::zeroize(&mut t);
+            r.top = num_limbs;
+            r.correct_top();
+            Ok(r)
+        }
+
+        /// Returns `a**p (mod n)`, where `a`, `one`, and the result are
+        /// Montgomery-encoded, in time that depends only on `p_bits` and the
+        /// length of `n`. `p_bits` must be at least the bit length of `p`.
+        ///
+        /// This is a fixed-window exponentiation that reads every table
+        /// entry for every window, so the memory access pattern doesn't
+        /// depend on `p` either.
+        #[cfg(feature = "rsa_signing")]
+        pub fn mont_exp_consttime(a: &BIGNUM, p: &BIGNUM, p_bits: usize,
+                                  one: &BIGNUM, n: &BIGNUM, n0: limb::Limb)
+                                  -> Result<BIGNUM, error::Unspecified> {
+            use limb::{LIMB_BITS, limb_is_zero, limbs_copy_conditional,
+                       limbs_mont_mul};
+
+            const WINDOW_BITS: usize = 5;
+            const TABLE_LEN: usize = 1 << WINDOW_BITS;
+
+            let num_limbs = n.top;
+            if num_limbs == 0 || a.top > num_limbs || one.top > num_limbs ||
+               p.top > MAX_LIMBS || p_bits > MAX_LIMBS * LIMB_BITS {
+                return Err(error::Unspecified);
+            }
+            let n = &n.d[..num_limbs];
+
+            // `table[i]` is `a**i`.
+            let mut table = vec![0; TABLE_LEN * num_limbs];
+            table[..num_limbs].copy_from_slice(&one.d[..num_limbs]);
+            table[num_limbs..(2 * num_limbs)]
+                .copy_from_slice(&a.d[..num_limbs]);
+            for i in 2..TABLE_LEN {
+                let (previous, entry) = table.split_at_mut(i * num_limbs);
+                limbs_mont_mul(&mut entry[..num_limbs],
+                               &previous[((i - 1) * num_limbs)..],
+                               &a.d[..num_limbs], n, n0);
+            }
+
+            let mut acc = [0; MAX_LIMBS];
+            let mut tmp = [0; MAX_LIMBS];
+            let mut entry = [0; MAX_LIMBS];
+            acc[..num_limbs].copy_from_slice(&one.d[..num_limbs]);
+
+            let num_windows = (p_bits + WINDOW_BITS - 1) / WINDOW_BITS;
+            for window in (0..num_windows).rev() {
+                for _ in 0..WINDOW_BITS {
+                    tmp[..num_limbs].copy_from_slice(&acc[..num_limbs]);
+                    limbs_mont_mul(&mut acc[..num_limbs], &tmp[..num_limbs],
+                                   &tmp[..num_limbs], n, n0);
+                }
+
+                // The limbs of `p` above `p.top` are zero.
+                let bit = window * WINDOW_BITS;
+                let limb_index = bit / LIMB_BITS;
+                let shift = bit % LIMB_BITS;
+                let mut index = p.d[limb_index] >> shift;
+                if shift + WINDOW_BITS > LIMB_BITS &&
+                   limb_index + 1 < MAX_LIMBS {
+                    index |= p.d[limb_index + 1] << (LIMB_BITS - shift);
+                }
+                let index = index & ((TABLE_LEN - 1) as limb::Limb);
+
+                for (i, candidate) in table.chunks(num_limbs).enumerate() {
+                    let mask = limb_is_zero((i as limb::Limb) ^ index);
+                    limbs_copy_conditional(&mut entry[..num_limbs],
+                                           candidate, mask);
+                }
+                tmp[..num_limbs].copy_from_slice(&acc[..num_limbs]);
+                limbs_mont_mul(&mut acc[..num_limbs], &tmp[..num_limbs],
+                               &entry[..num_limbs], n, n0);
+            }
+
+            zeroize::zeroize(&mut table);
+            zeroize::zeroize(&mut tmp);
+            zeroize::zeroize(&mut entry);
+
+            let mut r = BIGNUM {
+                d: acc,
+                top: num_limbs,
+            };
+            zeroize::zeroize(&mut acc);
+            r.correct_top();
+            Ok(r)
+        }
+
+        fn correct_top(&mut self) {
+            while self.top > 0 && self.d[self.top - 1] == 0 {
+                self.top -= 1;
+            }
+        }
+    }
+}
+
+#[cfg(any(not(feature = "use_heap"), feature = "pure_rust"))]
+pub use self::repr_fixed::BIGNUM;
+
+#[cfg(all(feature = "use_heap", not(feature = "pure_rust")))]
 extern {
     // `r` and/or 'a' and/or 'b' may alias.
     fn GFp_BN_mod_mul_mont(r: *mut BIGNUM, a: *const BIGNUM, b: *const BIGNUM,
@@ -1168,7 +1597,92 @@ extern {
                      m: *const limb::Limb, num_limbs: c::size_t);
 }
 
-#[cfg(feature = "rsa_signing")]
+// Rust versions of the above functions for `repr_fixed::BIGNUM`. They have the
+// same signatures as the C functions so that the callers don't need to care
+// which ones are used.
+
+#[cfg(any(not(feature = "use_heap"), feature = "pure_rust"))]
+#[allow(non_snake_case)]
+unsafe fn GFp_BN_mod_mul_mont(r: *mut BIGNUM, a: *const BIGNUM,
+                              b: *const BIGNUM, n: &BIGNUM, n0: &N0)
+                              -> c::int {
+    match BIGNUM::mont_mul(&*a, &*b, n, n0[0]) {
+        Ok(product) => {
+            *r = product;
+            1
+        },
+        Err(_) => 0,
+    }
+}
+
+#[cfg(any(not(feature = "use_heap"), feature = "pure_rust"))]
+#[allow(non_snake_case)]
+unsafe fn GFp_BN_copy(a: &mut BIGNUM, b: &BIGNUM) -> c::int {
+    a.copy_from(b);
+    1
+}
+
+#[cfg(any(not(feature = "use_heap"), feature = "pure_rust"))]
+#[allow(non_snake_case)]
+unsafe fn GFp_BN_from_montgomery_word(r: &mut BIGNUM, a: &mut BIGNUM,
+                                      n: &BIGNUM, n0: &N0) -> c::int {
+    match BIGNUM::from_montgomery(a, n, n0[0]) {
+        Ok(value) => {
+            *r = value;
+            1
+        },
+        Err(_) => 0,
+    }
+}
+
+// Returns `-n**-1 (mod 2**64)` for odd `n`.
+#[cfg(any(not(feature = "use_heap"), feature = "pure_rust"))]
+#[allow(non_snake_case)]
+unsafe fn GFp_bn_neg_inv_mod_r_u64(n: u64) -> u64 {
+    // Newton's method: `n` is its own inverse modulo 2**3, and each step
+    // doubles the number of correct low bits, so five steps give 96 bits.
+    debug_assert_eq!(n & 1, 1);
+    let mut inv = n;
+    for _ in 0..5 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(n.wrapping_mul(inv)));
+    }
+    inv.wrapping_neg()
+}
+
+// `r = 2 * a (mod m)` in constant time. `r` and `a` may alias, and `a` must be
+// less than `m`.
+#[cfg(any(not(feature = "use_heap"), feature = "pure_rust"))]
+#[allow(non_snake_case)]
+unsafe fn LIMBS_shl_mod(r: *mut limb::Limb, a: *const limb::Limb,
+                        m: *const limb::Limb, num_limbs: c::size_t) {
+    use limb::{LIMB_BITS, limb_sbb};
+
+    let mut carry = 0;
+    for i in 0..num_limbs {
+        let a_i = *a.offset(i as isize);
+        *r.offset(i as isize) = (a_i << 1) | carry;
+        carry = a_i >> (LIMB_BITS - 1);
+    }
+
+    let r = core::slice::from_raw_parts_mut(r, num_limbs);
+    let m = core::slice::from_raw_parts(m, num_limbs);
+
+    // Subtract `m` if the doubled value, including `carry`, is at least `m`.
+    let mut borrow = 0;
+    for (r, m) in r.iter().zip(m) {
+        let (_, b) = limb_sbb(*r, *m, borrow);
+        borrow = b;
+    }
+    let mask = (carry | (borrow ^ 1)).wrapping_neg();
+    let mut borrow = 0;
+    for (r, m) in r.iter_mut().zip(m) {
+        let (d, b) = limb_sbb(*r, *m & mask, borrow);
+        *r = d;
+        borrow = b;
+    }
+}
+
+#[cfg(all(feature = "rsa_signing", not(feature = "pure_rust")))]
 extern {
     // `r` and `a` may alias.
     fn GFp_BN_mod_exp_mont_consttime(r: *mut BIGNUM, a_mont: *const BIGNUM,
@@ -1190,6 +1704,112 @@ extern {
                         num_limbs: c::size_t);
 }
 
+// Rust versions of the signing functions above for `pure_rust` builds, which
+// use `repr_fixed::BIGNUM` even with `use_heap`.
+
+#[cfg(all(feature = "rsa_signing", feature = "pure_rust"))]
+#[allow(non_snake_case)]
+unsafe fn GFp_BN_mod_exp_mont_consttime(r: *mut BIGNUM, a_mont: *const BIGNUM,
+                                        p: &BIGNUM, p_bits: c::size_t,
+                                        one_mont: &BIGNUM, n: &BIGNUM,
+                                        n0: &N0) -> c::int {
+    match BIGNUM::mont_exp_consttime(&*a_mont, p, p_bits, one_mont, n, n0[0]) {
+        Ok(value) => {
+            *r = value;
+            1
+        },
+        Err(_) => 0,
+    }
+}
+
+// `r = a + b (mod m)` in constant time. `r` and `a` may alias, and `a` and `b`
+// must be less than `m`.
+#[cfg(all(feature = "rsa_signing", feature = "pure_rust"))]
+#[allow(non_snake_case)]
+unsafe fn LIMBS_add_mod(r: *mut limb::Limb, a: *const limb::Limb,
+                        b: *const limb::Limb, m: *const limb::Limb,
+                        num_limbs: c::size_t) {
+    use limb::{limb_adc, limb_sbb, limbs_less_than};
+
+    let mut carry = 0;
+    for i in 0..num_limbs {
+        let (s, c) = limb_adc(*a.offset(i as isize), *b.offset(i as isize),
+                              carry);
+        *r.offset(i as isize) = s;
+        carry = c;
+    }
+
+    let r = core::slice::from_raw_parts_mut(r, num_limbs);
+    let m = core::slice::from_raw_parts(m, num_limbs);
+    let mask = carry.wrapping_neg() | !limbs_less_than(r, m);
+    let mut borrow = 0;
+    for (r, m) in r.iter_mut().zip(m) {
+        let (d, b) = limb_sbb(*r, *m & mask, borrow);
+        *r = d;
+        borrow = b;
+    }
+}
+
+// `r -= a (mod m)`, where `a` has `a_limbs <= num_limbs` limbs. Like the C
+// version, this is not constant-time.
+#[cfg(all(feature = "rsa_signing", feature = "pure_rust"))]
+#[allow(non_snake_case)]
+unsafe fn LIMBS_sub_mod_ex(r: *mut limb::Limb, a: *const limb::Limb,
+                           m: *const limb::Limb, num_limbs: c::size_t,
+                           a_limbs: c::size_t) {
+    use limb::{limb_adc, limb_sbb};
+
+    debug_assert!(a_limbs <= num_limbs);
+    let r = core::slice::from_raw_parts_mut(r, num_limbs);
+    let a = core::slice::from_raw_parts(a, a_limbs);
+    let m = core::slice::from_raw_parts(m, num_limbs);
+
+    let mut borrow = 0;
+    for i in 0..num_limbs {
+        let (d, b) = limb_sbb(r[i], *a.get(i).unwrap_or(&0), borrow);
+        r[i] = d;
+        borrow = b;
+    }
+    let mask = borrow.wrapping_neg();
+    let mut carry = 0;
+    for (r, m) in r.iter_mut().zip(m) {
+        let (s, c) = limb_adc(*r, *m & mask, carry);
+        *r = s;
+        carry = c;
+    }
+}
+
+// `r += a`, returning the carry.
+#[cfg(all(feature = "rsa_signing", feature = "pure_rust"))]
+#[allow(non_snake_case)]
+unsafe fn LIMBS_add_assign(r: *mut limb::Limb, a: *const limb::Limb,
+                           num_limbs: c::size_t) -> limb::Limb {
+    let r = core::slice::from_raw_parts_mut(r, num_limbs);
+    let a = core::slice::from_raw_parts(a, num_limbs);
+    let mut carry = 0;
+    for (r, a) in r.iter_mut().zip(a) {
+        let (s, c) = limb::limb_adc(*r, *a, carry);
+        *r = s;
+        carry = c;
+    }
+    carry
+}
+
+// `r -= a`, ignoring the final borrow as the C version does.
+#[cfg(all(feature = "rsa_signing", feature = "pure_rust"))]
+#[allow(non_snake_case)]
+unsafe fn LIMBS_sub_assign(r: *mut limb::Limb, a: *const limb::Limb,
+                           num_limbs: c::size_t) {
+    let r = core::slice::from_raw_parts_mut(r, num_limbs);
+    let a = core::slice::from_raw_parts(a, num_limbs);
+    let mut borrow = 0;
+    for (r, a) in r.iter_mut().zip(a) {
+        let (d, b) = limb::limb_sbb(*r, *a, borrow);
+        *r = d;
+        borrow = b;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1228,6 +1848,21 @@ mod tests {
         assert!(x.into_odd_positive().is_err());
     }
 
+    #[cfg(any(not(feature = "use_heap"), feature = "pure_rust"))]
+    #[test]
+    fn test_positive_integer_from_be_bytes_capacity() {
+        use super::super::PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN;
+
+        // Values up to the capacity of the fixed-size `BIGNUM` are accepted.
+        let mut bytes = [0xffu8; PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN + 1];
+        assert!(Positive::from_be_bytes(untrusted::Input::from(
+                    &bytes[..PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN])).is_ok());
+        // Larger values are rejected.
+        bytes[0] = 1;
+        assert!(Positive::from_be_bytes(
+                    untrusted::Input::from(&bytes[..])).is_err());
+    }
+
 
     // Type-level representation of an arbitrary modulus.
     struct M {}
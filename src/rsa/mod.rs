@@ -91,7 +91,7 @@ fn check_public_modulus_and_exponent(
         n: bigint::Positive, e: bigint::Positive, n_min_bits: bits::BitLength,
         n_max_bits: bits::BitLength, e_min_bits: bits::BitLength)
         -> Result<(bigint::OddPositive, bigint::PublicExponent),
-                  error::Unspecified> {
+                  error::KeyRejected> {
     // This is an incomplete implementation of NIST SP800-56Br1 Section
     // 6.4.2.2, "Partial Public-Key Validation for RSA." That spec defers to
     // NIST SP800-89 Section 5.3.3, "(Explicit) Partial Public Key Validation
@@ -102,8 +102,10 @@ fn check_public_modulus_and_exponent(
     // keys.
 
     // Step 3 / Step c (out of order).
-    let n = n.into_odd_positive()?;
-    let e = e.into_odd_positive()?;
+    let n = n.into_odd_positive()
+        .map_err(|_| error::KeyRejected::invalid_encoding())?;
+    let e = e.into_odd_positive()
+        .map_err(|_| error::KeyRejected::invalid_encoding())?;
 
     // `pkcs1_encode` depends on this not being small. Otherwise,
     // `pkcs1_encode` would generate padding that is invalid (too few 0xFF
@@ -116,12 +118,13 @@ fn check_public_modulus_and_exponent(
     assert!(n_min_bits >= N_MIN_BITS);
     let n_bits = n.bit_length();
     let n_bits_rounded_up =
-        bits::BitLength::from_usize_bytes(n_bits.as_usize_bytes_rounded_up())?;
+        bits::BitLength::from_usize_bytes(n_bits.as_usize_bytes_rounded_up())
+            .map_err(|_| error::KeyRejected::unexpected_error())?;
     if n_bits_rounded_up < n_min_bits {
-        return Err(error::Unspecified);
+        return Err(error::KeyRejected::too_small());
     }
     if n_bits > n_max_bits {
-        return Err(error::Unspecified);
+        return Err(error::KeyRejected::too_large());
     }
 
     // Step 2 / Step b. NIST SP800-89 defers to FIPS 186-3, which requires
@@ -131,11 +134,12 @@ fn check_public_modulus_and_exponent(
     debug_assert!(e_min_bits >= bits::BitLength::from_usize_bits(2));
     let e_bits = e.bit_length();
     if e_bits < e_min_bits {
-        return Err(error::Unspecified);
+        return Err(error::KeyRejected::too_small());
     }
 
     // Only small public exponents are supported.
-    let e = e.into_public_exponent()?;
+    let e = e.into_public_exponent()
+        .map_err(|_| error::KeyRejected::too_large())?;
 
     // If `n` is less than `e` then somebody has probably accidentally swapped
     // them. The largest acceptable `e` is smaller than the smallest acceptable
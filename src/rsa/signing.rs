@@ -17,10 +17,16 @@
 use {bits, der, digest, error, pkcs8};
 use rand;
 use std;
+
+#[cfg(target_os = "linux")]
+use {private, secure_mem};
 use super::{blinding, bigint, N};
 use arithmetic::montgomery::{R, RR, RRR};
 use untrusted;
 
+#[cfg(test)]
+use limb;
+
 /// An RSA key pair, used for signing. Feature: `rsa_signing`.
 ///
 /// After constructing an `RSAKeyPair`, construct one or more
@@ -139,7 +145,7 @@ impl RSAKeyPair {
     ///     https://tools.ietf.org/html/rfc5958
     ///
     pub fn from_pkcs8(input: untrusted::Input)
-                      -> Result<RSAKeyPair, error::Unspecified> {
+                      -> Result<RSAKeyPair, error::KeyRejected> {
         const RSA_ENCRYPTION: &'static [u8] =
             include_bytes!("../data/alg-rsa-encryption.der");
         let (der, _) = pkcs8::unwrap_key_(&RSA_ENCRYPTION,
@@ -163,218 +169,238 @@ impl RSAKeyPair {
     /// [NIST SP-800-56B rev. 1]:
     ///     http://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-56Br1.pdf
     pub fn from_der(input: untrusted::Input)
-                    -> Result<RSAKeyPair, error::Unspecified> {
-        input.read_all(error::Unspecified, |input| {
-            der::nested(input, der::Tag::Sequence, error::Unspecified, |input| {
-                let version = der::small_nonnegative_integer(input)?;
+                    -> Result<RSAKeyPair, error::KeyRejected> {
+        input.read_all(error::KeyRejected::invalid_encoding(), |input| {
+            der::nested(input, der::Tag::Sequence,
+                        error::KeyRejected::invalid_encoding(), |input| {
+                let version = der::small_nonnegative_integer(input)
+                    .map_err(|error::Unspecified| {
+                        error::KeyRejected::invalid_encoding()
+                    })?;
                 if version != 0 {
-                    return Err(error::Unspecified);
+                    return Err(error::KeyRejected::invalid_encoding());
                 }
-                let n = bigint::Positive::from_der(input)?;
-                let e = bigint::Positive::from_der(input)?;
-                let d = bigint::Positive::from_der(input)?;
-                let p = bigint::Positive::from_der(input)?;
-                let q = bigint::Positive::from_der(input)?;
-                let dP = bigint::Positive::from_der(input)?;
-                let dQ = bigint::Positive::from_der(input)?;
-                let qInv = bigint::Positive::from_der(input)?;
-
-                let n_bits = n.bit_length();
-
-                // XXX: Some steps are done out of order, but the NIST steps
-                // are worded in such a way that it is clear that NIST intends
-                // for them to be done in order. TODO: Does this matter at all?
-
-                // 6.4.1.4.3/6.4.1.2.1 - Step 1.
-
-                // Step 1.a is omitted, as explained above.
-
-                // Step 1.b is omitted per above. Instead, we chek that the
-                // public modulus is 2048 to
-                // `PRIVATE_KEY_PUBLIC_MODULUS_MAX_BITS` bits. XXX: The maximum
-                // limit of 4096 bits is primarily due to lack of testing of
-                // larger key sizes; see, in particular,
-                // https://www.mail-archive.com/openssl-dev@openssl.org/msg44586.html
-                // and
-                // https://www.mail-archive.com/openssl-dev@openssl.org/msg44759.html.
-                // Also, this limit might help with memory management decisions
-                // later.
-
-                // Step 1.c. We validate e >= 2**16 = 65536, which, since e is odd,
-                // implies e >= 65537.
-                let (n, e) = super::check_public_modulus_and_exponent(
-                    n, e, bits::BitLength::from_usize_bits(2048),
-                    super::PRIVATE_KEY_PUBLIC_MODULUS_MAX_BITS,
-                    bits::BitLength::from_usize_bits(17))?;
-
-                // 6.4.1.4.3 says to skip 6.4.1.2.1 Step 2.
-
-                // 6.4.1.4.3 Step 3.
-
-                // Step 3.a is done below, out of order.
-                // Step 3.b is unneeded since `n_bits` is derived here from `n`.
-
-                // 6.4.1.4.3 says to skip 6.4.1.2.1 Step 4. (We don't need to
-                // recover the prime factors since they are already given.)
-
-                // 6.4.1.4.3 - Step 5.
-
-                // Steps 5.a and 5.b are omitted, as explained above.
-
-                // Step 5.c.
-                //
-                // TODO: First, stop if `p < (√2) * 2**((nBits/2) - 1)`.
-                //
-                // Second, stop if `p > 2**(nBits/2) - 1`.
-                let half_n_bits = n_bits.half_rounded_up();
-                if p.bit_length() != half_n_bits {
-                    return Err(error::Unspecified);
-                }
-                let p = p.into_odd_positive()?;
+                Self::from_der_components(input)
+            })
+        })
+    }
 
-                // TODO: Step 5.d: Verify GCD(p - 1, e) == 1.
+    fn from_der_components(input: &mut untrusted::Reader)
+                           -> Result<RSAKeyPair, error::KeyRejected> {
+        let n = positive_integer(input)?;
+        let e = positive_integer(input)?;
+        let d = positive_integer(input)?;
+        let p = positive_integer(input)?;
+        let q = positive_integer(input)?;
+        let dP = positive_integer(input)?;
+        let dQ = positive_integer(input)?;
+        let qInv = positive_integer(input)?;
 
-                // Steps 5.e and 5.f are omitted as explained above.
+        let n_bits = n.bit_length();
 
-                // Step 5.g.
-                //
-                // TODO: First, stop if `q < (√2) * 2**((nBits/2) - 1)`.
-                //
-                // Second, stop if `q > 2**(nBits/2) - 1`.
-                if p.bit_length() != q.bit_length() {
-                    return Err(error::Unspecified);
-                }
-                let q = q.into_odd_positive()?;
-
-                // TODO: Step 5.h: Verify GCD(p - 1, e) == 1.
-
-                let n = n.into_modulus::<N>()?;
-                let oneRR_mod_n = bigint::One::newRR(&n)?;
-
-                let q_mod_n_decoded = q.try_clone()?.into_elem(&n)?;
-
-                // Step 5.i
-                //
-                // XXX: |p < q| is actually OK, it seems, but our implementation
-                // of CRT-based moduluar exponentiation used requires that
-                // |q > p|. (|p == q| is just wrong.)
-                //
-                // Also, because we just check the bit length of p - q, we
-                // accept if the difference is exactly 2**(n_bits/2 - 100), even
-                // though the spec says that is the largest value that should be
-                // rejected. We assume there are no security implications to
-                // this simplification.
-                //
-                // 3.b is unneeded since `n_bits` is derived here from `n`.
-                q.verify_less_than(&p)?;
-                {
-                    let p_mod_n = {
-                        let p = p.try_clone()?;
-                        p.into_elem(&n)?
-                    };
-                    let p_minus_q_bits = {
-                        // Modular subtraction isn't necessary since we already
-                        // verified q < p, but we're doing modular subtraction
-                        // to avoid having to implement non-modular subtraction.
-                        // Modular subtraction without having already verified
-                        // q < p would be wrong.
-                        let p_minus_q =
-                            bigint::elem_sub(p_mod_n, &q_mod_n_decoded, &n)?;
-                        p_minus_q.bit_length()
-                    };
-                    let min_pq_bitlen_diff = half_n_bits.try_sub(
-                            bits::BitLength::from_usize_bits(100))?;
-                    if p_minus_q_bits <= min_pq_bitlen_diff {
-                        return Err(error::Unspecified);
-                    }
-                }
+        // XXX: Some steps are done out of order, but the NIST steps
+        // are worded in such a way that it is clear that NIST intends
+        // for them to be done in order. TODO: Does this matter at all?
 
-                // 6.4.1.4.3 - Step 3.a (out of order).
-                //
-                // Verify that p * q == n. We restrict ourselves to modular
-                // multiplication. We rely on the fact that we've verified
-                // 0 < q < p < n. We check that q and p are close to sqrt(n)
-                // and then assume that these preconditions are enough to
-                // let us assume that checking p * q == 0 (mod n) is equivalent
-                // to checking p * q == n.
-                let q_mod_n = {
-                    let clone = q_mod_n_decoded.try_clone()?;
-                    bigint::elem_mul(oneRR_mod_n.as_ref(), clone, &n)?
-                };
-                let p_mod_n = p.try_clone()?.into_elem(&n)?;
-                let pq_mod_n = bigint::elem_mul(&q_mod_n, p_mod_n, &n)?;
-                if !pq_mod_n.is_zero() {
-                    return Err(error::Unspecified);
-                }
+        // 6.4.1.4.3/6.4.1.2.1 - Step 1.
 
-                // 6.4.1.4.3/6.4.1.2.1 - Step 6.
+        // Step 1.a is omitted, as explained above.
 
-                // Step 6.a, partial.
-                //
-                // First, validate `2**half_n_bits < d`. Since 2**half_n_bits
-                // has a bit length of half_n_bits + 1, this check gives us
-                // 2**half_n_bits <= d, and knowing d is odd makes the
-                // inequality strict.
-                if !(half_n_bits < d.bit_length()) {
-                    return Err(error::Unspecified);
-                }
-                // XXX: This check should be `d < LCM(p - 1, q - 1)`, but we
-                // don't have a good way of calculating LCM, so it is omitted,
-                // as explained above.
-                let d = d.into_odd_positive()?;
-                d.verify_less_than(&n.value())?;
-
-                // Step 6.b is omitted as explained above.
-
-                // 6.4.1.4.3 - Step 7.
-
-                // Step 7.a.
-                //
-                // We need to prove that `dP < p - 1`. If we verify
-                // `dP < p` then we'll know that either `dP == p - 1` or
-                // `dP < p - 1`. Since `p` is odd, `p - 1` is even. `d` is odd,
-                // and an odd number modulo an even number is odd.
-                // Therefore `dP` must be odd. But then it cannot be `p - 1`
-                // and so we know `dP < p - 1`.
-                let p = PrivatePrime::new(p, dP)?;
-
-                // Step 7.b is done out-of-order below.
-
-                // Step 7.c.
-                let qInv = qInv.into_elem(&p.modulus)?;
-
-                // Steps 7.d and 7.e are omitted per the documentation above,
-                // and because we don't (in the long term) have a good way to
-                // do modulo with an even modulus.
-
-                // Step 7.f.
-                let qInv =
-                    bigint::elem_mul(p.oneRR.as_ref(), qInv, &p.modulus)?;
-                let q_mod_p = q.try_clone()?.into_elem(&p.modulus)?;
-                let qInv_times_q_mod_p =
-                    bigint::elem_mul(&qInv, q_mod_p, &p.modulus)?;
-                if !qInv_times_q_mod_p.is_one() {
-                    return Err(error::Unspecified);
-                }
+        // Step 1.b is omitted per above. Instead, we chek that the
+        // public modulus is 2048 to
+        // `PRIVATE_KEY_PUBLIC_MODULUS_MAX_BITS` bits. XXX: The maximum
+        // limit of 4096 bits is primarily due to lack of testing of
+        // larger key sizes; see, in particular,
+        // https://www.mail-archive.com/openssl-dev@openssl.org/msg44586.html
+        // and
+        // https://www.mail-archive.com/openssl-dev@openssl.org/msg44759.html.
+        // Also, this limit might help with memory management decisions
+        // later.
 
-                // Step 7.b (out of order). Same proof as for `dP < p - 1`.
-                let q = PrivatePrime::new(q, dQ)?;
-
-                let qq = bigint::elem_mul(&q_mod_n, q_mod_n_decoded, &n)?
-                    .into_modulus::<QQ>()?;
-
-                Ok(RSAKeyPair {
-                    n,
-                    e,
-                    p,
-                    q,
-                    qInv,
-                    oneRR_mod_n,
-                    q_mod_n,
-                    qq,
-                    n_bits
-                })
-            })
+        // Step 1.c. We validate e >= 2**16 = 65536, which, since e is odd,
+        // implies e >= 65537.
+        let (n, e) = super::check_public_modulus_and_exponent(
+            n, e, bits::BitLength::from_usize_bits(2048),
+            super::PRIVATE_KEY_PUBLIC_MODULUS_MAX_BITS,
+            bits::BitLength::from_usize_bits(17))?;
+
+        // 6.4.1.4.3 says to skip 6.4.1.2.1 Step 2.
+
+        // 6.4.1.4.3 Step 3.
+
+        // Step 3.a is done below, out of order.
+        // Step 3.b is unneeded since `n_bits` is derived here from `n`.
+
+        // 6.4.1.4.3 says to skip 6.4.1.2.1 Step 4. (We don't need to
+        // recover the prime factors since they are already given.)
+
+        // 6.4.1.4.3 - Step 5.
+
+        // Steps 5.a and 5.b are omitted, as explained above.
+
+        // Step 5.c.
+        //
+        // TODO: First, stop if `p < (√2) * 2**((nBits/2) - 1)`.
+        //
+        // Second, stop if `p > 2**(nBits/2) - 1`.
+        let half_n_bits = n_bits.half_rounded_up();
+        if p.bit_length() != half_n_bits {
+            return Err(error::KeyRejected::inconsistent_components());
+        }
+        let p = p.into_odd_positive().map_err(inconsistent)?;
+
+        // TODO: Step 5.d: Verify GCD(p - 1, e) == 1.
+
+        // Steps 5.e and 5.f are omitted as explained above.
+
+        // Step 5.g.
+        //
+        // TODO: First, stop if `q < (√2) * 2**((nBits/2) - 1)`.
+        //
+        // Second, stop if `q > 2**(nBits/2) - 1`.
+        if p.bit_length() != q.bit_length() {
+            return Err(error::KeyRejected::inconsistent_components());
+        }
+        let q = q.into_odd_positive().map_err(inconsistent)?;
+
+        // TODO: Step 5.h: Verify GCD(p - 1, e) == 1.
+
+        let n = n.into_modulus::<N>().map_err(unexpected)?;
+        let oneRR_mod_n = bigint::One::newRR(&n).map_err(unexpected)?;
+
+        let q_mod_n_decoded = q.try_clone().map_err(unexpected)?
+            .into_elem(&n).map_err(inconsistent)?;
+
+        // Step 5.i
+        //
+        // XXX: |p < q| is actually OK, it seems, but our implementation
+        // of CRT-based moduluar exponentiation used requires that
+        // |q > p|. (|p == q| is just wrong.)
+        //
+        // Also, because we just check the bit length of p - q, we
+        // accept if the difference is exactly 2**(n_bits/2 - 100), even
+        // though the spec says that is the largest value that should be
+        // rejected. We assume there are no security implications to
+        // this simplification.
+        //
+        // 3.b is unneeded since `n_bits` is derived here from `n`.
+        q.verify_less_than(&p).map_err(inconsistent)?;
+        {
+            let p_mod_n = {
+                let p = p.try_clone().map_err(unexpected)?;
+                p.into_elem(&n).map_err(inconsistent)?
+            };
+            let p_minus_q_bits = {
+                // Modular subtraction isn't necessary since we already
+                // verified q < p, but we're doing modular subtraction
+                // to avoid having to implement non-modular subtraction.
+                // Modular subtraction without having already verified
+                // q < p would be wrong.
+                let p_minus_q =
+                    bigint::elem_sub(p_mod_n, &q_mod_n_decoded, &n)
+                        .map_err(unexpected)?;
+                p_minus_q.bit_length()
+            };
+            let min_pq_bitlen_diff = half_n_bits.try_sub(
+                    bits::BitLength::from_usize_bits(100))
+                .map_err(unexpected)?;
+            if p_minus_q_bits <= min_pq_bitlen_diff {
+                return Err(
+                    error::KeyRejected::inconsistent_components());
+            }
+        }
+
+        // 6.4.1.4.3 - Step 3.a (out of order).
+        //
+        // Verify that p * q == n. We restrict ourselves to modular
+        // multiplication. We rely on the fact that we've verified
+        // 0 < q < p < n. We check that q and p are close to sqrt(n)
+        // and then assume that these preconditions are enough to
+        // let us assume that checking p * q == 0 (mod n) is equivalent
+        // to checking p * q == n.
+        let q_mod_n = {
+            let clone = q_mod_n_decoded.try_clone().map_err(unexpected)?;
+            bigint::elem_mul(oneRR_mod_n.as_ref(), clone, &n)
+                .map_err(unexpected)?
+        };
+        let p_mod_n = p.try_clone().map_err(unexpected)?
+            .into_elem(&n).map_err(inconsistent)?;
+        let pq_mod_n = bigint::elem_mul(&q_mod_n, p_mod_n, &n)
+            .map_err(unexpected)?;
+        if !pq_mod_n.is_zero() {
+            return Err(error::KeyRejected::inconsistent_components());
+        }
+
+        // 6.4.1.4.3/6.4.1.2.1 - Step 6.
+
+        // Step 6.a, partial.
+        //
+        // First, validate `2**half_n_bits < d`. Since 2**half_n_bits
+        // has a bit length of half_n_bits + 1, this check gives us
+        // 2**half_n_bits <= d, and knowing d is odd makes the
+        // inequality strict.
+        if !(half_n_bits < d.bit_length()) {
+            return Err(error::KeyRejected::inconsistent_components());
+        }
+        // XXX: This check should be `d < LCM(p - 1, q - 1)`, but we
+        // don't have a good way of calculating LCM, so it is omitted,
+        // as explained above.
+        let d = d.into_odd_positive().map_err(inconsistent)?;
+        d.verify_less_than(&n.value()).map_err(inconsistent)?;
+
+        // Step 6.b is omitted as explained above.
+
+        // 6.4.1.4.3 - Step 7.
+
+        // Step 7.a.
+        //
+        // We need to prove that `dP < p - 1`. If we verify
+        // `dP < p` then we'll know that either `dP == p - 1` or
+        // `dP < p - 1`. Since `p` is odd, `p - 1` is even. `d` is odd,
+        // and an odd number modulo an even number is odd.
+        // Therefore `dP` must be odd. But then it cannot be `p - 1`
+        // and so we know `dP < p - 1`.
+        let p = PrivatePrime::new(p, dP).map_err(inconsistent)?;
+
+        // Step 7.b is done out-of-order below.
+
+        // Step 7.c.
+        let qInv = qInv.into_elem(&p.modulus).map_err(inconsistent)?;
+
+        // Steps 7.d and 7.e are omitted per the documentation above,
+        // and because we don't (in the long term) have a good way to
+        // do modulo with an even modulus.
+
+        // Step 7.f.
+        let qInv =
+            bigint::elem_mul(p.oneRR.as_ref(), qInv, &p.modulus)
+                .map_err(unexpected)?;
+        let q_mod_p = q.try_clone().map_err(unexpected)?
+            .into_elem(&p.modulus).map_err(inconsistent)?;
+        let qInv_times_q_mod_p =
+            bigint::elem_mul(&qInv, q_mod_p, &p.modulus)
+                .map_err(unexpected)?;
+        if !qInv_times_q_mod_p.is_one() {
+            return Err(error::KeyRejected::inconsistent_components());
+        }
+
+        // Step 7.b (out of order). Same proof as for `dP < p - 1`.
+        let q = PrivatePrime::new(q, dQ).map_err(inconsistent)?;
+
+        let qq = bigint::elem_mul(&q_mod_n, q_mod_n_decoded, &n)
+            .and_then(|qq| qq.into_modulus::<QQ>())
+            .map_err(unexpected)?;
+
+        Ok(RSAKeyPair {
+            n,
+            e,
+            p,
+            q,
+            qInv,
+            oneRR_mod_n,
+            q_mod_n,
+            qq,
+            n_bits
         })
     }
 
@@ -386,6 +412,64 @@ impl RSAKeyPair {
     }
 }
 
+fn positive_integer(input: &mut untrusted::Reader)
+                    -> Result<bigint::Positive, error::KeyRejected> {
+    bigint::Positive::from_der(input)
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())
+}
+
+fn inconsistent(_: error::Unspecified) -> error::KeyRejected {
+    error::KeyRejected::inconsistent_components()
+}
+
+fn unexpected(_: error::Unspecified) -> error::KeyRejected {
+    error::KeyRejected::unexpected_error()
+}
+
+// The limbs of the private CRT components, for the tests in `zeroize`.
+#[cfg(test)]
+impl RSAKeyPair {
+    pub(crate) fn crt_limbs(&self) -> [&[limb::Limb]; 5] {
+        [self.p.modulus.limbs(), self.p.exponent.limbs(),
+         self.q.modulus.limbs(), self.q.exponent.limbs(), self.qInv.limbs()]
+    }
+
+    // Erases the private CRT components as dropping them would, and returns
+    // their limbs afterwards.
+    #[cfg(not(feature = "pure_rust"))]
+    pub(crate) fn erase_crt_limbs_for_test(&mut self) -> [&[limb::Limb]; 5] {
+        [self.p.modulus.erase_for_test(), self.p.exponent.erase_for_test(),
+         self.q.modulus.erase_for_test(), self.q.exponent.erase_for_test(),
+         self.qInv.erase_for_test()]
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl secure_mem::Relocate for RSAKeyPair {
+    fn locked_len(&self) -> usize {
+        self.n.locked_len() + self.p.locked_len() + self.q.locked_len() +
+            self.qInv.locked_len() + self.oneRR_mod_n.locked_len() +
+            self.qq.locked_len() + self.q_mod_n.locked_len()
+    }
+
+    fn relocate(&mut self, region: &mut secure_mem::Region)
+                -> Result<(), error::Unspecified> {
+        self.n.relocate(region)?;
+        self.p.relocate(region)?;
+        self.q.relocate(region)?;
+        self.qInv.relocate(region)?;
+        self.oneRR_mod_n.relocate(region)?;
+        self.qq.relocate(region)?;
+        self.q_mod_n.relocate(region)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl secure_mem::Lockable for RSAKeyPair {}
+
+#[cfg(target_os = "linux")]
+impl private::Private for RSAKeyPair {}
+
 struct PrivatePrime<M: Prime> {
     modulus: bigint::Modulus<M>,
     exponent: bigint::OddPositive,
@@ -437,6 +521,24 @@ impl<M: Prime> PrivatePrime<M> {
     }
 }
 
+#[cfg(target_os = "linux")]
+impl<M: Prime> secure_mem::Relocate for PrivatePrime<M> {
+    fn locked_len(&self) -> usize {
+        self.modulus.locked_len() + self.exponent.locked_len() +
+            self.oneR.locked_len() + self.oneRR.locked_len() +
+            self.oneRRR.locked_len()
+    }
+
+    fn relocate(&mut self, region: &mut secure_mem::Region)
+                -> Result<(), error::Unspecified> {
+        self.modulus.relocate(region)?;
+        self.exponent.relocate(region)?;
+        self.oneR.relocate(region)?;
+        self.oneRR.relocate(region)?;
+        self.oneRRR.relocate(region)
+    }
+}
+
 fn elem_exp_consttime<M, MM>(c: &bigint::Elem<MM>, p: &PrivatePrime<M>)
                              -> Result<bigint::Elem<M>, error::Unspecified>
                              where M: bigint::NotMuchSmallerModulus<MM>,
@@ -507,16 +609,46 @@ unsafe impl bigint::NotMuchSmallerModulus<QQ> for Q {}
 /// modular inversions; managing a pool of `RSASigningState`s in a
 /// most-recently-used fashion would improve the computational efficiency.
 pub struct RSASigningState {
-    key_pair: std::sync::Arc<RSAKeyPair>,
+    key_pair: SharedKeyPair,
     blinding: blinding::Blinding,
 }
 
+enum SharedKeyPair {
+    Unlocked(std::sync::Arc<RSAKeyPair>),
+
+    #[cfg(target_os = "linux")]
+    Locked(std::sync::Arc<secure_mem::SecureBox<RSAKeyPair>>),
+}
+
+impl AsRef<RSAKeyPair> for SharedKeyPair {
+    fn as_ref(&self) -> &RSAKeyPair {
+        match *self {
+            SharedKeyPair::Unlocked(ref key_pair) => key_pair,
+
+            #[cfg(target_os = "linux")]
+            SharedKeyPair::Locked(ref key_pair) => key_pair,
+        }
+    }
+}
+
 impl RSASigningState {
     /// Construct an `RSASigningState` for the given `RSAKeyPair`.
     pub fn new(key_pair: std::sync::Arc<RSAKeyPair>)
                -> Result<Self, error::Unspecified> {
         Ok(RSASigningState {
-            key_pair: key_pair,
+            key_pair: SharedKeyPair::Unlocked(key_pair),
+            blinding: blinding::Blinding::new(),
+        })
+    }
+
+    /// Construct an `RSASigningState` for the given `RSAKeyPair` in locked
+    /// memory.
+    #[cfg(target_os = "linux")]
+    pub fn new_locked(
+            key_pair: std::sync::Arc<secure_mem::SecureBox<RSAKeyPair>>)
+            -> Result<Self, error::Unspecified> {
+        Ok(RSASigningState {
+            key_pair: SharedKeyPair::Locked(key_pair),
             blinding: blinding::Blinding::new(),
         })
     }
@@ -547,7 +679,7 @@ impl RSASigningState {
     pub fn sign(&mut self, padding_alg: &'static ::signature::RSAEncoding,
                 rng: &rand::SecureRandom, msg: &[u8], signature: &mut [u8])
                 -> Result<(), error::Unspecified> {
-        let mod_bits = self.key_pair.n_bits;
+        let mod_bits = self.key_pair().n_bits;
         if signature.len() != mod_bits.as_usize_bytes_rounded_up() {
             return Err(error::Unspecified);
         }
@@ -556,6 +688,7 @@ impl RSASigningState {
             key_pair: ref key,
             blinding: ref mut blinding,
         } = self;
+        let key = key.as_ref();
 
         let m_hash = digest::digest(padding_alg.digest_alg(), msg);
         padding_alg.encode(&m_hash, signature, mod_bits, rng)?;
@@ -53,8 +53,6 @@ macro_rules! rsa_params {
     ( $VERIFY_ALGORITHM:ident, $min_bits:expr, $PADDING_ALGORITHM:expr,
       $doc_str:expr ) => {
         #[doc=$doc_str]
-        ///
-        /// Only available in `use_heap` mode.
         pub static $VERIFY_ALGORITHM: RSAParameters =
             RSAParameters {
                 padding_alg: $PADDING_ALGORITHM,
@@ -178,3 +176,71 @@ pub fn verify_rsa(params: &RSAParameters,
     untrusted::Input::from(decoded).read_all(
         error::Unspecified, |m| params.padding_alg.verify(&m_hash, m, n_bits))
 }
+
+// Without `use_heap` the integration tests in tests/rsa_tests.rs can't be
+// built, as they need `ring::test`, so the verification test vectors are run
+// here too, against the fixed-capacity `BIGNUM`.
+#[cfg(all(test, not(feature = "use_heap")))]
+mod tests {
+    use {signature, test};
+    use untrusted;
+
+    #[test]
+    fn test_signature_rsa_pkcs1_verify() {
+        test::from_file("tests/rsa_pkcs1_verify_tests.txt",
+                        |section, test_case| {
+            assert_eq!(section, "");
+
+            let digest_name = test_case.consume_string("Digest");
+            let alg = match digest_name.as_ref() {
+                "SHA1" => &signature::RSA_PKCS1_2048_8192_SHA1,
+                "SHA256" => &signature::RSA_PKCS1_2048_8192_SHA256,
+                "SHA384" => &signature::RSA_PKCS1_2048_8192_SHA384,
+                "SHA512" => &signature::RSA_PKCS1_2048_8192_SHA512,
+                _ =>  { panic!("Unsupported digest: {}", digest_name) }
+            };
+
+            let public_key = test_case.consume_bytes("Key");
+            let msg = test_case.consume_bytes("Msg");
+            let sig = test_case.consume_bytes("Sig");
+            let expected_result = test_case.consume_string("Result");
+
+            let actual_result =
+                signature::verify(alg, untrusted::Input::from(&public_key),
+                                  untrusted::Input::from(&msg),
+                                  untrusted::Input::from(&sig));
+            assert_eq!(actual_result.is_ok(), expected_result == "P");
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_signature_rsa_pss_verify() {
+        test::from_file("tests/rsa_pss_verify_tests.txt",
+                        |section, test_case| {
+            assert_eq!(section, "");
+
+            let digest_name = test_case.consume_string("Digest");
+            let alg = match digest_name.as_ref() {
+                "SHA256" => &signature::RSA_PSS_2048_8192_SHA256,
+                "SHA384" => &signature::RSA_PSS_2048_8192_SHA384,
+                "SHA512" => &signature::RSA_PSS_2048_8192_SHA512,
+                _ =>  { panic!("Unsupported digest: {}", digest_name) }
+            };
+
+            let public_key = test_case.consume_bytes("Key");
+            let msg = test_case.consume_bytes("Msg");
+            let sig = test_case.consume_bytes("Sig");
+            let expected_result = test_case.consume_string("Result");
+
+            let actual_result =
+                signature::verify(alg, untrusted::Input::from(&public_key),
+                                  untrusted::Input::from(&msg),
+                                  untrusted::Input::from(&sig));
+            assert_eq!(actual_result.is_ok(), expected_result == "P");
+
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,177 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! XSalsa20, as specified in [Extending the Salsa20 nonce], for
+//! `secretbox`.
+//!
+//! [Extending the Salsa20 nonce]: https://cr.yp.to/snuffle/xsalsa-20110204.pdf
+
+use core;
+use zeroize;
+use polyfill::slice::{le_u8_from_u32, u32_from_le_u8};
+
+pub const KEY_LEN: usize = 256 / 8;
+pub const NONCE_LEN: usize = 192 / 8;
+
+const HSALSA20_INPUT_LEN: usize = 128 / 8;
+const BLOCK_LEN: usize = 64;
+
+/// An XSalsa20 keystream.
+pub struct XSalsa20 {
+    subkey: [u32; KEY_LEN / 4],
+    nonce: [u32; 2],
+    counter: u64,
+    keystream: [u8; BLOCK_LEN],
+    keystream_used: usize,
+}
+
+impl Drop for XSalsa20 {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.subkey);
+        zeroize::zeroize(&mut self.keystream);
+    }
+}
+
+impl XSalsa20 {
+    /// Starts the keystream for `key` and `nonce` at its first byte.
+    pub fn new(key: &[u8; KEY_LEN], nonce: &[u8; NONCE_LEN]) -> XSalsa20 {
+        let (hsalsa20_input, salsa20_nonce) =
+            nonce.split_at(HSALSA20_INPUT_LEN);
+        let hsalsa20_input =
+            slice_as_array_ref!(hsalsa20_input, HSALSA20_INPUT_LEN).unwrap();
+        XSalsa20 {
+            subkey: hsalsa20(&words_from_le_bytes(key), hsalsa20_input),
+            nonce: [
+                u32_from_le_u8(slice_as_array_ref!(&salsa20_nonce[..4], 4)
+                                   .unwrap()),
+                u32_from_le_u8(slice_as_array_ref!(&salsa20_nonce[4..], 4)
+                                   .unwrap()),
+            ],
+            counter: 0,
+            keystream: [0; BLOCK_LEN],
+            keystream_used: BLOCK_LEN,
+        }
+    }
+
+    /// XORs the next `in_out.len()` bytes of the keystream into `in_out`.
+    pub fn xor_in_place(&mut self, in_out: &mut [u8]) {
+        let mut in_out = in_out;
+        while !in_out.is_empty() {
+            if self.keystream_used == BLOCK_LEN {
+                let mut input = initial_state(&self.subkey);
+                input[6] = self.nonce[0];
+                input[7] = self.nonce[1];
+                input[8] = self.counter as u32;
+                input[9] = (self.counter >> 32) as u32;
+                let mut x = input;
+                salsa20_rounds(&mut x);
+                for (out, (x, input)) in self.keystream.chunks_mut(4)
+                        .zip(x.iter().zip(input.iter())) {
+                    out.copy_from_slice(&le_u8_from_u32(
+                        x.wrapping_add(*input)));
+                }
+                self.counter = self.counter.wrapping_add(1);
+                self.keystream_used = 0;
+            }
+            let n = core::cmp::min(BLOCK_LEN - self.keystream_used,
+                                   in_out.len());
+            let keystream = &self.keystream[self.keystream_used..][..n];
+            for (b, k) in in_out[..n].iter_mut().zip(keystream.iter()) {
+                *b ^= *k;
+            }
+            self.keystream_used += n;
+            in_out = &mut {in_out}[n..];
+        }
+    }
+}
+
+// HSalsa20 maps a key and a 128-bit input to a 256-bit subkey.
+fn hsalsa20(key: &[u32; KEY_LEN / 4], input: &[u8; HSALSA20_INPUT_LEN])
+            -> [u32; KEY_LEN / 4] {
+    let mut x = initial_state(key);
+    for (x, input) in x[6..10].iter_mut().zip(input.chunks(4)) {
+        *x = u32_from_le_u8(slice_as_array_ref!(input, 4).unwrap());
+    }
+    salsa20_rounds(&mut x);
+    [x[0], x[5], x[10], x[15], x[6], x[7], x[8], x[9]]
+}
+
+// The Salsa20 input matrix, without the nonce and counter (or HSalsa20
+// input) in words 6 through 9.
+fn initial_state(key: &[u32; KEY_LEN / 4]) -> [u32; 16] {
+    // "expand 32-byte k"
+    const SIGMA: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+    [SIGMA[0], key[0], key[1], key[2], key[3], SIGMA[1], 0, 0,
+     0, 0, SIGMA[2], key[4], key[5], key[6], key[7], SIGMA[3]]
+}
+
+fn salsa20_rounds(x: &mut [u32; 16]) {
+    #[inline(always)]
+    fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize,
+                     d: usize) {
+        x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+        x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+        x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+        x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+    }
+
+    for _ in 0..10 {
+        // Column round.
+        quarter_round(x, 0, 4, 8, 12);
+        quarter_round(x, 5, 9, 13, 1);
+        quarter_round(x, 10, 14, 2, 6);
+        quarter_round(x, 15, 3, 7, 11);
+        // Row round.
+        quarter_round(x, 0, 1, 2, 3);
+        quarter_round(x, 5, 6, 7, 4);
+        quarter_round(x, 10, 11, 8, 9);
+        quarter_round(x, 15, 12, 13, 14);
+    }
+}
+
+fn words_from_le_bytes(bytes: &[u8; KEY_LEN]) -> [u32; KEY_LEN / 4] {
+    let mut words = [0u32; KEY_LEN / 4];
+    for (word, bytes) in words.iter_mut().zip(bytes.chunks(4)) {
+        *word = u32_from_le_u8(slice_as_array_ref!(bytes, 4).unwrap());
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From NaCl's tests/core2.c and tests/core2.out.
+    #[test]
+    fn test_hsalsa20() {
+        let key = [
+            0x1b, 0x27, 0x55, 0x64, 0x73, 0xe9, 0x85, 0xd4,
+            0x62, 0xcd, 0x51, 0x19, 0x7a, 0x9a, 0x46, 0xc7,
+            0x60, 0x09, 0x54, 0x9e, 0xac, 0x64, 0x74, 0xf2,
+            0x06, 0xc4, 0xee, 0x08, 0x44, 0xf6, 0x83, 0x89,
+        ];
+        let input = [
+            0x69, 0x69, 0x6e, 0xe9, 0x55, 0xb6, 0x2b, 0x73,
+            0xcd, 0x62, 0xbd, 0xa8, 0x75, 0xfc, 0x73, 0xd6,
+        ];
+        let expected = [
+            0xdc, 0x90, 0x8d, 0xda, 0x0b, 0x93, 0x44, 0xa9,
+            0x53, 0x62, 0x9b, 0x73, 0x38, 0x20, 0x77, 0x88,
+            0x80, 0xf3, 0xce, 0xb4, 0x21, 0xbb, 0x61, 0xb9,
+            0x1c, 0xbd, 0x4c, 0x3e, 0x66, 0x25, 0x6c, 0xe4,
+        ];
+        let subkey = hsalsa20(&words_from_le_bytes(&key), &input);
+        assert_eq!(subkey, words_from_le_bytes(&expected));
+    }
+}
@@ -0,0 +1,243 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! scrypt derivation and verification.
+//!
+//! Use `derive` to derive scrypt outputs. Use `verify` to verify secret
+//! against previously-derived outputs.
+//!
+//! scrypt is specified in [RFC 7914], which also gives the test vectors.
+//! scrypt is memory-hard: `derive` and `verify` allocate `128 * r * 2**log_n`
+//! bytes of memory.
+//!
+//! [RFC 7914]: https://tools.ietf.org/html/rfc7914
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::scrypt;
+//!
+//! # fn main() { f().unwrap() }
+//! # fn f() -> Result<(), ring::error::Unspecified> {
+//! // N = 2**15, r = 8, p = 1 uses 32 MiB of memory.
+//! let params = scrypt::Params::new(15, 8, 1)?;
+//! let salt = b"unique salt for this user";
+//!
+//! let mut credential = [0u8; 32];
+//! scrypt::derive(&params, salt, b"password", &mut credential);
+//!
+//! assert!(scrypt::verify(&params, salt, b"password", &credential).is_ok());
+//! assert!(scrypt::verify(&params, salt, b"passw0rd", &credential).is_err());
+//! # Ok(())
+//! # }
+//! ```
+
+use {constant_time, digest, error, pbkdf2, polyfill, zeroize};
+
+/// scrypt parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Params {
+    log_n: u8,
+    r: u32,
+    p: u32,
+}
+
+impl Params {
+    /// Validates and constructs scrypt parameters.
+    ///
+    /// The CPU/memory cost parameter `N` is `2**log_n`. `r` is the block size
+    /// and `p` is the parallelization parameter. `log_n` must be at least 1
+    /// and less than `16 * r`, `r` and `p` must be at least 1, `p * r` must
+    /// be less than `2**30`, and the `128 * r * N` bytes of memory needed
+    /// must be addressable.
+    pub fn new(log_n: u8, r: u32, p: u32) -> Result<Params, error::Unspecified> {
+        if log_n < 1 || log_n >= 64 || r < 1 || p < 1 {
+            return Err(error::Unspecified);
+        }
+        if u64::from(log_n) >= 16 * u64::from(r) {
+            return Err(error::Unspecified);
+        }
+        if u64::from(p) * u64::from(r) >= (1 << 30) {
+            return Err(error::Unspecified);
+        }
+        let params = Params { log_n, r, p };
+        let _ = params.v_len()?;
+        let _ = params.b_len()?;
+        Ok(params)
+    }
+
+    /// `log2(N)`.
+    #[inline]
+    pub fn log_n(&self) -> u8 { self.log_n }
+
+    /// The block size parameter `r`.
+    #[inline]
+    pub fn r(&self) -> u32 { self.r }
+
+    /// The parallelization parameter `p`.
+    #[inline]
+    pub fn p(&self) -> u32 { self.p }
+
+    fn n(&self) -> usize { 1 << self.log_n }
+
+    // The number of `u32`s in one `B_i`.
+    fn block_words(&self) -> usize { 32 * (self.r as usize) }
+
+    // The number of `u32`s in `V`.
+    fn v_len(&self) -> Result<usize, error::Unspecified> {
+        let n = 1usize.checked_shl(u32::from(self.log_n))
+            .ok_or(error::Unspecified)?;
+        let words = self.block_words().checked_mul(n)
+            .ok_or(error::Unspecified)?;
+        let _bytes = words.checked_mul(4).ok_or(error::Unspecified)?;
+        Ok(words)
+    }
+
+    // The number of bytes in `B`.
+    fn b_len(&self) -> Result<usize, error::Unspecified> {
+        (self.p as usize).checked_mul(self.block_words() * 4)
+            .ok_or(error::Unspecified)
+    }
+}
+
+/// Fills `out` with the key derived using scrypt with the given inputs.
+///
+/// # Panics
+///
+/// `derive` panics if `out.len()` is larger than `(2**32 - 1) * 32`, like
+/// `pbkdf2::derive` does.
+pub fn derive(params: &Params, salt: &[u8], secret: &[u8], out: &mut [u8]) {
+    // `Params::new` checked that these lengths don't overflow.
+    let mut b = vec![0u8; params.b_len().unwrap()];
+    pbkdf2::derive(&digest::SHA256, 1, salt, secret, &mut b);
+
+    let block_words = params.block_words();
+    let mut x = vec![0u32; block_words];
+    let mut y = vec![0u32; block_words];
+    let mut v = vec![0u32; params.v_len().unwrap()];
+
+    for b_i in b.chunks_mut(block_words * 4) {
+        for (x, b) in x.iter_mut().zip(b_i.chunks(4)) {
+            *x = polyfill::slice::u32_from_le_u8(
+                slice_as_array_ref!(b, 4).unwrap());
+        }
+        ro_mix(params, &mut x, &mut y, &mut v);
+        for (b, x) in b_i.chunks_mut(4).zip(x.iter()) {
+            b.copy_from_slice(&polyfill::slice::le_u8_from_u32(*x));
+        }
+    }
+
+    pbkdf2::derive(&digest::SHA256, 1, &b, secret, out);
+
+    // All of these were derived from the password.
+    zeroize::zeroize(&mut b);
+    zeroize::zeroize(&mut x);
+    zeroize::zeroize(&mut y);
+    zeroize::zeroize(&mut v);
+}
+
+/// Verifies that a previously-derived (e.g., using `derive`) scrypt key
+/// matches the scrypt key derived from the other inputs.
+///
+/// The comparison is done in constant time to prevent timing attacks. The
+/// comparison will fail if `previously_derived` is empty (has a length of
+/// zero).
+pub fn verify(params: &Params, salt: &[u8], secret: &[u8],
+              previously_derived: &[u8]) -> Result<(), error::Unspecified> {
+    if previously_derived.is_empty() {
+        return Err(error::Unspecified);
+    }
+    if previously_derived.len() as u64 > 0xffff_ffff * 32 {
+        return Err(error::Unspecified);
+    }
+    let mut derived = vec![0u8; previously_derived.len()];
+    derive(params, salt, secret, &mut derived);
+    constant_time::verify_slices_are_equal(&derived, previously_derived)
+}
+
+// scryptROMix, with `x` holding `B` on input and `B'` on output.
+fn ro_mix(params: &Params, x: &mut [u32], y: &mut [u32], v: &mut [u32]) {
+    let n = params.n();
+    let block_words = x.len();
+
+    for v_i in v.chunks_mut(block_words) {
+        v_i.copy_from_slice(x);
+        block_mix(x, y);
+    }
+
+    for _ in 0..n {
+        // Integerify(X) mod N. N is a power of two no larger than 2**63, so
+        // only the low 64 bits of Integerify(X) matter.
+        let last = &x[(block_words - 16)..];
+        let j = (u64::from(last[1]) << 32 | u64::from(last[0])) &
+                ((n as u64) - 1);
+        let v_j = &v[((j as usize) * block_words)..][..block_words];
+        for (x, v) in x.iter_mut().zip(v_j.iter()) {
+            *x ^= *v;
+        }
+        block_mix(x, y);
+    }
+}
+
+// scryptBlockMix, in place, using `y` as scratch space.
+fn block_mix(b: &mut [u32], y: &mut [u32]) {
+    let r2 = b.len() / 16;
+
+    let mut x = [0u32; 16];
+    x.copy_from_slice(&b[(b.len() - 16)..]);
+
+    for (i, b_i) in b.chunks(16).enumerate() {
+        for (x, b) in x.iter_mut().zip(b_i.iter()) {
+            *x ^= *b;
+        }
+        salsa20_8(&mut x);
+
+        // Y_0, Y_2, ..., Y_{2r-2} followed by Y_1, Y_3, ..., Y_{2r-1}.
+        let out = if i % 2 == 0 { i / 2 } else { (r2 / 2) + (i / 2) };
+        y[(out * 16)..][..16].copy_from_slice(&x);
+    }
+
+    b.copy_from_slice(y);
+}
+
+// The Salsa20/8 core function.
+fn salsa20_8(b: &mut [u32; 16]) {
+    let mut x = *b;
+
+    for _ in 0..4 {
+        // Column round.
+        quarter_round(&mut x, 0, 4, 8, 12);
+        quarter_round(&mut x, 5, 9, 13, 1);
+        quarter_round(&mut x, 10, 14, 2, 6);
+        quarter_round(&mut x, 15, 3, 7, 11);
+
+        // Row round.
+        quarter_round(&mut x, 0, 1, 2, 3);
+        quarter_round(&mut x, 5, 6, 7, 4);
+        quarter_round(&mut x, 10, 11, 8, 9);
+        quarter_round(&mut x, 15, 12, 13, 14);
+    }
+
+    for (b, x) in b.iter_mut().zip(x.iter()) {
+        *b = b.wrapping_add(*x);
+    }
+}
+
+#[inline(always)]
+fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+    x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+    x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+    x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+}
@@ -0,0 +1,134 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! NaCl's `crypto_secretbox`: authenticated encryption with XSalsa20 and
+//! Poly1305.
+//!
+//! This is compatible with `crypto_secretbox_xsalsa20poly1305` in NaCl and
+//! with `crypto_secretbox_easy` in libsodium: the sealed box is the 16-byte
+//! Poly1305 tag followed by the ciphertext, which is as long as the plaintext.
+//! There is no additional data.
+//!
+//! Nonces are 192 bits long, which is long enough that they may be chosen at
+//! random (e.g. with `rand::SystemRandom`) for every message. A nonce must
+//! never be used for two different messages with the same key.
+//!
+//! New protocols should use `ring::aead` instead.
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::secretbox;
+//!
+//! # fn main() { f().unwrap() }
+//! # fn f() -> Result<(), ring::error::Unspecified> {
+//! let key = secretbox::Key::new(&[0x42; secretbox::KEY_LEN])?;
+//! let nonce = [0x24; secretbox::NONCE_LEN];
+//!
+//! let mut in_out = vec![0u8; secretbox::TAG_LEN];
+//! in_out.extend_from_slice(b"hello, world");
+//! secretbox::seal_in_place(&key, &nonce, &mut in_out)?;
+//!
+//! let plaintext = secretbox::open_in_place(&key, &nonce, &mut in_out)?;
+//! assert_eq!(plaintext, b"hello, world");
+//! # Ok(())
+//! # }
+//! ```
+
+use {error, poly1305, salsa20, zeroize};
+
+/// The length of a key.
+pub const KEY_LEN: usize = salsa20::KEY_LEN;
+
+/// The length of a nonce.
+pub const NONCE_LEN: usize = salsa20::NONCE_LEN;
+
+/// The length of the tag at the start of a sealed box.
+pub const TAG_LEN: usize = poly1305::TAG_LEN;
+
+/// A key for sealing and opening boxes.
+pub struct Key {
+    bytes: [u8; KEY_LEN],
+}
+
+impl Drop for Key {
+    fn drop(&mut self) {
+        zeroize::zeroize(&mut self.bytes);
+    }
+}
+
+impl Key {
+    /// Constructs a key from `key_bytes`, which must be exactly `KEY_LEN`
+    /// bytes long.
+    pub fn new(key_bytes: &[u8]) -> Result<Key, error::Unspecified> {
+        let key_bytes = slice_as_array_ref!(key_bytes, KEY_LEN)?;
+        Ok(Key { bytes: *key_bytes })
+    }
+}
+
+/// Encrypts and signs (“seals”) a message in place.
+///
+/// `in_out` must be the plaintext prefixed with `TAG_LEN` bytes of space for
+/// the tag; the initial contents of those bytes are ignored. On success,
+/// `in_out` contains the sealed box: the tag followed by the ciphertext.
+///
+/// C analog: `crypto_secretbox_easy`
+pub fn seal_in_place(key: &Key, nonce: &[u8; NONCE_LEN], in_out: &mut [u8])
+                     -> Result<(), error::Unspecified> {
+    if in_out.len() < TAG_LEN {
+        return Err(error::Unspecified);
+    }
+    let (tag, in_out) = in_out.split_at_mut(TAG_LEN);
+    let tag = slice_as_array_ref_mut!(tag, TAG_LEN)?;
+
+    let (mut stream, poly1305_key) = start(key, nonce);
+    stream.xor_in_place(in_out);
+    poly1305::sign(poly1305_key, in_out, tag);
+    Ok(())
+}
+
+/// Authenticates and decrypts (“opens”) a sealed box in place.
+///
+/// `in_out` must be the sealed box: the tag followed by the ciphertext. On
+/// success, the plaintext is returned, and is `in_out[TAG_LEN..]`. The
+/// ciphertext is authenticated before it is decrypted, so `in_out` is not
+/// modified if it fails to authenticate.
+///
+/// C analog: `crypto_secretbox_open_easy`
+pub fn open_in_place<'a>(key: &Key, nonce: &[u8; NONCE_LEN],
+                         in_out: &'a mut [u8])
+                         -> Result<&'a mut [u8], error::Unspecified> {
+    if in_out.len() < TAG_LEN {
+        return Err(error::Unspecified);
+    }
+    let (tag, in_out) = in_out.split_at_mut(TAG_LEN);
+    let tag = slice_as_array_ref!(tag, TAG_LEN)?;
+
+    let (mut stream, poly1305_key) = start(key, nonce);
+    poly1305::verify(poly1305_key, in_out, tag)?;
+    stream.xor_in_place(in_out);
+    Ok(in_out)
+}
+
+// The first 32 bytes of the keystream are the Poly1305 key; the message is
+// encrypted with the rest.
+fn start(key: &Key, nonce: &[u8; NONCE_LEN])
+         -> (salsa20::XSalsa20, poly1305::Key) {
+    let mut stream = salsa20::XSalsa20::new(&key.bytes, nonce);
+    let mut poly1305_key_bytes = [0u8; poly1305::KEY_LEN];
+    stream.xor_in_place(&mut poly1305_key_bytes);
+    let poly1305_key = poly1305::Key::new(&poly1305_key_bytes);
+    zeroize::zeroize(&mut poly1305_key_bytes);
+    (stream, poly1305_key)
+}
@@ -0,0 +1,337 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Locked memory for long-lived private keys.
+//!
+//! A key pair that is kept for the life of a process, like the key of a
+//! server, may end up in swap or in a core dump. A `SecureBox` moves a key
+//! pair into its own memory mapping. The pages holding the key are locked
+//! into RAM with `mlock`, excluded from core dumps with `MADV_DONTDUMP`, and
+//! surrounded by inaccessible guard pages. When the `SecureBox` is dropped the
+//! key pair is dropped in place, the pages are zeroed, and the mapping is
+//! removed.
+//!
+//! Using a `SecureBox` is an explicit opt-in, made right after constructing
+//! the key pair:
+//!
+//! ```
+//! extern crate ring;
+//! extern crate untrusted;
+//!
+//! use ring::{rand, secure_mem, signature};
+//!
+//! # fn example() -> Result<(), ring::error::Unspecified> {
+//! let rng = rand::SystemRandom::new();
+//! let pkcs8_bytes = signature::Ed25519KeyPair::generate_pkcs8(&rng)?;
+//! let key_pair = secure_mem::SecureBox::new(
+//!     signature::Ed25519KeyPair::from_pkcs8(
+//!         untrusted::Input::from(&pkcs8_bytes))?)?;
+//! let _ = key_pair.sign(b"hello, world");
+//! # Ok(())
+//! # }
+//! # fn main() { example().unwrap() }
+//! ```
+//!
+//! The memory that held the key pair before it was moved into the `SecureBox`
+//! is erased, but other copies made while the key pair was being constructed
+//! and returned are not; the protection applies from the time the key pair is
+//! boxed. Locking memory counts against `RLIMIT_MEMLOCK`, and `SecureBox::new`
+//! fails if the limit would be exceeded.
+
+use {core, error, private, zeroize};
+use core::mem;
+
+pub(crate) use self::region::{LOCKED_ALIGN, Region, Relocate};
+use self::region::round_up;
+
+/// A key pair in locked memory.
+///
+/// A `SecureBox<T>` dereferences to `T`.
+pub struct SecureBox<T: Lockable> {
+    value: *mut T,
+
+    // Must be dropped after `*value`, since `*value` may point into it.
+    #[allow(dead_code)] // Only kept for its `Drop` implementation.
+    region: Region,
+}
+
+// `SecureBox` uniquely owns its value.
+unsafe impl<T: Lockable + Send> Send for SecureBox<T> {}
+unsafe impl<T: Lockable + Sync> Sync for SecureBox<T> {}
+
+impl<T: Lockable> SecureBox<T> {
+    /// Moves `value`, and any secret memory it owns, into locked memory.
+    pub fn new(value: T) -> Result<Self, error::Unspecified> {
+        let value_len = round_up(mem::size_of::<T>(), LOCKED_ALIGN);
+        let mut region = Region::new(value_len + value.locked_len())?;
+        let ptr = region.alloc(mem::size_of::<T>(), mem::align_of::<T>())?
+            as *mut T;
+
+        let mut value = mem::ManuallyDrop::new(value);
+        if let Err(err) = value.relocate(&mut region) {
+            let _ = mem::ManuallyDrop::into_inner(value);
+            return Err(err);
+        }
+        let old: *mut T = &mut *value;
+        unsafe {
+            core::ptr::copy_nonoverlapping(old, ptr, 1);
+
+            // `*ptr` owns everything `value` owned; erase the old copy without
+            // dropping it.
+            zeroize::zeroize(core::slice::from_raw_parts_mut(
+                old as *mut u8, mem::size_of::<T>()));
+        }
+
+        Ok(SecureBox { value: ptr, region })
+    }
+}
+
+impl<T: Lockable> core::ops::Deref for SecureBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T { unsafe { &*self.value } }
+}
+
+impl<T: Lockable> Drop for SecureBox<T> {
+    fn drop(&mut self) {
+        unsafe { core::ptr::drop_in_place(self.value) }
+    }
+}
+
+/// Key pair types that can be put in a `SecureBox`.
+///
+/// This trait is implemented by `signature::Ed25519KeyPair`,
+/// `signature::ECDSAKeyPair`, and, with the `rsa_signing` feature,
+/// `signature::RSAKeyPair`. It can't be implemented outside of *ring*.
+pub trait Lockable: Relocate + private::Private {}
+
+// `Relocate` and `Region` are public so that they can appear in the bounds of
+// `Lockable`, but they are not reachable from outside *ring*.
+mod region {
+    use {core, error, zeroize};
+    use libc;
+
+    /// Moves the memory owned by a value into a `Region`.
+    pub trait Relocate {
+        /// The number of bytes of `Region` that `relocate` will use.
+        fn locked_len(&self) -> usize;
+
+        /// Moves the secret memory owned by `self` into `region`, erasing the
+        /// original memory.
+        fn relocate(&mut self, region: &mut Region)
+                    -> Result<(), error::Unspecified>;
+    }
+
+    /// The alignment of every allocation a `Relocate` implementation makes, and
+    /// thus the granularity of `Relocate::locked_len()`.
+    pub const LOCKED_ALIGN: usize = 16;
+
+    /// A locked, non-dumpable memory mapping with guard pages.
+    ///
+    /// The mapping is laid out as a guard page, the locked pages, and a second
+    /// guard page. The guard pages are never accessible, so a linear overflow or
+    /// underflow out of the locked pages faults instead of reaching other memory.
+    pub struct Region {
+        mapping: *mut u8,
+        mapping_len: usize,
+        locked: *mut u8,
+        locked_len: usize,
+        used: usize,
+    }
+
+    impl Region {
+        pub fn new(len: usize) -> Result<Self, error::Unspecified> {
+            let page_len = page_len()?;
+            let locked_len = round_up(core::cmp::max(len, 1), page_len);
+            let mapping_len = locked_len + 2 * page_len;
+
+            let mapping = unsafe {
+                libc::mmap(core::ptr::null_mut(), mapping_len, libc::PROT_NONE,
+                           libc::MAP_PRIVATE | libc::MAP_ANONYMOUS, -1, 0)
+            };
+            if mapping == libc::MAP_FAILED {
+                return Err(error::Unspecified);
+            }
+
+            // From here on, `Drop` undoes whatever has been done so far.
+            let mut region = Region {
+                mapping: mapping as *mut u8,
+                mapping_len,
+                locked: unsafe { (mapping as *mut u8).offset(page_len as isize) },
+                locked_len: 0,
+                used: 0,
+            };
+            let locked = region.locked as *mut libc::c_void;
+            if unsafe {
+                libc::mprotect(locked, locked_len,
+                               libc::PROT_READ | libc::PROT_WRITE)
+            } != 0 {
+                return Err(error::Unspecified);
+            }
+            if unsafe { libc::madvise(locked, locked_len, libc::MADV_DONTDUMP) }
+                    != 0 {
+                return Err(error::Unspecified);
+            }
+            if unsafe { libc::mlock(locked, locked_len) } != 0 {
+                return Err(error::Unspecified);
+            }
+            region.locked_len = locked_len;
+            Ok(region)
+        }
+
+        /// Allocates `len` bytes, aligned to `align`, from the locked pages. The
+        /// memory is zeroed and lives as long as the `Region`.
+        pub fn alloc(&mut self, len: usize, align: usize)
+                     -> Result<*mut u8, error::Unspecified> {
+            debug_assert!(align.is_power_of_two() && align <= LOCKED_ALIGN);
+            let start = round_up(self.used, align);
+            if len > self.locked_len - start {
+                return Err(error::Unspecified);
+            }
+            self.used = start + len;
+            Ok(unsafe { self.locked.offset(start as isize) })
+        }
+    }
+
+    impl Drop for Region {
+        fn drop(&mut self) {
+            unsafe {
+                if self.locked_len != 0 {
+                    zeroize::zeroize(core::slice::from_raw_parts_mut(
+                        self.locked, self.locked_len));
+                    let _ = libc::munlock(self.locked as *const libc::c_void,
+                                          self.locked_len);
+                }
+                let _ = libc::munmap(self.mapping as *mut libc::c_void,
+                                     self.mapping_len);
+            }
+        }
+    }
+
+    pub fn page_len() -> Result<usize, error::Unspecified> {
+        let r = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        if r <= 0 {
+            return Err(error::Unspecified);
+        }
+        Ok(r as usize)
+    }
+
+    #[inline]
+    pub fn round_up(value: usize, multiple: usize) -> usize {
+        debug_assert!(multiple.is_power_of_two());
+        (value + multiple - 1) & !(multiple - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {rand, signature};
+    use std;
+    use std::string::String;
+    use super::*;
+    use untrusted;
+
+    // Returns the permissions and `VmFlags` of the mapping containing `addr`,
+    // according to /proc/self/smaps.
+    fn mapping_of(addr: usize) -> (String, String) {
+        use std::io::Read;
+
+        let mut smaps = String::new();
+        let _ = std::fs::File::open("/proc/self/smaps").unwrap()
+            .read_to_string(&mut smaps).unwrap();
+        let mut found = None;
+        for line in smaps.lines() {
+            if line.starts_with("VmFlags:") {
+                if let Some(perms) = found.take() {
+                    return (perms, String::from(line));
+                }
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let range = fields.next().unwrap();
+            let mut bounds = range.split('-');
+            let (start, end) = match (bounds.next(), bounds.next()) {
+                (Some(start), Some(end)) => (start, end),
+                _ => continue,
+            };
+            let start = usize::from_str_radix(start, 16);
+            let end = usize::from_str_radix(end, 16);
+            if let (Ok(start), Ok(end)) = (start, end) {
+                if start <= addr && addr < end {
+                    found = Some(String::from(fields.next().unwrap()));
+                }
+            }
+        }
+        panic!("No mapping for {:x}", addr);
+    }
+
+    #[test]
+    fn test_secure_box_ed25519() {
+        let rng = rand::SystemRandom::new();
+        let pkcs8 = signature::Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let key_pair = || {
+            signature::Ed25519KeyPair::from_pkcs8(untrusted::Input::from(&pkcs8))
+                .unwrap()
+        };
+        let boxed = SecureBox::new(key_pair()).unwrap();
+
+        const MESSAGE: &[u8] = b"hello, world";
+        assert_eq!(boxed.sign(MESSAGE).as_ref(),
+                   key_pair().sign(MESSAGE).as_ref());
+
+        let page_len = region::page_len().unwrap();
+        let addr = boxed.value as usize;
+        let (perms, vm_flags) = mapping_of(addr);
+        assert_eq!(perms, "rw-p");
+        assert!(vm_flags.split_whitespace().any(|flag| flag == "lo"));
+        assert!(vm_flags.split_whitespace().any(|flag| flag == "dd"));
+
+        // `value` is at the start of the locked pages, which are followed by
+        // the end guard page. This key pair fits in a single page.
+        assert_eq!(addr % page_len, 0);
+        assert_eq!(mapping_of(addr - page_len).0, "---p");
+        assert_eq!(mapping_of(addr + page_len).0, "---p");
+    }
+
+    #[cfg(feature = "rsa_signing")]
+    #[test]
+    fn test_secure_box_rsa() {
+        const MESSAGE: &[u8] = b"hello, world";
+        let key_bytes_der = untrusted::Input::from(
+            include_bytes!("rsa/signature_rsa_example_private_key.der"));
+        let rng = rand::SystemRandom::new();
+
+        let sign = |signing_state: &mut signature::RSASigningState| {
+            let mut signature =
+                vec![0; signing_state.key_pair().public_modulus_len()];
+            signing_state.sign(&signature::RSA_PKCS1_SHA256, &rng, MESSAGE,
+                               &mut signature).unwrap();
+            signature
+        };
+
+        let key_pair = signature::RSAKeyPair::from_der(key_bytes_der).unwrap();
+        let mut signing_state =
+            signature::RSASigningState::new(std::sync::Arc::new(key_pair))
+                .unwrap();
+        let expected = sign(&mut signing_state);
+
+        let key_pair = signature::RSAKeyPair::from_der(key_bytes_der).unwrap();
+        let key_pair = SecureBox::new(key_pair).unwrap();
+        let mut signing_state =
+            signature::RSASigningState::new_locked(std::sync::Arc::new(key_pair))
+                .unwrap();
+        assert_eq!(sign(&mut signing_state), expected);
+        assert_eq!(sign(&mut signing_state), expected);
+    }
+}
@@ -0,0 +1,589 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Known-answer self-tests and the error state.
+//!
+//! `run()` checks one known answer for each of SHA-2, HMAC, HKDF, PBKDF2,
+//! AES-GCM, ChaCha20-Poly1305, ECDSA, ECDH, Ed25519 and RSA. The known answers
+//! are taken from *ring*'s test vectors.
+//!
+//! With the `self_tests` feature, *ring* runs the self-tests the first time it
+//! is initialized, before any other cryptographic operation completes, and
+//! checks the consistency of every key pair it generates. Without the feature,
+//! the self-tests only run when `run()` is called.
+//!
+//! If a self-test or a consistency check fails, *ring* enters an error state
+//! that it never leaves: `status()` returns `Status::Failed`, `run()` fails,
+//! and every operation that initializes *ring* panics.
+
+use {aead, agreement, constant_time, core, digest, error, hkdf, hmac, pbkdf2,
+     rand, signature, std};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use untrusted;
+
+/// The state of the self-tests.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    /// The self-tests haven't completed yet.
+    NotRun,
+
+    /// The self-tests passed.
+    Passed,
+
+    /// A self-test or a consistency check failed; *ring* is in the error
+    /// state.
+    Failed,
+}
+
+/// The current state of the self-tests.
+pub fn status() -> Status {
+    match STATE.load(Ordering::Acquire) {
+        PASSED => Status::Passed,
+        FAILED => Status::Failed,
+        _ => Status::NotRun,
+    }
+}
+
+/// Runs the self-tests.
+///
+/// The self-tests are run again even if they have already passed. If they
+/// fail, *ring* enters the error state. Once *ring* is in the error state,
+/// `run()` fails without running them.
+pub fn run() -> Result<(), error::Unspecified> {
+    loop {
+        let state = STATE.load(Ordering::Acquire);
+        match state {
+            FAILED => {
+                return Err(error::Unspecified);
+            },
+            RUNNING => {
+                if running_on_this_thread() {
+                    return Err(error::Unspecified);
+                }
+                // Wait for the other thread to finish.
+                std::thread::yield_now();
+                continue;
+            },
+            _ => {},
+        }
+        if STATE.compare_exchange(state, RUNNING, Ordering::AcqRel,
+                                  Ordering::Acquire).is_ok() {
+            break;
+        }
+    }
+
+    let running = Running::new();
+    let result = run_all();
+    running.finish(result)
+}
+
+/// Called by `init::init_once()` before every operation that initializes
+/// *ring*.
+#[inline]
+pub fn check() {
+    match STATE.load(Ordering::Acquire) {
+        PASSED => {},
+        FAILED => error_state(),
+        // Without the `self_tests` feature the state stays `NOT_RUN` unless
+        // `run()` is called, so don't look at the thread-local state here.
+        _ if cfg!(feature = "self_tests") => check_slow(),
+        _ => {},
+    }
+}
+
+#[inline(never)]
+fn check_slow() {
+    // The self-tests themselves use *ring*.
+    if running_on_this_thread() {
+        return;
+    }
+    if run().is_err() {
+        error_state();
+    }
+}
+
+#[cold]
+fn error_state() -> ! {
+    panic!("ring is in the error state because a self-test failed");
+}
+
+/// Enters the error state if a key pair consistency check failed. Key pair
+/// generation returns the result.
+#[cfg(feature = "self_tests")]
+pub fn consistency_check_result(result: Result<(), error::Unspecified>)
+                                -> Result<(), error::Unspecified> {
+    if result.is_err() {
+        STATE.store(FAILED, Ordering::Release);
+    }
+    result
+}
+
+const NOT_RUN: usize = 0;
+const RUNNING: usize = 1;
+const PASSED: usize = 2;
+const FAILED: usize = 3;
+
+static STATE: AtomicUsize = AtomicUsize::new(NOT_RUN);
+
+thread_local! {
+    static RUNNING_ON_THIS_THREAD: core::cell::Cell<bool> =
+        core::cell::Cell::new(false);
+}
+
+fn running_on_this_thread() -> bool {
+    RUNNING_ON_THIS_THREAD.try_with(|running| running.get()).unwrap_or(false)
+}
+
+// Marks the self-tests as running on this thread. If a self-test panics, the
+// error state is entered when the `Running` is dropped.
+struct Running {
+    finished: bool,
+}
+
+impl Running {
+    fn new() -> Self {
+        RUNNING_ON_THIS_THREAD.with(|running| running.set(true));
+        Running { finished: false }
+    }
+
+    fn finish(mut self, result: Result<(), error::Unspecified>)
+              -> Result<(), error::Unspecified> {
+        self.finished = true;
+        let new_state = if result.is_ok() { PASSED } else { FAILED };
+
+        // A failed consistency check may have entered the error state while
+        // the self-tests were running; don't leave it.
+        if STATE.compare_exchange(RUNNING, new_state, Ordering::AcqRel,
+                                  Ordering::Acquire).is_err() {
+            return Err(error::Unspecified);
+        }
+        result
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        let _ = RUNNING_ON_THIS_THREAD.try_with(|running| running.set(false));
+        if !self.finished {
+            STATE.store(FAILED, Ordering::Release);
+        }
+    }
+}
+
+fn run_all() -> Result<(), error::Unspecified> {
+    sha2()?;
+    hmac_sha256()?;
+    hkdf_sha256()?;
+    pbkdf2_sha256()?;
+    aes_128_gcm()?;
+    chacha20_poly1305()?;
+    ecdsa_p256()?;
+    ecdh_p256()?;
+    ed25519()?;
+    rsa()?;
+    Ok(())
+}
+
+// From tests/digest_tests.txt.
+fn sha2() -> Result<(), error::Unspecified> {
+    const SHA256_ABC: &'static str =
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    const SHA384_ABC: &'static str = concat!(
+        "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed",
+        "8086072ba1e7cc2358baeca134c825a7");
+    const SHA512_ABC: &'static str = concat!(
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a",
+        "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f");
+
+    for &(alg, expected) in &[(&digest::SHA256, SHA256_ABC),
+                              (&digest::SHA384, SHA384_ABC),
+                              (&digest::SHA512, SHA512_ABC)] {
+        let mut buf = [0u8; digest::MAX_OUTPUT_LEN];
+        let expected = from_hex(expected, &mut buf)?;
+        let actual = digest::digest(alg, b"abc");
+        constant_time::verify_slices_are_equal(actual.as_ref(), expected)?;
+    }
+    Ok(())
+}
+
+// From tests/hmac_tests.txt.
+fn hmac_sha256() -> Result<(), error::Unspecified> {
+    const KEY: &'static str = concat!(
+        "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        "202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f");
+    const INPUT: &'static [u8] = b"Sample message for keylen=blocklen";
+    const OUTPUT: &'static str =
+        "8bb9a1db9806f20df7f77b82138c7914d174d59e13dc4d0169c9057b133e1d62";
+
+    let mut key = [0u8; 64];
+    let key = hmac::SigningKey::new(&digest::SHA256, from_hex(KEY, &mut key)?);
+    let mut expected = [0u8; digest::SHA256_OUTPUT_LEN];
+    let expected = from_hex(OUTPUT, &mut expected)?;
+    let actual = hmac::sign(&key, INPUT);
+    constant_time::verify_slices_are_equal(actual.as_ref(), expected)
+}
+
+// RFC 5869 Test Case 1, from tests/hkdf_tests.txt.
+fn hkdf_sha256() -> Result<(), error::Unspecified> {
+    const IKM: &'static str = "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b";
+    const SALT: &'static str = "000102030405060708090a0b0c";
+    const INFO: &'static str = "f0f1f2f3f4f5f6f7f8f9";
+    const OKM: &'static str = concat!(
+        "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf",
+        "34007208d5b887185865");
+
+    let mut ikm = [0u8; 22];
+    let mut salt = [0u8; 13];
+    let mut info = [0u8; 10];
+    let mut expected = [0u8; 42];
+    let salt = hmac::SigningKey::new(&digest::SHA256,
+                                     from_hex(SALT, &mut salt)?);
+    let expected = from_hex(OKM, &mut expected)?;
+    let mut actual = [0u8; 42];
+    hkdf::extract_and_expand(&salt, from_hex(IKM, &mut ikm)?,
+                             from_hex(INFO, &mut info)?, &mut actual);
+    constant_time::verify_slices_are_equal(&actual, expected)
+}
+
+// From tests/pbkdf2_tests.txt.
+fn pbkdf2_sha256() -> Result<(), error::Unspecified> {
+    const DK: &'static str =
+        "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43";
+
+    let mut expected = [0u8; digest::SHA256_OUTPUT_LEN];
+    let expected = from_hex(DK, &mut expected)?;
+    let mut actual = [0u8; digest::SHA256_OUTPUT_LEN];
+    pbkdf2::derive(&digest::SHA256, 2, b"salt", b"password", &mut actual);
+    constant_time::verify_slices_are_equal(&actual, expected)
+}
+
+// From tests/aead_aes_128_gcm_tests.txt.
+fn aes_128_gcm() -> Result<(), error::Unspecified> {
+    const KEY: &'static str = "3881e7be1bb3bbcaff20bdb78e5d1b67";
+    const NONCE: &'static str = "dcf5b7ae2d7552e2297fcfa9";
+    const IN: &'static str = "0a2714aa7d";
+    const AD: &'static str = "c60c64bbf7";
+    const CT_AND_TAG: &'static str =
+        concat!("5626f96ecb", "ff4c4f1d92b0abb1d0820833d9eb83c7");
+
+    let mut key = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    let mut in_ = [0u8; 5];
+    let mut ad = [0u8; 5];
+    let mut expected = [0u8; 5 + 16];
+    aead_kat(&aead::AES_128_GCM, from_hex(KEY, &mut key)?,
+             from_hex(NONCE, &mut nonce)?, from_hex(AD, &mut ad)?,
+             from_hex(IN, &mut in_)?, from_hex(CT_AND_TAG, &mut expected)?)
+}
+
+// RFC 7539 Section 2.8.2, from tests/aead_chacha20_poly1305_tests.txt.
+fn chacha20_poly1305() -> Result<(), error::Unspecified> {
+    const KEY: &'static str =
+        "808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f";
+    const NONCE: &'static str = "070000004041424344454647";
+    const IN: &'static [u8] =
+        b"Ladies and Gentlemen of the class of '99: If I could offer you \
+          only one tip for the future, sunscreen would be it.";
+    const AD: &'static str = "50515253c0c1c2c3c4c5c6c7";
+    const CT_AND_TAG: &'static str = concat!(
+        "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6",
+        "3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36",
+        "92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc",
+        "3ff4def08e4b7a9de576d26586cec64b6116",
+        "1ae10b594f09e26a7e902ecbd0600691");
+
+    let mut key = [0u8; 32];
+    let mut nonce = [0u8; 12];
+    let mut ad = [0u8; 12];
+    let mut expected = [0u8; 114 + 16];
+    aead_kat(&aead::CHACHA20_POLY1305, from_hex(KEY, &mut key)?,
+             from_hex(NONCE, &mut nonce)?, from_hex(AD, &mut ad)?, IN,
+             from_hex(CT_AND_TAG, &mut expected)?)
+}
+
+fn aead_kat(alg: &'static aead::Algorithm, key: &[u8], nonce: &[u8],
+            ad: &[u8], plaintext: &[u8], ct_and_tag: &[u8])
+            -> Result<(), error::Unspecified> {
+    let mut in_out = [0u8; 114 + aead::MAX_TAG_LEN];
+    let in_out = &mut in_out[..(plaintext.len() + alg.tag_len())];
+    in_out[..plaintext.len()].copy_from_slice(plaintext);
+
+    let sealing_key = aead::SealingKey::new(alg, key)?;
+    let len = aead::seal_in_place(&sealing_key, nonce, ad, in_out,
+                                  alg.tag_len())?;
+    constant_time::verify_slices_are_equal(&in_out[..len], ct_and_tag)?;
+
+    let opening_key = aead::OpeningKey::new(alg, key)?;
+    let opened = aead::open_in_place(&opening_key, nonce, ad, 0, in_out)?;
+    constant_time::verify_slices_are_equal(opened, plaintext)
+}
+
+// From tests/ecdsa_verify_fixed_tests.txt.
+fn ecdsa_p256() -> Result<(), error::Unspecified> {
+    const Q: &'static str = concat!(
+        "0430345fd47ea21a11129be651b0884bfac698377611acc9f689458e13b9ed7d",
+        "4b9d7599a68dcf125e7f31055ccb374cd04f6d6fd2b217438a63f6f667d50ef2",
+        "f0");
+    const SIG: &'static str = concat!(
+        "341f6779b75e98bb42e01095dd48356cbf9002dc704ac8bd2a8240b88d3796c6",
+        "555843b1b4e264fe6ffe6e2b705a376c05c09404303ffe5d2711f3e3b3a010a1");
+
+    let mut q = [0u8; 65];
+    let mut sig = [0u8; 64];
+    signature::verify(&signature::ECDSA_P256_SHA256_FIXED,
+                      untrusted::Input::from(from_hex(Q, &mut q)?),
+                      untrusted::Input::from(b""),
+                      untrusted::Input::from(from_hex(SIG, &mut sig)?))
+}
+
+// From tests/agreement_tests.txt.
+fn ecdh_p256() -> Result<(), error::Unspecified> {
+    const PEER_Q: &'static str = concat!(
+        "04d12dfb5289c8d4f81208b70270398c342296970a0bccb74c736fc7554494bf",
+        "6356fbf3ca366cc23e8157854c13c58d6aac23f046ada30f8353e74f33039872",
+        "ab");
+    const D: &'static str =
+        "c88f01f510d9ac3f70a292daa2316de544e9aab8afe84049c62a9c57862d1433";
+    const MY_Q: &'static str = concat!(
+        "04dad0b65394221cf9b051e1feca5787d098dfe637fc90b9ef945d0c37725811",
+        "805271a0461cdb8252d61f1c456fa3e59ab1f45b33accf5f58389e0577b8990b",
+        "b3");
+    const OUTPUT: &'static str =
+        "d6840f6b42f6edafd13116e0e12565202fef8e9ece7dce03812464d04b9442de";
+
+    let mut d = [0u8; 32];
+    let rng = FixedSliceRandom { bytes: from_hex(D, &mut d)? };
+    let private_key =
+        agreement::EphemeralPrivateKey::generate(&agreement::ECDH_P256, &rng)?;
+
+    let mut expected = [0u8; 65];
+    let expected = from_hex(MY_Q, &mut expected)?;
+    let mut my_q = [0u8; 65];
+    private_key.compute_public_key(&mut my_q)?;
+    constant_time::verify_slices_are_equal(&my_q, expected)?;
+
+    let mut peer_q = [0u8; 65];
+    let peer_q = untrusted::Input::from(from_hex(PEER_Q, &mut peer_q)?);
+    let mut expected = [0u8; 32];
+    let expected = from_hex(OUTPUT, &mut expected)?;
+    agreement::agree_ephemeral(private_key, &agreement::ECDH_P256, peer_q,
+                               error::Unspecified, |shared_key| {
+        constant_time::verify_slices_are_equal(shared_key, expected)
+    })
+}
+
+// RFC 8032 Section 7.1 TEST SHA(abc), from tests/ed25519_tests.txt.
+fn ed25519() -> Result<(), error::Unspecified> {
+    const SEED: &'static str =
+        "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42";
+    const PUB: &'static str =
+        "ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf";
+    const MESSAGE: &'static str = concat!(
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a",
+        "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f");
+    const SIG: &'static str = concat!(
+        "dc2a4459e7369633a52b1bf277839a00201009a3efbf3ecb69bea2186c26b589",
+        "09351fc9ac90b3ecfdfbc7c66431e0303dca179c138ac17ad9bef1177331a704");
+
+    let mut seed = [0u8; 32];
+    let mut public_key = [0u8; 32];
+    let mut msg = [0u8; 64];
+    let mut expected = [0u8; 64];
+    let public_key = untrusted::Input::from(from_hex(PUB, &mut public_key)?);
+    let msg = from_hex(MESSAGE, &mut msg)?;
+    let expected = from_hex(SIG, &mut expected)?;
+
+    let key_pair = signature::Ed25519KeyPair::from_seed_and_public_key(
+        untrusted::Input::from(from_hex(SEED, &mut seed)?), public_key)?;
+    let actual = key_pair.sign(msg);
+    constant_time::verify_slices_are_equal(actual.as_ref(), expected)?;
+
+    signature::verify(&signature::ED25519, public_key,
+                      untrusted::Input::from(msg),
+                      untrusted::Input::from(expected))
+}
+
+// The first SHA-256 test vector in tests/rsa_pkcs1_sign_tests.txt. `RSA_N` and
+// `RSA_E` are the public components of `RSA_KEY`.
+fn rsa() -> Result<(), error::Unspecified> {
+    let mut n = [0u8; 256];
+    let mut e = [0u8; 3];
+    let mut msg = [0u8; 128];
+    let mut expected = [0u8; 256];
+    let n = from_hex(RSA_N, &mut n)?;
+    let e = from_hex(RSA_E, &mut e)?;
+    let msg = from_hex(RSA_MSG, &mut msg)?;
+    let expected = from_hex(RSA_SIG, &mut expected)?;
+
+    signature::primitive::verify_rsa(
+        &signature::RSA_PKCS1_2048_8192_SHA256,
+        (untrusted::Input::from(n), untrusted::Input::from(e)),
+        untrusted::Input::from(msg), untrusted::Input::from(expected))?;
+
+    rsa_sign(msg, expected)
+}
+
+#[cfg(feature = "rsa_signing")]
+fn rsa_sign(msg: &[u8], expected: &[u8]) -> Result<(), error::Unspecified> {
+    let mut key = [0u8; 1192];
+    let key = signature::RSAKeyPair::from_der(
+        untrusted::Input::from(from_hex(RSA_KEY, &mut key)?))?;
+    let mut signing_state =
+        signature::RSASigningState::new(std::sync::Arc::new(key))?;
+    let mut actual = [0u8; 256];
+    signing_state.sign(&signature::RSA_PKCS1_SHA256, &rand::SystemRandom::new(),
+                       msg, &mut actual)?;
+    constant_time::verify_slices_are_equal(&actual, expected)
+}
+
+#[cfg(not(feature = "rsa_signing"))]
+fn rsa_sign(_msg: &[u8], _expected: &[u8]) -> Result<(), error::Unspecified> {
+    Ok(())
+}
+
+#[cfg(feature = "rsa_signing")]
+const RSA_KEY: &'static str = concat!(
+    "308204a40201000282010100cea80475324c1dc8347827818da58bac069d3419",
+    "c614a6ea1ac6a3b510dcd72cc516954905e9fef908d45e13006adf27d467a7d8",
+    "3c111d1a5df15ef293771aefb920032a5bb989f8e4f5e1b05093d3f130f984c0",
+    "7a772a3683f4dc6fb28a96815b32123ccdd13954f19d5b8b24a103e771a34c32",
+    "8755c65ed64e1924ffd04d30b2142cc262f6e0048fef6dbc652f21479ea1c4b1",
+    "d66d28f4d46ef7185e390cbfa2e02380582f3188bb94ebbf05d31487a09aff01",
+    "fcbb4cd4bfd1f0a833b38c11813c84360bb53c7d4481031c40bad8713bb6b835",
+    "cb08098ed15ba31ee4ba728a8c8e10f7294e1b4163b7aee57277bfd881a6f9d4",
+    "3e02c6925aa3a043fb7fb78d0203260445028201000997634c477c1a039d44c8",
+    "10b2aaa3c7862b0b88d3708272e1e15f66fc9389709f8a11f3ea6a5af7effa2d",
+    "01c189c50f0d5bcbe3fa272e56cfc4a4e1d388a9dcd65df8628902556c8b6bb6",
+    "a641709b5a35dd2622c73d4640bfa1359d0e76e1f219f8e33eb9bd0b59ec198e",
+    "b2fccaae0346bd8b401e12e3c67cb629569c185a2e0f35a2f741644c1cca5ebb",
+    "139d77a89a2953fc5e30048c0e619f07c8d21d1e56b8af07193d0fdf3f49cd49",
+    "f2ef3138b5138862f1470bd2d16e34a2b9e7777a6c8c8d4cb94b4e8b5d616cd5",
+    "393753e7b0f31cc7da559ba8e98d888914e334773baf498ad88d9631eb5fe32e",
+    "53a4145bf0ba548bf2b0a50c63f67b14e398a34b0d02818100f364e16ef12017",
+    "ec95b192308c01e087cee619ab50a5d537cc01841dc92b30bcef0d9f2c6bbd5d",
+    "c10bdf5b9f6c354a4f9f210520caa72b4f5c36b8d33f10324c55956141891e45",
+    "b84b49f59ea5bfac6ffa38900aca5099afcd02f6a8257c41ce5bb2e4153832b5",
+    "c22f91eb389fa2035c3cf9b3374531c483cb30ceb007259b1d02818100d95c09",
+    "95fabdfcbccfe63e0f3262f806869ab571e1793e97234cbb9bd4b6872a769538",
+    "9955cf6ce7245345a5df8021f7d9519563afbc2667f5311fad093de2c02cd069",
+    "109b630d68e3bf767f8a788a6add7ab199f2d8f6a40b7c1910d9dab52ac80d0d",
+    "333aacab321a9309dc884ddd4db637a0c1115ae3c08efa683f99eb7331028181",
+    "00d4f7ef9f9be947ba9d1b3bce59e5608839a1e464553e1b6d113d0f636758bb",
+    "b473a89f9949836ead40b6f314eee3ac2244d7b6f379e83f30e17783ad68d508",
+    "6897889c051c26e1558a4a220bfc242995860644b5d7a3ef513ac612b9c6c0a2",
+    "021bb6b9cde7dbd21fe5858746c79563e9bab7d06b43aab43a0a5cafab4519a6",
+    "610281803db2386f174f2ea3ef4b6bd1601749ce2d6afa8be35f051178621f16",
+    "a23ad36eba03c073136389241969e5b87edb0fcbcf1a0bd6e1aee97bae1f2d97",
+    "aabe19b17dbe7d9492cdb68a0897f572350e846c669660dc978c5068da598524",
+    "fca8a136358d3e5f8f6ad5cf78d9089c93f473189162ce0f8c4902a19902b633",
+    "b3e6926d02818100ddc971183dcf3450c43e06ba2af32379eedeb2d678513fb7",
+    "06b75a006098154041f4b09e6be385d4b25d80ec241c899e4a986a17b0a121da",
+    "ab91a1e4fc5a1802a7074df3fb3f7661f0e1c97799e36d21de937cc4209585db",
+    "30a56af0a228e001036ed792625e5368ce101574a2e9767f07338949f0afdf35",
+    "8cecd18c6d6f3f55",
+);
+
+const RSA_N: &'static str = concat!(
+    "cea80475324c1dc8347827818da58bac069d3419c614a6ea1ac6a3b510dcd72c",
+    "c516954905e9fef908d45e13006adf27d467a7d83c111d1a5df15ef293771aef",
+    "b920032a5bb989f8e4f5e1b05093d3f130f984c07a772a3683f4dc6fb28a9681",
+    "5b32123ccdd13954f19d5b8b24a103e771a34c328755c65ed64e1924ffd04d30",
+    "b2142cc262f6e0048fef6dbc652f21479ea1c4b1d66d28f4d46ef7185e390cbf",
+    "a2e02380582f3188bb94ebbf05d31487a09aff01fcbb4cd4bfd1f0a833b38c11",
+    "813c84360bb53c7d4481031c40bad8713bb6b835cb08098ed15ba31ee4ba728a",
+    "8c8e10f7294e1b4163b7aee57277bfd881a6f9d43e02c6925aa3a043fb7fb78d",
+);
+
+const RSA_E: &'static str = "260445";
+
+const RSA_MSG: &'static str = concat!(
+    "5af283b1b76ab2a695d794c23b35ca7371fc779e92ebf589e304c7f923d8cf97",
+    "6304c19818fcd89d6f07c8d8e08bf371068bdf28ae6ee83b2e02328af8c0e2f9",
+    "6e528e16f852f1fc5455e4772e288a68f159ca6bdcf902b858a1f94789b31638",
+    "23e2d0717ff56689eec7d0e54d93f520d96e1eb04515abc70ae90578ff38d31b",
+);
+
+const RSA_SIG: &'static str = concat!(
+    "6b8be97d9e518a2ede746ff4a7d91a84a1fc665b52f154a927650db6e7348c69",
+    "f8c8881f7bcf9b1a6d3366eed30c3aed4e93c203c43f5528a45de791895747ad",
+    "e9c5fa5eee81427edee02082147aa311712a6ad5fb1732e93b3d6cd23ffd46a0",
+    "b3caf62a8b69957cc68ae39f9993c1a779599cdda949bdaababb77f248fcfeaa",
+    "44059be5459fb9b899278e929528ee130facd53372ecbc42f3e8de2998425860",
+    "406440f248d817432de687112e504d734028e6c5620fa282ca07647006cf0a2f",
+    "f83e19a916554cc61810c2e855305db4e5cf893a6a96767365794556ff033359",
+    "084d7e38a8456e68e21155b76151314a29875feee09557161cbc654541e89e42",
+);
+
+// A `SecureRandom` that returns a fixed private key, like
+// `test::rand::FixedSliceRandom`.
+struct FixedSliceRandom<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> rand::SecureRandom for FixedSliceRandom<'a> {
+    fn fill(&self, dest: &mut [u8]) -> Result<(), error::Unspecified> {
+        if dest.len() != self.bytes.len() {
+            return Err(error::Unspecified);
+        }
+        dest.copy_from_slice(self.bytes);
+        Ok(())
+    }
+}
+
+// Decodes `hex` into the start of `out`.
+fn from_hex<'a>(hex: &str, out: &'a mut [u8])
+                -> Result<&'a [u8], error::Unspecified> {
+    let hex = hex.as_bytes();
+    if hex.len() % 2 != 0 || hex.len() / 2 > out.len() {
+        return Err(error::Unspecified);
+    }
+    let out = &mut out[..(hex.len() / 2)];
+    for (out, digits) in out.iter_mut().zip(hex.chunks(2)) {
+        let hi = from_hex_digit(digits[0])?;
+        let lo = from_hex_digit(digits[1])?;
+        *out = (hi * 0x10) | lo;
+    }
+    Ok(out)
+}
+
+fn from_hex_digit(d: u8) -> Result<u8, error::Unspecified> {
+    if d >= b'0' && d <= b'9' {
+        Ok(d - b'0')
+    } else if d >= b'a' && d <= b'f' {
+        Ok(d - b'a' + 10u8)
+    } else {
+        Err(error::Unspecified)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_tests_pass() {
+        assert!(run().is_ok());
+        assert_eq!(status(), Status::Passed);
+        check();
+    }
+
+    #[test]
+    fn test_from_hex() {
+        let mut buf = [0u8; 4];
+        assert_eq!(from_hex("00a0ff", &mut buf).unwrap(), &[0x00, 0xa0, 0xff]);
+        assert!(from_hex("0", &mut buf).is_err());
+        assert!(from_hex("0g", &mut buf).is_err());
+        assert!(from_hex("0000000000", &mut buf).is_err());
+    }
+}
@@ -215,7 +215,7 @@
 //! let private_key_der = read_file(private_key_path)?;
 //! let private_key_der = untrusted::Input::from(&private_key_der);
 //! let key_pair = signature::RSAKeyPair::from_der(private_key_der)
-//!     .map_err(|ring::error::Unspecified| MyError::BadPrivateKey)?;
+//!     .map_err(|_: ring::error::KeyRejected| MyError::BadPrivateKey)?;
 //!
 //! // Create a signing state.
 //! let key_pair = std::sync::Arc::new(key_pair);
@@ -328,10 +328,8 @@ pub use rsa::{
     RSA_PSS_SHA512,
 };
 
-#[cfg(feature = "use_heap")]
 pub use rsa::RSAParameters;
 
-#[cfg(feature = "use_heap")]
 pub use rsa::verification::{
     RSA_PKCS1_2048_8192_SHA1,
     RSA_PKCS1_2048_8192_SHA256,
@@ -350,7 +348,6 @@ pub use signature_impl::Signature;
 /// Lower-level verification primitives. Usage of `ring::signature::verify()`
 /// is preferred when the public key and signature are encoded in standard
 /// formats, as it also handles the parsing.
-#[cfg(feature = "use_heap")]
 pub mod primitive {
     pub use rsa::verification::verify_rsa;
 }
@@ -380,7 +377,6 @@ pub trait VerificationAlgorithm: core::fmt::Debug + Sync + private::Private {
 ///     InvalidSignature,
 /// }
 ///
-/// # #[cfg(feature = "use_heap")]
 /// fn verify_rsa_pkcs1_sha256(public_key: untrusted::Input,
 ///                            msg: untrusted::Input, sig: untrusted::Input)
 ///                            -> Result<(), Error> {
@@ -0,0 +1,541 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! SSH binary packet protection, as specified in [RFC 4253 Section 6], using
+//! the [chacha20-poly1305@openssh.com] and [aes256-gcm@openssh.com] ciphers.
+//!
+//! `PacketSealer` builds complete packets, i.e.
+//! `packet_length||padding_length||payload||random padding||mac`, and
+//! `PacketOpener` parses them, so that callers only deal with payloads. Each
+//! one tracks the sequence number of its direction of the connection, and
+//! pads packets to, or checks that they are aligned to, the cipher's block
+//! size.
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::{rand, ssh};
+//!
+//! # fn main() { f().unwrap() }
+//! # fn f() -> Result<(), ring::error::Unspecified> {
+//! let rng = rand::SystemRandom::new();
+//! let key = [0x42; 64];
+//!
+//! let mut sealer =
+//!     ssh::PacketSealer::new(&ssh::CHACHA20_POLY1305_OPENSSH, &key, &[], 3)?;
+//! let mut opener =
+//!     ssh::PacketOpener::new(&ssh::CHACHA20_POLY1305_OPENSSH, &key, &[], 3)?;
+//!
+//! let payload = b"\x05\x00\x00\x00\x0cssh-userauth";
+//! let mut packet = vec![0u8; sealer.sealed_len(payload.len())?];
+//! sealer.seal_packet(payload, &rng, &mut packet)?;
+//!
+//! // The receiver first reads the `packet_length` field to find out how much
+//! // more it has to read.
+//! let mut packet_length = [0u8; ssh::PACKET_LENGTH_LEN];
+//! packet_length.copy_from_slice(&packet[..ssh::PACKET_LENGTH_LEN]);
+//! assert_eq!(opener.packet_len(&packet_length)?, packet.len());
+//!
+//! assert_eq!(opener.open_packet(&mut packet)?, &payload[..]);
+//! assert_eq!(opener.sequence_number(), 4);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [RFC 4253 Section 6]: https://tools.ietf.org/html/rfc4253#section-6
+//! [chacha20-poly1305@openssh.com]:
+//!    http://cvsweb.openbsd.org/cgi-bin/cvsweb/src/usr.bin/ssh/PROTOCOL.chacha20poly1305?annotate=HEAD
+//! [aes256-gcm@openssh.com]: https://tools.ietf.org/html/rfc5647
+
+use {aead, core, error, polyfill, rand};
+use aead::chacha20_poly1305_openssh as chacha20_poly1305;
+
+/// An SSH cipher.
+pub struct Algorithm {
+    key_len: usize,
+    iv_len: usize,
+    block_len: usize,
+    max_packets: u64,
+    id: AlgorithmID,
+}
+
+/// chacha20-poly1305@openssh.com.
+///
+/// The key is 64 bytes long and there is no IV. The sequence number is used
+/// as the nonce, so a key can protect at most `2**32` packets.
+pub static CHACHA20_POLY1305_OPENSSH: Algorithm = Algorithm {
+    key_len: chacha20_poly1305::KEY_LEN,
+    iv_len: 0,
+    block_len: 8,
+    max_packets: 1 << 32,
+    id: AlgorithmID::CHACHA20_POLY1305_OPENSSH,
+};
+
+/// aes256-gcm@openssh.com, i.e. `AEAD_AES_256_GCM` as used in
+/// [RFC 5647].
+///
+/// The key is 32 bytes long and the IV is 12 bytes long. The nonce is the
+/// IV's fixed field followed by its invocation counter, which is incremented
+/// for every packet. The `packet_length` field is sent unencrypted.
+///
+/// [RFC 5647]: https://tools.ietf.org/html/rfc5647
+pub static AES256_GCM_OPENSSH: Algorithm = Algorithm {
+    key_len: 256 / 8,
+    iv_len: GCM_IV_LEN,
+    block_len: 16,
+    max_packets: core::u64::MAX,
+    id: AlgorithmID::AES256_GCM_OPENSSH,
+};
+
+impl Algorithm {
+    /// The length of the key.
+    #[inline(always)]
+    pub fn key_len(&self) -> usize { self.key_len }
+
+    /// The length of the IV, which is zero if the cipher doesn't use one.
+    #[inline(always)]
+    pub fn iv_len(&self) -> usize { self.iv_len }
+
+    // Returns `(packet_length, padding_length)` for a payload of
+    // `payload_len` bytes, using the least padding that is allowed.
+    //
+    // Like OpenSSH, for both ciphers this aligns `packet_length` itself, i.e.
+    // the packet without the `packet_length` field and the MAC, to the block
+    // size.
+    fn packet_length(&self, payload_len: usize)
+                     -> Result<(usize, usize), error::Unspecified> {
+        let block_len = polyfill::u64_from_usize(self.block_len);
+        let unpadded_len = polyfill::u64_from_usize(payload_len)
+            .checked_add(polyfill::u64_from_usize(PADDING_LENGTH_LEN))
+            .ok_or(error::Unspecified)?;
+        let mut padding_len = block_len - (unpadded_len % block_len);
+        if padding_len < polyfill::u64_from_usize(MIN_PADDING_LEN) {
+            padding_len += block_len;
+        }
+        let packet_length =
+            unpadded_len.checked_add(padding_len).ok_or(error::Unspecified)?;
+        if packet_length > MAX_PACKET_LENGTH {
+            return Err(error::Unspecified);
+        }
+        Ok((packet_length as usize, padding_len as usize))
+    }
+}
+
+derive_debug_from_field!(Algorithm, id);
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Eq, PartialEq)]
+enum AlgorithmID {
+    CHACHA20_POLY1305_OPENSSH,
+    AES256_GCM_OPENSSH,
+}
+
+impl PartialEq for Algorithm {
+    fn eq(&self, other: &Self) -> bool { self.id == other.id }
+}
+
+impl Eq for Algorithm {}
+
+/// Seals (encrypts and signs) the packets sent in one direction of a
+/// connection.
+pub struct PacketSealer {
+    algorithm: &'static Algorithm,
+
+    // Exactly one of these is `Some`, depending on `algorithm`.
+    chacha20_poly1305: Option<chacha20_poly1305::SealingKey>,
+    aes_gcm: Option<(aead::SealingKey, Iv)>,
+
+    sequence: Sequence,
+}
+
+impl PacketSealer {
+    /// Constructs a new `PacketSealer`.
+    ///
+    /// `key` and `iv` must be `algorithm.key_len()` and `algorithm.iv_len()`
+    /// bytes long. `sequence_number` is the sequence number of the first
+    /// packet to be sealed. Unless strict key exchange is used, sequence
+    /// numbers are not reset when new keys are taken into use.
+    pub fn new(algorithm: &'static Algorithm, key: &[u8], iv: &[u8],
+               sequence_number: u32)
+               -> Result<PacketSealer, error::Unspecified> {
+        let (chacha20_poly1305, aes_gcm) = match algorithm.id {
+            AlgorithmID::CHACHA20_POLY1305_OPENSSH => {
+                check_iv_len(algorithm, iv)?;
+                let key =
+                    slice_as_array_ref!(key, chacha20_poly1305::KEY_LEN)?;
+                (Some(chacha20_poly1305::SealingKey::new(key)), None)
+            },
+            AlgorithmID::AES256_GCM_OPENSSH => {
+                let key = aead::SealingKey::new(&aead::AES_256_GCM, key)?;
+                (None, Some((key, Iv::new(iv)?)))
+            },
+        };
+        Ok(PacketSealer {
+            algorithm,
+            chacha20_poly1305,
+            aes_gcm,
+            sequence: Sequence::new(algorithm, sequence_number),
+        })
+    }
+
+    /// The cipher.
+    #[inline(always)]
+    pub fn algorithm(&self) -> &'static Algorithm { self.algorithm }
+
+    /// The sequence number of the next packet to be sealed.
+    #[inline(always)]
+    pub fn sequence_number(&self) -> u32 { self.sequence.sequence_number }
+
+    /// The length of the packet that `seal_packet` produces for a payload of
+    /// `payload_len` bytes.
+    pub fn sealed_len(&self, payload_len: usize)
+                      -> Result<usize, error::Unspecified> {
+        let (packet_length, _) = self.algorithm.packet_length(payload_len)?;
+        Ok(PACKET_LENGTH_LEN + packet_length + TAG_LEN)
+    }
+
+    /// Builds and seals a packet containing `payload`.
+    ///
+    /// The packet is padded with the fewest random bytes, taken from `rng`,
+    /// that RFC 4253 allows. `out` must be at least
+    /// `self.sealed_len(payload.len())` bytes long. Returns the packet, which
+    /// is written to the start of `out`.
+    ///
+    /// Once the key has sealed as many packets as the cipher allows, every
+    /// call fails; the connection must be rekeyed before then.
+    pub fn seal_packet<'o>(&mut self, payload: &[u8],
+                           rng: &rand::SecureRandom, out: &'o mut [u8])
+                           -> Result<&'o [u8], error::Unspecified> {
+        self.sequence.check()?;
+        let (packet_length, padding_len) =
+            self.algorithm.packet_length(payload.len())?;
+        let sealed_len = PACKET_LENGTH_LEN + packet_length + TAG_LEN;
+        if out.len() < sealed_len {
+            return Err(error::Unspecified);
+        }
+        let out = &mut out[..sealed_len];
+
+        {
+            let (header, rest) =
+                out.split_at_mut(PACKET_LENGTH_LEN + PADDING_LENGTH_LEN);
+            header[..PACKET_LENGTH_LEN].copy_from_slice(
+                &polyfill::slice::be_u8_from_u32(packet_length as u32));
+            header[PACKET_LENGTH_LEN] = padding_len as u8;
+            let (payload_out, rest) = rest.split_at_mut(payload.len());
+            payload_out.copy_from_slice(payload);
+            rng.fill(&mut rest[..padding_len])?;
+        }
+
+        match (&self.chacha20_poly1305, &mut self.aes_gcm) {
+            (&Some(ref key), _) => {
+                let (packet, tag) =
+                    out.split_at_mut(PACKET_LENGTH_LEN + packet_length);
+                let tag = slice_as_array_ref_mut!(tag, TAG_LEN)?;
+                key.seal_in_place(self.sequence.sequence_number, packet, tag);
+            },
+            (_, &mut Some((ref key, ref mut iv))) => {
+                let (packet_length, in_out) =
+                    out.split_at_mut(PACKET_LENGTH_LEN);
+                let _ = aead::seal_in_place(key, &iv.0, packet_length, in_out,
+                                            TAG_LEN)?;
+                iv.advance();
+            },
+            _ => unreachable!(),
+        }
+        self.sequence.advance();
+
+        Ok(out)
+    }
+}
+
+/// Opens (authenticates and decrypts) the packets received in one direction
+/// of a connection.
+pub struct PacketOpener {
+    algorithm: &'static Algorithm,
+
+    // Exactly one of these is `Some`, depending on `algorithm`.
+    chacha20_poly1305: Option<chacha20_poly1305::OpeningKey>,
+    aes_gcm: Option<(aead::OpeningKey, Iv)>,
+
+    sequence: Sequence,
+    max_packet_len: usize,
+}
+
+impl PacketOpener {
+    /// Constructs a new `PacketOpener` that accepts packets of up to
+    /// `DEFAULT_MAX_PACKET_LEN` bytes.
+    ///
+    /// `key` and `iv` must be `algorithm.key_len()` and `algorithm.iv_len()`
+    /// bytes long. `sequence_number` is the sequence number of the first
+    /// packet to be opened. Unless strict key exchange is used, sequence
+    /// numbers are not reset when new keys are taken into use.
+    #[inline]
+    pub fn new(algorithm: &'static Algorithm, key: &[u8], iv: &[u8],
+               sequence_number: u32)
+               -> Result<PacketOpener, error::Unspecified> {
+        Self::with_max_packet_len(algorithm, key, iv, sequence_number,
+                                  DEFAULT_MAX_PACKET_LEN)
+    }
+
+    /// Like `new`, but the opener rejects packets that are longer than
+    /// `max_packet_len` bytes, including `packet_length` and the MAC.
+    pub fn with_max_packet_len(algorithm: &'static Algorithm, key: &[u8],
+                               iv: &[u8], sequence_number: u32,
+                               max_packet_len: usize)
+                               -> Result<PacketOpener, error::Unspecified> {
+        let (chacha20_poly1305, aes_gcm) = match algorithm.id {
+            AlgorithmID::CHACHA20_POLY1305_OPENSSH => {
+                check_iv_len(algorithm, iv)?;
+                let key =
+                    slice_as_array_ref!(key, chacha20_poly1305::KEY_LEN)?;
+                (Some(chacha20_poly1305::OpeningKey::new(key)), None)
+            },
+            AlgorithmID::AES256_GCM_OPENSSH => {
+                let key = aead::OpeningKey::new(&aead::AES_256_GCM, key)?;
+                (None, Some((key, Iv::new(iv)?)))
+            },
+        };
+        Ok(PacketOpener {
+            algorithm,
+            chacha20_poly1305,
+            aes_gcm,
+            sequence: Sequence::new(algorithm, sequence_number),
+            max_packet_len,
+        })
+    }
+
+    /// The cipher.
+    #[inline(always)]
+    pub fn algorithm(&self) -> &'static Algorithm { self.algorithm }
+
+    /// The sequence number of the next packet to be opened.
+    #[inline(always)]
+    pub fn sequence_number(&self) -> u32 { self.sequence.sequence_number }
+
+    /// Returns the length of the packet, including `packet_length` and the
+    /// MAC, given its first `PACKET_LENGTH_LEN` bytes.
+    ///
+    /// This fails if the packet would be longer than the opener's maximum
+    /// packet length, or if the packet can't be valid because of its length.
+    /// For chacha20-poly1305@openssh.com the length is decrypted, but it
+    /// isn't authenticated until the packet is opened with `open_packet`.
+    pub fn packet_len(&self, first_bytes: &[u8; PACKET_LENGTH_LEN])
+                      -> Result<usize, error::Unspecified> {
+        self.sequence.check()?;
+        let packet_length = match self.chacha20_poly1305 {
+            Some(ref key) =>
+                key.decrypt_packet_length(self.sequence.sequence_number,
+                                          *first_bytes),
+            None => *first_bytes,
+        };
+        let packet_length =
+            polyfill::slice::u32_from_be_u8(&packet_length) as usize;
+        if packet_length < PADDING_LENGTH_LEN + MIN_PADDING_LEN {
+            return Err(error::Unspecified);
+        }
+        if packet_length % self.algorithm.block_len != 0 {
+            return Err(error::Unspecified);
+        }
+        let packet_len = packet_length
+            .checked_add(PACKET_LENGTH_LEN + TAG_LEN)
+            .ok_or(error::Unspecified)?;
+        if packet_len > self.max_packet_len {
+            return Err(error::Unspecified);
+        }
+        Ok(packet_len)
+    }
+
+    /// Opens a packet and returns its payload.
+    ///
+    /// `packet` must be the entire packet, from `packet_length` through the
+    /// MAC; see `packet_len`. The payload is decrypted in place.
+    ///
+    /// A connection can't recover from a packet that can't be opened, so
+    /// after `open_packet` fails once, every later call fails too.
+    pub fn open_packet<'a>(&mut self, packet: &'a mut [u8])
+                           -> Result<&'a [u8], error::Unspecified> {
+        let result = self.open_packet_(packet);
+        match result {
+            Ok(_) => self.sequence.advance(),
+            Err(_) => self.sequence.packets_remaining = 0,
+        }
+        result
+    }
+
+    fn open_packet_<'a>(&mut self, packet: &'a mut [u8])
+                        -> Result<&'a [u8], error::Unspecified> {
+        if packet.len() < PACKET_LENGTH_LEN {
+            return Err(error::Unspecified);
+        }
+        let packet_len = self.packet_len(
+            slice_as_array_ref!(&packet[..PACKET_LENGTH_LEN],
+                                PACKET_LENGTH_LEN)?)?;
+        if packet.len() != packet_len {
+            return Err(error::Unspecified);
+        }
+
+        let plaintext: &[u8] =
+                match (&self.chacha20_poly1305, &mut self.aes_gcm) {
+            (&Some(ref key), _) => {
+                let (packet, tag) = packet.split_at_mut(packet_len - TAG_LEN);
+                let tag = slice_as_array_ref!(tag, TAG_LEN)?;
+                key.open_in_place(self.sequence.sequence_number, packet, tag)?
+            },
+            (_, &mut Some((ref key, ref mut iv))) => {
+                let (packet_length, in_out) =
+                    packet.split_at_mut(PACKET_LENGTH_LEN);
+                let plaintext =
+                    aead::open_in_place(key, &iv.0, packet_length, 0, in_out)?;
+                iv.advance();
+                plaintext
+            },
+            _ => unreachable!(),
+        };
+
+        // `packet_len` ensured that the `padding_length` field is present.
+        let padding_len = usize::from(plaintext[0]);
+        if padding_len < MIN_PADDING_LEN ||
+           padding_len > plaintext.len() - PADDING_LENGTH_LEN {
+            return Err(error::Unspecified);
+        }
+        Ok(&plaintext[PADDING_LENGTH_LEN..(plaintext.len() - padding_len)])
+    }
+}
+
+fn check_iv_len(algorithm: &Algorithm, iv: &[u8])
+                -> Result<(), error::Unspecified> {
+    if iv.len() != algorithm.iv_len {
+        return Err(error::Unspecified);
+    }
+    Ok(())
+}
+
+// The sequence number, and how many more packets the key may protect.
+struct Sequence {
+    sequence_number: u32,
+    packets_remaining: u64,
+}
+
+impl Sequence {
+    fn new(algorithm: &Algorithm, sequence_number: u32) -> Sequence {
+        Sequence {
+            sequence_number,
+            packets_remaining: algorithm.max_packets,
+        }
+    }
+
+    fn check(&self) -> Result<(), error::Unspecified> {
+        if self.packets_remaining == 0 {
+            return Err(error::Unspecified);
+        }
+        Ok(())
+    }
+
+    // RFC 4253 Section 6.4: The sequence number "wraps around to zero after
+    // every 2^32 packets."
+    fn advance(&mut self) {
+        self.sequence_number = self.sequence_number.wrapping_add(1);
+        self.packets_remaining -= 1;
+    }
+}
+
+// The aes256-gcm@openssh.com nonce: a 4-byte fixed field followed by an
+// 8-byte big-endian invocation counter.
+struct Iv([u8; GCM_IV_LEN]);
+
+impl Iv {
+    fn new(iv: &[u8]) -> Result<Iv, error::Unspecified> {
+        let mut r = Iv([0u8; GCM_IV_LEN]);
+        if iv.len() != r.0.len() {
+            return Err(error::Unspecified);
+        }
+        r.0.copy_from_slice(iv);
+        Ok(r)
+    }
+
+    // RFC 5647 Section 7.1: "the invocation_counter is treated as a 64-bit
+    // integer and is incremented after each invocation of AES-GCM".
+    fn advance(&mut self) {
+        for b in self.0[GCM_IV_FIXED_LEN..].iter_mut().rev() {
+            *b = b.wrapping_add(1);
+            if *b != 0 {
+                break;
+            }
+        }
+    }
+}
+
+/// The length of the `packet_length` field.
+pub const PACKET_LENGTH_LEN: usize = chacha20_poly1305::PACKET_LENGTH_LEN;
+
+/// The length of the MAC at the end of every packet.
+pub const TAG_LEN: usize = chacha20_poly1305::TAG_LEN;
+
+/// The default maximum length of a packet accepted by `PacketOpener`,
+/// including `packet_length` and the MAC.
+///
+/// [RFC 4253 Section 6.1] requires implementations to accept packets of up
+/// to 35000 bytes.
+///
+/// [RFC 4253 Section 6.1]: https://tools.ietf.org/html/rfc4253#section-6.1
+pub const DEFAULT_MAX_PACKET_LEN: usize = 35000;
+
+const PADDING_LENGTH_LEN: usize = 1;
+
+// RFC 4253 Section 6: "There MUST be at least four bytes of padding."
+const MIN_PADDING_LEN: usize = 4;
+
+// The largest value of `packet_length`, leaving room for `packet_length` and
+// the MAC so that the length of the whole packet fits in 32 bits.
+const MAX_PACKET_LENGTH: u64 =
+    0xffff_ffff - (PACKET_LENGTH_LEN + TAG_LEN) as u64;
+
+const GCM_IV_LEN: usize = 96 / 8;
+const GCM_IV_FIXED_LEN: usize = 4;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iv_advance() {
+        let mut iv = Iv([0x11, 0x22, 0x33, 0x44,
+                         0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0xff]);
+        iv.advance();
+        assert_eq!(&iv.0[GCM_IV_FIXED_LEN..], &[0, 0, 0, 0, 0, 0, 2, 0]);
+
+        // The invocation counter wraps around without touching the fixed
+        // field.
+        let mut iv = Iv([0x11, 0x22, 0x33, 0x44,
+                         0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+        iv.advance();
+        assert_eq!(iv.0, [0x11, 0x22, 0x33, 0x44, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_packet_length() {
+        // Neither cipher includes the `packet_length` field in the alignment,
+        // as in OpenSSH's packet.c.
+        let alg = &CHACHA20_POLY1305_OPENSSH;
+        assert_eq!(alg.packet_length(0).unwrap(), (8, 7));
+        assert_eq!(alg.packet_length(3).unwrap(), (8, 4));
+        assert_eq!(alg.packet_length(4).unwrap(), (16, 11));
+        assert_eq!(alg.packet_length(7).unwrap(), (16, 8));
+
+        let alg = &AES256_GCM_OPENSSH;
+        assert_eq!(alg.packet_length(0).unwrap(), (16, 15));
+        assert_eq!(alg.packet_length(11).unwrap(), (16, 4));
+        assert_eq!(alg.packet_length(12).unwrap(), (32, 19));
+    }
+}
@@ -0,0 +1,413 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! The TLS 1.3 key schedule.
+//!
+//! The key schedule is specified in [RFC 8446 Section 7]. The secrets are
+//! computed in order: an `EarlySecret` is extracted from the PSK (if any), the
+//! `EarlySecret` is turned into a `HandshakeSecret` by mixing in the (EC)DHE
+//! shared secret, and the `HandshakeSecret` is turned into a `MasterSecret`.
+//! Each stage derives the `Secret`s that RFC 8446 defines for it from a
+//! transcript hash, and each traffic `Secret` can then be used to derive the
+//! traffic key, the traffic IV, and the `finished` key for one direction.
+//!
+//! ```
+//! use ring::{aead, digest, tls13};
+//!
+//! # fn main() {
+//! # let ecdhe_shared_secret = [0u8; 32];
+//! # let client_hello_to_server_hello = digest::digest(&digest::SHA256, b"");
+//! let early_secret = tls13::EarlySecret::new(&digest::SHA256, None);
+//! let handshake_secret =
+//!     early_secret.into_handshake_secret(&ecdhe_shared_secret);
+//! let client_traffic_secret = handshake_secret
+//!     .client_handshake_traffic_secret(client_hello_to_server_hello.as_ref());
+//! let _key: aead::SealingKey =
+//!     client_traffic_secret.traffic_key(&aead::AES_128_GCM);
+//! let _iv = client_traffic_secret.traffic_iv();
+//! # }
+//! ```
+//!
+//! [RFC 8446 Section 7]: https://tools.ietf.org/html/rfc8446#section-7
+
+use {aead, digest, error, hkdf, hmac, polyfill, zeroize};
+
+/// The Early Secret.
+pub struct EarlySecret {
+    prk: hkdf::Prk,
+}
+
+impl EarlySecret {
+    /// Extracts the Early Secret from the given PSK, or from a string of
+    /// zeros if there is no PSK.
+    pub fn new(digest_alg: &'static digest::Algorithm, psk: Option<&[u8]>)
+               -> EarlySecret {
+        let zeros = [0u8; digest::MAX_OUTPUT_LEN];
+        let zeros = &zeros[..digest_alg.output_len];
+        let psk = psk.unwrap_or(zeros);
+        EarlySecret {
+            prk: hkdf::Salt::new(digest_alg, zeros).extract(psk),
+        }
+    }
+
+    /// `Derive-Secret(., "ext binder", "")`.
+    pub fn ext_binder_key(&self) -> Secret {
+        derive_secret_for_empty_transcript(&self.prk, b"ext binder")
+    }
+
+    /// `Derive-Secret(., "res binder", "")`.
+    pub fn res_binder_key(&self) -> Secret {
+        derive_secret_for_empty_transcript(&self.prk, b"res binder")
+    }
+
+    /// `Derive-Secret(., "c e traffic", ClientHello)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `client_hello_hash` isn't the length of the key
+    /// schedule's digest algorithm's output.
+    pub fn client_early_traffic_secret(&self,
+                                       client_hello_hash: &[u8])
+                                       -> Secret {
+        derive_secret(&self.prk, b"c e traffic", client_hello_hash)
+    }
+
+    /// `Derive-Secret(., "e exp master", ClientHello)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `client_hello_hash` isn't the length of the key
+    /// schedule's digest algorithm's output.
+    pub fn early_exporter_master_secret(&self,
+                                        client_hello_hash: &[u8])
+                                        -> Secret {
+        derive_secret(&self.prk, b"e exp master", client_hello_hash)
+    }
+
+    /// Mixes in the (EC)DHE shared secret to compute the Handshake Secret.
+    pub fn into_handshake_secret(self, ecdhe_shared_secret: &[u8])
+                                 -> HandshakeSecret {
+        HandshakeSecret { prk: next_stage(&self.prk, ecdhe_shared_secret) }
+    }
+
+    /// The key schedule's digest algorithm.
+    #[inline]
+    pub fn digest_algorithm(&self) -> &'static digest::Algorithm {
+        self.prk.digest_algorithm()
+    }
+}
+
+/// The Handshake Secret.
+pub struct HandshakeSecret {
+    prk: hkdf::Prk,
+}
+
+impl HandshakeSecret {
+    /// `Derive-Secret(., "c hs traffic", ClientHello...ServerHello)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript_hash` isn't the length of the key schedule's
+    /// digest algorithm's output.
+    pub fn client_handshake_traffic_secret(&self,
+                                           transcript_hash: &[u8])
+                                           -> Secret {
+        derive_secret(&self.prk, b"c hs traffic", transcript_hash)
+    }
+
+    /// `Derive-Secret(., "s hs traffic", ClientHello...ServerHello)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript_hash` isn't the length of the key schedule's
+    /// digest algorithm's output.
+    pub fn server_handshake_traffic_secret(&self,
+                                           transcript_hash: &[u8])
+                                           -> Secret {
+        derive_secret(&self.prk, b"s hs traffic", transcript_hash)
+    }
+
+    /// Computes the Master Secret.
+    pub fn into_master_secret(self) -> MasterSecret {
+        let zeros = [0u8; digest::MAX_OUTPUT_LEN];
+        let zeros = &zeros[..self.digest_algorithm().output_len];
+        MasterSecret { prk: next_stage(&self.prk, zeros) }
+    }
+
+    /// The key schedule's digest algorithm.
+    #[inline]
+    pub fn digest_algorithm(&self) -> &'static digest::Algorithm {
+        self.prk.digest_algorithm()
+    }
+}
+
+/// The Master Secret.
+pub struct MasterSecret {
+    prk: hkdf::Prk,
+}
+
+impl MasterSecret {
+    /// `Derive-Secret(., "c ap traffic", ClientHello...server Finished)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript_hash` isn't the length of the key schedule's
+    /// digest algorithm's output.
+    pub fn client_application_traffic_secret(
+            &self, transcript_hash: &[u8]) -> Secret {
+        derive_secret(&self.prk, b"c ap traffic", transcript_hash)
+    }
+
+    /// `Derive-Secret(., "s ap traffic", ClientHello...server Finished)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript_hash` isn't the length of the key schedule's
+    /// digest algorithm's output.
+    pub fn server_application_traffic_secret(
+            &self, transcript_hash: &[u8]) -> Secret {
+        derive_secret(&self.prk, b"s ap traffic", transcript_hash)
+    }
+
+    /// `Derive-Secret(., "exp master", ClientHello...server Finished)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript_hash` isn't the length of the key schedule's
+    /// digest algorithm's output.
+    pub fn exporter_master_secret(&self, transcript_hash: &[u8])
+                                  -> Secret {
+        derive_secret(&self.prk, b"exp master", transcript_hash)
+    }
+
+    /// `Derive-Secret(., "res master", ClientHello...client Finished)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript_hash` isn't the length of the key schedule's
+    /// digest algorithm's output.
+    pub fn resumption_master_secret(&self, transcript_hash: &[u8])
+                                    -> Secret {
+        derive_secret(&self.prk, b"res master", transcript_hash)
+    }
+
+    /// The key schedule's digest algorithm.
+    #[inline]
+    pub fn digest_algorithm(&self) -> &'static digest::Algorithm {
+        self.prk.digest_algorithm()
+    }
+}
+
+/// A secret derived by the key schedule, e.g. a traffic secret.
+pub struct Secret {
+    digest_alg: &'static digest::Algorithm,
+    value: [u8; digest::MAX_OUTPUT_LEN],
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) { zeroize::zeroize(&mut self.value); }
+}
+
+impl Secret {
+    /// Constructs a `Secret` with the given value.
+    ///
+    /// `value.len()` must be equal to `digest_alg.output_len`. This is useful
+    /// for restoring a resumption secret or a traffic secret that was
+    /// serialized with `value_less_safe()`.
+    pub fn new_less_safe(digest_alg: &'static digest::Algorithm,
+                         value: &[u8]) -> Result<Secret, error::Unspecified> {
+        if value.len() != digest_alg.output_len {
+            return Err(error::Unspecified);
+        }
+        let mut secret = Secret {
+            digest_alg,
+            value: [0u8; digest::MAX_OUTPUT_LEN],
+        };
+        secret.value[..value.len()].copy_from_slice(value);
+        Ok(secret)
+    }
+
+    /// The value of the secret.
+    ///
+    /// Usually one can avoid using this. It is useful when the application
+    /// intentionally wants to leak the secret, e.g. to implement
+    /// `SSLKEYLOGFILE` functionality, or when a resumption PSK needs to be
+    /// stored.
+    #[inline]
+    pub fn value_less_safe(&self) -> &[u8] {
+        &self.value[..self.digest_alg.output_len]
+    }
+
+    /// The key schedule's digest algorithm.
+    #[inline]
+    pub fn digest_algorithm(&self) -> &'static digest::Algorithm {
+        self.digest_alg
+    }
+
+    /// `HKDF-Expand-Label(Secret, "key", "", key_length)`.
+    ///
+    /// The result is usually an `aead::SealingKey` or an `aead::OpeningKey`.
+    pub fn traffic_key<K>(&self, aead_alg: &'static aead::Algorithm) -> K
+                          where K: for<'a> From<hkdf::Okm<'a,
+                                                &'static aead::Algorithm>> {
+        hkdf_expand_label(&self.prk(), b"key", b"", aead_alg)
+    }
+
+    /// `HKDF-Expand-Label(Secret, "iv", "", iv_length)`.
+    pub fn traffic_iv(&self) -> Iv {
+        let mut iv = Iv([0u8; IV_LEN]);
+        expand_label(&self.prk(), b"iv", b"", Len(IV_LEN),
+                     |okm| okm.fill(&mut iv.0)).unwrap();
+        iv
+    }
+
+    /// `HKDF-Expand-Label(BaseKey, "finished", "", Hash.length)`, where
+    /// `BaseKey` is this secret.
+    pub fn finished_key(&self) -> hmac::SigningKey {
+        hkdf_expand_label(&self.prk(), b"finished", b"", self.digest_alg)
+    }
+
+    /// The next application traffic secret, for use after a `KeyUpdate`:
+    /// `HKDF-Expand-Label(application_traffic_secret_N, "traffic upd", "",
+    /// Hash.length)`.
+    pub fn next_application_traffic_secret(&self) -> Secret {
+        hkdf_expand_label(&self.prk(), b"traffic upd", b"", self.digest_alg)
+    }
+
+    /// The PSK associated with a ticket, where this secret is the resumption
+    /// master secret: `HKDF-Expand-Label(resumption_master_secret,
+    /// "resumption", ticket_nonce, Hash.length)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ticket_nonce` is longer than 255 bytes.
+    pub fn resumption_psk(&self, ticket_nonce: &[u8]) -> Secret {
+        hkdf_expand_label(&self.prk(), b"resumption", ticket_nonce,
+                          self.digest_alg)
+    }
+
+    fn prk(&self) -> hkdf::Prk {
+        hkdf::Prk::new_less_safe(self.digest_alg, self.value_less_safe())
+    }
+}
+
+impl<'a> From<hkdf::Okm<'a, &'static digest::Algorithm>> for Secret {
+    fn from(okm: hkdf::Okm<'a, &'static digest::Algorithm>) -> Self {
+        let mut secret = Secret {
+            digest_alg: *okm.len(),
+            value: [0u8; digest::MAX_OUTPUT_LEN],
+        };
+        okm.fill(&mut secret.value[..secret.digest_alg.output_len]).unwrap();
+        secret
+    }
+}
+
+/// A traffic IV.
+pub struct Iv([u8; IV_LEN]);
+
+impl Iv {
+    /// The per-record nonce for the record with the given sequence number, as
+    /// described in [RFC 8446 Section 5.3].
+    ///
+    /// [RFC 8446 Section 5.3]: https://tools.ietf.org/html/rfc8446#section-5.3
+    pub fn nonce_for(&self, sequence_number: u64) -> [u8; IV_LEN] {
+        let mut nonce = self.0;
+        let sequence_number = polyfill::slice::be_u8_from_u64(sequence_number);
+        for (n, s) in nonce[(IV_LEN - 8)..].iter_mut()
+                                           .zip(sequence_number.iter()) {
+            *n ^= *s;
+        }
+        nonce
+    }
+}
+
+impl AsRef<[u8]> for Iv {
+    #[inline]
+    fn as_ref(&self) -> &[u8] { &self.0[..] }
+}
+
+/// The length of a traffic IV. All of the AEADs used with TLS 1.3 have
+/// 96-bit nonces.
+pub const IV_LEN: usize = 96 / 8;
+
+/// `HKDF-Expand-Label(Secret, Label, Context, Length)`.
+///
+/// `label` must not include the "tls13 " prefix; it is added automatically.
+///
+/// # Panics
+///
+/// Panics if `label` is longer than 249 bytes, if `context` is longer than
+/// 255 bytes, or if `len.len()` is larger than `hkdf::Prk::expand` allows.
+pub fn hkdf_expand_label<L, T>(secret: &hkdf::Prk, label: &[u8],
+                               context: &[u8], len: L) -> T
+                               where L: hkdf::KeyType,
+                                     T: for<'a> From<hkdf::Okm<'a, L>> {
+    expand_label(secret, label, context, len, |okm| T::from(okm))
+}
+
+/// `Derive-Secret(Secret, Label, Messages)`, where `transcript_hash` is
+/// the value of `Transcript-Hash(Messages)`.
+///
+/// # Panics
+///
+/// Panics if `transcript_hash` isn't the length of `secret`'s digest
+/// algorithm's output.
+pub fn derive_secret(secret: &hkdf::Prk, label: &[u8],
+                     transcript_hash: &[u8]) -> Secret {
+    assert_eq!(transcript_hash.len(), secret.digest_algorithm().output_len);
+    hkdf_expand_label(secret, label, transcript_hash,
+                      secret.digest_algorithm())
+}
+
+fn derive_secret_for_empty_transcript(secret: &hkdf::Prk, label: &[u8])
+                                      -> Secret {
+    let empty_hash = digest::digest(secret.digest_algorithm(), b"");
+    derive_secret(secret, label, empty_hash.as_ref())
+}
+
+// `HKDF-Extract(Derive-Secret(., "derived", ""), ikm)`.
+fn next_stage(prk: &hkdf::Prk, ikm: &[u8]) -> hkdf::Prk {
+    let salt = derive_secret_for_empty_transcript(prk, b"derived");
+    hkdf::Salt::new(salt.digest_alg, salt.value_less_safe()).extract(ikm)
+}
+
+fn expand_label<L, F, R>(secret: &hkdf::Prk, label: &[u8], context: &[u8],
+                         len: L, f: F) -> R
+                         where L: hkdf::KeyType,
+                               F: FnOnce(hkdf::Okm<L>) -> R {
+    const LABEL_PREFIX: &'static [u8] = b"tls13 ";
+
+    let output_len = len.len();
+    assert!(output_len <= 0xffff);
+    assert!(LABEL_PREFIX.len() + label.len() <= 0xff);
+    assert!(context.len() <= 0xff);
+
+    // The `HkdfLabel` structure.
+    let output_len = [(output_len >> 8) as u8, output_len as u8];
+    let label_len = [(LABEL_PREFIX.len() + label.len()) as u8];
+    let context_len = [context.len() as u8];
+    let info = [&output_len[..], &label_len[..], LABEL_PREFIX, label,
+                &context_len[..], context];
+
+    let okm = secret.expand(&info, len).unwrap();
+    f(okm)
+}
+
+/// An arbitrary output length for `expand_label`.
+struct Len(usize);
+
+impl hkdf::KeyType for Len {
+    #[inline]
+    fn len(&self) -> usize { self.0 }
+}
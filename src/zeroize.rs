@@ -0,0 +1,225 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Erasure of secret values.
+//!
+//! The compiler may remove writes to memory that is never read again, such as
+//! the writes that a `Drop` implementation makes just before the memory is
+//! freed or reused. `zeroize` writes with volatile stores, which the compiler
+//! must keep, followed by a compiler fence so that they aren't reordered after
+//! the memory is released.
+//!
+//! Every type in *ring* that holds a secret key, or state derived from one,
+//! erases it with `zeroize` when it is dropped. Values that are `Copy`, like
+//! `digest::Digest` and the field elements and scalars used internally during
+//! a computation, can't implement `Drop` and aren't erased.
+
+use core;
+use core::mem;
+
+/// Types for which all-zero bytes are a valid value, and which don't own any
+/// other memory.
+pub unsafe trait Zeroable {}
+
+unsafe impl Zeroable for u8 {}
+unsafe impl Zeroable for u32 {}
+unsafe impl Zeroable for u64 {}
+
+/// Overwrites `dest` with zeros.
+#[inline(never)]
+pub fn zeroize<T: Zeroable>(dest: &mut [T]) {
+    let len = dest.len() * mem::size_of::<T>();
+    let bytes = dest.as_mut_ptr() as *mut u8;
+    for i in 0..len {
+        unsafe {
+            core::ptr::write_volatile(bytes.offset(i as isize), 0);
+        }
+    }
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use {aead, chacha, core, digest, ec, hmac, poly1305, rand, secretbox,
+         signature, tls13};
+    use aead::{aes, chacha20_poly1305_openssh};
+    use core::mem;
+    use std::vec::Vec;
+    use untrusted;
+
+    // Drops `value` in place and checks that `secret`, which must be found in
+    // the memory of `value` beforehand, can't be found there afterwards.
+    fn assert_erased_on_drop<T>(value: T, secret: &[u8]) {
+        let mut value = mem::ManuallyDrop::new(value);
+        let ptr: *mut T = &mut *value;
+        assert!(contains(ptr, secret));
+        unsafe { core::ptr::drop_in_place(ptr) };
+        assert!(!contains(ptr, secret));
+    }
+
+    fn contains<T>(ptr: *const T, secret: &[u8]) -> bool {
+        let bytes = ptr as *const u8;
+        let len = mem::size_of::<T>();
+        let memory = (0..len)
+            .map(|i| unsafe { core::ptr::read_volatile(bytes.offset(i as isize)) })
+            .collect::<Vec<u8>>();
+        memory.windows(secret.len()).any(|window| window == secret)
+    }
+
+    fn secret<T: AsMut<[u8]> + Default>() -> T {
+        let mut secret = T::default();
+        for (i, b) in secret.as_mut().iter_mut().enumerate() {
+            *b = 0x80 | ((i * 7 + 3) as u8);
+        }
+        secret
+    }
+
+    #[test]
+    fn test_aead_key() {
+        let key: [u8; 32] = secret();
+        let sealing_key =
+            aead::SealingKey::new(&aead::CHACHA20_POLY1305, &key).unwrap();
+        assert_erased_on_drop(sealing_key, &key);
+    }
+
+    #[test]
+    fn test_aes_decrypt_key() {
+        let key: [u8; 16] = secret();
+        assert_erased_on_drop(aes::DecryptKey::new(&key).unwrap(), &key);
+    }
+
+    #[test]
+    fn test_chacha20_key() {
+        let key: [u8; 32] = secret();
+        assert_erased_on_drop(chacha::ChaCha20Key::new(&key).unwrap(), &key);
+    }
+
+    #[test]
+    fn test_chacha20_poly1305_openssh_key() {
+        let key: [u8; chacha20_poly1305_openssh::KEY_LEN] = {
+            let mut key = [0u8; chacha20_poly1305_openssh::KEY_LEN];
+            key[..32].copy_from_slice(&secret::<[u8; 32]>());
+            key[32..].copy_from_slice(&secret::<[u8; 32]>());
+            key
+        };
+        let sealing_key = chacha20_poly1305_openssh::SealingKey::new(&key);
+        assert_erased_on_drop(sealing_key, &key[..32]);
+    }
+
+    #[test]
+    fn test_digest_context() {
+        let data: [u8; 32] = secret();
+        let mut ctx = digest::Context::new(&digest::SHA256);
+        ctx.update(&data);
+        assert_erased_on_drop(ctx, &data);
+    }
+
+    #[test]
+    fn test_ec_private_key() {
+        let bytes: [u8; 32] = secret();
+        let private_key =
+            ec::PrivateKey::from_bytes(&ec::suite_b::curve::P256,
+                                       untrusted::Input::from(&bytes))
+                .unwrap();
+        assert_erased_on_drop(private_key, &bytes);
+    }
+
+    #[test]
+    fn test_ed25519_key_pair() {
+        let seed: [u8; 32] = secret();
+        let key_pair = signature::Ed25519KeyPair::from_seed_unchecked(
+            untrusted::Input::from(&seed)).unwrap();
+        let h = digest::digest(&digest::SHA512, &seed);
+        assert_erased_on_drop(key_pair, &h.as_ref()[32..]);
+    }
+
+    #[test]
+    fn test_hmac_signing_key() {
+        let key: [u8; 32] = secret();
+        let signing_key = hmac::SigningKey::new(&digest::SHA256, &key);
+
+        // The inner digest context keeps its last block of input, which is
+        // the padded key XORed with ipad.
+        let key_xor_ipad = key.iter().map(|b| b ^ 0x36).collect::<Vec<u8>>();
+        assert_erased_on_drop(signing_key, &key_xor_ipad);
+    }
+
+    #[test]
+    fn test_pkcs8_document() {
+        let rng = rand::SystemRandom::new();
+        let pkcs8 = signature::ECDSAKeyPair::generate_pkcs8(
+            &signature::ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+        let bytes = pkcs8.as_ref().to_vec();
+        assert_erased_on_drop(pkcs8, &bytes);
+    }
+
+    #[test]
+    fn test_poly1305_key() {
+        let key: [u8; poly1305::KEY_LEN] = secret();
+        assert_erased_on_drop(poly1305::Key::new(&key), &key);
+    }
+
+    // The CRT components are kept in limbs on the heap, which
+    // `BIGNUM::drop()` frees right after erasing them, so the erasure is
+    // checked on the live key pair instead.
+    #[cfg(all(feature = "rsa_signing", not(feature = "pure_rust")))]
+    #[test]
+    fn test_rsa_key_pair() {
+        const PRIVATE_KEY_DER: &'static [u8] =
+            include_bytes!("rsa/signature_rsa_example_private_key.der");
+        let mut key_pair = signature::RSAKeyPair::from_der(
+            untrusted::Input::from(PRIVATE_KEY_DER)).unwrap();
+        assert!(key_pair.crt_limbs().iter()
+            .all(|limbs| limbs.iter().any(|limb| *limb != 0)));
+        for limbs in key_pair.erase_crt_limbs_for_test().iter() {
+            assert!(!limbs.is_empty());
+            assert!(limbs.iter().all(|limb| *limb == 0));
+        }
+    }
+
+    // With `pure_rust` the limbs are stored inline in the key pair.
+    #[cfg(all(feature = "rsa_signing", feature = "pure_rust"))]
+    #[test]
+    fn test_rsa_key_pair() {
+        use limb;
+
+        const PRIVATE_KEY_DER: &'static [u8] =
+            include_bytes!("rsa/signature_rsa_example_private_key.der");
+        let key_pair = signature::RSAKeyPair::from_der(
+            untrusted::Input::from(PRIVATE_KEY_DER)).unwrap();
+        let secrets = key_pair.crt_limbs().iter()
+            .map(|limbs| limb::limbs_as_bytes(limbs).to_vec())
+            .collect::<Vec<_>>();
+
+        let mut key_pair = mem::ManuallyDrop::new(key_pair);
+        let ptr: *mut signature::RSAKeyPair = &mut *key_pair;
+        assert!(secrets.iter().all(|secret| contains(ptr, secret)));
+        unsafe { core::ptr::drop_in_place(ptr) };
+        assert!(!secrets.iter().any(|secret| contains(ptr, secret)));
+    }
+
+    #[test]
+    fn test_secretbox_key() {
+        let key: [u8; 32] = secret();
+        assert_erased_on_drop(secretbox::Key::new(&key).unwrap(), &key);
+    }
+
+    #[test]
+    fn test_tls13_secret() {
+        let value: [u8; 32] = secret();
+        let secret =
+            tls13::Secret::new_less_safe(&digest::SHA256, &value).unwrap();
+        assert_erased_on_drop(secret, &value);
+    }
+}
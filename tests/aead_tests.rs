@@ -34,6 +34,7 @@
 extern crate ring;
 
 use ring::{aead, error, test};
+use ring::aead::NonceSequence;
 use std::vec::Vec;
 
 #[test]
@@ -46,12 +47,181 @@ fn aead_aes_gcm_256() {
     test_aead(&aead::AES_256_GCM, "tests/aead_aes_256_gcm_tests.txt");
 }
 
+#[test]
+fn aead_aes_ccm_128() {
+    test_aead(&aead::AES_128_CCM, "tests/aead_aes_128_ccm_tests.txt");
+}
+
+#[test]
+fn aead_aes_ccm_256() {
+    test_aead(&aead::AES_256_CCM, "tests/aead_aes_256_ccm_tests.txt");
+}
+
+#[test]
+fn aead_aes_ccm_8_128() {
+    test_aead(&aead::AES_128_CCM_8, "tests/aead_aes_128_ccm_8_tests.txt");
+}
+
+#[test]
+fn aead_aes_ccm_8_256() {
+    test_aead(&aead::AES_256_CCM_8, "tests/aead_aes_256_ccm_8_tests.txt");
+}
+
+#[test]
+fn aead_aes_ccm_l2_128() {
+    test_aead(&aead::AES_128_CCM_L2, "tests/aead_aes_128_ccm_l2_tests.txt");
+}
+
+#[test]
+fn aead_aes_ccm_8_l2_128() {
+    test_aead(&aead::AES_128_CCM_8_L2,
+              "tests/aead_aes_128_ccm_8_l2_tests.txt");
+}
+
+#[test]
+fn aead_aes_ccm_4_l2_128() {
+    test_aead(&aead::AES_128_CCM_4_L2,
+              "tests/aead_aes_128_ccm_4_l2_tests.txt");
+}
+
+// CCM encodes the length of the additional data in two bytes when it is less
+// than `0xff00` bytes and in six bytes otherwise; check both sides of that
+// boundary.
+#[test]
+fn aead_aes_ccm_long_ad() {
+    let key_bytes: Vec<u8> = (0..16).collect();
+    let nonce: Vec<u8> = (0..12).collect();
+    let key = aead::SealingKey::new(&aead::AES_128_CCM, &key_bytes).unwrap();
+    let vectors = [
+        (0xfeff, "5b709f0bb32a9d8c74462ab6c724f8d691632f1c6a"),
+        (0xff00, "5b709f0bb378c6cba9fa6cdfdaa144a58b9c204cb3"),
+    ];
+    for &(ad_len, expected) in vectors.iter() {
+        let ad: Vec<u8> = (0..ad_len).map(|i: usize| (i % 251) as u8).collect();
+        let mut in_out = b"hello".to_vec();
+        in_out.extend_from_slice(&[0u8; 16]);
+        let out_len =
+            aead::seal_in_place(&key, &nonce, &ad, &mut in_out, 16).unwrap();
+        assert_eq!(&in_out[..out_len], &test::from_hex(expected).unwrap()[..]);
+    }
+}
+
 #[test]
 fn aead_chacha20_poly1305() {
     test_aead(&aead::CHACHA20_POLY1305,
               "tests/aead_chacha20_poly1305_tests.txt");
 }
 
+#[test]
+fn aead_counter_nonce_sequence() {
+    let mut nonces = aead::CounterNonceSequence::new(0x0102030405060708);
+    let mut nonce = [0xffu8; 12];
+    nonces.advance(&mut nonce).unwrap();
+    assert_eq!(nonce, [0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8]);
+    nonces.advance(&mut nonce).unwrap();
+    assert_eq!(nonce, [0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 9]);
+
+    // Nonces shorter than the counter can't be produced.
+    assert!(nonces.advance(&mut nonce[..7]).is_err());
+
+    // The sequence is exhausted after the largest counter value.
+    let mut nonces = aead::CounterNonceSequence::new(u64::max_value());
+    nonces.advance(&mut nonce).unwrap();
+    assert_eq!(nonce, [0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                       0xff]);
+    assert!(nonces.advance(&mut nonce).is_err());
+    assert!(nonces.advance(&mut nonce).is_err());
+}
+
+#[test]
+fn aead_random_prefix_nonce_sequence() {
+    let rng = test::rand::FixedSliceRandom { bytes: &[0xa, 0xb, 0xc, 0xd] };
+    let mut nonces = aead::RandomPrefixNonceSequence::new(&rng).unwrap();
+    let mut nonce = [0u8; 12];
+    nonces.advance(&mut nonce).unwrap();
+    assert_eq!(nonce, [0xa, 0xb, 0xc, 0xd, 0, 0, 0, 0, 0, 0, 0, 0]);
+    nonces.advance(&mut nonce).unwrap();
+    assert_eq!(nonce, [0xa, 0xb, 0xc, 0xd, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+    // Only 96-bit nonces are supported.
+    assert!(nonces.advance(&mut [0u8; 11]).is_err());
+    assert!(nonces.advance(&mut [0u8; 13]).is_err());
+}
+
+#[test]
+fn aead_sequenced_keys() {
+    for aead_alg in [&aead::AES_128_GCM, &aead::AES_256_GCM,
+                     &aead::AES_128_CCM, &aead::AES_128_CCM_8,
+                     &aead::CHACHA20_POLY1305].iter() {
+        test_aead_sequenced_keys(aead_alg).unwrap();
+    }
+}
+
+fn test_aead_sequenced_keys(aead_alg: &'static aead::Algorithm)
+                            -> Result<(), error::Unspecified> {
+    let key_data = vec![0x42u8; aead_alg.key_len()];
+    let tag_len = aead_alg.tag_len();
+    let ad = b"additional data";
+
+    let mut s_key = aead::SequencedSealingKey::new(
+        aead::SealingKey::new(aead_alg, &key_data)?,
+        aead::CounterNonceSequence::new(1));
+    let mut o_key = aead::SequencedOpeningKey::new(
+        aead::OpeningKey::new(aead_alg, &key_data)?,
+        aead::CounterNonceSequence::new(1));
+    assert_eq!(s_key.algorithm(), aead_alg);
+    assert_eq!(o_key.algorithm(), aead_alg);
+
+    let raw_s_key = aead::SealingKey::new(aead_alg, &key_data)?;
+
+    for counter in 1..4u8 {
+        let mut in_out = b"hello, world".to_vec();
+        in_out.extend_from_slice(&vec![0u8; tag_len]);
+        let mut expected = in_out.clone();
+
+        let out_len = s_key.seal_in_place(ad, &mut in_out, tag_len)?;
+
+        // The sealing key used the next nonce in its sequence.
+        let mut nonce = [0u8; 12];
+        nonce[11] = counter;
+        let expected_len = aead::seal_in_place(&raw_s_key, &nonce, ad,
+                                               &mut expected, tag_len)?;
+        assert_eq!(&in_out[..out_len], &expected[..expected_len]);
+
+        assert_eq!(o_key.open_in_place(ad, 0, &mut in_out[..out_len])?,
+                   b"hello, world");
+    }
+
+    // A failed seal still consumes a nonce, so the opening key falls out of
+    // step with the sealing key.
+    {
+        let mut in_out = b"hello, world".to_vec();
+        assert!(s_key.seal_in_place(ad, &mut in_out, 0).is_err());
+        in_out.extend_from_slice(&vec![0u8; tag_len]);
+        let out_len = s_key.seal_in_place(ad, &mut in_out, tag_len)?;
+        assert!(o_key.open_in_place(ad, 0, &mut in_out[..out_len]).is_err());
+    }
+
+    // The invocation limit is enforced.
+    let mut s_key = aead::SequencedSealingKey::with_max_invocations(
+        aead::SealingKey::new(aead_alg, &key_data)?,
+        aead::CounterNonceSequence::new(0), 2);
+    let mut in_out = vec![0u8; tag_len];
+    assert!(s_key.seal_in_place(ad, &mut in_out, tag_len).is_ok());
+    assert!(s_key.seal_in_place(ad, &mut in_out, tag_len).is_ok());
+    assert!(s_key.seal_in_place(ad, &mut in_out, tag_len).is_err());
+
+    // An exhausted nonce sequence stops the key from sealing anything else.
+    let mut s_key = aead::SequencedSealingKey::new(
+        aead::SealingKey::new(aead_alg, &key_data)?,
+        aead::CounterNonceSequence::new(u64::max_value()));
+    assert!(s_key.seal_in_place(ad, &mut in_out, tag_len).is_ok());
+    assert!(s_key.seal_in_place(ad, &mut in_out, tag_len).is_err());
+    assert!(s_key.seal_in_place(ad, &mut in_out, tag_len).is_err());
+
+    Ok(())
+}
+
 
 fn test_aead(aead_alg: &'static aead::Algorithm, file_path: &str) {
     test_aead_key_sizes(aead_alg);
@@ -0,0 +1,211 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{aes_kw, cipher, error, test};
+
+type WrapFn = for<'a> fn(&cipher::EncryptingKey, &[u8], &'a mut [u8])
+                         -> Result<&'a [u8], error::Unspecified>;
+type UnwrapFn = for<'a> fn(&cipher::DecryptingKey, &[u8], &'a mut [u8])
+                           -> Result<&'a [u8], error::Unspecified>;
+
+#[test]
+fn aes_kw_tests() {
+    test::from_file("tests/aes_kw_tests.txt", |section, test_case| {
+        let kek_bytes = test_case.consume_bytes("KEK");
+        let plaintext = test_case.consume_bytes("Plaintext");
+        let ciphertext = test_case.consume_bytes("Ciphertext");
+
+        let algorithm = match kek_bytes.len() {
+            16 => &cipher::AES_128,
+            32 => &cipher::AES_256,
+            _ => unreachable!(),
+        };
+        let (wrap, unwrap): (WrapFn, UnwrapFn) = match section {
+            "KW" => (aes_kw::wrap, aes_kw::unwrap),
+            "KWP" => (aes_kw::wrap_with_padding, aes_kw::unwrap_with_padding),
+            _ => unreachable!(),
+        };
+
+        let kek = cipher::EncryptingKey::new(algorithm, &kek_bytes)?;
+        let mut out = vec![0u8; ciphertext.len() + 1];
+        assert_eq!(wrap(&kek, &plaintext, &mut out)?, &ciphertext[..]);
+        let mut out = vec![0u8; ciphertext.len() - 1];
+        assert!(wrap(&kek, &plaintext, &mut out).is_err());
+
+        let kek = cipher::DecryptingKey::new(algorithm, &kek_bytes)?;
+        let unwrapped_len = ciphertext.len() - aes_kw::SEMIBLOCK_LEN;
+        let mut out = vec![0u8; unwrapped_len + 1];
+        assert_eq!(unwrap(&kek, &ciphertext, &mut out)?, &plaintext[..]);
+        let mut out = vec![0u8; unwrapped_len - 1];
+        assert!(unwrap(&kek, &ciphertext, &mut out).is_err());
+
+        // Any modification is detected.
+        let mut out = vec![0u8; unwrapped_len];
+        for i in 0..ciphertext.len() {
+            let mut modified = ciphertext.clone();
+            modified[i] ^= 0x01;
+            assert!(unwrap(&kek, &modified, &mut out).is_err());
+            assert!(out.iter().all(|b| *b == 0));
+        }
+        assert!(unwrap(&kek, &ciphertext[1..], &mut out).is_err());
+        assert!(unwrap(&kek, &ciphertext[aes_kw::SEMIBLOCK_LEN..],
+                       &mut out).is_err());
+
+        Ok(())
+    });
+}
+
+#[test]
+fn aes_kw_wrong_kek_test() {
+    let wrap_kek =
+        cipher::EncryptingKey::new(&cipher::AES_128, &[1u8; 16]).unwrap();
+    let unwrap_kek =
+        cipher::DecryptingKey::new(&cipher::AES_128, &[2u8; 16]).unwrap();
+    let key_data = [3u8; 16];
+    let mut wrapped = [0u8; 24];
+    let mut unwrapped = [0u8; 16];
+
+    let _ = aes_kw::wrap(&wrap_kek, &key_data, &mut wrapped).unwrap();
+    assert!(aes_kw::unwrap(&unwrap_kek, &wrapped, &mut unwrapped).is_err());
+
+    let _ = aes_kw::wrap_with_padding(&wrap_kek, &key_data, &mut wrapped)
+        .unwrap();
+    assert!(aes_kw::unwrap_with_padding(&unwrap_kek, &wrapped, &mut unwrapped)
+                .is_err());
+}
+
+#[test]
+fn aes_kw_key_data_lengths_test() {
+    let kek = cipher::EncryptingKey::new(&cipher::AES_128, &[0u8; 16]).unwrap();
+    let mut out = [0u8; 64];
+
+    // AES-KW needs at least two whole semiblocks.
+    for &len in [0, 1, 8, 15, 17, 23].iter() {
+        let key_data = vec![0u8; len];
+        assert!(aes_kw::wrap(&kek, &key_data, &mut out).is_err());
+    }
+    for &len in [16, 24, 32].iter() {
+        let key_data = vec![0u8; len];
+        assert!(aes_kw::wrap(&kek, &key_data, &mut out).is_ok());
+    }
+
+    // AES-KWP accepts any non-empty key data.
+    assert!(aes_kw::wrap_with_padding(&kek, &[], &mut out).is_err());
+    for len in 1..(out.len() - aes_kw::SEMIBLOCK_LEN + 1) {
+        let key_data = vec![0u8; len];
+        let wrapped_len = aes_kw::wrap_with_padding(&kek, &key_data, &mut out)
+            .unwrap()
+            .len();
+        assert_eq!(wrapped_len, ((len + 7) / 8 * 8) + aes_kw::SEMIBLOCK_LEN);
+    }
+}
+
+#[test]
+fn aes_kw_unwrap_with_padding_bad_padding_test() {
+    // Construct wrapped keys with valid integrity but inconsistent length
+    // indicators or non-zero padding by wrapping them with AES-KW with the
+    // alternative initial value of RFC 5649.
+    let kek_bytes = [0x42u8; 32];
+    let e_kek = cipher::EncryptingKey::new(&cipher::AES_256, &kek_bytes)
+        .unwrap();
+    let d_kek = cipher::DecryptingKey::new(&cipher::AES_256, &kek_bytes)
+        .unwrap();
+
+    struct Case {
+        mli: u32,
+        padded: &'static [u8],
+        valid: bool,
+    }
+    let cases = [
+        Case { mli: 1, padded: &[1, 0, 0, 0, 0, 0, 0, 0], valid: true },
+        Case { mli: 8, padded: &[1; 8], valid: true },
+        Case { mli: 0, padded: &[0; 8], valid: false },
+        Case { mli: 9, padded: &[1; 8], valid: false },
+        Case { mli: 1, padded: &[1, 0, 0, 0, 0, 0, 0, 1], valid: false },
+        Case { mli: 9, padded: &[1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0],
+               valid: true },
+        Case { mli: 16, padded: &[1; 16], valid: true },
+        Case { mli: 8, padded: &[1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+               valid: false },
+        Case { mli: 17, padded: &[1; 16], valid: false },
+        Case { mli: 10, padded: &[1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 1, 0],
+               valid: false },
+        Case { mli: 0xffff_ffff, padded: &[1; 16], valid: false },
+    ];
+
+    for case in cases.iter() {
+        let mut aiv = [0xa6, 0x59, 0x59, 0xa6, 0, 0, 0, 0];
+        for i in 0..4 {
+            aiv[4 + i] = (case.mli >> (24 - 8 * i)) as u8;
+        }
+        let wrapped = wrap_with_iv(&e_kek, &aiv, case.padded);
+
+        let mut out = [0u8; 16];
+        let result = aes_kw::unwrap_with_padding(&d_kek, &wrapped, &mut out);
+        if case.valid {
+            assert_eq!(result.unwrap(), &case.padded[..(case.mli as usize)]);
+        } else {
+            assert!(result.is_err());
+        }
+    }
+}
+
+// Wraps `padded` with AES-KWP, using an arbitrary alternative initial value.
+fn wrap_with_iv(kek: &cipher::EncryptingKey, aiv: &[u8; 8], padded: &[u8])
+                -> Vec<u8> {
+    let mut wrapped = aiv.to_vec();
+    wrapped.extend_from_slice(padded);
+    let mut b = [0u8; cipher::BLOCK_LEN];
+    if padded.len() == 8 {
+        b.copy_from_slice(&wrapped);
+        kek.encrypt_block(&mut b);
+        return b.to_vec();
+    }
+    let n = padded.len() / 8;
+    for j in 0..6 {
+        for i in 1..(n + 1) {
+            b[..8].copy_from_slice(&wrapped[..8]);
+            b[8..].copy_from_slice(&wrapped[(8 * i)..][..8]);
+            kek.encrypt_block(&mut b);
+            let t = (n * j + i) as u64;
+            for k in 0..8 {
+                b[k] ^= (t >> (56 - 8 * k)) as u8;
+            }
+            wrapped[..8].copy_from_slice(&b[..8]);
+            wrapped[(8 * i)..][..8].copy_from_slice(&b[8..]);
+        }
+    }
+    wrapped
+}
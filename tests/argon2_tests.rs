@@ -0,0 +1,126 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{argon2, error, test};
+
+#[test]
+pub fn argon2_tests() {
+    test::from_file("tests/argon2_tests.txt", |section, test_case| {
+        assert_eq!(section, "");
+        let secret = test_case.consume_bytes("P");
+        let salt = test_case.consume_bytes("S");
+        let key = test_case.consume_bytes("K");
+        let associated_data = test_case.consume_bytes("X");
+        let memory_kib = test_case.consume_usize("m");
+        let iterations = test_case.consume_usize("t");
+        let parallelism = test_case.consume_usize("p");
+        let tag = test_case.consume_bytes("Tag");
+        let verify_expected_result = test_case.consume_string("Verify");
+        let verify_expected_result =
+            match verify_expected_result.as_str() {
+                "OK" => Ok(()),
+                "Err" => Err(error::Unspecified),
+                _ => panic!("Unsupported value of \"Verify\""),
+            };
+
+        let params = argon2::Params::new(memory_kib as u32, iterations as u32,
+                                         parallelism as u32)?;
+
+        {
+            let mut out = vec![0u8; tag.len()];
+            argon2::derive_keyed(&params, &key, &associated_data, &salt,
+                                 &secret, &mut out);
+            assert_eq!(tag == out, verify_expected_result.is_ok());
+        }
+
+        assert_eq!(argon2::verify_keyed(&params, &key, &associated_data,
+                                        &salt, &secret, &tag),
+                   verify_expected_result);
+
+        if key.is_empty() && associated_data.is_empty() {
+            assert_eq!(argon2::verify(&params, &salt, &secret, &tag),
+                       verify_expected_result);
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn argon2_params_test() {
+    assert!(argon2::Params::new(8, 1, 1).is_ok());
+    assert!(argon2::Params::new(32, 3, 4).is_ok());
+
+    // m must be at least 8 * p.
+    assert!(argon2::Params::new(7, 1, 1).is_err());
+    assert!(argon2::Params::new(31, 1, 4).is_err());
+    // t and p must be at least 1.
+    assert!(argon2::Params::new(8, 0, 1).is_err());
+    assert!(argon2::Params::new(8, 1, 0).is_err());
+    // p must be less than 2**24.
+    assert!(argon2::Params::new(0xffff_ffff, 1, 1 << 24).is_err());
+
+    let params = argon2::Params::new(19 * 1024, 2, 1).unwrap();
+    assert_eq!(params.memory_kib(), 19 * 1024);
+    assert_eq!(params.iterations(), 2);
+    assert_eq!(params.parallelism(), 1);
+}
+
+#[test]
+pub fn argon2_verify_short_inputs_test() {
+    let params = argon2::Params::new(8, 1, 1).unwrap();
+    // "password"/"somesalt" with m = 8, t = 1, p = 1.
+    let tag = test::from_hex("6b7a947d").unwrap();
+    assert!(argon2::verify(&params, b"somesalt", b"password", &tag).is_ok());
+    assert!(argon2::verify(&params, b"somesalt", b"password", &tag[..3])
+                .is_err());
+    assert!(argon2::verify(&params, b"somesal", b"password", &tag).is_err());
+}
+
+#[test]
+#[should_panic]
+pub fn argon2_short_output_test() {
+    let params = argon2::Params::new(8, 1, 1).unwrap();
+    let mut out = [0u8; 3];
+    argon2::derive(&params, b"somesalt", b"password", &mut out);
+}
+
+#[test]
+#[should_panic]
+pub fn argon2_short_salt_test() {
+    let params = argon2::Params::new(8, 1, 1).unwrap();
+    let mut out = [0u8; 32];
+    argon2::derive(&params, b"somesal", b"password", &mut out);
+}
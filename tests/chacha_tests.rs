@@ -0,0 +1,111 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{chacha, test};
+
+#[test]
+fn chacha20_tests() {
+    test::from_file("tests/chacha_tests.txt", |section, test_case| {
+        assert_eq!(section, "");
+        let key = test_case.consume_bytes("Key");
+        let ctr = test_case.consume_usize("Ctr") as u32;
+        let nonce = test_case.consume_bytes("Nonce");
+        let input = test_case.consume_bytes("Input");
+        let output = test_case.consume_bytes("Output");
+
+        let key = chacha::ChaCha20Key::new(&key)?;
+        let mut nonce_array = [0u8; chacha::NONCE_LEN];
+        nonce_array.copy_from_slice(&nonce);
+        let nonce = nonce_array;
+
+        let mut in_out = input.clone();
+        key.xor_in_place(&nonce, ctr, &mut in_out)?;
+        assert_eq!(in_out, output);
+
+        // Seeking to any byte offset gives the corresponding part of the
+        // output, whether or not it's on a block boundary.
+        let start = u64::from(ctr) * (chacha::BLOCK_LEN as u64);
+        for &step in [1, 7, chacha::BLOCK_LEN, 100].iter() {
+            let mut in_out = input.clone();
+            let mut offset = 0;
+            for chunk in in_out.chunks_mut(step) {
+                key.xor_in_place_at(&nonce, start + offset as u64, chunk)?;
+                offset += chunk.len();
+            }
+            assert_eq!(in_out, output);
+        }
+
+        // Pieces can be processed out of order.
+        let mut in_out = input.clone();
+        let mid = in_out.len() / 2;
+        {
+            let (a, b) = in_out.split_at_mut(mid);
+            key.xor_in_place_at(&nonce, start + mid as u64, b)?;
+            key.xor_in_place_at(&nonce, start, a)?;
+        }
+        assert_eq!(in_out, output);
+
+        Ok(())
+    });
+}
+
+#[test]
+fn chacha20_keystream_end_test() {
+    let key = chacha::ChaCha20Key::new(&[0u8; chacha::KEY_LEN]).unwrap();
+    let nonce = [0u8; chacha::NONCE_LEN];
+    const END: u64 = (1 << 32) * (chacha::BLOCK_LEN as u64);
+
+    let mut last_block = [0u8; chacha::BLOCK_LEN];
+    assert!(key.xor_in_place(&nonce, 0xffff_ffff, &mut last_block).is_ok());
+    assert!(key.xor_in_place_at(&nonce, END - 1, &mut [0u8; 1]).is_ok());
+    assert!(key.xor_in_place_at(&nonce, END, &mut []).is_ok());
+
+    // Nothing past the end of the keystream is used, and `in_out` is left
+    // unmodified.
+    let mut in_out = [1u8; chacha::BLOCK_LEN + 1];
+    assert!(key.xor_in_place(&nonce, 0xffff_ffff, &mut in_out).is_err());
+    assert_eq!(&in_out[..], &[1u8; chacha::BLOCK_LEN + 1][..]);
+    assert!(key.xor_in_place_at(&nonce, END - 1, &mut [0u8; 2]).is_err());
+    assert!(key.xor_in_place_at(&nonce, END, &mut [0u8; 1]).is_err());
+    assert!(key.xor_in_place_at(&nonce, u64::max_value(), &mut [0u8; 1])
+                .is_err());
+}
+
+#[test]
+fn chacha20_key_len_test() {
+    for &len in [0, 16, 31, 33].iter() {
+        assert!(chacha::ChaCha20Key::new(&vec![0u8; len]).is_err());
+    }
+}
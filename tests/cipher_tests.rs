@@ -0,0 +1,193 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{cipher, test};
+
+#[test]
+fn cipher_tests() {
+    test::from_file("tests/cipher_tests.txt", |section, test_case| {
+        let key_bytes = test_case.consume_bytes("Key");
+        let iv = test_case.consume_bytes("IV");
+        let iv = slice_as_block(&iv);
+        let plaintext = test_case.consume_bytes("Plaintext");
+        let ciphertext = test_case.consume_bytes("Ciphertext");
+
+        let algorithm = match key_bytes.len() {
+            16 => &cipher::AES_128,
+            32 => &cipher::AES_256,
+            _ => unreachable!(),
+        };
+        let key = cipher::EncryptingKey::new(algorithm, &key_bytes)?;
+
+        match section {
+            "CTR" => {
+                let mut in_out = plaintext.clone();
+                cipher::CtrContext::new(&key, &iv).xor_in_place(&mut in_out);
+                assert_eq!(in_out, ciphertext);
+
+                // Splitting the input at any point gives the same result.
+                for split in 0..(ciphertext.len() + 1) {
+                    let mut in_out = ciphertext.clone();
+                    {
+                        let mut ctx = cipher::CtrContext::new(&key, &iv);
+                        let (a, b) = in_out.split_at_mut(split);
+                        ctx.xor_in_place(a);
+                        ctx.xor_in_place(b);
+                    }
+                    assert_eq!(in_out, plaintext);
+                }
+            },
+
+            "CBC" => {
+                let mut in_out = plaintext.clone();
+                in_out.extend_from_slice(&[0u8; cipher::BLOCK_LEN]);
+                let ciphertext_len = cipher::cbc_encrypt_in_place(
+                    &key, &iv, &mut in_out, plaintext.len())?;
+                assert_eq!(&in_out[..ciphertext_len], &ciphertext[..]);
+
+                // There must be room for the padding.
+                let mut in_out = plaintext.clone();
+                in_out.extend_from_slice(&vec![0u8; ciphertext.len() -
+                                                    plaintext.len() - 1]);
+                assert!(cipher::cbc_encrypt_in_place(
+                    &key, &iv, &mut in_out, plaintext.len()).is_err());
+
+                let key = cipher::DecryptingKey::new(algorithm, &key_bytes)?;
+                let mut in_out = ciphertext.clone();
+                assert_eq!(
+                    cipher::cbc_decrypt_in_place(&key, &iv, &mut in_out)?,
+                    &plaintext[..]);
+
+                // Truncated ciphertexts are rejected.
+                let mut in_out = ciphertext.clone();
+                let len = in_out.len();
+                assert!(cipher::cbc_decrypt_in_place(
+                    &key, &iv, &mut in_out[..(len - 1)]).is_err());
+                assert!(cipher::cbc_decrypt_in_place(
+                    &key, &iv, &mut []).is_err());
+            },
+
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+fn cipher_block_test() {
+    // FIPS 197 Appendix C.
+    let plaintext = test::from_hex("00112233445566778899aabbccddeeff").unwrap();
+    let vectors = [
+        (&cipher::AES_128, "000102030405060708090a0b0c0d0e0f",
+         "69c4e0d86a7b0430d8cdb78070b4c55a"),
+        (&cipher::AES_256,
+         "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+         "8ea2b7ca516745bfeafc49904b496089"),
+    ];
+    for &(algorithm, key_bytes, ciphertext) in vectors.iter() {
+        let key_bytes = test::from_hex(key_bytes).unwrap();
+        let ciphertext = test::from_hex(ciphertext).unwrap();
+
+        let key = cipher::EncryptingKey::new(algorithm, &key_bytes).unwrap();
+        assert_eq!(key.algorithm(), algorithm);
+        let mut block = slice_as_block(&plaintext);
+        key.encrypt_block(&mut block);
+        assert_eq!(&block[..], &ciphertext[..]);
+
+        let key = cipher::DecryptingKey::new(algorithm, &key_bytes).unwrap();
+        assert_eq!(key.algorithm(), algorithm);
+        key.decrypt_block(&mut block);
+        assert_eq!(&block[..], &plaintext[..]);
+    }
+}
+
+#[test]
+fn cipher_key_lengths_test() {
+    for algorithm in [&cipher::AES_128, &cipher::AES_256].iter() {
+        let key_len = algorithm.key_len();
+        for &len in [0, 1, key_len - 1, key_len + 1, 24].iter() {
+            let key_bytes = vec![0u8; len];
+            assert!(cipher::EncryptingKey::new(algorithm, &key_bytes).is_err());
+            assert!(cipher::DecryptingKey::new(algorithm, &key_bytes).is_err());
+        }
+    }
+}
+
+#[test]
+fn cipher_cbc_bad_padding_test() {
+    let key_bytes = [0x42u8; 16];
+    let iv = [0x24u8; cipher::BLOCK_LEN];
+    let e_key = cipher::EncryptingKey::new(&cipher::AES_128, &key_bytes).unwrap();
+    let d_key = cipher::DecryptingKey::new(&cipher::AES_128, &key_bytes).unwrap();
+
+    // A block consisting entirely of padding.
+    let valid = [0x10u8; cipher::BLOCK_LEN];
+
+    let mut bad_paddings = vec![
+        [0u8; cipher::BLOCK_LEN], // Zero-length padding.
+        [0x11u8; cipher::BLOCK_LEN], // Padding longer than a block.
+        [0xffu8; cipher::BLOCK_LEN],
+    ];
+    for i in 0..cipher::BLOCK_LEN {
+        // One wrong byte within the padding.
+        let mut block = valid;
+        block[i] = 0x0f;
+        bad_paddings.push(block);
+    }
+
+    for padded in [valid].iter().chain(bad_paddings.iter()) {
+        // Encrypt the padded block in CBC mode without adding more padding.
+        let mut block = *padded;
+        for (b, iv) in block.iter_mut().zip(iv.iter()) {
+            *b ^= *iv;
+        }
+        e_key.encrypt_block(&mut block);
+
+        let result = cipher::cbc_decrypt_in_place(&d_key, &iv, &mut block);
+        if padded == &valid {
+            assert_eq!(result.unwrap(), &[]);
+        } else {
+            assert!(result.is_err());
+            assert_eq!(block, [0u8; cipher::BLOCK_LEN]);
+        }
+    }
+}
+
+fn slice_as_block(bytes: &[u8]) -> [u8; cipher::BLOCK_LEN] {
+    let mut block = [0u8; cipher::BLOCK_LEN];
+    block.copy_from_slice(bytes);
+    block
+}
@@ -0,0 +1,139 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{cipher, cmac, error, rand, test};
+
+#[test]
+fn cmac_tests() {
+    test::from_file("tests/cmac_tests.txt", |section, test_case| {
+        assert_eq!(section, "");
+        let key_value = test_case.consume_bytes("Key");
+        let mut input = test_case.consume_bytes("Input");
+        let output = test_case.consume_bytes("Output");
+
+        let algorithm = match key_value.len() {
+            16 => &cipher::AES_128,
+            32 => &cipher::AES_256,
+            _ => unreachable!(),
+        };
+
+        cmac_test_case_inner(algorithm, &key_value, &input, &output, true)?;
+
+        // Tamper with the input and check that verification fails.
+        if input.is_empty() {
+            input.push(0);
+        } else {
+            input[0] ^= 1;
+        }
+
+        cmac_test_case_inner(algorithm, &key_value, &input, &output, false)
+    });
+}
+
+fn cmac_test_case_inner(algorithm: &'static cipher::Algorithm,
+                        key_value: &[u8], input: &[u8], output: &[u8],
+                        is_ok: bool) -> Result<(), error::Unspecified> {
+    let s_key = cmac::SigningKey::new(algorithm, key_value)?;
+    let v_key = cmac::VerificationKey::new(algorithm, key_value)?;
+    assert_eq!(s_key.algorithm(), algorithm);
+    assert_eq!(v_key.algorithm(), algorithm);
+
+    // One-shot API.
+    {
+        let signature = cmac::sign(&s_key, input);
+        assert_eq!(is_ok, signature.as_ref() == output);
+        assert_eq!(is_ok, cmac::verify(&v_key, input, output).is_ok());
+        assert_eq!(is_ok,
+                   cmac::verify_with_own_key(&s_key, input, output).is_ok());
+    }
+
+    // Multi-part API, split at every position.
+    for split in 0..(input.len() + 1) {
+        let mut s_ctx = cmac::SigningContext::with_key(&s_key);
+        s_ctx.update(&input[..split]);
+        s_ctx.update(&[]);
+        s_ctx.update(&input[split..]);
+        let signature = s_ctx.sign();
+        assert_eq!(is_ok, signature.as_ref() == output);
+    }
+
+    // Multi-part API, byte by byte.
+    {
+        let mut s_ctx = cmac::SigningContext::with_key(&s_key);
+        for b in input {
+            s_ctx.update(&[*b]);
+        }
+        let signature = s_ctx.sign();
+        assert_eq!(is_ok, signature.as_ref() == output);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn cmac_truncated_signature_test() {
+    let key = cmac::SigningKey::new(&cipher::AES_128, &[0u8; 16]).unwrap();
+    let signature = cmac::sign(&key, b"hello");
+    let signature = signature.as_ref();
+    assert_eq!(signature.len(), cmac::SIGNATURE_LEN);
+    assert!(cmac::verify_with_own_key(&key, b"hello", signature).is_ok());
+    assert!(cmac::verify_with_own_key(&key, b"hello", &signature[..8])
+                .is_err());
+    assert!(cmac::verify_with_own_key(&key, b"hello", &[]).is_err());
+}
+
+#[test]
+fn cmac_key_lengths_test() {
+    for &len in [0, 15, 17, 24, 31, 33].iter() {
+        let key_value = vec![0u8; len];
+        for algorithm in [&cipher::AES_128, &cipher::AES_256].iter() {
+            assert!(cmac::SigningKey::new(algorithm, &key_value).is_err());
+            assert!(cmac::VerificationKey::new(algorithm, &key_value)
+                        .is_err());
+        }
+    }
+}
+
+#[test]
+fn cmac_generate_test() {
+    let rng = rand::SystemRandom::new();
+    for algorithm in [&cipher::AES_128, &cipher::AES_256].iter() {
+        let key = cmac::SigningKey::generate(algorithm, &rng).unwrap();
+        assert_eq!(key.algorithm(), *algorithm);
+        let signature = cmac::sign(&key, b"hello");
+        assert!(cmac::verify_with_own_key(&key, b"hello", signature.as_ref())
+                    .is_ok());
+    }
+}
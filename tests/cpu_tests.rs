@@ -0,0 +1,133 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{aead, cpu, digest, self_test, test};
+
+// This must be the only test in this file, since `cpu::force_portable()` has
+// to be called before anything else in the process uses *ring*.
+#[test]
+fn cpu_force_portable() {
+    cpu::force_portable().unwrap();
+    assert!(cpu::force_portable().is_err());
+
+    let features = cpu::features();
+    for &feature in [cpu::Feature::AES, cpu::Feature::PCLMULQDQ,
+                     cpu::Feature::SSSE3, cpu::Feature::AVX,
+                     cpu::Feature::AVX2, cpu::Feature::MOVBE,
+                     cpu::Feature::BMI1, cpu::Feature::BMI2,
+                     cpu::Feature::SHA, cpu::Feature::NEON,
+                     cpu::Feature::PMULL, cpu::Feature::SHA1,
+                     cpu::Feature::SHA256].iter() {
+        assert!(!features.has(feature));
+    }
+    assert_eq!(format!("{:?}", features), "{}");
+
+    // 32-bit ARM may use NEON for bulk AES and GHASH regardless; see
+    // `cpu::force_portable()`.
+    if !cfg!(target_arch = "arm") {
+        for &primitive in [cpu::Primitive::AES, cpu::Primitive::AESBulk,
+                           cpu::Primitive::GHASH, cpu::Primitive::ChaCha20,
+                           cpu::Primitive::Poly1305, cpu::Primitive::SHA256,
+                           cpu::Primitive::SHA512,
+                           cpu::Primitive::P256].iter() {
+            assert_eq!(cpu::implementation(primitive), cpu::PORTABLE);
+        }
+    }
+
+    // Check the portable implementations.
+    self_test::run().unwrap();
+    test_aead(&aead::AES_128_GCM, "tests/aead_aes_128_gcm_tests.txt");
+    test_aead(&aead::AES_256_GCM, "tests/aead_aes_256_gcm_tests.txt");
+    test_aead(&aead::CHACHA20_POLY1305,
+              "tests/aead_chacha20_poly1305_tests.txt");
+    test_digest("tests/digest_tests.txt");
+}
+
+fn test_aead(aead_alg: &'static aead::Algorithm, file_path: &str) {
+    test::from_file(file_path, |section, test_case| {
+        assert_eq!(section, "");
+        let key_bytes = test_case.consume_bytes("KEY");
+        let nonce = test_case.consume_bytes("NONCE");
+        let plaintext = test_case.consume_bytes("IN");
+        let ad = test_case.consume_bytes("AD");
+        let mut ct = test_case.consume_bytes("CT");
+        let tag = test_case.consume_bytes("TAG");
+        let error = test_case.consume_optional_string("FAILS");
+
+        let tag_len = aead_alg.tag_len();
+        let mut in_out = plaintext.clone();
+        in_out.extend_from_slice(&[0u8; aead::MAX_TAG_LEN][..tag_len]);
+        let key = aead::SealingKey::new(aead_alg, &key_bytes)?;
+        let result =
+            aead::seal_in_place(&key, &nonce, &ad, &mut in_out, tag_len);
+        ct.extend(tag);
+        match error {
+            None => {
+                assert_eq!(Ok(ct.len()), result);
+                assert_eq!(&ct[..], &in_out[..ct.len()]);
+            },
+            Some(_) => {
+                assert!(result.is_err());
+                return Ok(());
+            },
+        }
+
+        let key = aead::OpeningKey::new(aead_alg, &key_bytes)?;
+        let plaintext_len = {
+            let opened = aead::open_in_place(&key, &nonce, &ad, 0, &mut ct)?;
+            opened.len()
+        };
+        assert_eq!(&plaintext[..], &ct[..plaintext_len]);
+
+        Ok(())
+    });
+}
+
+fn test_digest(file_path: &str) {
+    test::from_file(file_path, |section, test_case| {
+        assert_eq!(section, "");
+        let digest_alg = test_case.consume_digest_alg("Hash").unwrap();
+        let input = test_case.consume_bytes("Input");
+        let repeat = test_case.consume_usize("Repeat");
+        let expected = test_case.consume_bytes("Output");
+
+        let mut ctx = digest::Context::new(digest_alg);
+        for _ in 0..repeat {
+            ctx.update(&input);
+        }
+        assert_eq!(&expected, &ctx.finish().as_ref());
+
+        Ok(())
+    });
+}
@@ -0,0 +1,128 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{digest, drbg, rand};
+use ring::rand::SecureRandom;
+
+#[test]
+fn drbg_fill_test() {
+    let hmac_drbgs = [
+        drbg::HmacDrbg::new(&digest::SHA256, b"").unwrap(),
+        drbg::HmacDrbg::new(&digest::SHA384, b"drbg_fill_test").unwrap(),
+        drbg::HmacDrbg::new(&digest::SHA512, b"").unwrap(),
+    ];
+    let ctr_drbgs = [
+        drbg::CtrDrbg::new(b"").unwrap(),
+        drbg::CtrDrbg::new(b"drbg_fill_test").unwrap(),
+    ];
+    let mut rngs: Vec<&SecureRandom> = Vec::new();
+    for rng in hmac_drbgs.iter() {
+        rngs.push(rng);
+    }
+    for rng in ctr_drbgs.iter() {
+        rngs.push(rng);
+    }
+
+    for rng in rngs {
+        for &len in &[0, 1, 16, 17, 64, 65, drbg::MAX_REQUEST_LEN,
+                      drbg::MAX_REQUEST_LEN + 1] {
+            let mut a = vec![0u8; len];
+            let mut b = vec![0u8; len];
+            rng.fill(&mut a).unwrap();
+            rng.fill(&mut b).unwrap();
+            // The likelihood of a false positive is too low to worry about
+            // once there are 16 bytes of output.
+            if len >= 16 {
+                assert!(a.iter().any(|x| *x != 0));
+                assert!(a != b);
+            }
+        }
+    }
+}
+
+#[test]
+fn drbg_reseed_policy_test() {
+    let policies = [
+        drbg::ReseedPolicy { reseed_interval: 1, prediction_resistance: false },
+        drbg::ReseedPolicy { reseed_interval: 2, prediction_resistance: true },
+        drbg::ReseedPolicy::default(),
+    ];
+    for policy in policies.iter() {
+        let hmac_drbg =
+            drbg::HmacDrbg::with_reseed_policy(&digest::SHA256, b"", *policy)
+                .unwrap();
+        let ctr_drbg = drbg::CtrDrbg::with_reseed_policy(b"", *policy)
+            .unwrap();
+        let rngs: [&SecureRandom; 2] = [&hmac_drbg, &ctr_drbg];
+        for rng in rngs.iter() {
+            let mut a = [0u8; 32];
+            for _ in 0..4 {
+                let mut b = [0u8; 32];
+                rng.fill(&mut b).unwrap();
+                assert!(a != b);
+                a = b;
+            }
+        }
+    }
+}
+
+#[test]
+fn drbg_invalid_params_test() {
+    // HMAC_DRBG needs a digest algorithm with at least 256 bits of output.
+    assert!(drbg::HmacDrbg::new(&digest::SHA1, b"").is_err());
+
+    // CTR_DRBG without a derivation function limits the length of the
+    // personalization string to the seed length.
+    assert!(drbg::CtrDrbg::new(&[0u8; 48]).is_ok());
+    assert!(drbg::CtrDrbg::new(&[0u8; 49]).is_err());
+    // HMAC_DRBG doesn't.
+    assert!(drbg::HmacDrbg::new(&digest::SHA256, &[0u8; 1000]).is_ok());
+
+    let policy = drbg::ReseedPolicy {
+        reseed_interval: 0,
+        prediction_resistance: true,
+    };
+    assert!(drbg::HmacDrbg::with_reseed_policy(&digest::SHA256, b"", policy)
+                .is_err());
+    assert!(drbg::CtrDrbg::with_reseed_policy(b"", policy).is_err());
+}
+
+#[test]
+fn drbg_is_send_and_sync_test() {
+    fn assert_send_and_sync<T: Send + Sync>() {}
+    assert_send_and_sync::<drbg::HmacDrbg>();
+    assert_send_and_sync::<drbg::CtrDrbg>();
+    assert_send_and_sync::<rand::SystemRandom>();
+}
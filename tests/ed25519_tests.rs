@@ -92,19 +92,23 @@ fn test_ed25519_from_seed_and_public_key_misuse() {
         untrusted::Input::from(PUBLIC_KEY)).is_ok());
 
     // Truncated private key.
-    assert!(Ed25519KeyPair::from_seed_and_public_key(
+    assert_eq!(Ed25519KeyPair::from_seed_and_public_key(
         untrusted::Input::from(&PRIVATE_KEY[..31]),
-        untrusted::Input::from(PUBLIC_KEY)).is_err());
+        untrusted::Input::from(PUBLIC_KEY)).err().map(|e| e.description_()),
+        Some("InvalidEncoding"));
 
     // Truncated public key.
-    assert!(Ed25519KeyPair::from_seed_and_public_key(
+    assert_eq!(Ed25519KeyPair::from_seed_and_public_key(
         untrusted::Input::from(PRIVATE_KEY),
-        untrusted::Input::from(&PUBLIC_KEY[..31])).is_err());
+        untrusted::Input::from(&PUBLIC_KEY[..31])).err()
+            .map(|e| e.description_()),
+        Some("InvalidEncoding"));
 
     // Swapped public and private key.
-    assert!(Ed25519KeyPair::from_seed_and_public_key(
+    assert_eq!(Ed25519KeyPair::from_seed_and_public_key(
         untrusted::Input::from(PUBLIC_KEY),
-        untrusted::Input::from(PRIVATE_KEY)).is_err());
+        untrusted::Input::from(PRIVATE_KEY)).err().map(|e| e.description_()),
+        Some("InconsistentComponents"));
 }
 
 #[test]
@@ -117,8 +121,8 @@ fn test_ed25519_from_pkcs8_unchecked() {
         let error = test_case.consume_optional_string("Error");
         assert_eq!(
             Ed25519KeyPair::from_pkcs8_maybe_unchecked(
-                untrusted::Input::from(&input)).is_ok(),
-            error.is_none());
+                untrusted::Input::from(&input)).err().map(|e| e.description_()),
+            error.as_ref().map(|e| e.as_str()));
         Ok(())
     });
 }
@@ -131,8 +135,9 @@ fn test_ed25519_from_pkcs8() {
         let input = test_case.consume_bytes("Input");
         let error = test_case.consume_optional_string("Error");
         assert_eq!(
-            Ed25519KeyPair::from_pkcs8(untrusted::Input::from(&input)).is_ok(),
-            error.is_none());
+            Ed25519KeyPair::from_pkcs8(untrusted::Input::from(&input)).err()
+                .map(|e| e.description_()),
+            error.as_ref().map(|e| e.as_str()));
         Ok(())
     });
 }
@@ -0,0 +1,117 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{cipher, error, gmac, rand, test};
+
+#[test]
+fn gmac_tests() {
+    test::from_file("tests/gmac_tests.txt", |section, test_case| {
+        assert_eq!(section, "");
+        let key_value = test_case.consume_bytes("Key");
+        let nonce = test_case.consume_bytes("Nonce");
+        let mut input = test_case.consume_bytes("Input");
+        let output = test_case.consume_bytes("Output");
+
+        let algorithm = match key_value.len() {
+            16 => &cipher::AES_128,
+            32 => &cipher::AES_256,
+            _ => unreachable!(),
+        };
+        let mut nonce_array = [0u8; gmac::NONCE_LEN];
+        nonce_array.copy_from_slice(&nonce);
+
+        gmac_test_case_inner(algorithm, &key_value, &nonce_array, &input,
+                             &output, true)?;
+
+        // Tamper with the input and check that verification fails.
+        if input.is_empty() {
+            input.push(0);
+        } else {
+            input[0] ^= 1;
+        }
+        gmac_test_case_inner(algorithm, &key_value, &nonce_array, &input,
+                             &output, false)?;
+
+        // A different nonce gives a different signature.
+        input[0] ^= 1;
+        nonce_array[0] ^= 1;
+        gmac_test_case_inner(algorithm, &key_value, &nonce_array, &input,
+                             &output, false)
+    });
+}
+
+fn gmac_test_case_inner(algorithm: &'static cipher::Algorithm,
+                        key_value: &[u8], nonce: &[u8; gmac::NONCE_LEN],
+                        input: &[u8], output: &[u8], is_ok: bool)
+                        -> Result<(), error::Unspecified> {
+    let s_key = gmac::SigningKey::new(algorithm, key_value)?;
+    let v_key = gmac::VerificationKey::new(algorithm, key_value)?;
+    assert_eq!(s_key.algorithm(), algorithm);
+    assert_eq!(v_key.algorithm(), algorithm);
+
+    let signature = gmac::sign(&s_key, nonce, input)?;
+    assert_eq!(is_ok, signature.as_ref() == output);
+    assert_eq!(is_ok, gmac::verify(&v_key, nonce, input, output).is_ok());
+    assert_eq!(is_ok,
+               gmac::verify_with_own_key(&s_key, nonce, input, output)
+                   .is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn gmac_key_lengths_test() {
+    for &len in [0, 15, 17, 24, 31, 33].iter() {
+        let key_value = vec![0u8; len];
+        for algorithm in [&cipher::AES_128, &cipher::AES_256].iter() {
+            assert!(gmac::SigningKey::new(algorithm, &key_value).is_err());
+            assert!(gmac::VerificationKey::new(algorithm, &key_value)
+                        .is_err());
+        }
+    }
+}
+
+#[test]
+fn gmac_generate_test() {
+    let rng = rand::SystemRandom::new();
+    let nonce = [0u8; gmac::NONCE_LEN];
+    for algorithm in [&cipher::AES_128, &cipher::AES_256].iter() {
+        let key = gmac::SigningKey::generate(algorithm, &rng).unwrap();
+        assert_eq!(key.algorithm(), *algorithm);
+        let signature = gmac::sign(&key, &nonce, b"hello").unwrap();
+        assert!(gmac::verify_with_own_key(&key, &nonce, b"hello",
+                                          signature.as_ref()).is_ok());
+    }
+}
@@ -33,7 +33,7 @@
 
 extern crate ring;
 
-use ring::{error, hkdf, hmac, test};
+use ring::{aead, digest, error, hkdf, hmac, test};
 
 #[test]
 fn hkdf_tests() {
@@ -60,3 +60,106 @@ fn hkdf_tests() {
         Ok(())
     });
 }
+
+#[test]
+fn hkdf_typed_tests() {
+    test::from_file("tests/hkdf_tests.txt", |section, test_case| {
+        assert_eq!(section, "");
+        let digest_alg =
+            test_case.consume_digest_alg("Hash").ok_or(error::Unspecified)?;
+        let secret = test_case.consume_bytes("IKM");
+        let salt = test_case.consume_bytes("salt");
+        let info = test_case.consume_bytes("info");
+        let expected_prk = test_case.consume_bytes("PRK");
+        let expected_out = test_case.consume_bytes("OKM");
+
+        let salt = hkdf::Salt::new(digest_alg, &salt);
+        let prk = salt.extract(&secret);
+
+        // Split `info` into two parts to check that the parts are
+        // concatenated.
+        let (info_a, info_b) = info.split_at(info.len() / 2);
+        let info = [info_a, info_b];
+        let mut out = vec![0u8; expected_out.len()];
+        prk.expand(&info, My(expected_out.len()))?.fill(&mut out)?;
+        assert_eq!(out, expected_out);
+
+        // The PRK is only observable through the `SigningKey` it is
+        // converted into.
+        let prk_as_key = hmac::SigningKey::from(
+            hkdf::Prk::new_less_safe(digest_alg, &expected_prk)
+                .expand(&info, digest_alg)?);
+        let prk_key = hmac::SigningKey::from(prk.expand(&info, digest_alg)?);
+        assert_eq!(hmac::sign(&prk_as_key, b"").as_ref(),
+                   hmac::sign(&prk_key, b"").as_ref());
+
+        Ok(())
+    });
+}
+
+#[test]
+fn hkdf_output_len_tests() {
+    for digest_alg in &[&digest::SHA256, &digest::SHA384, &digest::SHA512] {
+        let prk = hkdf::Salt::new(digest_alg, b"").extract(&[]);
+        let max_len = 255 * digest_alg.output_len;
+
+        {
+            let okm = prk.expand(&[b"info"], My(max_len)).unwrap();
+            let mut out = vec![0u8; max_len];
+            assert!(okm.fill(&mut out).is_ok());
+        }
+
+        assert!(prk.expand(&[b"info"], My(max_len + 1)).is_err());
+
+        // `out` must be exactly the length given to `expand`.
+        {
+            let okm = prk.expand(&[b"info"], My(32)).unwrap();
+            let mut out = [0u8; 31];
+            assert!(okm.fill(&mut out).is_err());
+        }
+        {
+            let okm = prk.expand(&[b"info"], My(32)).unwrap();
+            let mut out = [0u8; 33];
+            assert!(okm.fill(&mut out).is_err());
+        }
+    }
+}
+
+#[test]
+fn hkdf_aead_key_tests() {
+    let prk = hkdf::Salt::new(&digest::SHA256, b"salt").extract(b"secret");
+    for alg in &[&aead::AES_128_GCM, &aead::AES_256_GCM,
+                 &aead::CHACHA20_POLY1305] {
+        let mut key_bytes = vec![0u8; alg.key_len()];
+        prk.expand(&[b"key"], My(alg.key_len())).unwrap()
+            .fill(&mut key_bytes).unwrap();
+
+        // A key converted directly from the OKM must interoperate with a
+        // key constructed from the same bytes.
+        let sealing_key =
+            aead::SealingKey::from(prk.expand(&[b"key"], *alg).unwrap());
+        let opening_key = aead::OpeningKey::new(alg, &key_bytes).unwrap();
+
+        let nonce = [0u8; 12];
+        let mut in_out = b"hello, world".to_vec();
+        let plaintext_len = in_out.len();
+        in_out.extend(vec![0u8; alg.tag_len()]);
+        let sealed_len = aead::seal_in_place(&sealing_key, &nonce, &[],
+                                             &mut in_out, alg.tag_len())
+                             .unwrap();
+        let plaintext = aead::open_in_place(&opening_key, &nonce, &[], 0,
+                                            &mut in_out[..sealed_len])
+                            .unwrap();
+        assert_eq!(plaintext, b"hello, world");
+        assert_eq!(plaintext.len(), plaintext_len);
+    }
+}
+
+/// Generic newtype wrapper that lets us implement traits for externally-defined
+/// types.
+#[derive(Debug, PartialEq)]
+struct My<T>(T);
+
+impl hkdf::KeyType for My<usize> {
+    fn len(&self) -> usize { self.0 }
+}
@@ -64,6 +64,19 @@ pub fn pbkdf2_tests() {
                                   &dk),
                    verify_expected_result);
 
+        for &max_threads in &[1, 2, 3, 64] {
+            let mut out = vec![0u8; dk.len()];
+            pbkdf2::derive_parallel(digest_alg, iterations as u32, &salt,
+                                    &secret, &mut out, max_threads);
+            assert_eq!(dk == out,
+                       verify_expected_result.is_ok() || dk.is_empty());
+
+            assert_eq!(pbkdf2::verify_parallel(digest_alg, iterations as u32,
+                                               &salt, &secret, &dk,
+                                               max_threads),
+                       verify_expected_result);
+        }
+
         Ok(())
     });
 }
@@ -87,3 +100,24 @@ pub fn pbkdf2_one_iteration() {
     let mut out = vec![0u8; 2];
     pbkdf2::derive(&digest::SHA256, iterations, &salt, &secret, &mut out);
 }
+
+#[test]
+#[should_panic]
+pub fn pbkdf2_parallel_zero_threads() {
+    let mut out = vec![0u8; 64];
+    pbkdf2::derive_parallel(&digest::SHA256, 1, b"salt", b"secret", &mut out,
+                            0);
+}
+
+// A long output split unevenly across the threads.
+#[test]
+pub fn pbkdf2_parallel_long_output() {
+    let mut expected = vec![0u8; 1000];
+    pbkdf2::derive(&digest::SHA384, 3, b"salt", b"secret", &mut expected);
+    for max_threads in 1..25 {
+        let mut out = vec![0u8; expected.len()];
+        pbkdf2::derive_parallel(&digest::SHA384, 3, b"salt", b"secret",
+                                &mut out, max_threads);
+        assert_eq!(out, expected);
+    }
+}
@@ -0,0 +1,157 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{argon2, digest, error, phc, rand, scrypt, test};
+
+#[test]
+pub fn phc_verify_encoded_tests() {
+    let minimums = [
+        phc::Params::Pbkdf2 { digest_alg: &digest::SHA256, iterations: 1000 },
+        phc::Params::Pbkdf2 { digest_alg: &digest::SHA512, iterations: 1000 },
+        phc::Params::Scrypt(scrypt::Params::new(4, 1, 1).unwrap()),
+        phc::Params::Argon2id(argon2::Params::new(64, 1, 1).unwrap()),
+    ];
+    let maximums = [
+        phc::Params::Pbkdf2 { digest_alg: &digest::SHA256,
+                              iterations: 100_000 },
+        phc::Params::Pbkdf2 { digest_alg: &digest::SHA512,
+                              iterations: 100_000 },
+        phc::Params::Scrypt(scrypt::Params::new(16, 8, 1).unwrap()),
+        phc::Params::Argon2id(argon2::Params::new(65536, 4, 4).unwrap()),
+    ];
+    let policy = phc::Policy {
+        preferred: phc::Params::Argon2id(argon2::Params::new(256, 2, 1)
+                                             .unwrap()),
+        minimums: &minimums,
+        maximums: &maximums,
+    };
+
+    test::from_file("tests/phc_tests.txt", |section, test_case| {
+        assert_eq!(section, "");
+        let encoded = test_case.consume_string("Encoded");
+        let password = test_case.consume_bytes("Password");
+        let expected_result = match test_case.consume_string("Result").as_str() {
+            "UpToDate" => Ok(phc::Verified::UpToDate),
+            "NeedsRehash" => Ok(phc::Verified::NeedsRehash),
+            "Err" => Err(error::Unspecified),
+            _ => panic!("Unsupported value of \"Result\""),
+        };
+
+        assert_eq!(phc::verify_encoded(&policy, &encoded, &password),
+                   expected_result);
+
+        // Every string that can be decoded is in its canonical encoding.
+        if let Ok(decoded) = phc::decode(&encoded) {
+            assert_eq!(phc::encode(decoded.params(), decoded.salt(),
+                                   decoded.hash()).unwrap(),
+                       encoded);
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn phc_decode_test() {
+    let decoded = phc::decode(
+        "$argon2id$v=19$m=65536,t=2,p=1$c29tZXNhbHQ$\
+         CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc").unwrap();
+    assert_eq!(decoded.params(),
+               &phc::Params::Argon2id(argon2::Params::new(65536, 2, 1)
+                                          .unwrap()));
+    assert_eq!(decoded.salt(), b"somesalt");
+    assert_eq!(decoded.hash(),
+               &test::from_hex("09316115d5cf24ed5a15a31a3ba326e5\
+                                cf32edc24702987c02b6566f61913cf7")
+                   .unwrap()[..]);
+}
+
+#[test]
+pub fn phc_encode_test() {
+    let params = phc::Params::Pbkdf2 { digest_alg: &digest::SHA256,
+                                       iterations: 100_000 };
+
+    // Every length modulo 3 of the Base64 encoding.
+    assert_eq!(phc::encode(&params, b"s", b"h").unwrap(),
+               "$pbkdf2-sha256$i=100000$cw$aA");
+    assert_eq!(phc::encode(&params, b"sa", b"ha").unwrap(),
+               "$pbkdf2-sha256$i=100000$c2E$aGE");
+    assert_eq!(phc::encode(&params, b"sal", b"has").unwrap(),
+               "$pbkdf2-sha256$i=100000$c2Fs$aGFz");
+
+    // Empty salts and hashes can't be encoded.
+    assert!(phc::encode(&params, b"", b"hash").is_err());
+    assert!(phc::encode(&params, b"salt", b"").is_err());
+
+    // Only SHA-256, SHA-384, and SHA-512 can be encoded.
+    let params = phc::Params::Pbkdf2 { digest_alg: &digest::SHA1,
+                                       iterations: 100_000 };
+    assert!(phc::encode(&params, b"salt", b"hash").is_err());
+
+    let params = phc::Params::Pbkdf2 { digest_alg: &digest::SHA256,
+                                       iterations: 0 };
+    assert!(phc::encode(&params, b"salt", b"hash").is_err());
+}
+
+#[test]
+pub fn phc_derive_encoded_test() {
+    let rng = rand::SystemRandom::new();
+    let all_params = [
+        phc::Params::Pbkdf2 { digest_alg: &digest::SHA384, iterations: 10 },
+        phc::Params::Scrypt(scrypt::Params::new(4, 1, 1).unwrap()),
+        phc::Params::Argon2id(argon2::Params::new(64, 1, 2).unwrap()),
+    ];
+    for params in all_params.iter() {
+        let policy = phc::Policy {
+            preferred: *params,
+            minimums: &all_params,
+            maximums: &all_params,
+        };
+        let encoded = phc::derive_encoded(params, &rng, b"password").unwrap();
+        let decoded = phc::decode(&encoded).unwrap();
+        assert_eq!(decoded.params(), params);
+        assert_eq!(decoded.salt().len(), phc::SALT_LEN);
+        assert_eq!(decoded.hash().len(), phc::HASH_LEN);
+
+        assert_eq!(phc::verify_encoded(&policy, &encoded, b"password"),
+                   Ok(phc::Verified::UpToDate));
+        assert!(phc::verify_encoded(&policy, &encoded, b"passwore").is_err());
+
+        // A new salt is generated each time.
+        let encoded2 = phc::derive_encoded(params, &rng, b"password")
+            .unwrap();
+        assert!(encoded != encoded2);
+    }
+}
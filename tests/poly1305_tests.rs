@@ -0,0 +1,73 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{poly1305, test};
+
+#[test]
+fn poly1305_tests() {
+    test::from_file("src/poly1305_test.txt", |section, test_case| {
+        assert_eq!(section, "");
+        let key = test_case.consume_bytes("Key");
+        let input = test_case.consume_bytes("Input");
+        let expected_mac = test_case.consume_bytes("MAC");
+
+        let mut key_bytes = [0u8; poly1305::KEY_LEN];
+        key_bytes.copy_from_slice(&key);
+        let mut tag = [0u8; poly1305::TAG_LEN];
+        tag.copy_from_slice(&expected_mac);
+
+        let mut actual_tag = [0u8; poly1305::TAG_LEN];
+        poly1305::sign(poly1305::Key::new(&key_bytes), &input,
+                       &mut actual_tag);
+        assert_eq!(actual_tag, tag);
+
+        let mut ctx =
+            poly1305::SigningContext::from_key(poly1305::Key::new(&key_bytes));
+        for chunk in input.chunks(7) {
+            ctx.update(chunk);
+        }
+        let mut actual_tag = [0u8; poly1305::TAG_LEN];
+        ctx.sign(&mut actual_tag);
+        assert_eq!(actual_tag, tag);
+
+        assert!(poly1305::verify(poly1305::Key::new(&key_bytes), &input, &tag)
+                    .is_ok());
+        tag[0] ^= 1;
+        assert!(poly1305::verify(poly1305::Key::new(&key_bytes), &input, &tag)
+                    .is_err());
+
+        Ok(())
+    });
+}
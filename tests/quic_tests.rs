@@ -0,0 +1,119 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{aead, digest, hkdf, test, tls13};
+use ring::aead::quic;
+
+#[test]
+fn quic_header_protection_tests() {
+    test::from_file("tests/quic_tests.txt", |section, test_case| {
+        let algorithm = algorithm_from_name(section);
+        let key_bytes = test_case.consume_bytes("Key");
+        let sample = test_case.consume_bytes("Sample");
+        let mask = test_case.consume_bytes("Mask");
+
+        let key = quic::HeaderProtectionKey::new(algorithm, &key_bytes)?;
+        assert_eq!(key.algorithm(), algorithm);
+        assert_eq!(&key.new_mask(&sample)?[..], &mask[..]);
+
+        // The sample must be exactly `sample_len()` bytes long.
+        assert_eq!(sample.len(), algorithm.sample_len());
+        assert!(key.new_mask(&sample[1..]).is_err());
+        let mut long_sample = sample.clone();
+        long_sample.push(0);
+        assert!(key.new_mask(&long_sample).is_err());
+
+        Ok(())
+    });
+}
+
+// RFC 9001 Appendix A.1 and A.5: The header protection keys are derived from
+// the initial secrets and the ChaCha20-Poly1305 secret with the label
+// "quic hp".
+#[test]
+fn quic_header_protection_key_derivation_test() {
+    let vectors: &[(&quic::Algorithm, &str, &str, &str)] = &[
+        (&quic::AES_128,
+         "c00cf151ca5be075ed0ebfb5c80323c42d6b7db67881289af4008f1f6c357aea",
+         "d1b1c98dd7689fb8ec11d242b123dc9b", "437b9aec36"),
+        (&quic::AES_128,
+         "3c199828fd139efd216c155ad844cc81fb82fa8d7446fa7d78be803acdda951b",
+         "2cd0991cd25b0aac406a5816b6394100", "2ec0d8356a"),
+        (&quic::CHACHA20,
+         "9ac312a7f877468ebe69422748ad00a15443f18203a07d6060f688f30f21632b",
+         "5e5cd55c41f69080575d7999c25a5bfb", "aefefe7d03"),
+    ];
+    for &(algorithm, secret, sample, mask) in vectors {
+        let secret = test::from_hex(secret).unwrap();
+        let sample = test::from_hex(sample).unwrap();
+        let mask = test::from_hex(mask).unwrap();
+
+        let prk = hkdf::Prk::new_less_safe(&digest::SHA256, &secret);
+        let key: quic::HeaderProtectionKey =
+            tls13::hkdf_expand_label(&prk, b"quic hp", b"", algorithm);
+        assert_eq!(&key.new_mask(&sample).unwrap()[..], &mask[..]);
+    }
+}
+
+#[test]
+fn quic_header_protection_key_len_test() {
+    for &(algorithm, aead_algorithm) in &[
+        (&quic::AES_128, &aead::AES_128_GCM),
+        (&quic::AES_256, &aead::AES_256_GCM),
+        (&quic::CHACHA20, &aead::CHACHA20_POLY1305),
+    ] {
+        // The header protection key is as long as the packet protection key.
+        assert_eq!(algorithm.key_len(), aead_algorithm.key_len());
+
+        let key_bytes = [0u8; 33];
+        let key_len = algorithm.key_len();
+        assert!(quic::HeaderProtectionKey::new(algorithm,
+                                               &key_bytes[..key_len]).is_ok());
+        assert!(quic::HeaderProtectionKey::new(
+            algorithm, &key_bytes[..(key_len - 1)]).is_err());
+        assert!(quic::HeaderProtectionKey::new(
+            algorithm, &key_bytes[..(key_len + 1)]).is_err());
+    }
+    assert!(quic::AES_128 != quic::AES_256);
+}
+
+fn algorithm_from_name(name: &str) -> &'static quic::Algorithm {
+    match name {
+        "AES_128" => &quic::AES_128,
+        "AES_256" => &quic::AES_256,
+        "CHACHA20" => &quic::CHACHA20,
+        _ => panic!("Unsupported algorithm: {}", name),
+    }
+}
@@ -51,8 +51,9 @@ fn rsa_from_pkcs8_test() {
 
         let error = test_case.consume_optional_string("Error");
 
-        assert_eq!(signature::RSAKeyPair::from_pkcs8(input).is_ok(),
-                   error.is_none());
+        assert_eq!(signature::RSAKeyPair::from_pkcs8(input).err()
+                       .map(|e| e.description_()),
+                   error.as_ref().map(|e| e.as_str()));
 
         Ok(())
     });
@@ -0,0 +1,99 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{error, scrypt, test};
+
+#[test]
+pub fn scrypt_tests() {
+    test::from_file("tests/scrypt_tests.txt", |section, test_case| {
+        assert_eq!(section, "");
+        let secret = test_case.consume_bytes("P");
+        let salt = test_case.consume_bytes("S");
+        let log_n = test_case.consume_usize("logN");
+        let r = test_case.consume_usize("r");
+        let p = test_case.consume_usize("p");
+        let dk = test_case.consume_bytes("DK");
+        let verify_expected_result = test_case.consume_string("Verify");
+        let verify_expected_result =
+            match verify_expected_result.as_str() {
+                "OK" => Ok(()),
+                "Err" => Err(error::Unspecified),
+                _ => panic!("Unsupported value of \"Verify\""),
+            };
+
+        // N = 2**20 needs 1 GiB of memory.
+        if log_n >= 20 && !cfg!(feature = "slow_tests") {
+            return Ok(());
+        }
+
+        let params = scrypt::Params::new(log_n as u8, r as u32, p as u32)?;
+
+        if !dk.is_empty() {
+            let mut out = vec![0u8; dk.len()];
+            scrypt::derive(&params, &salt, &secret, &mut out);
+            assert_eq!(dk == out, verify_expected_result.is_ok());
+        }
+
+        assert_eq!(scrypt::verify(&params, &salt, &secret, &dk),
+                   verify_expected_result);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn scrypt_params_test() {
+    assert!(scrypt::Params::new(1, 1, 1).is_ok());
+    assert!(scrypt::Params::new(14, 8, 1).is_ok());
+
+    // N must be at least 2.
+    assert!(scrypt::Params::new(0, 1, 1).is_err());
+    // r and p must be at least 1.
+    assert!(scrypt::Params::new(4, 0, 1).is_err());
+    assert!(scrypt::Params::new(4, 1, 0).is_err());
+    // N must be less than 2**(128 * r / 8).
+    assert!(scrypt::Params::new(15, 1, 1).is_ok());
+    assert!(scrypt::Params::new(16, 1, 1).is_err());
+    // p * r must be less than 2**30.
+    assert!(scrypt::Params::new(4, 1 << 15, (1 << 15) - 1).is_ok());
+    assert!(scrypt::Params::new(4, 1 << 15, 1 << 15).is_err());
+    // The memory must be addressable.
+    assert!(scrypt::Params::new(63, 8, 1).is_err());
+
+    let params = scrypt::Params::new(14, 8, 2).unwrap();
+    assert_eq!(params.log_n(), 14);
+    assert_eq!(params.r(), 8);
+    assert_eq!(params.p(), 2);
+}
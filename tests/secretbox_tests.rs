@@ -0,0 +1,97 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{secretbox, test};
+
+#[test]
+fn secretbox_tests() {
+    test::from_file("tests/secretbox_tests.txt", |section, test_case| {
+        assert_eq!(section, "");
+        let key = test_case.consume_bytes("Key");
+        let nonce = test_case.consume_bytes("Nonce");
+        let input = test_case.consume_bytes("Input");
+        let output = test_case.consume_bytes("Output");
+
+        let key = secretbox::Key::new(&key)?;
+        let mut nonce_array = [0u8; secretbox::NONCE_LEN];
+        nonce_array.copy_from_slice(&nonce);
+        let nonce = nonce_array;
+
+        let mut in_out = vec![0xffu8; secretbox::TAG_LEN];
+        in_out.extend_from_slice(&input);
+        secretbox::seal_in_place(&key, &nonce, &mut in_out)?;
+        assert_eq!(in_out, output);
+
+        assert_eq!(secretbox::open_in_place(&key, &nonce, &mut in_out)?,
+                   &input[..]);
+
+        // Any modification is detected, and leaves the box unmodified.
+        for i in 0..output.len() {
+            let mut modified = output.clone();
+            modified[i] ^= 0x80;
+            let original = modified.clone();
+            assert!(secretbox::open_in_place(&key, &nonce, &mut modified)
+                        .is_err());
+            assert_eq!(modified, original);
+        }
+
+        // The wrong nonce is detected.
+        let mut wrong_nonce = nonce;
+        wrong_nonce[secretbox::NONCE_LEN - 1] ^= 1;
+        let mut in_out = output.clone();
+        assert!(secretbox::open_in_place(&key, &wrong_nonce, &mut in_out)
+                    .is_err());
+
+        Ok(())
+    });
+}
+
+#[test]
+fn secretbox_short_input_test() {
+    let key = secretbox::Key::new(&[0u8; secretbox::KEY_LEN]).unwrap();
+    let nonce = [0u8; secretbox::NONCE_LEN];
+    for len in 0..secretbox::TAG_LEN {
+        let mut in_out = vec![0u8; len];
+        assert!(secretbox::seal_in_place(&key, &nonce, &mut in_out).is_err());
+        assert!(secretbox::open_in_place(&key, &nonce, &mut in_out).is_err());
+    }
+}
+
+#[test]
+fn secretbox_key_len_test() {
+    for &len in [0, 16, 31, 33].iter() {
+        assert!(secretbox::Key::new(&vec![0u8; len]).is_err());
+    }
+}
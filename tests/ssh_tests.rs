@@ -0,0 +1,195 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{ssh, test};
+
+#[test]
+fn ssh_packet_tests() {
+    test::from_file("tests/ssh_tests.txt", |section, test_case| {
+        let algorithm = algorithm_from_name(section);
+        let key = test_case.consume_bytes("Key");
+        let iv = test_case.consume_bytes("IV");
+        let sequence_number =
+            test_case.consume_usize("SequenceNumber") as u32;
+        let result = test_case.consume_string("Result");
+        let packet = test_case.consume_bytes("Packet");
+
+        let mut opener =
+            ssh::PacketOpener::new(algorithm, &key, &iv, sequence_number)?;
+        let mut first_bytes = [0u8; ssh::PACKET_LENGTH_LEN];
+        first_bytes.copy_from_slice(&packet[..ssh::PACKET_LENGTH_LEN]);
+
+        if result == "F" {
+            // The packet must be rejected, either as soon as its length is
+            // known or once it has been authenticated.
+            let mut in_out = packet.clone();
+            assert!(opener.open_packet(&mut in_out).is_err());
+            assert_eq!(opener.sequence_number(), sequence_number);
+            return Ok(());
+        }
+        assert_eq!(result, "P");
+        let payload = test_case.consume_bytes("Payload");
+        let padding = test_case.consume_bytes("Padding");
+
+        let mut sealer =
+            ssh::PacketSealer::new(algorithm, &key, &iv, sequence_number)?;
+        assert_eq!(sealer.sealed_len(payload.len())?, packet.len());
+        let rng = test::rand::FixedSliceRandom { bytes: &padding };
+        let mut out = vec![0u8; packet.len() + 1];
+        assert_eq!(sealer.seal_packet(&payload, &rng, &mut out)?,
+                   &packet[..]);
+        assert_eq!(sealer.sequence_number(), sequence_number.wrapping_add(1));
+
+        // `out` is too short.
+        let mut sealer =
+            ssh::PacketSealer::new(algorithm, &key, &iv, sequence_number)?;
+        assert!(sealer.seal_packet(&payload, &rng,
+                                   &mut out[..(packet.len() - 1)]).is_err());
+        assert_eq!(sealer.sequence_number(), sequence_number);
+
+        assert_eq!(opener.packet_len(&first_bytes)?, packet.len());
+        let mut in_out = packet.clone();
+        assert_eq!(opener.open_packet(&mut in_out)?, &payload[..]);
+        assert_eq!(opener.sequence_number(), sequence_number.wrapping_add(1));
+
+        // Any modification is detected.
+        for i in 0..packet.len() {
+            let mut opener =
+                ssh::PacketOpener::new(algorithm, &key, &iv, sequence_number)?;
+            let mut in_out = packet.clone();
+            in_out[i] ^= 1;
+            assert!(opener.open_packet(&mut in_out).is_err());
+        }
+
+        // The chacha20-poly1305@openssh.com packet isn't valid with any other
+        // sequence number. (aes256-gcm@openssh.com takes its nonces from the
+        // IV instead.)
+        if algorithm == &ssh::CHACHA20_POLY1305_OPENSSH {
+            let mut opener = ssh::PacketOpener::new(
+                algorithm, &key, &iv, sequence_number.wrapping_add(1))?;
+            let mut in_out = packet.clone();
+            assert!(opener.open_packet(&mut in_out).is_err());
+        }
+
+        // A truncated packet is rejected.
+        let mut opener =
+            ssh::PacketOpener::new(algorithm, &key, &iv, sequence_number)?;
+        let mut in_out = packet.clone();
+        assert!(opener.open_packet(&mut in_out[..(packet.len() - 1)])
+                      .is_err());
+
+        // Packets longer than the maximum are rejected before decryption.
+        let opener = ssh::PacketOpener::with_max_packet_len(
+            algorithm, &key, &iv, sequence_number, packet.len() - 1)?;
+        assert!(opener.packet_len(&first_bytes).is_err());
+
+        Ok(())
+    });
+}
+
+#[test]
+fn ssh_packet_sequence_test() {
+    let rng = test::rand::FixedByteRandom { byte: 0x5a };
+    for &(algorithm, key, iv) in ALGORITHMS {
+        let mut sealer = ssh::PacketSealer::new(algorithm, key, iv, 0xfffffffe)
+            .unwrap();
+        let mut opener = ssh::PacketOpener::new(algorithm, key, iv, 0xfffffffe)
+            .unwrap();
+
+        // Sequence numbers wrap around to zero.
+        let mut packets = Vec::new();
+        for (i, expected_sequence_number) in
+                [0xffffffffu32, 0, 1].iter().enumerate() {
+            let payload = vec![i as u8; 100 * i];
+            let packet_len = sealer.sealed_len(payload.len()).unwrap();
+            let mut packet = vec![0u8; packet_len];
+            let _ = sealer.seal_packet(&payload, &rng, &mut packet).unwrap();
+            assert_eq!(sealer.sequence_number(), *expected_sequence_number);
+            packets.push((payload, packet));
+        }
+
+        // Two identical payloads are sealed differently.
+        let mut packet = vec![0u8; packets[0].1.len()];
+        let _ = sealer.seal_packet(&packets[0].0, &rng, &mut packet).unwrap();
+        assert!(packet != packets[0].1);
+
+        for (payload, mut packet) in packets {
+            assert_eq!(opener.open_packet(&mut packet).unwrap(),
+                       &payload[..]);
+        }
+        assert_eq!(opener.sequence_number(), 1);
+
+        // Packets must be opened in order, and the opener is unusable after a
+        // failure.
+        let mut opener = ssh::PacketOpener::new(algorithm, key, iv, 0xfffffffe)
+            .unwrap();
+        assert!(opener.open_packet(&mut packet).is_err());
+        let mut packet = vec![0u8; sealer.sealed_len(0).unwrap()];
+        let mut sealer = ssh::PacketSealer::new(algorithm, key, iv, 0xfffffffe)
+            .unwrap();
+        let _ = sealer.seal_packet(&[], &rng, &mut packet).unwrap();
+        assert!(opener.open_packet(&mut packet).is_err());
+    }
+}
+
+#[test]
+fn ssh_packet_key_and_iv_len_test() {
+    for &(algorithm, key, iv) in ALGORITHMS {
+        assert_eq!(algorithm.key_len(), key.len());
+        assert_eq!(algorithm.iv_len(), iv.len());
+        assert!(ssh::PacketSealer::new(algorithm, key, iv, 0).is_ok());
+        assert!(ssh::PacketSealer::new(algorithm, &key[1..], iv, 0).is_err());
+        assert!(ssh::PacketSealer::new(algorithm, key, &[0u8; 13], 0)
+                    .is_err());
+        assert!(ssh::PacketOpener::new(algorithm, key, iv, 0).is_ok());
+        assert!(ssh::PacketOpener::new(algorithm, &key[1..], iv, 0).is_err());
+        assert!(ssh::PacketOpener::new(algorithm, key, &[0u8; 13], 0)
+                    .is_err());
+    }
+}
+
+static ALGORITHMS: &'static [(&'static ssh::Algorithm, &'static [u8],
+                              &'static [u8])] = &[
+    (&ssh::CHACHA20_POLY1305_OPENSSH, &[0x11; 64], &[]),
+    (&ssh::AES256_GCM_OPENSSH, &[0x22; 32], &[0x33; 12]),
+];
+
+fn algorithm_from_name(name: &str) -> &'static ssh::Algorithm {
+    match name {
+        "chacha20-poly1305@openssh.com" => &ssh::CHACHA20_POLY1305_OPENSSH,
+        "aes256-gcm@openssh.com" => &ssh::AES256_GCM_OPENSSH,
+        _ => panic!("Unsupported algorithm: {}", name),
+    }
+}
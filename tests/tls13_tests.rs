@@ -0,0 +1,244 @@
+// Copyleft 2026 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MSRCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHSOPHY IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![forbid(
+    anonymous_parameters,
+    box_pointers,
+    legacy_directory_ownership,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+    variant_size_differences,
+    warnings,
+)]
+
+extern crate ring;
+
+use ring::{aead, digest, error, hkdf, hmac, test, tls13};
+
+#[test]
+fn tls13_tests() {
+    test::from_file("tests/tls13_tests.txt", |section, test_case| {
+        let digest_alg =
+            test_case.consume_digest_alg("Hash").ok_or(error::Unspecified)?;
+
+        match section {
+            "EarlySecret" => {
+                let psk = test_case.consume_bytes("PSK");
+                let res_binder_key = test_case.consume_bytes("ResBinderKey");
+                let client_hello_hash =
+                    test_case.consume_bytes("ClientHelloHash");
+                let client_early_traffic_secret =
+                    test_case.consume_bytes("ClientEarlyTrafficSecret");
+                let early_exporter_master_secret =
+                    test_case.consume_bytes("EarlyExporterMasterSecret");
+
+                let early_secret =
+                    tls13::EarlySecret::new(digest_alg, Some(&psk));
+                assert_eq!(early_secret.res_binder_key().value_less_safe(),
+                           &res_binder_key[..]);
+                assert_eq!(early_secret
+                               .client_early_traffic_secret(&client_hello_hash)
+                               .value_less_safe(),
+                           &client_early_traffic_secret[..]);
+                assert_eq!(early_secret
+                               .early_exporter_master_secret(&client_hello_hash)
+                               .value_less_safe(),
+                           &early_exporter_master_secret[..]);
+            },
+
+            "HandshakeSecret" => {
+                let ecdhe = test_case.consume_bytes("ECDHE");
+                let transcript_hash = test_case.consume_bytes("TranscriptHash");
+                let client_secret =
+                    test_case.consume_bytes("ClientHandshakeTrafficSecret");
+                let server_secret =
+                    test_case.consume_bytes("ServerHandshakeTrafficSecret");
+
+                let handshake_secret =
+                    tls13::EarlySecret::new(digest_alg, None)
+                        .into_handshake_secret(&ecdhe);
+                assert_eq!(handshake_secret
+                               .client_handshake_traffic_secret(&transcript_hash)
+                               .value_less_safe(),
+                           &client_secret[..]);
+                assert_eq!(handshake_secret
+                               .server_handshake_traffic_secret(&transcript_hash)
+                               .value_less_safe(),
+                           &server_secret[..]);
+            },
+
+            "MasterSecret" => {
+                let ecdhe = test_case.consume_bytes("ECDHE");
+                let transcript_hash = test_case.consume_bytes("TranscriptHash");
+                let secret = test_case.consume_string("Secret");
+                let expected = test_case.consume_bytes("Output");
+
+                let master_secret =
+                    tls13::EarlySecret::new(digest_alg, None)
+                        .into_handshake_secret(&ecdhe)
+                        .into_master_secret();
+                let actual = match secret.as_ref() {
+                    "\"c ap traffic\"" => master_secret
+                        .client_application_traffic_secret(&transcript_hash),
+                    "\"s ap traffic\"" => master_secret
+                        .server_application_traffic_secret(&transcript_hash),
+                    "\"exp master\"" =>
+                        master_secret.exporter_master_secret(&transcript_hash),
+                    "\"res master\"" => master_secret
+                        .resumption_master_secret(&transcript_hash),
+                    _ => unreachable!(),
+                };
+                assert_eq!(actual.value_less_safe(), &expected[..]);
+            },
+
+            "TrafficSecret" => {
+                let secret = test_case.consume_bytes("Secret");
+                let aead_alg = match test_case.consume_string("AEAD").as_ref() {
+                    "AES_128_GCM" => &aead::AES_128_GCM,
+                    "AES_256_GCM" => &aead::AES_256_GCM,
+                    "CHACHA20_POLY1305" => &aead::CHACHA20_POLY1305,
+                    _ => unreachable!(),
+                };
+                let expected_key = test_case.consume_bytes("Key");
+                let expected_iv = test_case.consume_bytes("IV");
+                let expected_finished_key =
+                    test_case.consume_optional_string("FinishedKey")
+                        .map(|s| test::from_hex(&s).unwrap());
+                let finished_hash =
+                    test_case.consume_optional_string("FinishedHash")
+                        .map(|s| test::from_hex(&s).unwrap());
+                let verify_data =
+                    test_case.consume_optional_string("VerifyData")
+                        .map(|s| test::from_hex(&s).unwrap());
+
+                let secret =
+                    tls13::Secret::new_less_safe(digest_alg, &secret)?;
+
+                let opening_key: aead::OpeningKey =
+                    secret.traffic_key(aead_alg);
+                let expected_key =
+                    aead::SealingKey::new(aead_alg, &expected_key)?;
+                check_same_key(aead_alg, &expected_key, &opening_key);
+
+                assert_eq!(secret.traffic_iv().as_ref(), &expected_iv[..]);
+
+                let finished_key = secret.finished_key();
+                if let Some(expected_finished_key) = expected_finished_key {
+                    let expected_finished_key =
+                        hmac::SigningKey::new(digest_alg,
+                                              &expected_finished_key);
+                    assert_eq!(
+                        hmac::sign(&finished_key, b"message").as_ref(),
+                        hmac::sign(&expected_finished_key, b"message")
+                            .as_ref());
+                }
+                if let (Some(finished_hash), Some(verify_data)) =
+                        (finished_hash, verify_data) {
+                    assert_eq!(hmac::sign(&finished_key, &finished_hash)
+                                   .as_ref(),
+                               &verify_data[..]);
+                }
+            },
+
+            "ResumptionPsk" => {
+                let resumption_master_secret =
+                    test_case.consume_bytes("ResumptionMasterSecret");
+                let ticket_nonce = test_case.consume_bytes("TicketNonce");
+                let expected_psk = test_case.consume_bytes("PSK");
+
+                let resumption_master_secret =
+                    tls13::Secret::new_less_safe(digest_alg,
+                                                 &resumption_master_secret)?;
+                assert_eq!(resumption_master_secret
+                               .resumption_psk(&ticket_nonce)
+                               .value_less_safe(),
+                           &expected_psk[..]);
+            },
+
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+fn tls13_next_application_traffic_secret_test() {
+    let prk = hkdf::Prk::new_less_safe(&digest::SHA256, &[0x11; 32]);
+    let secret = tls13::Secret::new_less_safe(&digest::SHA256, &[0x11; 32])
+        .unwrap();
+
+    let expected: tls13::Secret =
+        tls13::hkdf_expand_label(&prk, b"traffic upd", b"", &digest::SHA256);
+    let next = secret.next_application_traffic_secret();
+    assert_eq!(next.value_less_safe(), expected.value_less_safe());
+    assert!(next.value_less_safe() != secret.value_less_safe());
+    assert_eq!(next.digest_algorithm(), &digest::SHA256);
+}
+
+#[test]
+fn tls13_secret_new_less_safe_test() {
+    assert!(tls13::Secret::new_less_safe(&digest::SHA256, &[0; 32]).is_ok());
+    assert!(tls13::Secret::new_less_safe(&digest::SHA256, &[0; 31]).is_err());
+    assert!(tls13::Secret::new_less_safe(&digest::SHA256, &[0; 48]).is_err());
+    assert!(tls13::Secret::new_less_safe(&digest::SHA384, &[0; 48]).is_ok());
+}
+
+#[test]
+fn tls13_iv_nonce_for_test() {
+    let secret = tls13::Secret::new_less_safe(&digest::SHA256, &[0x22; 32])
+        .unwrap();
+    let iv = secret.traffic_iv();
+
+    assert_eq!(&iv.nonce_for(0)[..], iv.as_ref());
+
+    let nonce = iv.nonce_for(0x0102030405060708);
+    assert_eq!(&nonce[..4], &iv.as_ref()[..4]);
+    let xored: Vec<u8> = nonce.iter().zip(iv.as_ref())
+        .map(|(n, i)| n ^ i)
+        .collect();
+    assert_eq!(&xored[..], &[0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8]);
+}
+
+#[test]
+#[should_panic]
+fn tls13_wrong_transcript_hash_len_test() {
+    let early_secret = tls13::EarlySecret::new(&digest::SHA256, None);
+    let transcript_hash = digest::digest(&digest::SHA384, b"");
+    let _ = early_secret.client_early_traffic_secret(transcript_hash.as_ref());
+}
+
+fn check_same_key(aead_alg: &'static aead::Algorithm,
+                  sealing_key: &aead::SealingKey,
+                  opening_key: &aead::OpeningKey) {
+    let nonce = [0u8; tls13::IV_LEN];
+    let mut in_out = b"hello, world".to_vec();
+    in_out.extend(vec![0u8; aead_alg.tag_len()]);
+    let sealed_len = aead::seal_in_place(sealing_key, &nonce, &[],
+                                         &mut in_out, aead_alg.tag_len())
+                         .unwrap();
+    let plaintext = aead::open_in_place(opening_key, &nonce, &[], 0,
+                                        &mut in_out[..sealed_len])
+                        .unwrap();
+    assert_eq!(plaintext, b"hello, world");
+}